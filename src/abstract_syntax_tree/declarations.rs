@@ -1,13 +1,12 @@
 use pest::error::Error;
 
 use crate::syntax_parsing::Rule;
-use crate::abstract_syntax_tree::expressions::build_identifier;
+use crate::abstract_syntax_tree::expressions::{build_expression, build_identifier, build_string_literal};
 
 use super::nodes::*;
-use crate::errors::make_ast_error_from_pair;
+use crate::errors::{make_ast_error, make_ast_error_from_pair};
 
 // exported macros are available in the crate root (global scope)
-use crate::unwrap_or_err_panic;
 use crate::ok_build_node;
 
 
@@ -15,8 +14,14 @@ pub fn build_parameter_list(pair: pest::iterators::Pair<Rule>) -> Result<Vec<Nod
     let mut parameters = Vec::new();
 
     for inner_pair in pair.into_inner() {
-        let parameter_node = unwrap_or_err_panic!(build_declaration(inner_pair));
-        
+        let parameter_node = build_declaration(inner_pair)?;
+        if let Some(initializer) = &parameter_node.data.initializer {
+            return Err(make_ast_error(
+                initializer.sp,
+                "🟠 Function parameters can't have a default value"
+            ));
+        }
+
         // TODO: check that the parameter is not an array
         
         parameters.push(parameter_node);
@@ -32,78 +37,196 @@ pub fn get_type_from_pair(pair: pest::iterators::Pair<Rule>) -> Result<TypeSpeci
     Ok(type_specifier)
 }
 
-fn potential_array_size_from_pair(potential_pair: Option<pest::iterators::Pair<Rule>>) -> Result<Option<usize>, Error<Rule>> {
-    match potential_pair {
-        Some(actual_array_size_pair) => {
-            let array_size_str = actual_array_size_pair.as_str();
-            match array_size_str.parse::<usize>() {
-                Ok(array_size) => Ok(Some(array_size)),
-                Err(_) => return Err(make_ast_error_from_pair(
-                    actual_array_size_pair, 
-                    format!(
-                        "🟠 Invalid array size (must be a positive integer): {}", 
-                        array_size_str
-                    ).as_str()
-                )),
+fn build_initializer(pair: pest::iterators::Pair<Rule>) -> Result<Node<Initializer>, Error<Rule>> {
+    let inner_pair = pair.clone().into_inner().next().unwrap();
+    let initializer = match inner_pair.as_rule() {
+        Rule::initializer_list => {
+            let mut elements = Vec::new();
+            for element_pair in inner_pair.into_inner() {
+                elements.push(build_expression(element_pair)?);
             }
+            Initializer::List(elements)
         },
-        None => Ok(None),
-    }
+        Rule::string_literal => Initializer::String(build_string_literal(inner_pair)?),
+        _ => Initializer::Expression(build_expression(inner_pair)?),
+    };
+    ok_build_node!(pair, initializer)
 }
 
-pub fn build_declaration(pair: pest::iterators::Pair<Rule>) -> Result<Node<Declaration>, Error<Rule>> {
-    let mut inner_pairs = pair.clone().into_inner();
-    let first_pair = inner_pairs.next().unwrap();
-    let second_pair = inner_pairs.next().unwrap();
-    let potential_third_pair = inner_pairs.next();
-
-    let declaration_type = unwrap_or_err_panic!(get_type_from_pair(first_pair));
-    let identifier = unwrap_or_err_panic!(build_identifier(second_pair));
-    let array_size: Option<usize> = unwrap_or_err_panic!(potential_array_size_from_pair(potential_third_pair));
+/// Build a declaration from the pairs following its type specifier:
+/// identifier ~ array_size? ~ initializer?
+fn declaration_from_declarator_pairs<'a>(
+    pair: pest::iterators::Pair<'a, Rule>,
+    is_const: bool,
+    type_specifier: TypeSpecifier,
+    mut inner_pairs: pest::iterators::Pairs<'a, Rule>,
+) -> Result<Node<'a, Declaration<'a>>, Error<Rule>> {
+    let identifier = build_identifier(inner_pairs.next().unwrap())?;
+
+    let mut array_size = None;
+    let mut initializer = None;
+    for inner_pair in inner_pairs {
+        match inner_pair.as_rule() {
+            Rule::array_size => {
+                let size_expression_pair = inner_pair.into_inner().next().unwrap();
+                array_size = Some(build_expression(size_expression_pair)?);
+            },
+            Rule::initializer => initializer = Some(build_initializer(inner_pair)?),
+            _ => unreachable!(),
+        }
+    }
 
     ok_build_node!(pair, Declaration {
-        type_specifier: declaration_type,
+        is_const,
+        type_specifier,
         identifier,
         array_size,
+        initializer,
     })
 }
 
+pub fn build_declaration(pair: pest::iterators::Pair<Rule>) -> Result<Node<Declaration>, Error<Rule>> {
+    let mut inner_pairs = pair.clone().into_inner();
+    let mut first_pair = inner_pairs.next().unwrap();
+
+    let is_const = first_pair.as_rule() == Rule::const_qualifier;
+    if is_const {
+        first_pair = inner_pairs.next().unwrap();
+    }
+    let declaration_type = get_type_from_pair(first_pair)?;
+
+    declaration_from_declarator_pairs(pair, is_const, declaration_type, inner_pairs)
+}
+
 fn declaration_from_followup(
     pair: pest::iterators::Pair<Rule>,
+    is_const: bool,
     common_type: TypeSpecifier,
 ) -> Result<Node<Declaration>, Error<Rule>> {
-    let mut inner_pairs = pair.clone().into_inner();
-    let first_pair = inner_pairs.next().unwrap();
-    let potential_array_pair = inner_pairs.next();
-    
-    let identifier = unwrap_or_err_panic!(build_identifier(first_pair));
-    let array_size: Option<usize> = unwrap_or_err_panic!(potential_array_size_from_pair(potential_array_pair));
-    
-    ok_build_node!(pair, Declaration {
-        type_specifier: common_type,
-        identifier,
-        array_size,
-    })
+    let inner_pairs = pair.clone().into_inner();
+    declaration_from_declarator_pairs(pair, is_const, common_type, inner_pairs)
+}
+
+/// Check the initializer of a variable declaration (i.e. not a parameter).
+fn check_declaration_initializer(declaration_node: &Node<Declaration>) -> Result<(), Error<Rule>> {
+    // only const variables and arrays can be initialized in their declaration,
+    // and const variables must be, since they can't be assigned afterwards
+    let declaration = &declaration_node.data;
+    let identifier = &declaration.identifier;
+    match (&declaration.initializer, declaration.is_const) {
+        (None, true) => return Err(make_ast_error(
+            declaration_node.sp,
+            format!("🟠 Const variable {} must be initialized", identifier.data.name).as_str()
+        )),
+        (Some(initializer), false) if !declaration.is_array() => return Err(make_ast_error(
+            initializer.sp,
+            format!("🟠 Only const variables and arrays can be initialized in their declaration: {}", identifier.data.name).as_str()
+        )),
+        (Some(initializer), _) => {
+            let is_list = matches!(initializer.data, Initializer::List(_) | Initializer::String(_));
+            if is_list != declaration.is_array() {
+                return Err(make_ast_error(
+                    initializer.sp,
+                    format!(
+                        "🟠 Arrays must be initialized with a list {{...}}, and only arrays: {}",
+                        identifier.data.name
+                    ).as_str()
+                ));
+            }
+            let is_string = matches!(initializer.data, Initializer::String(_));
+            if is_string && declaration.type_specifier != TypeSpecifier::Char {
+                return Err(make_ast_error(
+                    initializer.sp,
+                    format!(
+                        "🟠 Only char arrays can be initialized with a string literal: {}",
+                        identifier.data.name
+                    ).as_str()
+                ));
+            }
+        },
+        (None, false) => {},
+    }
+
+    Ok(())
 }
 
 pub fn build_multi_declaration(pair: pest::iterators::Pair<Rule>) -> Result<Vec<Node<Declaration>>, Error<Rule>> {
     let mut declarations: Vec<Node<Declaration>> = Vec::new();
 
     // multiple declarations are separated by a comma
-    // and we need to get their common type specifier from the first declaration
+    // and we need to get their common type specifier (and const qualifier) from the first declaration
     let mut inner_pairs = pair.clone().into_inner();
     let first_pair = inner_pairs.next().unwrap();
-    let first_declaration = unwrap_or_err_panic!(build_declaration(first_pair));
-    let common_type = first_declaration.data.type_specifier;
+    let first_declaration = build_declaration(first_pair)?;
+    check_declaration_initializer(&first_declaration)?;
+    let common_type = first_declaration.data.type_specifier.clone();
+    let is_const = first_declaration.data.is_const;
     declarations.push(first_declaration);
 
     // iterate over the rest of the declarations
     for inner_pair in inner_pairs {
-        let followup_declaration = unwrap_or_err_panic!(
-            declaration_from_followup(inner_pair, common_type)
-        );
+        let followup_declaration = declaration_from_followup(inner_pair, is_const, common_type.clone())?;
+        check_declaration_initializer(&followup_declaration)?;
         declarations.push(followup_declaration);
     }
 
     Ok(declarations)
-}
\ No newline at end of file
+}
+
+pub fn build_struct_definition(pair: pest::iterators::Pair<Rule>) -> Result<Node<StructDefinition>, Error<Rule>> {
+    let mut inner_pairs = pair.clone().into_inner();
+    let struct_type_pair = inner_pairs.next().unwrap();
+    let name = build_identifier(struct_type_pair.into_inner().next().unwrap())?;
+
+    // struct fields are declared like variables, but can only be scalars or structs
+    let mut fields: Vec<Node<Declaration>> = Vec::new();
+    for multi_declaration_pair in inner_pairs {
+        for field in build_multi_declaration(multi_declaration_pair)? {
+            if field.data.is_const || field.data.is_array() {
+                return Err(make_ast_error(
+                    field.sp,
+                    format!(
+                        "🟠 Struct fields can't be const or arrays: {}.{}", 
+                        name.data.name, field.data.identifier.data.name
+                    ).as_str()
+                ));
+            }
+            fields.push(field);
+        }
+    }
+
+    ok_build_node!(pair, StructDefinition {
+        name,
+        fields,
+    })
+}
+
+fn build_enumerator(pair: pest::iterators::Pair<Rule>) -> Result<Node<Enumerator>, Error<Rule>> {
+    let mut inner_pairs = pair.clone().into_inner();
+    let identifier = build_identifier(inner_pairs.next().unwrap())?;
+    let value = match inner_pairs.next() {
+        Some(expression_pair) => Some(build_expression(expression_pair)?),
+        None => None,
+    };
+
+    ok_build_node!(pair, Enumerator {
+        identifier,
+        value,
+    })
+}
+
+pub fn build_enum_definition(pair: pest::iterators::Pair<Rule>) -> Result<Node<EnumDefinition>, Error<Rule>> {
+    let mut inner_pairs = pair.clone().into_inner();
+    let enum_type_pair = inner_pairs.next().unwrap();
+    let name = build_identifier(enum_type_pair.into_inner().next().unwrap())?;
+
+    let mut enumerators = Vec::new();
+    for enumerator_pair in inner_pairs {
+        enumerators.push(build_enumerator(enumerator_pair)?);
+    }
+
+    ok_build_node!(pair, EnumDefinition {
+        name,
+        enumerators,
+    })
+}
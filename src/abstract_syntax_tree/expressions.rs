@@ -1,12 +1,12 @@
 use pest::error::Error;
 
 use crate::syntax_parsing::Rule;
+use crate::semantic::data_model::get_integer_type_info;
 
 use super::nodes::*;
 use crate::errors::make_ast_error_from_pair;
 
 // exported macros are available in the crate root (global scope)
-use crate::unwrap_or_err_panic;
 use crate::ok_build_node;
 
 // in this situation, pest removes whitespace and everything in between the pairs.
@@ -115,6 +115,77 @@ macro_rules! build_chained_operations {
     }};
 }
 
+/// Parse the digits of an integer literal and check that the resulting value,
+/// once its sign is applied, fits into the given integer type of the current data model.
+/// The sign is needed since `-32768` is a valid 16 bits int literal while `32768` is not.
+fn check_integer_literal_range(
+    pair: pest::iterators::Pair<Rule>,
+    digits: &str,
+    is_negative: bool,
+    integer_type: TypeSpecifier,
+) -> Result<i128, Error<Rule>> {
+    let type_info = get_integer_type_info(&integer_type).unwrap();
+    let sign = if is_negative { "-" } else { "" };
+    let int_value_for_test: i128 = match digits.parse::<i128>() {
+        Ok(int_value) => if is_negative { -int_value } else { int_value },
+        Err(_) => {
+            let message = format!(
+                "🔴 Integer literal {}{} is too big. Max possible value is {}.", 
+                sign,
+                digits, 
+                type_info.max_value()
+            );
+            return Err(make_ast_error_from_pair(pair, &message))
+        },
+    };
+    if int_value_for_test > type_info.max_value() {
+        let message = format!(
+            "🔴 Integer literal {} is too big. Max possible value is {} ({} bits {}).", 
+            int_value_for_test, 
+            type_info.max_value(),
+            type_info.bits,
+            integer_type
+        );
+        return Err(make_ast_error_from_pair(pair, &message))
+    } else if int_value_for_test < type_info.min_value() {
+        let message = format!(
+            "🔴 Integer literal {} is too small. Min possible value is {} ({} bits {}).", 
+            int_value_for_test, 
+            type_info.min_value(),
+            type_info.bits,
+            integer_type
+        );
+        return Err(make_ast_error_from_pair(pair, &message))
+    }
+    Ok(int_value_for_test)
+}
+
+/// Get the type of an integer literal from its optional suffix, like in C.
+/// Unlike C, an unsuffixed literal is always an int (it is not promoted to long when too big).
+fn get_integer_literal_type(potential_suffix_pair: Option<pest::iterators::Pair<Rule>>) -> TypeSpecifier {
+    match potential_suffix_pair {
+        Some(suffix_pair) => match suffix_pair.as_str().to_lowercase().as_str() {
+            "u" => TypeSpecifier::UnsignedInt,
+            "l" => TypeSpecifier::Long,
+            _ => TypeSpecifier::UnsignedLong, // "ul" or "lu"
+        },
+        None => TypeSpecifier::Int,
+    }
+}
+
+// integer_literal = { integer ~ integer_suffix? }
+fn build_integer_literal_value(
+    pair: pest::iterators::Pair<Rule>,
+    integer_literal_pair: pest::iterators::Pair<Rule>,
+    is_negative: bool,
+) -> Result<Value, Error<Rule>> {
+    let mut inner = integer_literal_pair.into_inner();
+    let digits = inner.next().unwrap().as_str();
+    let integer_type = get_integer_literal_type(inner.next());
+    let int_value = check_integer_literal_range(pair, digits, is_negative, integer_type.clone())?;
+    Ok(Value::from_integer(integer_type, int_value).unwrap())
+}
+
 fn build_literal(pair: pest::iterators::Pair<Rule>) -> Result<Node<Expression>, Error<Rule>> {
     let literal = pair.clone().into_inner().next().unwrap();
     let res = match literal.as_rule() {
@@ -147,26 +218,10 @@ fn build_literal(pair: pest::iterators::Pair<Rule>) -> Result<Node<Expression>,
             let real_char = char_trimmed.chars().next().unwrap();
             let real_char_ascii = real_char as u8;
             Expression::Literal(Value::Char(real_char_ascii))},
-        Rule::integer => {
+        Rule::integer_literal => {
             // need to check for potential overflow
-            let int_value_for_test: i64 = literal.as_str().parse().unwrap();
-            if int_value_for_test > std::i16::MAX as i64 {
-                let message = format!(
-                    "🔴 Integer literal {} is too big. Max possible value is {}.", 
-                    int_value_for_test, 
-                    std::i16::MAX
-                );
-                return Err(make_ast_error_from_pair(pair, &message))
-            } else if int_value_for_test < std::i16::MIN as i64 {
-                let message = format!(
-                    "🔴 Integer literal {} is too small. Min possible value is {}.", 
-                    int_value_for_test, 
-                    std::i16::MIN
-                );
-                return Err(make_ast_error_from_pair(pair, &message))
-            }
-            // return correct value as i32
-            Expression::Literal(Value::Int(literal.as_str().parse().unwrap()))
+            let int_value = build_integer_literal_value(pair.clone(), literal, false)?;
+            Expression::Literal(int_value)
         },
         _ => {
             let message = format!("🔴 Unexpected rule in <literal> match tree: {:?}", literal.as_rule());
@@ -176,13 +231,58 @@ fn build_literal(pair: pest::iterators::Pair<Rule>) -> Result<Node<Expression>,
     ok_build_node!(pair, res)
 }
 
-fn build_type_specifier(pair: pest::iterators::Pair<Rule>) -> Result<Node<TypeSpecifier>, Error<Rule>> {
-    let res = match pair.as_str() {
-        "bool" => TypeSpecifier::Bool,
-        "float" => TypeSpecifier::Float,
-        "char" => TypeSpecifier::Char,
-        "int" => TypeSpecifier::Int,
+/// Get the characters of a string literal, without its quotes and with its escape sequences replaced.
+/// Like char values, the characters must be ASCII.
+pub fn build_string_literal(pair: pest::iterators::Pair<Rule>) -> Result<Vec<u8>, Error<Rule>> {
+    let string_literal = pair.as_str();
+    let mut characters = Vec::new();
+    let mut inner_chars = string_literal[1..string_literal.len() - 1].chars();
+    while let Some(current_char) = inner_chars.next() {
+        let real_char = match current_char {
+            '\\' => match inner_chars.next() {
+                Some('n') => '\n',
+                Some('t') => '\t',
+                Some(escaped_char) => escaped_char, // \" and \\
+                None => unreachable!("a string literal can't end with a backslash"),
+            },
+            _ => current_char,
+        };
+        if !real_char.is_ascii() {
+            return Err(make_ast_error_from_pair(
+                pair,
+                format!("🟠 Only ASCII characters are allowed in string literals, got '{}'", real_char).as_str()
+            ));
+        }
+        characters.push(real_char as u8);
+    }
+    Ok(characters)
+}
+
+pub fn build_string_operand(pair: pest::iterators::Pair<Rule>) -> Result<Node<StringOperand>, Error<Rule>> {
+    let inner_pair = pair.clone().into_inner().next().unwrap();
+    let string_operand = match inner_pair.as_rule() {
+        Rule::identifier => StringOperand::Array(build_identifier(inner_pair)?),
+        _ => StringOperand::Literal(build_string_literal(inner_pair)?),
+    };
+    ok_build_node!(pair, string_operand)
+}
+
+fn build_string_function(pair: pest::iterators::Pair<Rule>) -> Result<Node<Expression>, Error<Rule>> {
+    let mut operands = pair.clone().into_inner();
+    let first_operand = build_string_operand(operands.next().unwrap())?;
+    match pair.as_rule() {
+        Rule::strlen_expression => ok_build_node!(pair, Expression::StringLength(first_operand)),
         _ => {
+            let second_operand = build_string_operand(operands.next().unwrap())?;
+            ok_build_node!(pair, Expression::StringCompare(first_operand, second_operand))
+        },
+    }
+}
+
+fn build_type_specifier(pair: pest::iterators::Pair<Rule>) -> Result<Node<TypeSpecifier>, Error<Rule>> {
+    let res = match TypeSpecifier::from_str(pair.as_str()) {
+        Some(type_specifier) => type_specifier,
+        None => {
             let message = format!("🔴 Unexpected <type_specifier>: {}", pair.clone().as_str());
             return Err(make_ast_error_from_pair(pair, &message))
         },
@@ -190,6 +290,19 @@ fn build_type_specifier(pair: pest::iterators::Pair<Rule>) -> Result<Node<TypeSp
     ok_build_node!(pair, res)
 }
 
+// primary = { literal } and literal = { integer_literal }
+fn get_potential_integer_literal_pair(primary_pair: pest::iterators::Pair<Rule>) -> Option<pest::iterators::Pair<Rule>> {
+    let literal_pair = primary_pair.into_inner().next()?;
+    if literal_pair.as_rule() != Rule::literal {
+        return None;
+    }
+    let integer_literal_pair = literal_pair.into_inner().next()?;
+    match integer_literal_pair.as_rule() {
+        Rule::integer_literal => Some(integer_literal_pair),
+        _ => None,
+    }
+}
+
 // factor = { unary_operator? ~ primary }
 fn build_factor(pair: pest::iterators::Pair<Rule>) -> Result<Node<Expression>, Error<Rule>> {
     let mut inner = pair.clone().into_inner();
@@ -202,6 +315,24 @@ fn build_factor(pair: pest::iterators::Pair<Rule>) -> Result<Node<Expression>, E
         Rule::unary_operator => {
             let unary_operator = UnaryOperator::from_str(first_pair.clone().as_str()).unwrap();
             let primary = inner.next().unwrap();
+
+            // A minus sign directly followed by an integer literal is a negative literal.
+            // This is the only way to write the min value of a signed type, since its opposite overflows.
+            // Unsigned literals are not folded: -1u is the negation of 1u, which wraps around like in C.
+            if unary_operator == UnaryOperator::Negation {
+                if let Some(integer_literal_pair) = get_potential_integer_literal_pair(primary.clone()) {
+                    let literal_type = get_integer_literal_type(
+                        integer_literal_pair.clone().into_inner().nth(1)
+                    );
+                    if get_integer_type_info(&literal_type).unwrap().is_signed {
+                        let int_value = build_integer_literal_value(
+                            pair.clone(), integer_literal_pair, true
+                        )?;
+                        return ok_build_node!(pair, Expression::Literal(int_value));
+                    }
+                }
+            }
+
             let primary = build_expression(primary)?;
             let res = Expression::UnaryExpression(
                 UnaryExpression {
@@ -224,14 +355,19 @@ fn build_factor(pair: pest::iterators::Pair<Rule>) -> Result<Node<Expression>, E
 
 pub fn build_get_or_set_value(pair: pest::iterators::Pair<Rule>) -> Result<Node<GetOrSetValue>, Error<Rule>> {
     let mut inner = pair.clone().into_inner();
-    let identifier = unwrap_or_err_panic!(build_identifier(inner.next().unwrap()));
-    let index = match inner.next() {
-        Some(pair) => Some(Box::new(build_expression(pair)?)),
-        None => None,
-    };
+    let identifier = build_identifier(inner.next().unwrap())?;
+    let mut index = None;
+    let mut members = Vec::new();
+    for inner_pair in inner {
+        match inner_pair.as_rule() {
+            Rule::expression => index = Some(Box::new(build_expression(inner_pair)?)),
+            _ => members.push(build_identifier(inner_pair)?),
+        }
+    }
     ok_build_node!(pair, GetOrSetValue {
         identifier,
         index,
+        members,
     })
 }
 
@@ -240,14 +376,45 @@ pub fn build_identifier(pair: pest::iterators::Pair<Rule>) -> Result<Node<Identi
     ok_build_node!(pair, Identifier { name: identifier })
 }
 
+/// Comparisons can't be chained like `a < b < c` or `a == b == c`:
+/// like in C, it would compare the boolean result of `a < b` with `c`,
+/// which is rarely what was meant. Parenthesized comparisons are allowed.
+fn check_no_chained_comparison(pair: &pest::iterators::Pair<Rule>) -> Result<(), Error<Rule>> {
+    let inner_pairs: Vec<pest::iterators::Pair<Rule>> = pair.clone().into_inner().collect();
+    if inner_pairs.len() <= 3 {
+        return Ok(());
+    }
+    // the inner pairs alternate between an operand and an operator
+    let comparisons: Vec<String> = inner_pairs.windows(3).step_by(2)
+        .map(|comparison| format!(
+            "({} {} {})",
+            comparison[0].as_str().trim(), comparison[1].as_str(), comparison[2].as_str().trim()
+        ))
+        .collect();
+    Err(make_ast_error_from_pair(
+        pair.clone(),
+        format!(
+            "🟠 Comparisons can't be chained, since {} would compare the result of the first comparison with the next operand. Did you mean {}?",
+            pair.as_str().trim(), comparisons.join(" && ")
+        ).as_str()
+    ))
+}
+
+fn build_comparison(pair: pest::iterators::Pair<Rule>) -> Result<Node<Expression>, Error<Rule>> {
+    check_no_chained_comparison(&pair)?;
+    match pair.as_rule() {
+        Rule::equality => Ok(build_chained_operations!(pair, BinaryOperator::Equal, BinaryOperator::NotEqual)),
+        _ => Ok(build_chained_operations!(pair, BinaryOperator::Less, BinaryOperator::LessOrEqual, BinaryOperator::Greater, BinaryOperator::GreaterOrEqual)),
+    }
+}
+
 pub fn build_expression(pair: pest::iterators::Pair<Rule>) -> Result<Node<Expression>, Error<Rule>> {
     let rule = pair.as_rule();
     match rule {
         Rule::expression => build_expression(pair.into_inner().next().unwrap()),
         Rule::disjunction => Ok(build_chained_operations!(pair, BinaryOperator::LogicalOr)),
         Rule::conjunction => Ok(build_chained_operations!(pair, BinaryOperator::LogicalAnd)),
-        Rule::equality => Ok(build_chained_operations!(pair, BinaryOperator::Equal, BinaryOperator::NotEqual)),
-        Rule::relation => Ok(build_chained_operations!(pair, BinaryOperator::Less, BinaryOperator::LessOrEqual, BinaryOperator::Greater, BinaryOperator::GreaterOrEqual)),
+        Rule::equality | Rule::relation => build_comparison(pair),
         Rule::addition => Ok(build_chained_operations!(pair, BinaryOperator::Plus, BinaryOperator::Minus)),
         Rule::term => Ok(build_chained_operations!(pair, BinaryOperator::Multiply, BinaryOperator::Divide, BinaryOperator::Modulo)),
         Rule::factor => build_factor(pair),
@@ -256,13 +423,13 @@ pub fn build_expression(pair: pest::iterators::Pair<Rule>) -> Result<Node<Expres
         Rule::literal => build_literal(pair),
         Rule::function_call => {
             let mut inner = pair.clone().into_inner();
-            let identifier = unwrap_or_err_panic!(build_identifier(inner.next().unwrap()));
+            let identifier = build_identifier(inner.next().unwrap())?;
 
             // function call may have 0 or more arguments
             let arguments = {
                 let mut arguments = Vec::new();
                 for argument in inner {
-                    arguments.push(unwrap_or_err_panic!(build_expression(argument)));
+                    arguments.push(build_expression(argument)?);
                 }
                 arguments
             };
@@ -275,8 +442,8 @@ pub fn build_expression(pair: pest::iterators::Pair<Rule>) -> Result<Node<Expres
         },
         Rule::type_cast => {
             let mut inner = pair.clone().into_inner();
-            let type_specifier = unwrap_or_err_panic!(build_type_specifier(inner.next().unwrap())).data;
-            let expression = unwrap_or_err_panic!(build_expression(inner.next().unwrap()));
+            let type_specifier = build_type_specifier(inner.next().unwrap())?.data;
+            let expression = build_expression(inner.next().unwrap())?;
             ok_build_node!(pair, Expression::TypeCast(
                 TypeCast {
                     type_specifier,
@@ -288,6 +455,19 @@ pub fn build_expression(pair: pest::iterators::Pair<Rule>) -> Result<Node<Expres
             let get_or_set_value = build_get_or_set_value(pair.clone())?;
             ok_build_node!(pair, Expression::GetOrSetValue(get_or_set_value.data))
         },
+        Rule::sizeof_expression => {
+            let operand_pair = pair.clone().into_inner().next().unwrap();
+            let operand = match operand_pair.as_rule() {
+                Rule::type_specifier => SizeOfOperand::Type(build_type_specifier(operand_pair)?.data),
+                _ => SizeOfOperand::Variable(build_get_or_set_value(operand_pair)?.data),
+            };
+            ok_build_node!(pair, Expression::SizeOf(operand))
+        },
+        Rule::strlen_expression | Rule::strcmp_expression => build_string_function(pair),
+        Rule::length_expression => {
+            let array_identifier = build_identifier(pair.clone().into_inner().next().unwrap())?;
+            ok_build_node!(pair, Expression::Length(array_identifier))
+        },
         _ => {
             let message = format!("🔴 Unexpected rule in <expression> match tree: {:?}", rule);
             return Err(make_ast_error_from_pair(pair, &message))
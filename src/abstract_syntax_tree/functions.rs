@@ -12,7 +12,6 @@ use crate::abstract_syntax_tree::statements::build_multi_statement;
 use super::nodes::*;
 
 // exported macros are available in the crate root (global scope)
-use crate::unwrap_or_err_panic;
 use crate::ok_build_node;
 
 pub fn build_block(pair: Pair<Rule>) -> Result<Node<Block>, Error<Rule>> {
@@ -22,13 +21,11 @@ pub fn build_block(pair: Pair<Rule>) -> Result<Node<Block>, Error<Rule>> {
     for inner_pair in pair.clone().into_inner() {
         match inner_pair.as_rule() {
             Rule::multi_declaration => {
-                declarations.extend(
-                    unwrap_or_err_panic!(build_multi_declaration(inner_pair))
-                );
+                declarations.extend(build_multi_declaration(inner_pair)?);
             },
             Rule::multi_statement => {
                 statements.extend(
-                    unwrap_or_err_panic!(build_multi_statement(inner_pair))
+                    build_multi_statement(inner_pair)?
                 );
             },
             Rule::function_return => {
@@ -44,7 +41,7 @@ pub fn build_block(pair: Pair<Rule>) -> Result<Node<Block>, Error<Rule>> {
                         );
                     },
                 };
-                let function_return = unwrap_or_err_panic!(build_expression(return_expr_pair));
+                let function_return = build_expression(return_expr_pair)?;
                 return ok_build_node!(pair, Block {
                     declarations,
                     statements,
@@ -72,13 +69,13 @@ pub fn build_function_definition(pair: Pair<Rule>) -> Result<Node<Function>, Err
     let third_pair = inner_pairs.next().unwrap();
     let potential_fourth_pair = inner_pairs.next();
 
-    let type_specifier = unwrap_or_err_panic!(get_type_from_pair(first_pair));
-    let identifier = unwrap_or_err_panic!(build_identifier(second_pair));
+    let type_specifier = get_type_from_pair(first_pair)?;
+    let identifier = build_identifier(second_pair)?;
     
     if let Some(actual_fourth_pair) = potential_fourth_pair {
         // if there is as fourth pair, then parse the parameters
-        let parameters = unwrap_or_err_panic!(build_parameter_list(third_pair));
-        let body = unwrap_or_err_panic!(build_block(actual_fourth_pair));
+        let parameters = build_parameter_list(third_pair)?;
+        let body = build_block(actual_fourth_pair)?;
         return ok_build_node!(pair, 
             Function {
                 name: identifier,
@@ -89,7 +86,7 @@ pub fn build_function_definition(pair: Pair<Rule>) -> Result<Node<Function>, Err
         );
     } else {
         // no parameters, so the third pair is the body
-        let body = unwrap_or_err_panic!(build_block(third_pair));
+        let body = build_block(third_pair)?;
         return ok_build_node!(pair,
             Function {
                 name: identifier,
@@ -101,6 +98,28 @@ pub fn build_function_definition(pair: Pair<Rule>) -> Result<Node<Function>, Err
     }
 }
 
+pub fn build_function_prototype(pair: Pair<Rule>) -> Result<Node<FunctionPrototype>, Error<Rule>> {
+    let mut inner_pairs = pair.clone().into_inner();
+    let first_pair = inner_pairs.next().unwrap();
+    let second_pair = inner_pairs.next().unwrap();
+    let potential_third_pair = inner_pairs.next();
+
+    let type_specifier = get_type_from_pair(first_pair)?;
+    let identifier = build_identifier(second_pair)?;
+    let parameters = match potential_third_pair {
+        Some(third_pair) => Some(build_parameter_list(third_pair)?),
+        None => None,
+    };
+
+    ok_build_node!(pair,
+        FunctionPrototype {
+            name: identifier,
+            return_type: type_specifier,
+            params: parameters,
+        }
+    )
+}
+
 pub fn build_entry_point_function(pair: Pair<Rule>) -> Result<Node<Function>, Error<Rule>> {
     let mut inner_pairs = pair.clone().into_inner();
     //log::info!("inner_pairs len: {:?}", inner_pairs.clone().count());
@@ -111,7 +130,7 @@ pub fn build_entry_point_function(pair: Pair<Rule>) -> Result<Node<Function>, Er
         sp: pair.as_span(),
         data: Identifier { name: String::from("main") },
     };
-    let body = unwrap_or_err_panic!(build_block(first_pair));
+    let body = build_block(first_pair)?;
 
     ok_build_node!(pair, 
         Function {
@@ -1,11 +1,14 @@
+use indexmap::IndexMap;
+
 use pest::iterators::Pair;
 use pest::error::Error;
 
-use crate::abstract_syntax_tree::functions::{build_entry_point_function, build_function_definition};
-use crate::{ok_build_node, unwrap_or_err_panic};
+use crate::abstract_syntax_tree::declarations::{build_struct_definition, build_enum_definition};
+use crate::abstract_syntax_tree::functions::{build_entry_point_function, build_function_definition, build_function_prototype};
+use crate::ok_build_node;
 use crate::syntax_parsing::Rule;
 
-use crate::errors::make_ast_error_from_pair;
+use crate::errors::{make_ast_error, make_ast_error_from_pair};
 use nodes::*;
 
 pub mod nodes;
@@ -23,53 +26,55 @@ pub fn build_translation_unit(pair: Pair<Rule>) -> Result<AST, Error<Rule>> {
     //     print!("inner pair: {}\n", inner_pair.as_str());
     // }
 
-    let mut successive_pair_non_empty = Vec::new();
-
-    // get all non empty pairs
+    // build struct and enum definitions, prototypes and functions
+    // NOTE: the main function can be anywhere, but must be defined exactly once
+    let mut structs = Vec::new();
+    let mut enums = Vec::new();
+    let mut prototypes = Vec::new();
+    let mut functions = IndexMap::new();
+    let mut potential_entry_point_function = None;
     while let Some(current_inner_pair) = inner_pairs.next() {
-        // check that the pair is not empty
-        if !current_inner_pair.as_str().is_empty() {
-            successive_pair_non_empty.push(current_inner_pair);
-        }
-    }
-
-    // the last non_empty added pair is the entry point function
-    let last_valid_pair = {
-        let last_pair = successive_pair_non_empty.pop();
-        match last_pair {
-            Some(pair) => {
-                match pair.as_rule() {
-                    Rule::entry_point_function_definition => pair,
-                    _ => return Err(make_ast_error_from_pair(
-                        pair, 
-                        "Last function must be the entry point function."
-                    )),
+        match current_inner_pair.as_rule() {
+            Rule::struct_definition => structs.push(build_struct_definition(current_inner_pair)?),
+            Rule::enum_definition => enums.push(build_enum_definition(current_inner_pair)?),
+            Rule::function_prototype => prototypes.push(build_function_prototype(current_inner_pair)?),
+            Rule::function_definition => {
+                let function = build_function_definition(current_inner_pair)?;
+                if functions.contains_key(&function.data.name.data) {
+                    return Err(make_ast_error(
+                        function.data.name.sp,
+                        format!("🟠 Function {} is already defined.", function.data.name.data.name).as_str()
+                    ));
                 }
-            }
-            None => return Err(
-                make_ast_error_from_pair(pair, "Empty program not allowed. Missing main function.")
-            ),
-        }
-    };
-    let entry_point_function = unwrap_or_err_panic!(build_entry_point_function(last_valid_pair));
-
-    // build other functions
-    let functions = {
-        if successive_pair_non_empty.is_empty() {
-            None
-        } else {
-            let mut tmp_functions = Vec::new();
-            for valid_pair in successive_pair_non_empty {
-                tmp_functions.push(
-                    unwrap_or_err_panic!(build_function_definition(valid_pair))
-                );
-            }
-            Some(tmp_functions)
+                functions.insert(function.data.name.data.clone(), function);
+            },
+            Rule::entry_point_function_definition => {
+                if potential_entry_point_function.is_some() {
+                    return Err(make_ast_error_from_pair(
+                        current_inner_pair, 
+                        "🟠 Function main is already defined."
+                    ));
+                }
+                potential_entry_point_function = Some(build_entry_point_function(current_inner_pair)?);
+            },
+            _ => {}, // EOI
         }
+    }
+    let entry_point_function = match potential_entry_point_function {
+        Some(entry_point_function) => entry_point_function,
+        None => return Err(
+            make_ast_error_from_pair(pair, "Missing main function.")
+        ),
     };
+    let structs = if structs.is_empty() { None } else { Some(structs) };
+    let enums = if enums.is_empty() { None } else { Some(enums) };
+    let prototypes = if prototypes.is_empty() { None } else { Some(prototypes) };
     
     ok_build_node!(pair,
         TranslationUnit {
+            structs,
+            enums,
+            prototypes,
             functions,
             main_function: entry_point_function 
         }
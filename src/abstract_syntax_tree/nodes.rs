@@ -1,3 +1,4 @@
+use indexmap::IndexMap;
 use pest::Span;
 use std::fmt;
 
@@ -27,7 +28,10 @@ pub type AST<'a> = Node<'a, TranslationUnit<'a>>;
 // AST nodes
 #[derive(Debug, PartialEq)]
 pub struct TranslationUnit<'a> {
-    pub functions: Option<Vec<Node<'a, Function<'a>>>>,
+    pub structs: Option<Vec<Node<'a, StructDefinition<'a>>>>,
+    pub enums: Option<Vec<Node<'a, EnumDefinition<'a>>>>,
+    pub prototypes: Option<Vec<Node<'a, FunctionPrototype<'a>>>>,
+    pub functions: IndexMap<Identifier, Node<'a, Function<'a>>>, // function table, indexed by name in source order (main excluded)
     pub main_function: Node<'a, Function<'a>>,
 }
 
@@ -35,31 +39,23 @@ impl<'a> TranslationUnit<'a> {
     pub fn get_function_node(
         &self, function_identifier: Node<'a, Identifier>
     ) -> Result<&Node<'a, Function<'a>>, SemanticError> {
-        let function_name = function_identifier.data.name.clone();
-
         // check if the function is the main function
-        if self.main_function.data.name.data.name == function_name {
+        if self.main_function.data.name.data == function_identifier.data {
             return Ok(&self.main_function);
-        } else {
-            // check if the function is in the list of functions
-            if let Some(functions) = &self.functions {
-                for function in functions {
-                    if function.data.name.data.name == function_name {
-                        return Ok(&function);
-                    }
-                }
-            }
+        }
 
-            // the function is not in the list of functions
-            return Err(SemanticError::UndeclaredFunction(
+        // look for the function in the function table
+        match self.functions.get(&function_identifier.data) {
+            Some(function) => Ok(function),
+            None => Err(SemanticError::UndeclaredFunction(
                 UndeclaredFunctionError::init(
                     function_identifier.sp,
                     format!(
                         "Function {:?} is not in the list of functions of this program.", 
-                        function_name
+                        function_identifier.data.name
                     ).as_str(),
                 )
-            ));
+            )),
         }
     }
 }
@@ -72,46 +68,153 @@ pub struct Function<'a> {
     pub body: Node<'a, Block<'a>>,
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+impl Function<'_> {
+    /// Get the signature of the function, like `int square(int x)`.
+    pub fn get_signature(&self) -> String {
+        let params: Vec<String> = self.params.iter().flatten()
+            .map(|param| param.data.describe())
+            .collect();
+        format!("{} {}({})", self.return_type, self.name.data.name, params.join(", "))
+    }
+}
+
+/// Forward declaration of a function, like `int f(int a);`.
+/// Its signature must match the one of the function definition.
+#[derive(Debug, PartialEq)]
+pub struct FunctionPrototype<'a> {
+    pub name: Node<'a, Identifier>,
+    pub return_type: TypeSpecifier,
+    pub params: Option<Vec<Node<'a, Declaration<'a>>>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct StructDefinition<'a> {
+    pub name: Node<'a, Identifier>,
+    pub fields: Vec<Node<'a, Declaration<'a>>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct EnumDefinition<'a> {
+    pub name: Node<'a, Identifier>,
+    pub enumerators: Vec<Node<'a, Enumerator<'a>>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Enumerator<'a> {
+    pub identifier: Node<'a, Identifier>,
+    pub value: Option<Node<'a, Expression<'a>>>, // constant expression, previous value + 1 by default
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum TypeSpecifier {
     Bool,
     Float,
+    Double,
     Char,
+    Short,
+    UnsignedShort,
     Int,
+    UnsignedInt,
+    Long,
+    UnsignedLong,
+    Struct(Identifier), // user-defined "struct name"
+    Enum(Identifier), // user-defined "enum name", with int values
 }
 
 impl TypeSpecifier {
     pub fn from_str(s: &str) -> Option<Self> {
-        match s {
+        // multi-word type specifiers can be separated by any whitespace
+        let words: Vec<&str> = s.split_whitespace().collect();
+        match words.as_slice() {
+            ["struct", struct_name] => return Some(
+                TypeSpecifier::Struct(Identifier { name: struct_name.to_string() })
+            ),
+            ["enum", enum_name] => return Some(
+                TypeSpecifier::Enum(Identifier { name: enum_name.to_string() })
+            ),
+            _ => {},
+        }
+        match words.join(" ").as_str() {
             "bool" => Some(TypeSpecifier::Bool),
             "float" => Some(TypeSpecifier::Float),
+            "double" => Some(TypeSpecifier::Double),
             "char" => Some(TypeSpecifier::Char),
+            "short" => Some(TypeSpecifier::Short),
+            "unsigned short" => Some(TypeSpecifier::UnsignedShort),
             "int" => Some(TypeSpecifier::Int),
+            "unsigned" | "unsigned int" => Some(TypeSpecifier::UnsignedInt),
+            "long" => Some(TypeSpecifier::Long),
+            "unsigned long" => Some(TypeSpecifier::UnsignedLong),
             _ => None,
         }
     }
 
-    pub fn as_str(&self) -> &'static str {
+    pub fn is_floating(&self) -> bool {
+        matches!(self, TypeSpecifier::Float | TypeSpecifier::Double)
+    }
+}
+
+impl fmt::Display for TypeSpecifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            TypeSpecifier::Bool => "bool",
-            TypeSpecifier::Float => "float",
-            TypeSpecifier::Char => "char",
-            TypeSpecifier::Int => "int",
+            TypeSpecifier::Bool => write!(f, "bool"),
+            TypeSpecifier::Float => write!(f, "float"),
+            TypeSpecifier::Double => write!(f, "double"),
+            TypeSpecifier::Char => write!(f, "char"),
+            TypeSpecifier::Short => write!(f, "short"),
+            TypeSpecifier::UnsignedShort => write!(f, "unsigned short"),
+            TypeSpecifier::Int => write!(f, "int"),
+            TypeSpecifier::UnsignedInt => write!(f, "unsigned int"),
+            TypeSpecifier::Long => write!(f, "long"),
+            TypeSpecifier::UnsignedLong => write!(f, "unsigned long"),
+            TypeSpecifier::Struct(struct_name) => write!(f, "struct {}", struct_name.name),
+            TypeSpecifier::Enum(enum_name) => write!(f, "enum {}", enum_name.name),
         }
     }
 }
 
 #[derive(Debug, PartialEq)]
 pub struct Declaration<'a> {
+    pub is_const: bool,
     pub type_specifier: TypeSpecifier,
     pub identifier: Node<'a, Identifier>,
-    pub array_size: Option<usize>,  // For "[" ~ integer ~ "]" in the grammar
+    pub array_size: Option<Node<'a, Expression<'a>>>,  // For "[" ~ expression ~ "]" in the grammar, a constant expression
+    pub initializer: Option<Node<'a, Initializer<'a>>>,
 }
 
 impl Declaration<'_> {
     pub fn is_array(&self) -> bool {
         self.array_size.is_some()
     }
+
+    /// Describe the declaration as written, without its initializer, like `const int a[SIZE]`.
+    pub fn describe(&self) -> String {
+        let const_prefix = if self.is_const { "const " } else { "" };
+        let array_suffix = self.array_size.as_ref()
+            .map_or(String::new(), |array_size| format!("[{}]", array_size.sp.as_str()));
+        format!("{}{} {}{}", const_prefix, self.type_specifier, self.identifier.data.name, array_suffix)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Initializer<'a> {
+    Expression(Node<'a, Expression<'a>>),
+    List(Vec<Node<'a, Expression<'a>>>), // for arrays
+    String(Vec<u8>), // for char arrays, without its terminating NUL
+}
+
+impl Initializer<'_> {
+    /// Check if the initializer is the `{0}` shorthand, setting all the elements
+    /// of an array to zero, including the fields of arrays of structs.
+    pub fn is_zero_list(&self) -> bool {
+        match self {
+            Initializer::List(elements) => matches!(
+                elements.as_slice(),
+                [Node { data: Expression::Literal(Value::Int(0)), .. }]
+            ),
+            Initializer::Expression(_) | Initializer::String(_) => false,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -128,6 +231,9 @@ pub enum Statement<'a> {
     Assignment(AssignmentStatement<'a>),
     If(IfStatement<'a>),
     While(WhileStatement<'a>),
+    Switch(SwitchStatement<'a>),
+    Assert(AssertStatement<'a>),
+    StringCopy(StringCopyStatement<'a>),
 }
 
 #[derive(Debug, PartialEq)]
@@ -149,6 +255,33 @@ pub struct WhileStatement<'a> {
     pub body: Vec<Node<'a, Statement<'a>>>,
 }
 
+// NOTE: there is no fall through between cases, only the body of the matching case is interpreted
+#[derive(Debug, PartialEq)]
+pub struct SwitchStatement<'a> {
+    pub expression: Node<'a, Expression<'a>>,
+    pub cases: Vec<Node<'a, SwitchCase<'a>>>,
+    pub default_body: Option<Vec<Node<'a, Statement<'a>>>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SwitchCase<'a> {
+    pub label: Node<'a, Expression<'a>>, // integer constant expression
+    pub body: Vec<Node<'a, Statement<'a>>>,
+}
+
+// NOTE: a failed assertion stops the interpretation
+#[derive(Debug, PartialEq)]
+pub struct AssertStatement<'a> {
+    pub condition: Node<'a, Expression<'a>>,
+}
+
+// NOTE: `strcpy(destination, source);` is a statement, since there are no expression statements
+#[derive(Debug, PartialEq)]
+pub struct StringCopyStatement<'a> {
+    pub destination: Node<'a, Identifier>, // a char array
+    pub source: Node<'a, StringOperand<'a>>,
+}
+
 
 
 
@@ -163,6 +296,10 @@ pub enum Expression<'a> {
     FunctionCall(FunctionCall<'a>),
     TypeCast(TypeCast<'a>),
     GetOrSetValue(GetOrSetValue<'a>),
+    SizeOf(SizeOfOperand<'a>),
+    Length(Node<'a, Identifier>), // len(array)
+    StringLength(Node<'a, StringOperand<'a>>), // strlen(s)
+    StringCompare(Node<'a, StringOperand<'a>>, Node<'a, StringOperand<'a>>), // strcmp(s1, s2)
 }
 
 #[derive(Debug, Eq, Hash, PartialEq, Clone)]
@@ -174,6 +311,7 @@ pub struct Identifier {
 pub struct GetOrSetValue<'a> {
     pub identifier: Node<'a, Identifier>,
     pub index: Option<Box<Node<'a, Expression<'a>>>>,
+    pub members: Vec<Node<'a, Identifier>>, // struct member accesses, like in "a[0].x.y"
 }
 
 #[derive(Debug, PartialEq)]
@@ -201,36 +339,190 @@ pub struct TypeCast<'a> {
     pub expression: Box<Node<'a, Expression<'a>>>,
 }
 
+// NOTE: like in C, the size only depends on the type of the operand, which is never evaluated
+#[derive(Debug, PartialEq)]
+pub enum SizeOfOperand<'a> {
+    Type(TypeSpecifier),
+    Variable(GetOrSetValue<'a>),
+}
+
+// NOTE: like in C, strings are NUL-terminated char arrays
+#[derive(Debug, PartialEq)]
+pub enum StringOperand<'a> {
+    Array(Node<'a, Identifier>),
+    Literal(Vec<u8>), // without its terminating NUL
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Value {
-    Int(i16),
+    Int(i64), // checked against the int width of the current data model
+    UnsignedInt(u64), // same width as int
+    Short(i16),
+    UnsignedShort(u16),
+    Long(i64), // checked against the long width of the current data model
+    UnsignedLong(u64), // same width as long
     Float(f32),
+    Double(f64),
     Char(u8),
     Bool(bool),
+    Struct(StructValue),
+    Enum(EnumValue),
 }
 
 impl Value {
     pub fn as_type_specifier(&self) -> TypeSpecifier {
         match self {
             Value::Int(_) => TypeSpecifier::Int,
+            Value::UnsignedInt(_) => TypeSpecifier::UnsignedInt,
+            Value::Short(_) => TypeSpecifier::Short,
+            Value::UnsignedShort(_) => TypeSpecifier::UnsignedShort,
+            Value::Long(_) => TypeSpecifier::Long,
+            Value::UnsignedLong(_) => TypeSpecifier::UnsignedLong,
             Value::Float(_) => TypeSpecifier::Float,
+            Value::Double(_) => TypeSpecifier::Double,
             Value::Char(_) => TypeSpecifier::Char,
             Value::Bool(_) => TypeSpecifier::Bool,
+            Value::Struct(struct_value) => TypeSpecifier::Struct(struct_value.type_name.clone()),
+            Value::Enum(enum_value) => TypeSpecifier::Enum(enum_value.type_name.clone()),
+        }
+    }
+
+    /// Get the value of any integer type (char included) as an i128,
+    /// which can hold the values of all of them.
+    pub fn as_integer(&self) -> Option<i128> {
+        match self {
+            Value::Int(int) => Some(*int as i128),
+            Value::UnsignedInt(int) => Some(*int as i128),
+            Value::Short(int) => Some(*int as i128),
+            Value::UnsignedShort(int) => Some(*int as i128),
+            Value::Long(int) => Some(*int as i128),
+            Value::UnsignedLong(int) => Some(*int as i128),
+            Value::Char(character) => Some(*character as i128),
+            Value::Enum(enum_value) => Some(enum_value.value as i128),
+            Value::Float(_) | Value::Double(_) | Value::Bool(_) | Value::Struct(_) => None,
+        }
+    }
+
+    /// Build a value of the given integer type.
+    /// The value must already be in the bounds of the type.
+    pub fn from_integer(type_specifier: TypeSpecifier, value: i128) -> Option<Value> {
+        match type_specifier {
+            TypeSpecifier::Int => Some(Value::Int(value as i64)),
+            TypeSpecifier::UnsignedInt => Some(Value::UnsignedInt(value as u64)),
+            TypeSpecifier::Short => Some(Value::Short(value as i16)),
+            TypeSpecifier::UnsignedShort => Some(Value::UnsignedShort(value as u16)),
+            TypeSpecifier::Long => Some(Value::Long(value as i64)),
+            TypeSpecifier::UnsignedLong => Some(Value::UnsignedLong(value as u64)),
+            TypeSpecifier::Char => Some(Value::Char(value as u8)),
+            TypeSpecifier::Bool | TypeSpecifier::Float | TypeSpecifier::Double 
+                | TypeSpecifier::Struct(_) | TypeSpecifier::Enum(_) => None,
+        }
+    }
+
+    /// Get the zero value of a type, like the elements of an array
+    /// that are not given by its initializer list.
+    /// Struct types have no zero value on their own, see `StructValue::set_zero_fields`.
+    pub fn zero(type_specifier: &TypeSpecifier) -> Option<Value> {
+        match type_specifier {
+            TypeSpecifier::Bool => Some(Value::Bool(false)),
+            TypeSpecifier::Float => Some(Value::Float(0.0)),
+            TypeSpecifier::Double => Some(Value::Double(0.0)),
+            TypeSpecifier::Enum(enum_name) => Some(Value::Enum(EnumValue {
+                type_name: enum_name.clone(),
+                value: 0,
+            })),
+            integer_type => Value::from_integer(integer_type.clone(), 0),
         }
     }
 }
 
+/// Value of a struct: each field keeps its own type and value,
+/// so that fields are converted, checked for overflow and checked for assignment individually.
+#[derive(Debug, PartialEq, Clone)]
+pub struct StructValue {
+    pub type_name: Identifier,
+    pub fields: Vec<StructField>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct StructField {
+    pub id: Identifier,
+    pub type_specifier: TypeSpecifier,
+    pub value: Option<Value>, // None until the field is assigned
+}
+
+impl StructValue {
+    pub fn get_field(&self, field_id: &Identifier) -> Option<&StructField> {
+        self.fields.iter().find(|field| field.id == *field_id)
+    }
+
+    pub fn get_mut_field(&mut self, field_id: &Identifier) -> Option<&mut StructField> {
+        self.fields.iter_mut().find(|field| field.id == *field_id)
+    }
+
+    /// Set all the fields to zero, including the fields of nested structs.
+    pub fn set_zero_fields(&mut self) {
+        for field in &mut self.fields {
+            match &mut field.value {
+                Some(Value::Struct(nested_struct_value)) => nested_struct_value.set_zero_fields(),
+                _ => field.value = Value::zero(&field.type_specifier),
+            }
+        }
+    }
+
+    /// Get the path of the first field that has not been assigned a value,
+    /// looking into nested structs (e.g. "position.x").
+    pub fn get_first_unassigned_field(&self) -> Option<String> {
+        for field in &self.fields {
+            match &field.value {
+                None => return Some(field.id.name.clone()),
+                Some(Value::Struct(nested_struct_value)) => {
+                    if let Some(nested_path) = nested_struct_value.get_first_unassigned_field() {
+                        return Some(format!("{}.{}", field.id.name, nested_path));
+                    }
+                },
+                Some(_) => {},
+            }
+        }
+        None
+    }
+}
+
+/// Value of an enum: an int, tagged with its enum type
+/// so that values of different enum types are not mixed up.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EnumValue {
+    pub type_name: Identifier,
+    pub value: i64,
+}
+
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Value::Int(i) => write!(f, "{} (Int)", i),
+            Value::UnsignedInt(i) => write!(f, "{} (UnsignedInt)", i),
+            Value::Short(i) => write!(f, "{} (Short)", i),
+            Value::UnsignedShort(i) => write!(f, "{} (UnsignedShort)", i),
+            Value::Long(i) => write!(f, "{} (Long)", i),
+            Value::UnsignedLong(i) => write!(f, "{} (UnsignedLong)", i),
             Value::Float(fl) => write!(f, "{} (Float)", fl),
+            Value::Double(fl) => write!(f, "{} (Double)", fl),
             Value::Char(c) => write!(
                 f, "{} (Char)", 
                 // convert u8 into ASCII char
                 char::from(*c)
             ),
             Value::Bool(b) => write!(f, "{} (Bool)", b),
+            Value::Struct(struct_value) => {
+                let fields: Vec<String> = struct_value.fields.iter()
+                    .map(|field| match &field.value {
+                        Some(value) => format!("{}: {}", field.id.name, value),
+                        None => format!("{}: ?", field.id.name),
+                    })
+                    .collect();
+                write!(f, "{{{}}} (struct {})", fields.join(", "), struct_value.type_name.name)
+            },
+            Value::Enum(enum_value) => write!(f, "{} (enum {})", enum_value.value, enum_value.type_name.name),
         }
     }
 }
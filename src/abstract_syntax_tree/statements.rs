@@ -3,12 +3,12 @@ use pest::error::Error;
 use crate::syntax_parsing::Rule;
 use crate::abstract_syntax_tree::expressions::build_expression;
 use crate::abstract_syntax_tree::expressions::build_get_or_set_value;
+use crate::abstract_syntax_tree::expressions::{build_identifier, build_string_operand};
 
 use super::nodes::*;
 use crate::errors::make_ast_error_from_pair;
 
 // exported macros are available in the crate root (global scope)
-use crate::unwrap_or_err_panic;
 use crate::ok_build_node;
 
 fn build_assignment_statement(pair: pest::iterators::Pair<Rule>) -> Result<Node<Statement>, Error<Rule>> {
@@ -16,8 +16,8 @@ fn build_assignment_statement(pair: pest::iterators::Pair<Rule>) -> Result<Node<
     let first_pair = inner_pairs.next().unwrap();
     let second_pair = inner_pairs.next().unwrap();
 
-    let set_value_node = unwrap_or_err_panic!(build_get_or_set_value(first_pair));
-    let expression_node = unwrap_or_err_panic!(build_expression(second_pair));
+    let set_value_node = build_get_or_set_value(first_pair)?;
+    let expression_node = build_expression(second_pair)?;
 
     ok_build_node!(pair, Statement::Assignment(
         AssignmentStatement {
@@ -37,7 +37,7 @@ pub fn build_multi_statement(pair: pest::iterators::Pair<Rule>) -> Result<Vec<No
             continue;
         }
 
-        let statement_node = unwrap_or_err_panic!(build_statement(inner_pair));
+        let statement_node = build_statement(inner_pair)?;
         statements.push(statement_node);
     }
     Ok(statements)
@@ -49,11 +49,11 @@ fn build_if_else_statement(pair: pest::iterators::Pair<Rule>) -> Result<Node<Sta
     let second_pair = inner_pairs.next().unwrap();
     let potential_third_pair = inner_pairs.next();
 
-    let condition_expression = unwrap_or_err_panic!(build_expression(first_pair));
-    let if_body_statements = unwrap_or_err_panic!(build_multi_statement(second_pair));
+    let condition_expression = build_expression(first_pair)?;
+    let if_body_statements = build_multi_statement(second_pair)?;
     let else_body_statements = match potential_third_pair {
         Some(third_pair) => {
-            Some(unwrap_or_err_panic!(build_multi_statement(third_pair)))
+            Some(build_multi_statement(third_pair)?)
         },
         None => None,
     };
@@ -72,8 +72,8 @@ fn build_while_statement(pair: pest::iterators::Pair<Rule>) -> Result<Node<State
     let first_pair = inner_pairs.next().unwrap();
     let second_pair = inner_pairs.next().unwrap();
 
-    let condition_expression = unwrap_or_err_panic!(build_expression(first_pair));
-    let body_statements = unwrap_or_err_panic!(build_multi_statement(second_pair));
+    let condition_expression = build_expression(first_pair)?;
+    let body_statements = build_multi_statement(second_pair)?;
 
     ok_build_node!(pair, Statement::While(
         WhileStatement {
@@ -83,6 +83,74 @@ fn build_while_statement(pair: pest::iterators::Pair<Rule>) -> Result<Node<State
     ))
 }
 
+fn build_switch_case(pair: pest::iterators::Pair<Rule>) -> Result<Node<SwitchCase>, Error<Rule>> {
+    let mut inner_pairs = pair.clone().into_inner();
+    let label_pair = inner_pairs.next().unwrap();
+    let body_pair = inner_pairs.next().unwrap();
+
+    let label_expression = build_expression(label_pair)?;
+    let body_statements = build_multi_statement(body_pair)?;
+
+    ok_build_node!(pair, SwitchCase {
+        label: label_expression,
+        body: body_statements,
+    })
+}
+
+fn build_switch_statement(pair: pest::iterators::Pair<Rule>) -> Result<Node<Statement>, Error<Rule>> {
+    let mut inner_pairs = pair.clone().into_inner();
+    let expression_pair = inner_pairs.next().unwrap();
+    let switch_expression = build_expression(expression_pair)?;
+
+    let mut cases = Vec::new();
+    let mut default_body = None;
+    for inner_pair in inner_pairs {
+        match inner_pair.as_rule() {
+            Rule::switch_case => cases.push(build_switch_case(inner_pair)?),
+            Rule::default_case => {
+                let body_pair = inner_pair.into_inner().next().unwrap();
+                default_body = Some(build_multi_statement(body_pair)?);
+            },
+            _ => return Err(make_ast_error_from_pair(
+                inner_pair.clone(), 
+                format!("🔴 Unexpected rule inside <switch_statement>: {:?}", inner_pair.as_rule()).as_str()
+            )),
+        }
+    }
+
+    ok_build_node!(pair, Statement::Switch(
+        SwitchStatement {
+            expression: switch_expression,
+            cases,
+            default_body,
+        }
+    ))
+}
+
+fn build_assert_statement(pair: pest::iterators::Pair<Rule>) -> Result<Node<Statement>, Error<Rule>> {
+    let condition_pair = pair.clone().into_inner().next().unwrap();
+    let condition_expression = build_expression(condition_pair)?;
+
+    ok_build_node!(pair, Statement::Assert(
+        AssertStatement {
+            condition: condition_expression,
+        }
+    ))
+}
+
+fn build_strcpy_statement(pair: pest::iterators::Pair<Rule>) -> Result<Node<Statement>, Error<Rule>> {
+    let mut inner_pairs = pair.clone().into_inner();
+    let destination = build_identifier(inner_pairs.next().unwrap())?;
+    let source = build_string_operand(inner_pairs.next().unwrap())?;
+
+    ok_build_node!(pair, Statement::StringCopy(
+        StringCopyStatement {
+            destination,
+            source,
+        }
+    ))
+}
+
 pub fn build_statement(pair: pest::iterators::Pair<Rule>) -> Result<Node<Statement>, Error<Rule>> {
     match pair.as_rule() {
         Rule::statement => {
@@ -95,6 +163,9 @@ pub fn build_statement(pair: pest::iterators::Pair<Rule>) -> Result<Node<Stateme
         Rule::assignment_statement => build_assignment_statement(pair),
         Rule::if_else_statement => build_if_else_statement(pair),
         Rule::while_statement => build_while_statement(pair),
+        Rule::switch_statement => build_switch_statement(pair),
+        Rule::assert_statement => build_assert_statement(pair),
+        Rule::strcpy_statement => build_strcpy_statement(pair),
         _ => Err(make_ast_error_from_pair(
             pair.clone(), 
             format!("🔴 Unexpected rule inside <statement>: {:?}", pair.clone().as_rule()).as_str()
@@ -0,0 +1,306 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::abstract_syntax_tree::nodes::{
+    AST, Node, Identifier, Expression, Statement, Function, Value, TypeSpecifier, BinaryOperator
+};
+use crate::interpretation::hooks::InterpretationHook;
+use crate::preprocessing::PreprocessedSource;
+use crate::semantic::errors::SemanticError;
+use crate::semantic::type_casts::convert_to_type;
+use crate::symbol_table::structs::SymbolTable;
+
+/// A condition with two branches: the condition of an `if` or a `while`, or an operand of `&&` or `||`.
+#[derive(Debug, Default, PartialEq)]
+pub struct BranchBlock {
+    pub line: usize,
+    pub evaluations: u64,
+    pub true_count: u64,
+    pub false_count: u64,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct FunctionCoverage {
+    pub line: usize,
+    pub calls: u64,
+}
+
+/// The coverage of a source file. The branch blocks are indexed by the range of their condition in the file.
+#[derive(Debug, Default, PartialEq)]
+pub struct FileCoverage {
+    pub lines: BTreeMap<usize, u64>,
+    pub functions: BTreeMap<String, FunctionCoverage>,
+    pub branches: BTreeMap<(usize, usize), BranchBlock>,
+}
+
+/// The line, function and branch coverage of the interpreted programs, merged by source file,
+/// so that the files included by several programs add up.
+#[derive(Debug, Default, PartialEq)]
+pub struct Coverage {
+    pub files: BTreeMap<PathBuf, FileCoverage>,
+}
+
+impl Coverage {
+    /// Write the coverage in the LCOV tracefile format, read by `genhtml` and the coverage viewers of editors.
+    /// A branch whose condition was never evaluated is written as not executed (`-`).
+    pub fn write_lcov(&self, output: &mut dyn Write) -> std::io::Result<()> {
+        for (path, file_coverage) in &self.files {
+            writeln!(output, "TN:")?;
+            writeln!(output, "SF:{}", path.display())?;
+
+            let mut functions: Vec<(&String, &FunctionCoverage)> = file_coverage.functions.iter().collect();
+            functions.sort_by_key(|(name, function_coverage)| (function_coverage.line, *name));
+            for (name, function_coverage) in &functions {
+                writeln!(output, "FN:{},{}", function_coverage.line, name)?;
+            }
+            for (name, function_coverage) in &functions {
+                writeln!(output, "FNDA:{},{}", function_coverage.calls, name)?;
+            }
+            writeln!(output, "FNF:{}", functions.len())?;
+            writeln!(output, "FNH:{}", functions.iter().filter(|(_, function_coverage)| function_coverage.calls > 0).count())?;
+
+            let mut branch_hit_count = 0;
+            for (block_index, block) in file_coverage.branches.values().enumerate() {
+                for (branch_index, taken_count) in [block.true_count, block.false_count].into_iter().enumerate() {
+                    let taken = match block.evaluations {
+                        0 => "-".to_string(),
+                        _ => taken_count.to_string(),
+                    };
+                    writeln!(output, "BRDA:{},{},{},{}", block.line, block_index, branch_index, taken)?;
+                    if taken_count > 0 {
+                        branch_hit_count += 1;
+                    }
+                }
+            }
+            writeln!(output, "BRF:{}", file_coverage.branches.len() * 2)?;
+            writeln!(output, "BRH:{}", branch_hit_count)?;
+
+            for (line, hit_count) in &file_coverage.lines {
+                writeln!(output, "DA:{},{}", line, hit_count)?;
+            }
+            writeln!(output, "LF:{}", file_coverage.lines.len())?;
+            writeln!(output, "LH:{}", file_coverage.lines.values().filter(|hit_count| **hit_count > 0).count())?;
+            writeln!(output, "end_of_record")?;
+        }
+        Ok(())
+    }
+}
+
+/// What an AST node counts when it is interpreted, in its original file.
+enum Probe {
+    Line(PathBuf, usize),
+    Function(PathBuf, String),
+    Branch(PathBuf, (usize, usize)),
+}
+
+/// Register the lines, functions and branches of a program, by the address of their node,
+/// so that the ones that are never reached are also reported.
+struct ProgramRegistration<'s> {
+    source: &'s PreprocessedSource,
+    coverage: &'s mut Coverage,
+    probes: HashMap<usize, Probe>,
+}
+
+impl ProgramRegistration<'_> {
+    /// Get the original file of a range of the preprocessed content, with the range and first line in this file.
+    fn locate(&self, start: usize, end: usize) -> Option<(PathBuf, (usize, usize), usize)> {
+        let (file_id, file_start) = self.source.source_map.locate(start)?;
+        let file_end = match self.source.source_map.locate_end(end) {
+            Some((end_file_id, file_end)) if end_file_id == file_id => file_end,
+            _ => file_start,
+        };
+        let file = self.source.source_map.get_file(file_id);
+        let line = file.content[..file_start].matches('\n').count() + 1;
+        Some((file.path.clone(), (file_start, file_end), line))
+    }
+
+    fn register_line<T>(&mut self, node: &Node<T>) {
+        if let Some((path, _, line)) = self.locate(node.sp.start(), node.sp.end()) {
+            self.coverage.files.entry(path.clone()).or_default().lines.entry(line).or_default();
+            self.probes.insert(node as *const _ as usize, Probe::Line(path, line));
+        }
+    }
+
+    fn register_branch(&mut self, condition_node: &Node<Expression>) {
+        if let Some((path, range, line)) = self.locate(condition_node.sp.start(), condition_node.sp.end()) {
+            let file_coverage = self.coverage.files.entry(path.clone()).or_default();
+            file_coverage.branches.entry(range).or_insert(BranchBlock { line, ..BranchBlock::default() });
+            self.probes.insert(condition_node as *const _ as usize, Probe::Branch(path, range));
+        }
+    }
+
+    fn register_function(&mut self, function_node: &Node<Function>) {
+        let function_name = function_node.data.name.data.name.clone();
+        if let Some((path, _, line)) = self.locate(function_node.sp.start(), function_node.sp.end()) {
+            let file_coverage = self.coverage.files.entry(path.clone()).or_default();
+            file_coverage.functions.entry(function_name.clone()).or_insert(FunctionCoverage { line, calls: 0 });
+            self.probes.insert(function_node as *const _ as usize, Probe::Function(path, function_name));
+        }
+        self.register_statements(&function_node.data.body.data.statements);
+        let function_return = &function_node.data.body.data.function_return;
+        self.register_line(function_return);
+        self.register_expression(function_return);
+    }
+
+    fn register_statements(&mut self, statements: &[Node<Statement>]) {
+        for statement_node in statements {
+            self.register_line(statement_node);
+            match &statement_node.data {
+                Statement::Assignment(assignment_statement) => {
+                    if let Some(index) = &assignment_statement.left_var.data.index {
+                        self.register_expression(index);
+                    }
+                    self.register_expression(&assignment_statement.right_expr);
+                },
+                Statement::If(if_statement) => {
+                    self.register_branch(&if_statement.condition);
+                    self.register_expression(&if_statement.condition);
+                    self.register_statements(&if_statement.if_body);
+                    if let Some(else_body) = &if_statement.else_body {
+                        self.register_statements(else_body);
+                    }
+                },
+                Statement::While(while_statement) => {
+                    self.register_branch(&while_statement.condition);
+                    self.register_expression(&while_statement.condition);
+                    self.register_statements(&while_statement.body);
+                },
+                Statement::Switch(switch_statement) => {
+                    self.register_expression(&switch_statement.expression);
+                    for case in &switch_statement.cases {
+                        self.register_statements(&case.data.body);
+                    }
+                    if let Some(default_body) = &switch_statement.default_body {
+                        self.register_statements(default_body);
+                    }
+                },
+                Statement::Assert(assert_statement) => self.register_expression(&assert_statement.condition),
+                Statement::StringCopy(_) => {},
+            }
+        }
+    }
+
+    /// Register the operands of the `&&` and `||` of an expression.
+    fn register_expression(&mut self, expression_node: &Node<Expression>) {
+        match &expression_node.data {
+            Expression::BinaryExpression(binary_expression) => {
+                if matches!(binary_expression.operator, BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr) {
+                    self.register_branch(&binary_expression.left);
+                    self.register_branch(&binary_expression.right);
+                }
+                self.register_expression(&binary_expression.left);
+                self.register_expression(&binary_expression.right);
+            },
+            Expression::UnaryExpression(unary_expression) => self.register_expression(&unary_expression.expression),
+            Expression::TypeCast(type_cast) => self.register_expression(&type_cast.expression),
+            Expression::FunctionCall(function_call) => {
+                for argument in &function_call.arguments {
+                    self.register_expression(argument);
+                }
+            },
+            Expression::GetOrSetValue(get_or_set_value) => {
+                if let Some(index) = &get_or_set_value.index {
+                    self.register_expression(index);
+                }
+            },
+            Expression::Literal(_) | Expression::SizeOf(_) | Expression::Length(_)
+                | Expression::StringLength(_) | Expression::StringCompare(_, _) => {},
+        }
+    }
+}
+
+/// An interpretation hook counting the lines, function calls and branches of a program in a shared `Coverage`.
+pub struct CoverageCollector {
+    coverage: Rc<RefCell<Coverage>>,
+    probes: HashMap<usize, Probe>,
+}
+
+impl CoverageCollector {
+    /// Register the lines, functions and branches of a program in the coverage, before interpreting it.
+    pub fn new(coverage: Rc<RefCell<Coverage>>, ast: &AST, source: &PreprocessedSource) -> Self {
+        let probes = {
+            let mut registration = ProgramRegistration {
+                source,
+                coverage: &mut coverage.borrow_mut(),
+                probes: HashMap::new(),
+            };
+            registration.register_function(&ast.data.main_function);
+            for function_node in ast.data.functions.values() {
+                registration.register_function(function_node);
+            }
+            registration.probes
+        };
+        Self { coverage, probes }
+    }
+
+    fn hit_line<T>(&self, node: &Node<T>) {
+        if let Some(Probe::Line(path, line)) = self.probes.get(&(node as *const _ as usize)) {
+            let mut coverage = self.coverage.borrow_mut();
+            *coverage.files.entry(path.clone()).or_default().lines.entry(*line).or_default() += 1;
+        }
+    }
+}
+
+impl InterpretationHook for CoverageCollector {
+    fn before_statement<'a>(
+        &mut self,
+        statement_node: &Node<'a, Statement<'a>>,
+        _symbol_table: &SymbolTable,
+        _current_scope_node_id: &Node<'a, Identifier>,
+    ) -> Result<(), SemanticError> {
+        self.hit_line(statement_node);
+        Ok(())
+    }
+
+    fn before_expression<'a>(
+        &mut self,
+        expression_node: &Node<'a, Expression<'a>>,
+        _symbol_table: &SymbolTable,
+        _current_scope_node_id: &Node<'a, Identifier>,
+    ) -> Result<(), SemanticError> {
+        // the returns of the functions
+        self.hit_line(expression_node);
+        Ok(())
+    }
+
+    fn after_expression<'a>(
+        &mut self,
+        expression_node: &Node<'a, Expression<'a>>,
+        result: &Result<Node<'a, Value>, SemanticError>,
+        _symbol_table: &SymbolTable,
+        _current_scope_node_id: &Node<'a, Identifier>,
+    ) -> Result<(), SemanticError> {
+        let Some(Probe::Branch(path, range)) = self.probes.get(&(expression_node as *const _ as usize)) else {
+            return Ok(());
+        };
+        let Some(is_condition_true) = result.as_ref().ok()
+            .and_then(|value_node| convert_to_type(value_node.clone(), TypeSpecifier::Bool).ok())
+            .map(|bool_node| bool_node.data == Value::Bool(true)) else {
+            return Ok(());
+        };
+        let mut coverage = self.coverage.borrow_mut();
+        let block = coverage.files.entry(path.clone()).or_default().branches.entry(*range).or_default();
+        block.evaluations += 1;
+        match is_condition_true {
+            true => block.true_count += 1,
+            false => block.false_count += 1,
+        }
+        Ok(())
+    }
+
+    fn enter_function<'a>(
+        &mut self,
+        function_node: &Node<'a, Function<'a>>,
+        _symbol_table: &SymbolTable,
+    ) -> Result<(), SemanticError> {
+        if let Some(Probe::Function(path, function_name)) = self.probes.get(&(function_node as *const _ as usize)) {
+            let mut coverage = self.coverage.borrow_mut();
+            let file_coverage = coverage.files.entry(path.clone()).or_default();
+            file_coverage.functions.entry(function_name.clone()).or_default().calls += 1;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,566 @@
+use std::cell::RefCell;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use pest::Span;
+use serde_json::{json, Value as JsonValue};
+
+use crate::abstract_syntax_tree::nodes::{Node, Identifier, Statement, Function, Value};
+use crate::interpretation::hooks::{InterpretationHook, add_hook, clear_hooks};
+use crate::interpretation::interpret_function::interpret_translation_unit;
+use crate::pipelines::parse_content_into_ast;
+use crate::preprocessing::preprocess_file;
+use crate::protocol::{read_message, write_message};
+use crate::semantic::errors::{SemanticError, SemanticErrorTrait, InterpretationAbortedError};
+use crate::semantic::warnings::take_warnings;
+use crate::symbol_table::build_static_symbol_table;
+use crate::symbol_table::structs::{SymbolTable, Variable};
+
+use super::{Breakpoint, DebugState, PauseReason, Stepping};
+
+// Ctiny programs have a single thread.
+const THREAD_ID: i64 = 1;
+
+/// The messages of the Debug Adapter Protocol, each one preceded by a `Content-Length` header.
+struct DapConnection {
+    input: Box<dyn BufRead>,
+    output: Box<dyn Write>,
+    seq: i64,
+}
+
+impl DapConnection {
+    fn read_message(&mut self) -> Option<JsonValue> {
+        read_message(&mut self.input)
+    }
+
+    fn send(&mut self, mut message: JsonValue) {
+        self.seq += 1;
+        message["seq"] = json!(self.seq);
+        write_message(&mut self.output, &message);
+    }
+
+    fn send_response(&mut self, request: &JsonValue, body: JsonValue) {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": true,
+            "body": body,
+        }));
+    }
+
+    fn send_error_response(&mut self, request: &JsonValue, message: &str) {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": false,
+            "message": message,
+        }));
+    }
+
+    fn send_event(&mut self, event: &str, body: JsonValue) {
+        self.send(json!({
+            "type": "event",
+            "event": event,
+            "body": body,
+        }));
+    }
+
+    fn send_output(&mut self, category: &str, output: &str) {
+        self.send_event("output", json!({ "category": category, "output": format!("{}\n", output) }));
+    }
+}
+
+/// The arguments of a `launch` request.
+struct LaunchArguments {
+    program: PathBuf,
+    stop_on_entry: bool,
+}
+
+/// What to do once a request has been answered.
+enum RequestOutcome {
+    Handled,
+    Launch(LaunchArguments),
+    ConfigurationDone,
+    Resume(Stepping),
+    Disconnect,
+}
+
+/// The variables that the client can expand, by their reference.
+enum VariablesReference {
+    Frame(usize),
+    Array { frame_index: usize, name: String },
+}
+
+/// The symbol table and the current statement, while the interpretation is paused.
+struct PausedProgram<'s, 'a> {
+    symbol_table: &'s SymbolTable,
+    sp: Span<'a>,
+}
+
+/// A debugging session with a DAP client, shared between the server and the interpretation hook.
+struct DapSession {
+    connection: DapConnection,
+    state: DebugState,
+    variables_references: Vec<VariablesReference>,
+    is_disconnected: bool,
+    // a disconnection while the program runs is answered once the program has ended
+    disconnect_request: Option<JsonValue>,
+}
+
+fn canonicalize_path(path: &str) -> String {
+    std::fs::canonicalize(path)
+        .map_or(path.to_string(), |path| path.to_string_lossy().to_string())
+}
+
+fn make_source(file: &Option<String>) -> JsonValue {
+    match file {
+        Some(file) => json!({
+            "name": Path::new(file).file_name().map_or(file.clone(), |name| name.to_string_lossy().to_string()),
+            "path": file,
+        }),
+        None => JsonValue::Null,
+    }
+}
+
+fn describe_value(value: Option<&Value>) -> String {
+    value.map_or("<unassigned>".to_string(), |value| value.to_string())
+}
+
+impl DapSession {
+    fn new(input: Box<dyn BufRead>, output: Box<dyn Write>) -> Self {
+        Self {
+            connection: DapConnection { input, output, seq: 0 },
+            state: DebugState::new(None, Stepping::Continue),
+            variables_references: Vec::new(),
+            is_disconnected: false,
+            disconnect_request: None,
+        }
+    }
+
+    fn add_variables_reference(&mut self, variables_reference: VariablesReference) -> usize {
+        self.variables_references.push(variables_reference);
+        self.variables_references.len()
+    }
+
+    fn set_breakpoints(&mut self, request: &JsonValue) {
+        let arguments = &request["arguments"];
+        let Some(path) = arguments["source"]["path"].as_str() else {
+            self.connection.send_error_response(request, "Breakpoints need the path of their source");
+            return;
+        };
+        let file = canonicalize_path(path);
+        let lines: Vec<usize> = match arguments["breakpoints"].as_array() {
+            Some(breakpoints) => breakpoints.iter()
+                .filter_map(|breakpoint| breakpoint["line"].as_u64())
+                .map(|line| line as usize)
+                .collect(),
+            None => arguments["lines"].as_array().into_iter().flatten()
+                .filter_map(|line| line.as_u64())
+                .map(|line| line as usize)
+                .collect(),
+        };
+
+        // the breakpoints of a source replace all its previous ones
+        self.state.breakpoints.retain(|breakpoint| breakpoint.file.as_deref() != Some(file.as_str()));
+        let mut breakpoints = Vec::new();
+        for line in lines {
+            breakpoints.push(json!({ "verified": true, "line": line, "source": { "path": path } }));
+            self.state.breakpoints.push(Breakpoint { file: Some(file.clone()), line });
+        }
+        self.connection.send_response(request, json!({ "breakpoints": breakpoints }));
+    }
+
+    fn send_stack_trace(&mut self, request: &JsonValue) {
+        // the frames go from the innermost one, and are identified by their index in the call stack
+        let frames: Vec<JsonValue> = self.state.call_stack.iter().enumerate().rev()
+            .map(|(index, frame)| json!({
+                "id": index,
+                "name": frame.function_name,
+                "source": make_source(&frame.location.as_ref().and_then(|location| location.file.clone())),
+                "line": frame.location.as_ref().map_or(0, |location| location.line),
+                "column": 1,
+            }))
+            .collect();
+        let total_frames = frames.len();
+        self.connection.send_response(request, json!({ "stackFrames": frames, "totalFrames": total_frames }));
+    }
+
+    fn send_scopes(&mut self, request: &JsonValue) {
+        let frame_index = request["arguments"]["frameId"].as_u64().unwrap_or(0) as usize;
+        if frame_index >= self.state.call_stack.len() {
+            self.connection.send_error_response(request, &format!("No frame {}", frame_index));
+            return;
+        }
+        let variables_reference = self.add_variables_reference(VariablesReference::Frame(frame_index));
+        self.connection.send_response(request, json!({
+            "scopes": [{
+                "name": "Locals",
+                "presentationHint": "locals",
+                "variablesReference": variables_reference,
+                "expensive": false,
+            }],
+        }));
+    }
+
+    /// Find the variables of a frame. A recursive function has a single scope for all its calls.
+    fn get_frame_variables<'s>(
+        &self,
+        frame_index: usize,
+        paused_program: &PausedProgram<'s, '_>,
+    ) -> Vec<&'s Variable> {
+        let Some(frame) = self.state.call_stack.get(frame_index) else {
+            return Vec::new();
+        };
+        let scope_id = Node {
+            sp: paused_program.sp,
+            data: Identifier { name: frame.function_name.clone() },
+        };
+        let mut variables: Vec<&Variable> = paused_program.symbol_table.get_scope(&scope_id)
+            .map(|scope| scope.get_variables().collect())
+            .unwrap_or_default();
+        variables.sort_by_key(|variable| &variable.get_id().name);
+        variables
+    }
+
+    fn send_variables(&mut self, request: &JsonValue, paused_program: &PausedProgram) {
+        let reference = request["arguments"]["variablesReference"].as_u64().unwrap_or(0) as usize;
+        let variables: Vec<JsonValue> = match self.variables_references.get(reference.wrapping_sub(1)) {
+            Some(VariablesReference::Frame(frame_index)) => {
+                let frame_index = *frame_index;
+                let frame_variables = self.get_frame_variables(frame_index, paused_program);
+                let mut variables = Vec::new();
+                for variable in frame_variables {
+                    variables.push(match variable {
+                        Variable::NormalVar(normal_var_data) => json!({
+                            "name": normal_var_data.id.name,
+                            "value": describe_value(normal_var_data.get_value()),
+                            "type": normal_var_data.type_specifier.to_string(),
+                            "variablesReference": 0,
+                        }),
+                        Variable::ArrayVar(array_var_data) => {
+                            let array_reference = self.add_variables_reference(VariablesReference::Array {
+                                frame_index,
+                                name: array_var_data.id.name.clone(),
+                            });
+                            json!({
+                                "name": array_var_data.id.name,
+                                "value": format!("{}[{}]", array_var_data.type_specifier, array_var_data.size),
+                                "type": format!("{}[{}]", array_var_data.type_specifier, array_var_data.size),
+                                "variablesReference": array_reference,
+                                "indexedVariables": array_var_data.size,
+                            })
+                        },
+                    });
+                }
+                variables
+            },
+            Some(VariablesReference::Array { frame_index, name }) => {
+                let array = self.get_frame_variables(*frame_index, paused_program).into_iter()
+                    .find(|variable| &variable.get_id().name == name);
+                match array {
+                    Some(Variable::ArrayVar(array_var_data)) => (0..array_var_data.size)
+                        .map(|index| json!({
+                            "name": format!("[{}]", index),
+                            "value": describe_value(array_var_data.get_value(index)),
+                            "type": array_var_data.type_specifier.to_string(),
+                            "variablesReference": 0,
+                        }))
+                        .collect(),
+                    _ => Vec::new(),
+                }
+            },
+            None => {
+                self.connection.send_error_response(request, &format!("No variables for reference {}", reference));
+                return;
+            },
+        };
+        self.connection.send_response(request, json!({ "variables": variables }));
+    }
+
+    /// Evaluate the name of a variable of a frame, like when hovering it in an editor.
+    fn send_evaluation(&mut self, request: &JsonValue, paused_program: &PausedProgram) {
+        let arguments = &request["arguments"];
+        let name = arguments["expression"].as_str().unwrap_or("").trim();
+        let frame_index = arguments["frameId"].as_u64()
+            .map_or(self.state.call_stack.len().saturating_sub(1), |frame_id| frame_id as usize);
+        let description = self.get_frame_variables(frame_index, paused_program).into_iter()
+            .find(|variable| variable.get_id().name == name)
+            .map(Variable::describe);
+        match description {
+            Some(description) => self.connection.send_response(
+                request, json!({ "result": description, "variablesReference": 0 })
+            ),
+            None => self.connection.send_error_response(
+                request, &format!("Only variables of the current functions can be evaluated, not {}", name)
+            ),
+        }
+    }
+
+    /// Answer a request. The state of the program can only be inspected while it is paused.
+    fn handle_request(&mut self, request: &JsonValue, paused_program: Option<&PausedProgram>) -> RequestOutcome {
+        let command = request["command"].as_str().unwrap_or("");
+        let depth = self.state.get_depth();
+        match (command, paused_program) {
+            ("initialize", _) => {
+                self.connection.send_response(request, json!({
+                    "supportsConfigurationDoneRequest": true,
+                    "supportsEvaluateForHovers": true,
+                    "supportsTerminateRequest": true,
+                }));
+                self.connection.send_event("initialized", json!({}));
+            },
+            ("launch", _) => {
+                let arguments = &request["arguments"];
+                let Some(program) = arguments["program"].as_str() else {
+                    self.connection.send_error_response(request, "The launch request needs the path of a program");
+                    return RequestOutcome::Handled;
+                };
+                if !Path::new(program).is_file() {
+                    self.connection.send_error_response(request, &format!("Cannot find the program {}", program));
+                    return RequestOutcome::Handled;
+                }
+                self.connection.send_response(request, json!({}));
+                return RequestOutcome::Launch(LaunchArguments {
+                    program: PathBuf::from(program),
+                    stop_on_entry: arguments["stopOnEntry"].as_bool().unwrap_or(false),
+                });
+            },
+            ("setBreakpoints", _) => self.set_breakpoints(request),
+            ("setExceptionBreakpoints", _) => {
+                // the interpretation always pauses on the first runtime error
+                self.connection.send_response(request, json!({ "breakpoints": [] }));
+            },
+            ("configurationDone", _) => {
+                self.connection.send_response(request, json!({}));
+                return RequestOutcome::ConfigurationDone;
+            },
+            ("threads", _) => {
+                self.connection.send_response(request, json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] }));
+            },
+            // answered by the caller, after the end of the program if it runs
+            ("disconnect" | "terminate", _) => return RequestOutcome::Disconnect,
+            ("stackTrace", Some(_)) => self.send_stack_trace(request),
+            ("scopes", Some(_)) => self.send_scopes(request),
+            ("variables", Some(paused_program)) => self.send_variables(request, paused_program),
+            ("evaluate", Some(paused_program)) => self.send_evaluation(request, paused_program),
+            ("continue" | "next" | "stepIn" | "stepOut", Some(_)) => {
+                self.connection.send_response(request, json!({ "allThreadsContinued": true }));
+                return RequestOutcome::Resume(match command {
+                    "next" => Stepping::StepOver(depth),
+                    "stepIn" => Stepping::StepInto,
+                    "stepOut" => Stepping::StepOut(depth),
+                    _ => Stepping::Continue,
+                });
+            },
+            ("stackTrace" | "scopes" | "variables" | "evaluate" | "continue" | "next" | "stepIn" | "stepOut", None) => {
+                self.connection.send_error_response(request, "The program is not paused");
+            },
+            _ => self.connection.send_error_response(request, &format!("Unsupported request {}", command)),
+        }
+        RequestOutcome::Handled
+    }
+
+    /// Answer the requests until the program is launched and configured,
+    /// and get the launch arguments. None if the client disconnects before.
+    fn configure(&mut self) -> Option<LaunchArguments> {
+        let mut launch_arguments: Option<LaunchArguments> = None;
+        let mut is_configuration_done = false;
+        while launch_arguments.is_none() || !is_configuration_done {
+            let request = self.connection.read_message()?;
+            match self.handle_request(&request, None) {
+                RequestOutcome::Launch(arguments) => launch_arguments = Some(arguments),
+                RequestOutcome::ConfigurationDone => is_configuration_done = true,
+                RequestOutcome::Disconnect => {
+                    self.connection.send_response(&request, json!({}));
+                    return None;
+                },
+                _ => {},
+            }
+        }
+        launch_arguments
+    }
+
+    /// Tell the client why the program is paused, then answer its requests until it resumes.
+    /// Disconnecting aborts the interpretation with an error at the current statement.
+    fn pause(&mut self, reason: &str, description: Option<String>, paused_program: &PausedProgram) -> Result<(), SemanticError> {
+        self.variables_references.clear();
+        let mut body = json!({ "reason": reason, "threadId": THREAD_ID, "allThreadsStopped": true });
+        if let Some(description) = description {
+            body["description"] = json!("Paused on error");
+            body["text"] = json!(description);
+        }
+        self.connection.send_event("stopped", body);
+        loop {
+            let request = match self.connection.read_message() {
+                Some(request) => request,
+                None => {
+                    self.is_disconnected = true;
+                    break;
+                },
+            };
+            match self.handle_request(&request, Some(paused_program)) {
+                RequestOutcome::Resume(stepping) => {
+                    self.state.stepping = stepping;
+                    return Ok(());
+                },
+                RequestOutcome::Disconnect => {
+                    self.is_disconnected = true;
+                    self.disconnect_request = Some(request);
+                    break;
+                },
+                _ => {},
+            }
+        }
+        Err(SemanticError::InterpretationAborted(
+            InterpretationAbortedError::init(paused_program.sp, "Interpretation stopped by the debug client")
+        ))
+    }
+
+    /// Pause on the first runtime error, before it is propagated.
+    fn pause_on_error(&mut self, error: &SemanticError, paused_program: &PausedProgram) {
+        if self.is_disconnected || !self.state.check_pause_on_error(error) {
+            return;
+        }
+        let description = self.state.describe_error(error);
+        let _ = self.pause("exception", Some(description), paused_program);
+    }
+
+    /// Answer the requests once the program is over, until the client disconnects.
+    fn wait_for_disconnection(&mut self) {
+        while !self.is_disconnected {
+            match self.connection.read_message() {
+                Some(request) => {
+                    if let RequestOutcome::Disconnect = self.handle_request(&request, None) {
+                        self.connection.send_response(&request, json!({}));
+                        self.is_disconnected = true;
+                    }
+                },
+                None => self.is_disconnected = true,
+            }
+        }
+    }
+}
+
+/// The interpretation hook of a DAP session.
+struct DapHook {
+    session: Rc<RefCell<DapSession>>,
+    is_first_pause: bool,
+}
+
+impl InterpretationHook for DapHook {
+    fn before_statement<'a>(
+        &mut self,
+        statement_node: &Node<'a, Statement<'a>>,
+        symbol_table: &SymbolTable,
+        _current_scope_node_id: &Node<'a, Identifier>,
+    ) -> Result<(), SemanticError> {
+        let mut session = self.session.borrow_mut();
+        if session.is_disconnected {
+            return Ok(());
+        }
+        let location = session.state.get_location(statement_node.sp);
+        let reason = match session.state.check_pause_before_statement(&location) {
+            Some(PauseReason::Step) if self.is_first_pause => "entry",
+            Some(PauseReason::Step) => "step",
+            Some(PauseReason::Breakpoint) => "breakpoint",
+            None => return Ok(()),
+        };
+        self.is_first_pause = false;
+        session.pause(reason, None, &PausedProgram { symbol_table, sp: statement_node.sp })
+    }
+
+    fn after_statement<'a>(
+        &mut self,
+        statement_node: &Node<'a, Statement<'a>>,
+        result: &Result<(), SemanticError>,
+        symbol_table: &SymbolTable,
+        _current_scope_node_id: &Node<'a, Identifier>,
+    ) -> Result<(), SemanticError> {
+        if let Err(error) = result {
+            self.session.borrow_mut()
+                .pause_on_error(error, &PausedProgram { symbol_table, sp: statement_node.sp });
+        }
+        Ok(())
+    }
+
+    fn enter_function<'a>(
+        &mut self,
+        function_node: &Node<'a, Function<'a>>,
+        _symbol_table: &SymbolTable,
+    ) -> Result<(), SemanticError> {
+        self.session.borrow_mut().state.enter_function(&function_node.data.name.data.name);
+        Ok(())
+    }
+
+    fn exit_function<'a>(
+        &mut self,
+        function_node: &Node<'a, Function<'a>>,
+        result: &Result<Node<'a, Value>, SemanticError>,
+        symbol_table: &SymbolTable,
+    ) -> Result<(), SemanticError> {
+        let mut session = self.session.borrow_mut();
+        // an error in the return expression is not caught by the statement hooks
+        if let Err(error) = result {
+            let function_return = &function_node.data.body.data.function_return;
+            session.pause_on_error(error, &PausedProgram { symbol_table, sp: function_return.sp });
+        }
+        session.state.exit_function();
+        Ok(())
+    }
+}
+
+/// Interpret the launched program under the DAP session, then report how it ended.
+fn run_program(session: &Rc<RefCell<DapSession>>, launch_arguments: LaunchArguments) {
+    let stepping = if launch_arguments.stop_on_entry { Stepping::StepInto } else { Stepping::Continue };
+    let result = match preprocess_file(&launch_arguments.program) {
+        Ok(source) => {
+            let source = Rc::new(source);
+            let result = parse_content_into_ast(&source.content, None).and_then(|ast| {
+                let mut symbol_table = build_static_symbol_table(&ast)?;
+                session.borrow_mut().state.set_source(source.clone(), stepping);
+                add_hook(Box::new(DapHook { session: session.clone(), is_first_pause: true }));
+                let result = interpret_translation_unit(&ast, &mut symbol_table)
+                    .map(|value_node| value_node.data);
+                clear_hooks();
+                result
+            });
+            let mut session = session.borrow_mut();
+            for warning in take_warnings() {
+                session.connection.send_output("console", &source.relocate_error(warning).to_string());
+            }
+            result.map_err(|error| source.relocate_error(error.get_error()).to_string())
+        },
+        Err(error) => Err(error.to_string()),
+    };
+
+    let mut session = session.borrow_mut();
+    let exit_code = match result {
+        Ok(value) => {
+            session.connection.send_output("stdout", &format!("Program return value: {}", value));
+            0
+        },
+        Err(error) => {
+            session.connection.send_output("stderr", &error);
+            1
+        },
+    };
+    session.connection.send_event("exited", json!({ "exitCode": exit_code }));
+    session.connection.send_event("terminated", json!({}));
+    if let Some(disconnect_request) = session.disconnect_request.take() {
+        session.connection.send_response(&disconnect_request, json!({}));
+    }
+}
+
+/// Run a DAP server reading the requests from `input` and writing the responses and events to `output`,
+/// for a single debugging session.
+pub fn run_dap_server(input: Box<dyn BufRead>, output: Box<dyn Write>) {
+    let session = Rc::new(RefCell::new(DapSession::new(input, output)));
+    let launch_arguments = session.borrow_mut().configure();
+    if let Some(launch_arguments) = launch_arguments {
+        run_program(&session, launch_arguments);
+        session.borrow_mut().wait_for_disconnection();
+    }
+}
@@ -0,0 +1,161 @@
+use crate::abstract_syntax_tree::nodes::Value;
+use crate::interpretation::undo_log::{UndoEntry, get_undo_entries_from, get_undo_log_length};
+
+use super::Location;
+
+/// A statement run by the program, with the positions of the undo log when it started and ended.
+/// The writes of the statement, and of the functions it calls, are the entries between them.
+pub struct Step {
+    pub location: Location,
+    pub function_name: String,
+    pub statement_line: String,
+    pub depth: usize,
+    pub undo_start: usize,
+    pub undo_end: Option<usize>,
+}
+
+/// The write of a slot, with the values before and after it.
+pub struct SlotWrite {
+    pub step_index: usize,
+    pub target: String,
+    pub old_value: Option<Value>,
+    pub new_value: Option<Value>,
+}
+
+/// The statements run so far, to step backwards through them.
+///
+/// The interpretation itself can't go back, so going back to a step only changes the step being viewed:
+/// its values are the current ones, with the writes done since the step undone from the undo log.
+#[derive(Default)]
+pub struct History {
+    steps: Vec<Step>,
+    // the statements being run, from the outermost one
+    running_steps: Vec<usize>,
+    // the step viewed after stepping backwards, `None` for the present
+    viewed_step_index: Option<usize>,
+}
+
+impl History {
+    /// Record that a statement starts. Recording a step goes back to the present.
+    pub fn start_step(&mut self, location: Location, function_name: String, statement_line: String, depth: usize) {
+        self.running_steps.push(self.steps.len());
+        self.steps.push(Step {
+            location,
+            function_name,
+            statement_line,
+            depth,
+            undo_start: get_undo_log_length(),
+            undo_end: None,
+        });
+        self.viewed_step_index = None;
+    }
+
+    pub fn end_step(&mut self) {
+        if let Some(step_index) = self.running_steps.pop() {
+            self.steps[step_index].undo_end = Some(get_undo_log_length());
+        }
+    }
+
+    pub fn is_rewound(&self) -> bool {
+        self.viewed_step_index.is_some()
+    }
+
+    pub fn get_viewed_step_index(&self) -> Option<usize> {
+        self.viewed_step_index.or(self.steps.len().checked_sub(1))
+    }
+
+    pub fn get_viewed_step(&self) -> Option<&Step> {
+        self.get_viewed_step_index().map(|step_index| &self.steps[step_index])
+    }
+
+    /// View the step before the viewed one, if there is one.
+    pub fn reverse_step(&mut self) -> Option<&Step> {
+        let step_index = self.get_viewed_step_index()?.checked_sub(1)?;
+        self.viewed_step_index = Some(step_index);
+        Some(&self.steps[step_index])
+    }
+
+    /// View the step after the viewed one, going back to the present after the last one.
+    pub fn forward_step(&mut self) -> Option<&Step> {
+        let step_index = self.viewed_step_index? + 1;
+        if step_index + 1 >= self.steps.len() {
+            self.viewed_step_index = None;
+        } else {
+            self.viewed_step_index = Some(step_index);
+        }
+        self.steps.get(step_index)
+    }
+
+    /// Get the writes of a variable of a function done since the viewed step, in their order.
+    pub fn get_undo_entries_since_viewed_step(&self, scope: &str, variable: &str) -> Vec<UndoEntry> {
+        let Some(step) = self.get_viewed_step() else {
+            return Vec::new();
+        };
+        get_undo_entries_from(step.undo_start).into_iter()
+            .filter(|undo_entry| undo_entry.scope == scope && undo_entry.variable == variable)
+            .collect()
+    }
+
+    /// Find the last write of a variable (or an element, like `t[2]`) of a function before the viewed step,
+    /// and view the step that wrote it.
+    pub fn reverse_to_last_write(&mut self, scope: &str, target: &str, current_value: impl Fn(Option<usize>) -> Option<Value>) -> Option<SlotWrite> {
+        let viewed_undo_start = self.get_viewed_step()?.undo_start;
+        let undo_entries = get_undo_entries_from(0);
+        let is_written_slot = |undo_entry: &UndoEntry| {
+            undo_entry.scope == scope && (undo_entry.variable == target || undo_entry.get_target() == target)
+        };
+        let (position, undo_entry) = undo_entries[..viewed_undo_start].iter()
+            .enumerate()
+            .rev()
+            .find(|(_, undo_entry)| is_written_slot(undo_entry))?;
+
+        // the innermost statement running when the slot was written
+        let step_index = self.steps.iter()
+            .enumerate()
+            .rev()
+            .find(|(_, step)| step.undo_start <= position && step.undo_end.is_none_or(|undo_end| position < undo_end))
+            .map(|(step_index, _)| step_index)?;
+        // the value written is the one the slot had before its next write
+        let new_value = undo_entries[position + 1..].iter()
+            .find(|next_entry| next_entry.scope == undo_entry.scope
+                && next_entry.variable == undo_entry.variable
+                && next_entry.index == undo_entry.index)
+            .map_or_else(|| current_value(undo_entry.index), |next_entry| next_entry.old_value.clone());
+
+        // the write is before the viewed step, so the step is in the past
+        self.viewed_step_index = Some(step_index);
+        Some(SlotWrite {
+            step_index,
+            target: undo_entry.get_target(),
+            old_value: undo_entry.old_value.clone(),
+            new_value,
+        })
+    }
+
+    /// Get the steps of the call stack at the viewed step, from the innermost one.
+    pub fn get_viewed_call_stack(&self) -> Vec<&Step> {
+        let Some(step_index) = self.get_viewed_step_index() else {
+            return Vec::new();
+        };
+        let mut call_stack = vec![&self.steps[step_index]];
+        // the statement of each caller is the last step run at its depth
+        for step in self.steps[..step_index].iter().rev() {
+            if step.depth < call_stack.last().unwrap().depth {
+                call_stack.push(step);
+            }
+        }
+        call_stack
+    }
+
+    pub fn get_step(&self, step_index: usize) -> &Step {
+        &self.steps[step_index]
+    }
+}
+
+/// Get the value of a slot at a previous step, from the writes of its variable since this step, and its current value.
+pub fn get_previous_value(undo_entries: &[UndoEntry], index: Option<usize>, current_value: Option<&Value>) -> Option<Value> {
+    // the first write since the step has the value the slot had then
+    undo_entries.iter()
+        .find(|undo_entry| undo_entry.index == index)
+        .map_or(current_value.cloned(), |undo_entry| undo_entry.old_value.clone())
+}
@@ -0,0 +1,589 @@
+pub mod dap;
+pub mod history;
+
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+use pest::Span;
+
+use crate::abstract_syntax_tree::nodes::{Node, Identifier, Statement, Function, Value};
+use crate::interpretation::hooks::{InterpretationHook, add_hook, clear_hooks};
+use crate::interpretation::interpret_function::interpret_translation_unit;
+use crate::interpretation::undo_log::{start_undo_log, stop_undo_log};
+use crate::pipelines::parse_content_into_ast;
+use crate::preprocessing::{PreprocessedSource, preprocess_file};
+use crate::semantic::errors::{SemanticError, SemanticErrorTrait, InterpretationAbortedError};
+use crate::semantic::warnings::take_warnings;
+use crate::symbol_table::build_static_symbol_table;
+use crate::symbol_table::structs::{SymbolTable, Scope, Variable};
+
+use history::{History, Step, get_previous_value};
+
+const PROMPT: &str = "(ctiny-debug) ";
+
+const HELP: &str = "\
+Commands:
+    break <line>, b <line>         pause before the statements of a line (also <file>:<line>)
+    delete <line>, d <line>        remove a breakpoint (also <file>:<line>)
+    breakpoints                    list the breakpoints
+    step, s                        run until the next statement, entering function calls
+                                   (after going back, go to the next statement run)
+    next, n                        run until the next statement of the current function
+    out, o                         run until the current function returns
+    continue, c                    run until a breakpoint or an error
+    reverse-step, rs               go back to the previous statement run, with the values it had
+    last-write, lw <variable>      go back to the last statement writing a variable (or an element, like t[2])
+    print <variable>, p <variable> print a variable of the current function
+    locals, l                      print all the variables of the current function
+    backtrace, bt                  print the call stack
+    help, h                        print this help
+    quit, q                        stop the program";
+
+/// A line of the program, in its original file when the program has been preprocessed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Location {
+    pub file: Option<String>,
+    pub line: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}:{}", file, self.line),
+            None => write!(f, "line {}", self.line),
+        }
+    }
+}
+
+/// A breakpoint on a line. Without a file, the line of any file matches.
+#[derive(Debug, PartialEq)]
+pub struct Breakpoint {
+    pub file: Option<String>,
+    pub line: usize,
+}
+
+impl Breakpoint {
+    fn parse(argument: &str) -> Option<Self> {
+        let (file, line) = match argument.rsplit_once(':') {
+            Some((file, line)) => (Some(file.to_string()), line),
+            None => (None, argument),
+        };
+        let line = line.trim().parse().ok()?;
+        Some(Self { file, line })
+    }
+
+    fn matches(&self, location: &Location) -> bool {
+        if self.line != location.line {
+            return false;
+        }
+        match (&self.file, &location.file) {
+            (None, _) => true,
+            (Some(file), Some(location_file)) => Path::new(location_file).ends_with(file),
+            (Some(_), None) => false,
+        }
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let location = Location { file: self.file.clone(), line: self.line };
+        write!(f, "{}", location)
+    }
+}
+
+/// When to pause before the next statement, the depths being the ones of the call stack.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stepping {
+    StepInto,
+    StepOver(usize),
+    StepOut(usize),
+    Continue,
+}
+
+/// Why the interpretation is paused before a statement.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PauseReason {
+    Step,
+    Breakpoint,
+}
+
+/// A function call being interpreted, with the location of its current statement.
+pub struct Frame {
+    pub function_name: String,
+    pub location: Option<Location>,
+}
+
+/// The state shared by the debugger front-ends: the breakpoints, the stepping and the call stack.
+pub struct DebugState {
+    source: Option<Rc<PreprocessedSource>>,
+    pub breakpoints: Vec<Breakpoint>,
+    pub stepping: Stepping,
+    pub call_stack: Vec<Frame>,
+    has_stopped_on_error: bool,
+}
+
+impl DebugState {
+    /// Without a preprocessed source, the lines are the ones of the parsed content.
+    pub fn new(source: Option<Rc<PreprocessedSource>>, stepping: Stepping) -> Self {
+        Self {
+            source,
+            breakpoints: Vec::new(),
+            stepping,
+            call_stack: Vec::new(),
+            has_stopped_on_error: false,
+        }
+    }
+
+    /// Set the program to debug, keeping the breakpoints set before it was loaded.
+    pub fn set_source(&mut self, source: Rc<PreprocessedSource>, stepping: Stepping) {
+        self.source = Some(source);
+        self.stepping = stepping;
+    }
+
+    pub fn get_location(&self, sp: Span) -> Location {
+        let source_line = self.source.as_ref()
+            .and_then(|source| source.get_source_line(sp.start()))
+            .map(|(path, line)| (path.to_string_lossy().to_string(), line));
+        match source_line {
+            Some((file, line)) => Location { file: Some(file), line },
+            None => Location { file: None, line: sp.start_pos().line_col().0 },
+        }
+    }
+
+    pub fn get_depth(&self) -> usize {
+        self.call_stack.len()
+    }
+
+    pub fn get_current_function_name(&self) -> String {
+        self.call_stack.last()
+            .map_or("<unknown>".to_string(), |frame| frame.function_name.clone())
+    }
+
+    /// Describe an error, pointing to its original file when the program has been preprocessed.
+    pub fn describe_error(&self, error: &SemanticError) -> String {
+        match &self.source {
+            Some(source) => source.relocate_error(error.get_error()).to_string(),
+            None => error.to_string(),
+        }
+    }
+
+    pub fn enter_function(&mut self, function_name: &str) {
+        self.call_stack.push(Frame {
+            function_name: function_name.to_string(),
+            location: None,
+        });
+    }
+
+    pub fn exit_function(&mut self) {
+        self.call_stack.pop();
+    }
+
+    /// Record that the current function reached a statement,
+    /// and tell why to pause before it, if the interpretation should pause.
+    pub fn check_pause_before_statement(&mut self, location: &Location) -> Option<PauseReason> {
+        if let Some(frame) = self.call_stack.last_mut() {
+            frame.location = Some(location.clone());
+        }
+        let depth = self.get_depth();
+        let is_stepping_done = match self.stepping {
+            Stepping::StepInto => true,
+            Stepping::StepOver(step_depth) => depth <= step_depth,
+            Stepping::StepOut(step_depth) => depth < step_depth,
+            Stepping::Continue => false,
+        };
+        if is_stepping_done {
+            Some(PauseReason::Step)
+        } else if self.breakpoints.iter().any(|breakpoint| breakpoint.matches(location)) {
+            Some(PauseReason::Breakpoint)
+        } else {
+            None
+        }
+    }
+
+    /// Tell whether to pause on an error. Only the first runtime error pauses,
+    /// and not again for each statement and function it is propagated through.
+    pub fn check_pause_on_error(&mut self, error: &SemanticError) -> bool {
+        if self.has_stopped_on_error || matches!(error, SemanticError::InterpretationAborted(_)) {
+            return false;
+        }
+        self.has_stopped_on_error = true;
+        true
+    }
+}
+
+/// A step debugger, driven by commands read line by line.
+///
+/// The debugger pauses before the first statement of the program, then according to
+/// the stepping commands and the breakpoints. It also pauses on the first runtime error,
+/// before the error is propagated, so that the variables leading to it can be inspected.
+///
+/// While paused, it can go back through the statements already run, the writes of the variables
+/// being recorded in the undo log. The commands resuming the interpretation resume from the present.
+pub struct Debugger {
+    input: Box<dyn BufRead>,
+    output: Box<dyn Write>,
+    state: DebugState,
+    history: History,
+}
+
+impl Debugger {
+    /// Create a debugger reading its commands from `input`.
+    /// Without a preprocessed source, the lines are the ones of the parsed content.
+    pub fn new(
+        input: Box<dyn BufRead>,
+        output: Box<dyn Write>,
+        source: Option<Rc<PreprocessedSource>>,
+    ) -> Self {
+        Self {
+            input,
+            output,
+            state: DebugState::new(source, Stepping::StepInto),
+            history: History::default(),
+        }
+    }
+
+    fn print(&mut self, text: &str) {
+        let _ = writeln!(self.output, "{}", text);
+    }
+
+    /// Get the scope of the function viewed: the current one, or the one of the step viewed after going back.
+    fn get_viewed_scope<'s>(&self, symbol_table: &'s SymbolTable, current_scope_node_id: &Node<Identifier>) -> Option<&'s Scope> {
+        match self.history.is_rewound() {
+            true => {
+                let function_name = &self.history.get_viewed_step()?.function_name;
+                symbol_table.get_scope(&Node {
+                    sp: current_scope_node_id.sp,
+                    data: Identifier { name: function_name.clone() },
+                })
+            },
+            false => symbol_table.get_scope(current_scope_node_id),
+        }
+    }
+
+    fn get_viewed_function_name(&self, current_scope_node_id: &Node<Identifier>) -> String {
+        match self.history.is_rewound() {
+            true => self.history.get_viewed_step().map_or(String::new(), |step| step.function_name.clone()),
+            false => current_scope_node_id.data.name.clone(),
+        }
+    }
+
+    /// Describe a variable with its values at the step viewed, undoing the writes done since then.
+    fn describe_variable(&self, variable: &Variable, function_name: &str) -> String {
+        if !self.history.is_rewound() {
+            return variable.describe();
+        }
+        let undo_entries = self.history.get_undo_entries_since_viewed_step(function_name, &variable.get_id().name);
+        variable.describe_with(|index, current_value| get_previous_value(&undo_entries, index, current_value))
+    }
+
+    fn print_variable(&mut self, name: &str, symbol_table: &SymbolTable, current_scope_node_id: &Node<Identifier>) {
+        let function_name = self.get_viewed_function_name(current_scope_node_id);
+        let description = self.get_viewed_scope(symbol_table, current_scope_node_id)
+            .and_then(|scope| scope.get_variables().find(|variable| variable.get_id().name == name))
+            .map(|variable| self.describe_variable(variable, &function_name));
+        match description {
+            Some(description) => self.print(&description),
+            None => {
+                let message = format!("No variable {} in function {}", name, function_name);
+                self.print(&message);
+            },
+        }
+    }
+
+    fn print_locals(&mut self, symbol_table: &SymbolTable, current_scope_node_id: &Node<Identifier>) {
+        let function_name = self.get_viewed_function_name(current_scope_node_id);
+        let mut descriptions: Vec<(String, String)> = match self.get_viewed_scope(symbol_table, current_scope_node_id) {
+            Some(scope) => scope.get_variables()
+                .map(|variable| (variable.get_id().name.clone(), self.describe_variable(variable, &function_name)))
+                .collect(),
+            None => Vec::new(),
+        };
+        descriptions.sort();
+        for (_, description) in descriptions {
+            self.print(&description);
+        }
+    }
+
+    fn print_backtrace(&mut self) {
+        let frames: Vec<String> = match self.history.is_rewound() {
+            true => self.history.get_viewed_call_stack().iter().enumerate()
+                .map(|(index, step)| format!("#{} {} at {}", index, step.function_name, step.location))
+                .collect(),
+            false => self.state.call_stack.iter().rev().enumerate()
+                .map(|(index, frame)| match &frame.location {
+                    Some(location) => format!("#{} {} at {}", index, frame.function_name, location),
+                    None => format!("#{} {}", index, frame.function_name),
+                })
+                .collect(),
+        };
+        for frame in frames {
+            self.print(&frame);
+        }
+    }
+
+    /// Go back to the last write of a variable or an element of the function viewed.
+    fn reverse_to_last_write(&mut self, target: &str, symbol_table: &SymbolTable, current_scope_node_id: &Node<Identifier>) {
+        let function_name = self.get_viewed_function_name(current_scope_node_id);
+        let variable_name = target.split('[').next().unwrap_or(target).trim();
+        let variable = self.get_viewed_scope(symbol_table, current_scope_node_id)
+            .and_then(|scope| scope.get_variables().find(|variable| variable.get_id().name == variable_name));
+        let current_value = |index: Option<usize>| match (variable, index) {
+            (Some(Variable::NormalVar(normal_var_data)), None) => normal_var_data.get_value().cloned(),
+            (Some(Variable::ArrayVar(array_var_data)), Some(index)) => array_var_data.get_value(index).cloned(),
+            _ => None,
+        };
+        let Some(slot_write) = self.history.reverse_to_last_write(&function_name, target, current_value) else {
+            self.print(&format!("No write of {} in function {} before this statement", target, function_name));
+            return;
+        };
+
+        let describe_value = |value: &Option<Value>| {
+            value.as_ref().map_or("<unassigned>".to_string(), |value| value.to_string())
+        };
+        let messages = [
+            format!("Rewound to {}", describe_step(self.history.get_step(slot_write.step_index))),
+            format!(
+                "{} written: {} -> {}",
+                slot_write.target, describe_value(&slot_write.old_value), describe_value(&slot_write.new_value)
+            ),
+        ];
+        for message in messages {
+            self.print(&message);
+        }
+    }
+
+    /// Read and run commands until one resumes the interpretation.
+    /// Quitting aborts the interpretation with an error at the given span.
+    fn pause(
+        &mut self,
+        sp: Span,
+        symbol_table: &SymbolTable,
+        current_scope_node_id: &Node<Identifier>,
+    ) -> Result<(), SemanticError> {
+        let depth = self.state.get_depth();
+        loop {
+            let _ = write!(self.output, "{}", PROMPT);
+            let _ = self.output.flush();
+            let mut line = String::new();
+            if matches!(self.input.read_line(&mut line), Ok(0) | Err(_)) {
+                // no more commands: run the rest of the program without pausing
+                self.state.breakpoints.clear();
+                self.state.stepping = Stepping::Continue;
+                return Ok(());
+            }
+
+            let (command, argument) = line.trim().split_once(char::is_whitespace)
+                .map_or((line.trim(), ""), |(command, argument)| (command, argument.trim()));
+            match command {
+                "" => {},
+                "step" | "s" if self.history.is_rewound() => {
+                    let step_description = self.history.forward_step().map(describe_step).unwrap_or_default();
+                    let message = match self.history.is_rewound() {
+                        true => format!("Rewound to {}", step_description),
+                        false => format!("Back at {}", step_description),
+                    };
+                    self.print(&message);
+                },
+                "step" | "s" => {
+                    self.state.stepping = Stepping::StepInto;
+                    return Ok(());
+                },
+                "next" | "n" => {
+                    self.state.stepping = Stepping::StepOver(depth);
+                    return Ok(());
+                },
+                "out" | "o" => {
+                    self.state.stepping = Stepping::StepOut(depth);
+                    return Ok(());
+                },
+                "continue" | "c" => {
+                    self.state.stepping = Stepping::Continue;
+                    return Ok(());
+                },
+                "break" | "b" => match Breakpoint::parse(argument) {
+                    Some(breakpoint) => {
+                        self.print(&format!("Breakpoint set at {}", breakpoint));
+                        self.state.breakpoints.push(breakpoint);
+                    },
+                    None => self.print("Expected a line, like: break 12"),
+                },
+                "delete" | "d" => match Breakpoint::parse(argument) {
+                    Some(breakpoint) if self.state.breakpoints.contains(&breakpoint) => {
+                        self.print(&format!("Breakpoint deleted at {}", breakpoint));
+                        self.state.breakpoints.retain(|existing_breakpoint| *existing_breakpoint != breakpoint);
+                    },
+                    _ => self.print(&format!("No breakpoint at {}", argument)),
+                },
+                "breakpoints" => {
+                    let breakpoints: Vec<String> = self.state.breakpoints.iter()
+                        .map(|breakpoint| breakpoint.to_string())
+                        .collect();
+                    for breakpoint in breakpoints {
+                        self.print(&breakpoint);
+                    }
+                },
+                "reverse-step" | "rs" => {
+                    let message = match self.history.reverse_step() {
+                        Some(step) => format!("Rewound to {}", describe_step(step)),
+                        None => "No previous statement".to_string(),
+                    };
+                    self.print(&message);
+                },
+                "last-write" | "lw" => match argument {
+                    "" => self.print("Expected a variable, like: last-write x"),
+                    target => self.reverse_to_last_write(target, symbol_table, current_scope_node_id),
+                },
+                "print" | "p" => self.print_variable(argument, symbol_table, current_scope_node_id),
+                "locals" | "l" => self.print_locals(symbol_table, current_scope_node_id),
+                "backtrace" | "bt" => self.print_backtrace(),
+                "help" | "h" => self.print(HELP),
+                "quit" | "q" => {
+                    return Err(SemanticError::InterpretationAborted(
+                        InterpretationAbortedError::init(sp, "Interpretation stopped from the debugger")
+                    ));
+                },
+                _ => self.print(&format!("Unknown command {}, see help", command)),
+            }
+        }
+    }
+
+    /// Pause on the first runtime error, before it is propagated.
+    /// Resuming or quitting lets the error stop the interpretation.
+    fn pause_on_error(
+        &mut self,
+        sp: Span,
+        error: &SemanticError,
+        symbol_table: &SymbolTable,
+        current_scope_node_id: &Node<Identifier>,
+    ) {
+        if !self.state.check_pause_on_error(error) {
+            return;
+        }
+        let message = format!(
+            "Stopped on error in {} at {}:\n{}",
+            self.state.get_current_function_name(), self.state.get_location(sp), self.state.describe_error(error)
+        );
+        self.print(&message);
+        let _ = self.pause(sp, symbol_table, current_scope_node_id);
+    }
+}
+
+/// Describe a statement run, like `line 3 in square: y = x * x;`.
+fn describe_step(step: &Step) -> String {
+    format!("{} in {}: {}", step.location, step.function_name, step.statement_line)
+}
+
+impl InterpretationHook for Debugger {
+    fn before_statement<'a>(
+        &mut self,
+        statement_node: &Node<'a, Statement<'a>>,
+        symbol_table: &SymbolTable,
+        current_scope_node_id: &Node<'a, Identifier>,
+    ) -> Result<(), SemanticError> {
+        let location = self.state.get_location(statement_node.sp);
+        let statement_line = statement_node.sp.as_str().lines().next().unwrap_or("").trim().to_string();
+        self.history.start_step(
+            location.clone(), self.state.get_current_function_name(), statement_line.clone(), self.state.get_depth()
+        );
+        if self.state.check_pause_before_statement(&location).is_none() {
+            return Ok(());
+        }
+
+        let message = format!(
+            "Paused at {} in {}: {}", location, self.state.get_current_function_name(), statement_line
+        );
+        self.print(&message);
+        self.pause(statement_node.sp, symbol_table, current_scope_node_id)
+    }
+
+    fn after_statement<'a>(
+        &mut self,
+        statement_node: &Node<'a, Statement<'a>>,
+        result: &Result<(), SemanticError>,
+        symbol_table: &SymbolTable,
+        current_scope_node_id: &Node<'a, Identifier>,
+    ) -> Result<(), SemanticError> {
+        self.history.end_step();
+        if let Err(error) = result {
+            self.pause_on_error(statement_node.sp, error, symbol_table, current_scope_node_id);
+        }
+        Ok(())
+    }
+
+    fn enter_function<'a>(
+        &mut self,
+        function_node: &Node<'a, Function<'a>>,
+        _symbol_table: &SymbolTable,
+    ) -> Result<(), SemanticError> {
+        // the writes are recorded from the start of main, the arguments of the calls included
+        if self.state.get_depth() == 0 {
+            self.history = History::default();
+            start_undo_log();
+        }
+        self.state.enter_function(&function_node.data.name.data.name);
+        Ok(())
+    }
+
+    fn exit_function<'a>(
+        &mut self,
+        function_node: &Node<'a, Function<'a>>,
+        result: &Result<Node<'a, Value>, SemanticError>,
+        symbol_table: &SymbolTable,
+    ) -> Result<(), SemanticError> {
+        // an error in the return expression is not caught by the statement hooks
+        if let Err(error) = result {
+            let function_return = &function_node.data.body.data.function_return;
+            self.pause_on_error(function_return.sp, error, symbol_table, &function_node.data.name);
+        }
+        self.state.exit_function();
+        if self.state.get_depth() == 0 {
+            stop_undo_log();
+        }
+        Ok(())
+    }
+}
+
+/// Interpret a program under the debugger, reading the commands from the standard input.
+pub fn run_debugger(file: &Path) {
+    let source = match preprocess_file(file) {
+        Ok(source) => Rc::new(source),
+        Err(error) => {
+            println!("🚧 Preprocessing ERROR: \n {}\n", error);
+            return;
+        },
+    };
+    let file_name = file.file_name().and_then(|file_name| file_name.to_str());
+    let ast = match parse_content_into_ast(&source.content, file_name) {
+        Ok(ast) => ast,
+        Err(error) => {
+            println!("🚧 Syntax or AST Parsing ERROR: \n {}\n", source.relocate_error(error.get_error()));
+            return;
+        },
+    };
+    let mut symbol_table = match build_static_symbol_table(&ast) {
+        Ok(symbol_table) => symbol_table,
+        Err(error) => {
+            println!("🚧 Static semantic ERROR: \n {}\n", source.relocate_error(error.get_error()));
+            return;
+        },
+    };
+
+    println!("Debugging {}, enter help for the commands", file.display());
+    add_hook(Box::new(Debugger::new(
+        Box::new(std::io::stdin().lock()),
+        Box::new(std::io::stdout()),
+        Some(source.clone()),
+    )));
+    let result = interpret_translation_unit(&ast, &mut symbol_table);
+    clear_hooks();
+
+    for warning in take_warnings() {
+        println!("⚠️ Interpretation WARNING: \n {}\n", source.relocate_error(warning));
+    }
+    match result {
+        Ok(program_return_value) => println!("Program return value: {}", program_return_value.data),
+        Err(error) => println!("🚧 Interpretation ERROR: \n {}\n", source.relocate_error(error.get_error())),
+    }
+}
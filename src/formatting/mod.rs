@@ -0,0 +1,602 @@
+pub mod trivia;
+
+use std::path::PathBuf;
+
+use clap::ValueEnum;
+
+use crate::abstract_syntax_tree::nodes::{
+    AST, Node, Declaration, Initializer, Function, FunctionPrototype, StructDefinition, EnumDefinition,
+    Block, Statement, Expression, GetOrSetValue, SizeOfOperand, StringOperand, BinaryOperator, Operator,
+};
+use crate::pipelines::parse_content_into_ast;
+use crate::semantic::errors::SemanticError;
+
+use trivia::{TriviaKind, TriviaTable};
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum BraceStyle {
+    /// opening braces at the end of the line, like `if (a) {`
+    SameLine,
+    /// opening braces on their own line
+    NextLine,
+}
+
+#[derive(Clone, Debug)]
+pub struct FormatOptions {
+    pub indent_width: usize,
+    pub brace_style: BraceStyle,
+    /// Split declarations like `int a, b;` into one declaration per line.
+    pub one_declaration_per_line: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            indent_width: 4,
+            brace_style: BraceStyle::SameLine,
+            one_declaration_per_line: false,
+        }
+    }
+}
+
+// Precedence levels of the grammar, from the disjunction to the primary expressions.
+// An operand is parenthesized when its precedence is lower than the one its position needs.
+const COMPARISON_PRECEDENCES: std::ops::RangeInclusive<u8> = 3..=4;
+const ADDITION_PRECEDENCE: u8 = 5;
+const UNARY_PRECEDENCE: u8 = 7;
+const PRIMARY_PRECEDENCE: u8 = 8;
+
+fn get_binary_precedence(operator: &BinaryOperator) -> u8 {
+    match operator {
+        BinaryOperator::LogicalOr => 1,
+        BinaryOperator::LogicalAnd => 2,
+        BinaryOperator::Equal | BinaryOperator::NotEqual => 3,
+        BinaryOperator::Less | BinaryOperator::Greater
+            | BinaryOperator::LessOrEqual | BinaryOperator::GreaterOrEqual => 4,
+        BinaryOperator::Plus | BinaryOperator::Minus => ADDITION_PRECEDENCE,
+        BinaryOperator::Multiply | BinaryOperator::Divide | BinaryOperator::Modulo => 6,
+    }
+}
+
+/// Get a literal as written in the source, like `2.50` or `10u`.
+/// The span of a literal can include the parentheses around it.
+fn get_literal_text(expression: &Node<Expression>) -> String {
+    expression.sp.as_str()
+        .trim_matches(|c: char| c == '(' || c == ')' || c.is_whitespace())
+        .split_whitespace()
+        .collect()
+}
+
+fn get_precedence(expression: &Node<Expression>) -> u8 {
+    match &expression.data {
+        Expression::BinaryExpression(binary_expression) => get_binary_precedence(&binary_expression.operator),
+        Expression::UnaryExpression(_) => UNARY_PRECEDENCE,
+        // negative literals are folded unary expressions
+        Expression::Literal(_) if get_literal_text(expression).starts_with('-') => UNARY_PRECEDENCE,
+        _ => PRIMARY_PRECEDENCE,
+    }
+}
+
+fn format_string_literal(bytes: &[u8]) -> String {
+    let mut literal = String::from("\"");
+    for byte in bytes {
+        match byte {
+            b'"' => literal.push_str("\\\""),
+            b'\\' => literal.push_str("\\\\"),
+            b'\n' => literal.push_str("\\n"),
+            b'\t' => literal.push_str("\\t"),
+            _ => literal.push(char::from(*byte)),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+fn format_string_operand(operand: &Node<StringOperand>) -> String {
+    match &operand.data {
+        StringOperand::Array(identifier) => identifier.data.name.clone(),
+        StringOperand::Literal(bytes) => format_string_literal(bytes),
+    }
+}
+
+fn format_get_or_set_value(value: &GetOrSetValue) -> String {
+    let mut text = value.identifier.data.name.clone();
+    if let Some(index) = &value.index {
+        text.push_str(&format!("[{}]", format_expression(index)));
+    }
+    for member in &value.members {
+        text.push_str(&format!(".{}", member.data.name));
+    }
+    text
+}
+
+/// Format an operand, with parentheses if its precedence is lower than `min_precedence`.
+fn format_operand(expression: &Node<Expression>, min_precedence: u8) -> String {
+    if get_precedence(expression) < min_precedence {
+        format!("({})", format_expression(expression))
+    } else {
+        format_expression(expression)
+    }
+}
+
+/// Format an expression with spaces around the binary operators,
+/// keeping only the parentheses needed by the precedence of the operators.
+fn format_expression(expression: &Node<Expression>) -> String {
+    match &expression.data {
+        Expression::Literal(_) => get_literal_text(expression),
+        Expression::BinaryExpression(binary_expression) => {
+            let precedence = get_binary_precedence(&binary_expression.operator);
+            // binary operators are left-associative, except comparisons which can't be chained:
+            // the comparisons compared with each other, like `(a < b) == (c < d)`, keep their parentheses
+            let (left_precedence, right_precedence) = match precedence {
+                _ if COMPARISON_PRECEDENCES.contains(&precedence) => (ADDITION_PRECEDENCE, ADDITION_PRECEDENCE),
+                _ => (precedence, precedence + 1),
+            };
+            format!(
+                "{} {} {}",
+                format_operand(&binary_expression.left, left_precedence),
+                binary_expression.operator.as_str(),
+                format_operand(&binary_expression.right, right_precedence),
+            )
+        },
+        Expression::UnaryExpression(unary_expression) => format!(
+            "{}{}",
+            unary_expression.operator.as_str(),
+            format_operand(&unary_expression.expression, PRIMARY_PRECEDENCE),
+        ),
+        Expression::FunctionCall(function_call) => {
+            let arguments: Vec<String> = function_call.arguments.iter().map(format_expression).collect();
+            format!("{}({})", function_call.name.data.name, arguments.join(", "))
+        },
+        Expression::TypeCast(type_cast) => format!(
+            "({}) {}",
+            type_cast.type_specifier,
+            format_operand(&type_cast.expression, PRIMARY_PRECEDENCE),
+        ),
+        Expression::GetOrSetValue(value) => format_get_or_set_value(value),
+        Expression::SizeOf(SizeOfOperand::Type(type_specifier)) => format!("sizeof({})", type_specifier),
+        Expression::SizeOf(SizeOfOperand::Variable(value)) => format!("sizeof({})", format_get_or_set_value(value)),
+        Expression::Length(identifier) => format!("len({})", identifier.data.name),
+        Expression::StringLength(operand) => format!("strlen({})", format_string_operand(operand)),
+        Expression::StringCompare(left, right) => format!(
+            "strcmp({}, {})", format_string_operand(left), format_string_operand(right)
+        ),
+    }
+}
+
+/// Format the part of a declaration after its type, like `a[3] = {1, 2, 3}`.
+fn format_declarator(declaration: &Declaration) -> String {
+    let mut text = declaration.identifier.data.name.clone();
+    if let Some(array_size) = &declaration.array_size {
+        text.push_str(&format!("[{}]", format_expression(array_size)));
+    }
+    if let Some(initializer) = &declaration.initializer {
+        let initializer = match &initializer.data {
+            Initializer::Expression(expression) => format_expression(expression),
+            Initializer::List(elements) => {
+                let elements: Vec<String> = elements.iter().map(format_expression).collect();
+                format!("{{{}}}", elements.join(", "))
+            },
+            Initializer::String(bytes) => format_string_literal(bytes),
+        };
+        text.push_str(&format!(" = {}", initializer));
+    }
+    text
+}
+
+fn format_declaration(declaration: &Declaration) -> String {
+    let const_prefix = if declaration.is_const { "const " } else { "" };
+    format!("{}{} {}", const_prefix, declaration.type_specifier, format_declarator(declaration))
+}
+
+fn format_signature(
+    return_type: &impl std::fmt::Display,
+    name: &str,
+    params: &Option<Vec<Node<Declaration>>>,
+) -> String {
+    let params: Vec<String> = params.iter().flatten()
+        .map(|param| format_declaration(&param.data))
+        .collect();
+    format!("{} {}({})", return_type, name, params.join(", "))
+}
+
+/// A definition of the translation unit. They are formatted in the order of the source.
+enum TopLevelItem<'t, 'a> {
+    Struct(&'t Node<'a, StructDefinition<'a>>),
+    Enum(&'t Node<'a, EnumDefinition<'a>>),
+    Prototype(&'t Node<'a, FunctionPrototype<'a>>),
+    Function(&'t Node<'a, Function<'a>>),
+}
+
+impl TopLevelItem<'_, '_> {
+    fn get_start(&self) -> usize {
+        match self {
+            TopLevelItem::Struct(node) => node.sp.start(),
+            TopLevelItem::Enum(node) => node.sp.start(),
+            TopLevelItem::Prototype(node) => node.sp.start(),
+            TopLevelItem::Function(node) => node.sp.start(),
+        }
+    }
+}
+
+/// Write the AST of a source file back into formatted source, with the trivia of the file
+/// (comments and directives) where they were. Blank lines between items are kept, at most one.
+struct Formatter<'c> {
+    options: &'c FormatOptions,
+    content: &'c str,
+    // the content without its trivia, to look for the tokens that are not in the AST, like braces
+    masked_content: &'c str,
+    trivia: TriviaTable,
+    output: String,
+    indent_level: usize,
+}
+
+impl<'c> Formatter<'c> {
+    fn write_line(&mut self, line: &str) {
+        self.output.push_str(&" ".repeat(self.indent_level * self.options.indent_width));
+        self.output.push_str(line);
+        self.output.push('\n');
+    }
+
+    /// Separate what comes next with a blank line, except at the start of the file or of a block.
+    fn ensure_blank_line(&mut self) {
+        if !self.output.is_empty() && !self.output.ends_with("\n\n") && !self.output.ends_with("{\n") {
+            self.output.push('\n');
+        }
+    }
+
+    fn has_blank_line_before(&self, offset: usize) -> bool {
+        let before = &self.content[..offset];
+        let blank_start = before.trim_end().len();
+        before[blank_start..].matches('\n').count() >= 2
+    }
+
+    /// Get the position of the next occurrence of a token, outside of the trivia.
+    fn find_after(&self, offset: usize, token: &str) -> usize {
+        self.masked_content[offset..].find(token).map_or(self.content.len(), |index| offset + index)
+    }
+
+    fn is_next_token(&self, offset: usize, token: &str) -> bool {
+        self.masked_content[offset..].trim_start().starts_with(token)
+    }
+
+    /// Write the comments and directives that are before an offset and have not been written yet.
+    fn write_trivia_before(&mut self, offset: usize) {
+        let trivia: Vec<(TriviaKind, usize, String)> = self.trivia.take_before(offset).iter()
+            .map(|trivia| (trivia.kind, trivia.start, trivia.text.clone()))
+            .collect();
+        for (kind, start, text) in trivia {
+            if self.has_blank_line_before(start) {
+                self.ensure_blank_line();
+            }
+            match kind {
+                // directives stay at the start of their line
+                TriviaKind::Directive => {
+                    self.output.push_str(&text);
+                    self.output.push('\n');
+                },
+                // the next lines of block comments are kept as they are
+                TriviaKind::Comment => {
+                    let mut lines = text.lines();
+                    self.write_line(lines.next().unwrap_or_default());
+                    for line in lines {
+                        self.output.push_str(line.trim_end());
+                        self.output.push('\n');
+                    }
+                },
+            }
+        }
+    }
+
+    fn start_item(&mut self, start: usize) {
+        self.write_trivia_before(start);
+        if self.has_blank_line_before(start) {
+            self.ensure_blank_line();
+        }
+    }
+
+    /// Append the comment following an item on the same line, if any.
+    fn end_item(&mut self, end: usize) {
+        if let Some(comment) = self.trivia.take_trailing_comment(self.content, end) {
+            self.output.pop();
+            self.output.push(' ');
+            self.output.push_str(&comment.text);
+            self.output.push('\n');
+        }
+    }
+
+    /// Write an item on its own line, like a declaration or an assignment.
+    /// The comments inside the item are written before it.
+    fn write_item(&mut self, start: usize, end: usize, line: &str) {
+        self.start_item(start);
+        self.write_trivia_before(end);
+        self.write_line(line);
+        self.end_item(end);
+    }
+
+    fn open_block(&mut self, header: &str) {
+        match self.options.brace_style {
+            BraceStyle::SameLine => self.write_line(&format!("{} {{", header)),
+            BraceStyle::NextLine => {
+                self.write_line(header);
+                self.write_line("{");
+            },
+        }
+        self.indent_level += 1;
+    }
+
+    fn close_block(&mut self, closing_brace: usize, suffix: &str) {
+        self.write_trivia_before(closing_brace);
+        self.indent_level -= 1;
+        self.write_line(&format!("}}{}", suffix));
+    }
+
+    fn format_translation_unit(&mut self, ast: &AST) {
+        let translation_unit = &ast.data;
+        let mut items: Vec<TopLevelItem> = Vec::new();
+        items.extend(translation_unit.structs.iter().flatten().map(TopLevelItem::Struct));
+        items.extend(translation_unit.enums.iter().flatten().map(TopLevelItem::Enum));
+        items.extend(translation_unit.prototypes.iter().flatten().map(TopLevelItem::Prototype));
+        items.extend(translation_unit.functions.values().map(TopLevelItem::Function));
+        items.push(TopLevelItem::Function(&translation_unit.main_function));
+        items.sort_by_key(|item| item.get_start());
+
+        let mut is_previous_item_a_block = false;
+        for (index, item) in items.iter().enumerate() {
+            // definitions with a body are always separated by a blank line
+            let is_block = !matches!(item, TopLevelItem::Prototype(_));
+            if index > 0 && (is_block || is_previous_item_a_block) {
+                self.ensure_blank_line();
+            }
+            is_previous_item_a_block = is_block;
+            match item {
+                TopLevelItem::Struct(struct_definition) => self.format_struct(struct_definition),
+                TopLevelItem::Enum(enum_definition) => self.format_enum(enum_definition),
+                TopLevelItem::Prototype(prototype) => self.write_item(
+                    prototype.sp.start(),
+                    prototype.sp.end(),
+                    &format!("{};", format_signature(
+                        &prototype.data.return_type, &prototype.data.name.data.name, &prototype.data.params
+                    )),
+                ),
+                TopLevelItem::Function(function) => self.format_function(function),
+            }
+        }
+        self.write_trivia_before(self.content.len());
+    }
+
+    fn format_struct(&mut self, struct_definition: &Node<StructDefinition>) {
+        self.start_item(struct_definition.sp.start());
+        self.open_block(&format!("struct {}", struct_definition.data.name.data.name));
+        self.format_declarations(&struct_definition.data.fields);
+        let last_field_end = struct_definition.data.fields.last().map_or(struct_definition.sp.start(), |field| field.sp.end());
+        let closing_brace = self.find_after(last_field_end, "}");
+        self.close_block(closing_brace, ";");
+        self.end_item(struct_definition.sp.end());
+    }
+
+    fn format_enum(&mut self, enum_definition: &Node<EnumDefinition>) {
+        self.start_item(enum_definition.sp.start());
+        self.open_block(&format!("enum {}", enum_definition.data.name.data.name));
+        let enumerators = &enum_definition.data.enumerators;
+        for (index, enumerator) in enumerators.iter().enumerate() {
+            let is_last = index + 1 == enumerators.len();
+            let mut line = enumerator.data.identifier.data.name.clone();
+            if let Some(value) = &enumerator.data.value {
+                line.push_str(&format!(" = {}", format_expression(value)));
+            }
+            let end = if is_last {
+                enumerator.sp.end()
+            } else {
+                line.push(',');
+                self.find_after(enumerator.sp.end(), ",") + 1
+            };
+            self.write_item(enumerator.sp.start(), end, &line);
+        }
+        let last_enumerator_end = enumerators.last().map_or(enum_definition.sp.start(), |enumerator| enumerator.sp.end());
+        let closing_brace = self.find_after(last_enumerator_end, "}");
+        self.close_block(closing_brace, ";");
+        self.end_item(enum_definition.sp.end());
+    }
+
+    /// Format declarations, keeping the ones declared together, like `int a, b;`, on the same line
+    /// unless each declaration must be on its own line.
+    fn format_declarations(&mut self, declarations: &[Node<Declaration>]) {
+        let mut group_start = 0;
+        while group_start < declarations.len() {
+            let mut group_end = group_start;
+            while group_end + 1 < declarations.len() && self.is_next_token(declarations[group_end].sp.end(), ",") {
+                group_end += 1;
+            }
+            let group = &declarations[group_start..=group_end];
+            let semicolon_end = self.find_after(group[group.len() - 1].sp.end(), ";") + 1;
+
+            if self.options.one_declaration_per_line || group.len() == 1 {
+                for (index, declaration) in group.iter().enumerate() {
+                    let end = if index + 1 == group.len() {
+                        semicolon_end
+                    } else {
+                        self.find_after(declaration.sp.end(), ",") + 1
+                    };
+                    self.write_item(declaration.sp.start(), end, &format!("{};", format_declaration(&declaration.data)));
+                }
+            } else {
+                let declarators: Vec<String> = group[1..].iter()
+                    .map(|declaration| format_declarator(&declaration.data))
+                    .collect();
+                let line = format!("{}, {};", format_declaration(&group[0].data), declarators.join(", "));
+                self.write_item(group[0].sp.start(), semicolon_end, &line);
+            }
+            group_start = group_end + 1;
+        }
+    }
+
+    fn format_function(&mut self, function: &Node<Function>) {
+        self.start_item(function.sp.start());
+        // comments in the signature are written before it
+        self.write_trivia_before(function.data.body.sp.start());
+        let header = format_signature(&function.data.return_type, &function.data.name.data.name, &function.data.params);
+        self.open_block(&header);
+        self.format_block_content(&function.data.body);
+        self.close_block(function.data.body.sp.end() - 1, "");
+        self.end_item(function.sp.end());
+    }
+
+    fn format_block_content(&mut self, block: &Node<Block>) {
+        self.format_declarations(&block.data.declarations);
+        self.format_statements(&block.data.statements);
+
+        let last_item_end = match (block.data.statements.last(), block.data.declarations.last()) {
+            (Some(statement), _) => statement.sp.end(),
+            (None, Some(declaration)) => declaration.sp.end(),
+            (None, None) => block.sp.start(),
+        };
+        let return_start = self.find_after(last_item_end, "return");
+        let function_return = &block.data.function_return;
+        let return_end = self.find_after(function_return.sp.end(), ";") + 1;
+        self.write_item(return_start, return_end, &format!("return {};", format_expression(function_return)));
+    }
+
+    fn format_statements(&mut self, statements: &[Node<Statement>]) {
+        for statement in statements {
+            self.format_statement(statement);
+        }
+    }
+
+    fn format_statement(&mut self, statement: &Node<Statement>) {
+        let (start, end) = (statement.sp.start(), statement.sp.end());
+        match &statement.data {
+            Statement::Assignment(assignment) => self.write_item(start, end, &format!(
+                "{} = {};",
+                format_get_or_set_value(&assignment.left_var.data),
+                format_expression(&assignment.right_expr),
+            )),
+            Statement::Assert(assert_statement) => self.write_item(start, end, &format!(
+                "assert({});", format_expression(&assert_statement.condition)
+            )),
+            Statement::StringCopy(string_copy) => self.write_item(start, end, &format!(
+                "strcpy({}, {});",
+                string_copy.destination.data.name,
+                format_string_operand(&string_copy.source),
+            )),
+            Statement::If(if_statement) => {
+                self.start_item(start);
+                self.write_trivia_before(if_statement.condition.sp.end());
+                self.open_block(&format!("if ({})", format_expression(&if_statement.condition)));
+                self.format_statements(&if_statement.if_body);
+                let last_if_end = if_statement.if_body.last()
+                    .map_or(if_statement.condition.sp.end(), |statement| statement.sp.end());
+                let if_closing_brace = self.find_after(last_if_end, "}");
+                match &if_statement.else_body {
+                    None => self.close_block(if_closing_brace, ""),
+                    Some(else_body) => {
+                        self.write_trivia_before(if_closing_brace);
+                        self.indent_level -= 1;
+                        match self.options.brace_style {
+                            BraceStyle::SameLine => self.write_line("} else {"),
+                            BraceStyle::NextLine => {
+                                self.write_line("}");
+                                self.write_line("else");
+                                self.write_line("{");
+                            },
+                        }
+                        self.indent_level += 1;
+                        self.format_statements(else_body);
+                        self.close_block(end - 1, "");
+                    },
+                }
+                self.end_item(end);
+            },
+            Statement::While(while_statement) => {
+                self.start_item(start);
+                self.write_trivia_before(while_statement.condition.sp.end());
+                self.open_block(&format!("while ({})", format_expression(&while_statement.condition)));
+                self.format_statements(&while_statement.body);
+                self.close_block(end - 1, "");
+                self.end_item(end);
+            },
+            Statement::Switch(switch_statement) => {
+                self.start_item(start);
+                self.write_trivia_before(switch_statement.expression.sp.end());
+                self.open_block(&format!("switch ({})", format_expression(&switch_statement.expression)));
+                for case in &switch_statement.cases {
+                    self.start_item(case.sp.start());
+                    self.write_trivia_before(case.data.label.sp.end());
+                    self.write_line(&format!("case {}:", format_expression(&case.data.label)));
+                    self.indent_level += 1;
+                    self.format_statements(&case.data.body);
+                    self.indent_level -= 1;
+                }
+                if let Some(default_body) = &switch_statement.default_body {
+                    let last_case_end = switch_statement.cases.last()
+                        .map_or(switch_statement.expression.sp.end(), |case| case.sp.end());
+                    self.start_item(self.find_after(last_case_end, "default"));
+                    self.write_line("default:");
+                    self.indent_level += 1;
+                    self.format_statements(default_body);
+                    self.indent_level -= 1;
+                }
+                self.close_block(end - 1, "");
+                self.end_item(end);
+            },
+        }
+    }
+}
+
+/// Format the content of a Ctiny file: indentation, spaces around operators, braces and declarations,
+/// keeping its comments and directives.
+/// The file is parsed without being preprocessed, so its macros must be used like identifiers or function calls.
+pub fn format_source(content: &str, options: &FormatOptions) -> Result<String, SemanticError> {
+    let (trivia, masked_content) = TriviaTable::collect(content);
+    let ast = parse_content_into_ast(&masked_content, None)?;
+    let mut formatter = Formatter {
+        options,
+        content,
+        masked_content: &masked_content,
+        trivia,
+        output: String::new(),
+        indent_level: 0,
+    };
+    formatter.format_translation_unit(&ast);
+    Ok(formatter.output)
+}
+
+/// Format files in place or, with `check`, only list the ones that are not formatted.
+/// Return false if a file could not be formatted, or is not formatted in check mode.
+pub fn run_formatter(files: &[PathBuf], check: bool, options: &FormatOptions) -> bool {
+    let mut is_success = true;
+    for file in files {
+        let content = match std::fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(error) => {
+                log::error!("🚧 Cannot read file {}: {}", file.display(), error);
+                is_success = false;
+                continue;
+            },
+        };
+        let formatted_content = match format_source(&content, options) {
+            Ok(formatted_content) => formatted_content,
+            Err(error) => {
+                log::error!(
+                    "🚧 Formatting ERROR: \n {}\n",
+                    error.get_error().with_path(file.to_string_lossy().as_ref())
+                );
+                is_success = false;
+                continue;
+            },
+        };
+        if formatted_content == content {
+            continue;
+        }
+        if check {
+            println!("Not formatted: {}", file.display());
+            is_success = false;
+        } else if let Err(error) = std::fs::write(file, formatted_content) {
+            log::error!("🚧 Cannot write file {}: {}", file.display(), error);
+            is_success = false;
+        } else {
+            println!("Formatted: {}", file.display());
+        }
+    }
+    is_success
+}
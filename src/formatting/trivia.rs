@@ -0,0 +1,121 @@
+/// What the parser does not see: comments are dropped by the `COMMENT` rule,
+/// and directive lines are removed by the preprocessor.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TriviaKind {
+    Comment,
+    Directive,
+}
+
+/// A comment or a directive line of a source file, at `start..end`.
+#[derive(Debug, PartialEq)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+/// The trivia of a source file, in the order of the file, side by side with its AST.
+/// The formatter takes them as it reaches their position, so that each one is written once.
+pub struct TriviaTable {
+    trivia: Vec<Trivia>,
+    next_index: usize,
+}
+
+/// Replace a part of a text by spaces, keeping its line breaks and its offsets.
+fn mask(masked_bytes: &mut [u8], start: usize, end: usize) {
+    for byte in &mut masked_bytes[start..end] {
+        if *byte != b'\n' {
+            *byte = b' ';
+        }
+    }
+}
+
+impl TriviaTable {
+    /// Collect the trivia of a source file, and get its content without them,
+    /// with the same offsets so that the spans of the AST also point into the original content.
+    pub fn collect(content: &str) -> (TriviaTable, String) {
+        let bytes = content.as_bytes();
+        let mut masked_bytes = bytes.to_vec();
+        let mut trivia = Vec::new();
+        let mut is_line_start = true;
+        let mut position = 0;
+        while position < bytes.len() {
+            let start = position;
+            let end = match bytes[position] {
+                b'#' if is_line_start => {
+                    let end = content[start..].find('\n').map_or(content.len(), |index| start + index);
+                    trivia.push(Trivia {
+                        kind: TriviaKind::Directive,
+                        start,
+                        end,
+                        text: content[start..end].trim_end().to_string(),
+                    });
+                    mask(&mut masked_bytes, start, end);
+                    end
+                },
+                b'/' if content[start..].starts_with("//") || content[start..].starts_with("/*") => {
+                    let end = if content[start..].starts_with("//") {
+                        content[start..].find('\n').map_or(content.len(), |index| start + index)
+                    } else {
+                        content[start + 2..].find("*/").map_or(content.len(), |index| start + 2 + index + 2)
+                    };
+                    trivia.push(Trivia {
+                        kind: TriviaKind::Comment,
+                        start,
+                        end,
+                        text: content[start..end].trim_end().to_string(),
+                    });
+                    mask(&mut masked_bytes, start, end);
+                    end
+                },
+                // comment markers in string literals are not comments
+                b'"' => {
+                    let mut end = start + 1;
+                    while end < bytes.len() && bytes[end] != b'"' && bytes[end] != b'\n' {
+                        end += if bytes[end] == b'\\' { 2 } else { 1 };
+                    }
+                    (end + 1).min(bytes.len())
+                },
+                _ => start + 1,
+            };
+            // directives are the first non-blank characters of their line
+            for byte in &bytes[start..end] {
+                match byte {
+                    b'\n' => is_line_start = true,
+                    b' ' | b'\t' | b'\r' => {},
+                    _ => is_line_start = false,
+                }
+            }
+            position = end;
+        }
+        // only comments and directives were replaced, by ASCII spaces
+        let masked_content = String::from_utf8(masked_bytes).unwrap();
+        (TriviaTable { trivia, next_index: 0 }, masked_content)
+    }
+
+    /// Take the trivia starting before an offset, that have not been taken yet.
+    pub fn take_before(&mut self, offset: usize) -> &[Trivia] {
+        let start_index = self.next_index;
+        while self.next_index < self.trivia.len() && self.trivia[self.next_index].start < offset {
+            self.next_index += 1;
+        }
+        &self.trivia[start_index..self.next_index]
+    }
+
+    /// Take the next trivia if it is a comment on the same line as the code ending at `end`,
+    /// like `a = 1; // comment`.
+    pub fn take_trailing_comment(&mut self, content: &str, end: usize) -> Option<&Trivia> {
+        let next_trivia = self.trivia.get(self.next_index)?;
+        let is_trailing = next_trivia.kind == TriviaKind::Comment
+            && next_trivia.start >= end
+            && content[end..next_trivia.start].trim().is_empty()
+            && !content[end..next_trivia.start].contains('\n')
+            && !next_trivia.text.contains('\n');
+        if !is_trailing {
+            return None;
+        }
+        self.next_index += 1;
+        Some(next_trivia)
+    }
+}
@@ -0,0 +1,144 @@
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use pest::Span;
+
+use crate::semantic::errors::{
+    SemanticError, SemanticErrorTrait, StepLimitError, StackOverflowError, TimeoutError
+};
+
+pub const DEFAULT_MAX_STEPS: u64 = 1_000_000;
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 1_000;
+
+// Each call of a Ctiny function takes several frames of the interpreter on the Rust stack:
+// up to about 64 KiB in a debug build for a call nested in a loop, a condition and an expression.
+// The interpreter runs in a thread with a large stack, and the call depth is bounded
+// so that this stack can't overflow.
+pub const MAX_CALL_DEPTH: usize = 5_000;
+pub const INTERPRETER_STACK_SIZE: usize = 1024 * 1024 * 1024;
+
+/// The limits guarding the interpretation of programs that may not terminate.
+///
+/// A step is the interpretation of a statement or an expression, so the step budget (the fuel)
+/// bounds all the loops and calls of a program together. The call depth is the number
+/// of nested function calls from `main`, it can't exceed `MAX_CALL_DEPTH`. `None` means no limit,
+/// except `MAX_CALL_DEPTH` for the call depth.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ExecutionLimits {
+    pub max_steps: Option<u64>,
+    pub max_call_depth: Option<usize>,
+    pub timeout: Option<Duration>,
+}
+
+const DEFAULT_EXECUTION_LIMITS: ExecutionLimits = ExecutionLimits {
+    max_steps: Some(DEFAULT_MAX_STEPS),
+    max_call_depth: Some(DEFAULT_MAX_CALL_DEPTH),
+    timeout: None,
+};
+
+impl Default for ExecutionLimits {
+    fn default() -> Self {
+        DEFAULT_EXECUTION_LIMITS
+    }
+}
+
+/// What the current interpretation has used of its limits.
+#[derive(Copy, Clone, Debug)]
+struct ExecutionState {
+    steps: u64,
+    call_depth: usize,
+    deadline: Option<Instant>,
+}
+
+thread_local! {
+    static EXECUTION_LIMITS: Cell<ExecutionLimits> = const { Cell::new(DEFAULT_EXECUTION_LIMITS) };
+    static EXECUTION_STATE: Cell<ExecutionState> = const {
+        Cell::new(ExecutionState {
+            steps: 0,
+            call_depth: 0,
+            deadline: None,
+        })
+    };
+}
+
+/// Run the interpreter in a new thread with a stack of `INTERPRETER_STACK_SIZE`, and wait for its result.
+/// The per-thread settings have to be selected in this thread.
+pub fn run_with_interpreter_stack<T: Send + 'static>(run: impl FnOnce() -> T + Send + 'static) -> T {
+    let interpreter_thread = std::thread::Builder::new()
+        .name("interpreter".to_string())
+        .stack_size(INTERPRETER_STACK_SIZE)
+        .spawn(run)
+        .expect("Cannot start the thread of the interpreter");
+    interpreter_thread.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+}
+
+/// Select the limits used for all subsequent interpretation in the current thread.
+pub fn set_execution_limits(execution_limits: ExecutionLimits) {
+    EXECUTION_LIMITS.with(|current| current.set(execution_limits));
+}
+
+pub fn get_execution_limits() -> ExecutionLimits {
+    EXECUTION_LIMITS.with(|current| current.get())
+}
+
+/// Start a new interpretation with all its budget: no step used,
+/// no call in progress, and the timeout counted from now.
+pub fn start_execution() {
+    let deadline = get_execution_limits().timeout.map(|timeout| Instant::now() + timeout);
+    EXECUTION_STATE.with(|state| state.set(ExecutionState { steps: 0, call_depth: 0, deadline }));
+}
+
+/// Count a step of the interpretation, at the given statement or expression,
+/// failing if the step budget is exhausted or the timeout is reached.
+pub fn count_step<'a>(span: Span<'a>) -> Result<(), SemanticError> {
+    let limits = get_execution_limits();
+    let mut state = EXECUTION_STATE.with(|state| state.get());
+    state.steps += 1;
+    EXECUTION_STATE.with(|current| current.set(state));
+
+    if let Some(max_steps) = limits.max_steps {
+        if state.steps > max_steps {
+            return Err(SemanticError::StepLimit(StepLimitError::init(
+                span,
+                &format!("Maximum number of steps reached (max: {}), the program may not terminate.", max_steps),
+            )));
+        }
+    }
+    if let (Some(deadline), Some(timeout)) = (state.deadline, limits.timeout) {
+        if Instant::now() > deadline {
+            return Err(SemanticError::Timeout(TimeoutError::init(
+                span,
+                &format!("Timeout reached after {} ms, the program may not terminate.", timeout.as_millis()),
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Enter a function call, at the given call site, failing if the maximum call depth is reached.
+/// A successful call must be followed by `exit_call` once the function returns.
+pub fn enter_call<'a>(span: Span<'a>, function_name: &str) -> Result<(), SemanticError> {
+    let mut state = EXECUTION_STATE.with(|state| state.get());
+    let max_call_depth = get_execution_limits().max_call_depth
+        .map_or(MAX_CALL_DEPTH, |max_call_depth| max_call_depth.min(MAX_CALL_DEPTH));
+    if state.call_depth >= max_call_depth {
+        return Err(SemanticError::StackOverflow(StackOverflowError::init(
+            span,
+            &format!(
+                "Maximum call depth reached (max: {}) when calling {}, the recursion may not terminate.",
+                max_call_depth, function_name
+            ),
+        )));
+    }
+    state.call_depth += 1;
+    EXECUTION_STATE.with(|current| current.set(state));
+    Ok(())
+}
+
+pub fn exit_call() {
+    EXECUTION_STATE.with(|state| {
+        let mut current_state = state.get();
+        current_state.call_depth = current_state.call_depth.saturating_sub(1);
+        state.set(current_state);
+    });
+}
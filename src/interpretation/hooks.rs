@@ -0,0 +1,193 @@
+use std::cell::RefCell;
+
+use crate::abstract_syntax_tree::nodes::{Node, Identifier, Expression, Statement, Function, Value};
+use crate::semantic::errors::SemanticError;
+use crate::symbol_table::structs::SymbolTable;
+
+/// A tool following the interpretation of a program, like a debugger.
+///
+/// The interpreter calls the hooks around each statement, expression and function,
+/// with the current state of the symbol table. All the methods do nothing by default.
+/// An error returned by a hook stops the interpretation with this error.
+pub trait InterpretationHook {
+    fn before_statement<'a>(
+        &mut self,
+        _statement_node: &Node<'a, Statement<'a>>,
+        _symbol_table: &SymbolTable,
+        _current_scope_node_id: &Node<'a, Identifier>,
+    ) -> Result<(), SemanticError> {
+        Ok(())
+    }
+
+    /// Called after a statement, with its result. On an error, the hooks are called
+    /// for each statement and function being unwound, from the innermost one.
+    fn after_statement<'a>(
+        &mut self,
+        _statement_node: &Node<'a, Statement<'a>>,
+        _result: &Result<(), SemanticError>,
+        _symbol_table: &SymbolTable,
+        _current_scope_node_id: &Node<'a, Identifier>,
+    ) -> Result<(), SemanticError> {
+        Ok(())
+    }
+
+    fn before_expression<'a>(
+        &mut self,
+        _expression_node: &Node<'a, Expression<'a>>,
+        _symbol_table: &SymbolTable,
+        _current_scope_node_id: &Node<'a, Identifier>,
+    ) -> Result<(), SemanticError> {
+        Ok(())
+    }
+
+    fn after_expression<'a>(
+        &mut self,
+        _expression_node: &Node<'a, Expression<'a>>,
+        _result: &Result<Node<'a, Value>, SemanticError>,
+        _symbol_table: &SymbolTable,
+        _current_scope_node_id: &Node<'a, Identifier>,
+    ) -> Result<(), SemanticError> {
+        Ok(())
+    }
+
+    /// Called after an assignment statement has stored its value, with the assigned
+    /// variable, element or member (like `t[1].x`), and its value before the assignment if it had one.
+    fn after_assignment<'a>(
+        &mut self,
+        _statement_node: &Node<'a, Statement<'a>>,
+        _target: &str,
+        _old_value: Option<&Value>,
+        _new_value: &Value,
+    ) -> Result<(), SemanticError> {
+        Ok(())
+    }
+
+    /// Called when a value is implicitly converted to another type by `cast_to_type`,
+    /// like an operand of an arithmetic conversion, or a value assigned to a variable of another type.
+    fn on_implicit_cast<'a>(
+        &mut self,
+        _input_node: &Node<'a, Value>,
+        _output_value: &Value,
+    ) -> Result<(), SemanticError> {
+        Ok(())
+    }
+
+    /// Called when a function starts, once its arguments have been set (`main` included).
+    fn enter_function<'a>(
+        &mut self,
+        _function_node: &Node<'a, Function<'a>>,
+        _symbol_table: &SymbolTable,
+    ) -> Result<(), SemanticError> {
+        Ok(())
+    }
+
+    fn exit_function<'a>(
+        &mut self,
+        _function_node: &Node<'a, Function<'a>>,
+        _result: &Result<Node<'a, Value>, SemanticError>,
+        _symbol_table: &SymbolTable,
+    ) -> Result<(), SemanticError> {
+        Ok(())
+    }
+}
+
+thread_local! {
+    static HOOKS: RefCell<Vec<Box<dyn InterpretationHook>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Follow all subsequent interpretation in the current thread with the given hook.
+pub fn add_hook(hook: Box<dyn InterpretationHook>) {
+    HOOKS.with(|hooks| hooks.borrow_mut().push(hook));
+}
+
+/// Remove all the hooks of the current thread.
+pub fn clear_hooks() {
+    HOOKS.with(|hooks| hooks.borrow_mut().clear());
+}
+
+/// Whether a hook follows the interpretation in the current thread,
+/// to skip the work only needed by the hooks.
+pub fn has_hooks() -> bool {
+    HOOKS.with(|hooks| hooks.try_borrow().is_ok_and(|hooks| !hooks.is_empty()))
+}
+
+/// Call all the hooks in the order they were added, stopping at the first error.
+/// The interpretation done by a hook itself, like evaluating an expression, is not followed.
+fn call_hooks(
+    mut call_hook: impl FnMut(&mut dyn InterpretationHook) -> Result<(), SemanticError>,
+) -> Result<(), SemanticError> {
+    HOOKS.with(|hooks| {
+        let Ok(mut hooks) = hooks.try_borrow_mut() else {
+            return Ok(());
+        };
+        for hook in hooks.iter_mut() {
+            call_hook(hook.as_mut())?;
+        }
+        Ok(())
+    })
+}
+
+pub fn call_before_statement<'a>(
+    statement_node: &Node<'a, Statement<'a>>,
+    symbol_table: &SymbolTable,
+    current_scope_node_id: &Node<'a, Identifier>,
+) -> Result<(), SemanticError> {
+    call_hooks(|hook| hook.before_statement(statement_node, symbol_table, current_scope_node_id))
+}
+
+pub fn call_after_statement<'a>(
+    statement_node: &Node<'a, Statement<'a>>,
+    result: &Result<(), SemanticError>,
+    symbol_table: &SymbolTable,
+    current_scope_node_id: &Node<'a, Identifier>,
+) -> Result<(), SemanticError> {
+    call_hooks(|hook| hook.after_statement(statement_node, result, symbol_table, current_scope_node_id))
+}
+
+pub fn call_before_expression<'a>(
+    expression_node: &Node<'a, Expression<'a>>,
+    symbol_table: &SymbolTable,
+    current_scope_node_id: &Node<'a, Identifier>,
+) -> Result<(), SemanticError> {
+    call_hooks(|hook| hook.before_expression(expression_node, symbol_table, current_scope_node_id))
+}
+
+pub fn call_after_expression<'a>(
+    expression_node: &Node<'a, Expression<'a>>,
+    result: &Result<Node<'a, Value>, SemanticError>,
+    symbol_table: &SymbolTable,
+    current_scope_node_id: &Node<'a, Identifier>,
+) -> Result<(), SemanticError> {
+    call_hooks(|hook| hook.after_expression(expression_node, result, symbol_table, current_scope_node_id))
+}
+
+pub fn call_after_assignment<'a>(
+    statement_node: &Node<'a, Statement<'a>>,
+    target: &str,
+    old_value: Option<&Value>,
+    new_value: &Value,
+) -> Result<(), SemanticError> {
+    call_hooks(|hook| hook.after_assignment(statement_node, target, old_value, new_value))
+}
+
+pub fn call_on_implicit_cast<'a>(
+    input_node: &Node<'a, Value>,
+    output_value: &Value,
+) -> Result<(), SemanticError> {
+    call_hooks(|hook| hook.on_implicit_cast(input_node, output_value))
+}
+
+pub fn call_enter_function<'a>(
+    function_node: &Node<'a, Function<'a>>,
+    symbol_table: &SymbolTable,
+) -> Result<(), SemanticError> {
+    call_hooks(|hook| hook.enter_function(function_node, symbol_table))
+}
+
+pub fn call_exit_function<'a>(
+    function_node: &Node<'a, Function<'a>>,
+    result: &Result<Node<'a, Value>, SemanticError>,
+    symbol_table: &SymbolTable,
+) -> Result<(), SemanticError> {
+    call_hooks(|hook| hook.exit_function(function_node, result, symbol_table))
+}
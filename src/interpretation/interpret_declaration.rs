@@ -1,7 +1,13 @@
-use crate::abstract_syntax_tree::nodes::{Node, Declaration, Identifier};
+use std::collections::HashMap;
+
+use crate::abstract_syntax_tree::nodes::{Node, Declaration, Identifier, Initializer, TranslationUnit, Value};
+use crate::semantic::const_evaluation::evaluate_array_size;
 use crate::semantic::errors::SemanticError;
 use crate::symbol_table::structs::{SymbolTable, Variable, NormalVarData, ArrayVarData};
 
+use super::interpret_expression::interpret_expression;
+use super::undo_log::record_writes;
+
 fn interpret_normal_declaration<'a>(
     declaration_node: &Node<'a, Declaration<'a>>,
     symbol_table: &mut SymbolTable,
@@ -15,7 +21,7 @@ fn interpret_normal_declaration<'a>(
     let normal_var = Variable::NormalVar(
         NormalVarData::new(
             identifier_node.data.clone(),
-            declaration.type_specifier,
+            declaration.type_specifier.clone(),
         )
     );
     current_scope.add_variable(Node {
@@ -39,7 +45,7 @@ fn interpret_array_declaration<'a>(
     let array_var = Variable::ArrayVar(
         ArrayVarData::new(
             identifier_node.data.clone(),
-        declaration.type_specifier,
+        declaration.type_specifier.clone(),
         size,
         )
     );
@@ -58,9 +64,10 @@ pub fn interpret_declaration<'a>(
     let declaration = &declaration_node.data;
 
     // we need to know if we are declaring a normal variable or an array
-    match declaration.array_size {
-        Some(size) => {
+    match &declaration.array_size {
+        Some(size_expression) => {
             // we are declaring an array
+            let size = evaluate_array_size(size_expression, &HashMap::new())?;
             interpret_array_declaration(declaration_node, symbol_table, current_scope_node_id, size)
         },
         None => {
@@ -68,4 +75,56 @@ pub fn interpret_declaration<'a>(
             interpret_normal_declaration(declaration_node, symbol_table, current_scope_node_id)
         },
     }
-}
\ No newline at end of file
+}
+
+/// Assign the initializer list of a non-const array, like `int a[5] = {1, 2, 3};`
+/// or `char s[16] = "hello";`.
+/// Unlike const arrays, its elements can be any expression, so they are evaluated
+/// each time the function is called, and the elements that are not given are set to zero.
+pub fn interpret_array_initializer<'a>(
+    declaration_node: &Node<'a, Declaration<'a>>,
+    symbol_table: &mut SymbolTable,
+    current_scope_node_id: &Node<'a, Identifier>,
+    translation_unit: &TranslationUnit<'a>,
+) -> Result<(), SemanticError> {
+    let declaration = &declaration_node.data;
+    let elements = match &declaration.initializer {
+        Some(initializer) if initializer.data.is_zero_list() => &Vec::new(),
+        Some(Node { data: Initializer::List(elements), .. }) => elements,
+        Some(Node { data: Initializer::String(string), sp }) => {
+            let current_scope = symbol_table.get_mut_scope(current_scope_node_id).unwrap();
+            let undo_entries = current_scope.get_undo_entries(&declaration.identifier.data, None);
+            if let Variable::ArrayVar(array_var_data) = current_scope.get_mut_variable(&declaration.identifier)? {
+                array_var_data.set_string(string, *sp)?;
+                array_var_data.set_zero_values(string.len() + 1);
+            }
+            record_writes(undo_entries);
+            return Ok(());
+        },
+        // only arrays have a list, and only const variables have a scalar initializer
+        _ => return Ok(()),
+    };
+
+    for (index, element) in elements.iter().enumerate() {
+        let value_node = interpret_expression(
+            element,
+            symbol_table,
+            current_scope_node_id,
+            translation_unit,
+        )?;
+        let index_node = Node {
+            sp: element.sp,
+            data: Value::Int(index as i64),
+        };
+        let current_scope = symbol_table.get_mut_scope(current_scope_node_id).unwrap();
+        current_scope.set_array_variable_value(&declaration.identifier, index_node, value_node)?;
+    }
+
+    let current_scope = symbol_table.get_mut_scope(current_scope_node_id).unwrap();
+    let undo_entries = current_scope.get_undo_entries(&declaration.identifier.data, None);
+    if let Variable::ArrayVar(array_var_data) = current_scope.get_mut_variable(&declaration.identifier)? {
+        array_var_data.set_zero_values(elements.len());
+    }
+    record_writes(undo_entries);
+    Ok(())
+}
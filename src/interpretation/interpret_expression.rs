@@ -1,30 +1,28 @@
-use crate::abstract_syntax_tree::nodes::{Node, Value, Expression, Identifier, UnaryOperator, TypeSpecifier, TranslationUnit};
+use crate::abstract_syntax_tree::nodes::{Node, Value, Expression, Identifier, TranslationUnit};
 use crate::semantic::errors::{SemanticError, UnexpectedExpressionParsingError, SemanticErrorTrait, ArgumentNumberMismatchError};
-use crate::semantic::operations::perform_binary_operation;
-use crate::semantic::type_casts::cast_to_type;
+use crate::semantic::operations::{perform_binary_operation, perform_unary_operation};
+use crate::semantic::type_casts::convert_to_type;
 use crate::symbol_table::structs::SymbolTable;
 
+use super::execution_limits::{count_step, enter_call, exit_call};
+use super::hooks::{call_before_expression, call_after_expression};
 use super::interpret_function::interpret_function;
+use super::interpret_string::interpret_string_function;
 
 fn interpret_potential_index<'a>(
     potential_index: &Option<Box<Node<'a, Expression<'a>>>>,
     symbol_table: &mut SymbolTable,
     current_scope_node_id: &Node<'a, Identifier>,
     translation_unit: &TranslationUnit<'a>,
-) -> Option<Node<'a, Value>> {
+) -> Result<Option<Node<'a, Value>>, SemanticError> {
     match potential_index {
         Some(index) => {
             let interpreted_index = interpret_expression(
-                &index, symbol_table, current_scope_node_id, translation_unit
-            );
-            match interpreted_index {
-                Ok(interpreted_index) => Some(interpreted_index),
-                Err(error) => {
-                    panic!("Error interpreting index: {}", error);
-                },
-            }
+                index, symbol_table, current_scope_node_id, translation_unit
+            )?;
+            Ok(Some(interpreted_index))
         },
-        None => None,
+        None => Ok(None),
     }
 }
 
@@ -54,12 +52,20 @@ fn interpret_get_value<'a>(
     };
     let identifier = &get_or_set_value.identifier;
     let potential_index = &get_or_set_value.index;
+
+    // enumerators are global constants (variables can't have the name of an enumerator)
+    if potential_index.is_none() && get_or_set_value.members.is_empty() {
+        if let Some(enumerator_value) = symbol_table.get_enumerator_value(identifier) {
+            return Ok(enumerator_value);
+        }
+    }
+
     let potential_index_value = interpret_potential_index(
         potential_index, symbol_table, current_scope_node_id, translation_unit
-    );
+    )?;
 
     let current_scope = symbol_table.get_scope(current_scope_node_id).unwrap();
-    current_scope.get_variable_value(identifier, potential_index_value)
+    current_scope.get_variable_value(identifier, potential_index_value, &get_or_set_value.members)
 }
 
 fn interpret_type_cast<'a>(
@@ -86,12 +92,13 @@ fn interpret_type_cast<'a>(
             },
         }
     };
-    let target_type = type_cast.type_specifier;
+    let target_type = type_cast.type_specifier.clone();
     let interpreted_expression = interpret_expression(
         &type_cast.expression, symbol_table, current_scope_node_id, translation_unit
     )?;
 
-    cast_to_type(
+    // explicit casts are not implicit conversions, for the interpretation hooks
+    convert_to_type(
         interpreted_expression, target_type
     )
 }
@@ -123,72 +130,7 @@ fn interpret_unary_expression<'a>(
     let interpreted_expression = interpret_expression(
         &unary_expression.expression, symbol_table, current_scope_node_id, translation_unit
     )?;
-    match unary_expression.operator {
-        UnaryOperator::Negation => {
-            match interpreted_expression.data {
-                Value::Int(int) => {
-                    Ok(Node {
-                        sp: expression_node.sp,
-                        data: Value::Int(-int),
-                    })
-                },
-                Value::Float(float) => {
-                    Ok(Node {
-                        sp: expression_node.sp,
-                        data: Value::Float(-float),
-                    })
-                },
-                // negation of a char is just ignored
-                Value::Char(_) => {
-                    Ok(Node {
-                        sp: expression_node.sp,
-                        data: interpreted_expression.data,
-                    })
-                },
-                // negation of a bool is just ignored
-                Value::Bool(_) => {
-                    Ok(Node {
-                        sp: expression_node.sp,
-                        data: interpreted_expression.data,
-                    })
-                },
-            }
-        },
-        UnaryOperator::Not => {
-            match interpreted_expression.data {
-                Value::Bool(bool) => {
-                    Ok(Node {
-                        sp: expression_node.sp,
-                        data: Value::Bool(!bool),
-                    })
-                },
-                // if Value is not a bool, convert to bool and then negate
-                not_bool_value => {
-                    let casted_bool_value = cast_to_type(
-                        Node {
-                            sp: expression_node.sp,
-                            data: not_bool_value,
-                        },
-                        TypeSpecifier::Bool,
-                    )?;
-                    match casted_bool_value.data {
-                        Value::Bool(bool) => {
-                            Ok(Node {
-                                sp: expression_node.sp,
-                                data: Value::Bool(!bool),
-                            })
-                        },
-                        unexpected_non_bool_value => {
-                            panic!(
-                                "In interpret_unary_expression, cast to bool of {:?} failed in ", 
-                                unexpected_non_bool_value
-                            );
-                        },
-                    }
-                }
-            }
-        },
-    }
+    perform_unary_operation(interpreted_expression, &unary_expression.operator, expression_node.sp)
 }
 
 fn interpret_binary_expression<'a>(
@@ -295,22 +237,43 @@ fn interpret_function_call<'a>(
         )?;
     }
 
-    // interpret function
-    interpret_function(
-        translation_unit.get_function_node(function_id_node)?,
+    // interpret function, guarding against the recursions that don't terminate
+    let function_node = translation_unit.get_function_node(function_id_node.clone())?;
+    enter_call(function_call_node.sp, &function_id_node.data.name)?;
+    let result = interpret_function(
+        function_node,
         symbol_table,
         translation_unit,
-    )
+    );
+    exit_call();
+    // the value of a call is located at the call site, not at the return statement of the function
+    result.map(|value_node| Node {
+        sp: function_call_node.sp,
+        data: value_node.data,
+    })
 }
 
 
 
-/// interpret an expression and return a value
+/// interpret an expression and return a value, calling the interpretation hooks around it
 pub fn interpret_expression<'a>(
     expression_node: &Node<'a, Expression<'a>>,
     symbol_table: &mut SymbolTable,
     current_scope_node_id: &Node<'a, Identifier>,
     translation_unit: &TranslationUnit<'a>, // for function calls
+) -> Result<Node<'a, Value>, SemanticError> {
+    count_step(expression_node.sp)?;
+    call_before_expression(expression_node, symbol_table, current_scope_node_id)?;
+    let result = dispatch_expression(expression_node, symbol_table, current_scope_node_id, translation_unit);
+    call_after_expression(expression_node, &result, symbol_table, current_scope_node_id)?;
+    result
+}
+
+fn dispatch_expression<'a>(
+    expression_node: &Node<'a, Expression<'a>>,
+    symbol_table: &mut SymbolTable,
+    current_scope_node_id: &Node<'a, Identifier>,
+    translation_unit: &TranslationUnit<'a>,
 ) -> Result<Node<'a, Value>, SemanticError> {
     match &expression_node.data {
         Expression::Literal(literal) => {
@@ -360,5 +323,12 @@ pub fn interpret_expression<'a>(
                 translation_unit
             )
         }
+        Expression::SizeOf(_) | Expression::Length(_) => {
+            // the operand is not evaluated, the size only depends on declarations
+            symbol_table.evaluate_size_expression(expression_node, current_scope_node_id)
+        }
+        Expression::StringLength(_) | Expression::StringCompare(_, _) => {
+            interpret_string_function(expression_node, symbol_table, current_scope_node_id)
+        }
     }
 }
\ No newline at end of file
@@ -3,6 +3,9 @@ use crate::semantic::errors::SemanticError;
 use crate::semantic::type_casts::cast_to_type;
 use crate::symbol_table::structs::SymbolTable;
 
+use super::execution_limits::start_execution;
+use super::hooks::{call_enter_function, call_exit_function};
+use super::interpret_declaration::interpret_array_initializer;
 use super::interpret_expression::interpret_expression;
 use super::interpret_statement::interpret_statement;
 
@@ -14,6 +17,17 @@ pub fn interpret_function<'a>(
     symbol_table: &mut SymbolTable,
     translation_unit: &TranslationUnit<'a>,
 ) -> Result<Node<'a, Value>, SemanticError> {
+    call_enter_function(function_node, symbol_table)?;
+    let result = interpret_function_body(function_node, symbol_table, translation_unit);
+    call_exit_function(function_node, &result, symbol_table)?;
+    result
+}
+
+fn interpret_function_body<'a>(
+    function_node: &Node<'a, Function<'a>>,
+    symbol_table: &mut SymbolTable,
+    translation_unit: &TranslationUnit<'a>,
+) -> Result<Node<'a, Value>, SemanticError> {
 
     // get function scope
     let function_scope_id_node = &function_node.data.name;
@@ -28,6 +42,17 @@ pub fn interpret_function<'a>(
     //         function_scope_id_node
     //     )?;
     // }
+    // Only the initializer lists of non-const arrays depend on the current call.
+    for declaration in &function_body.data.declarations {
+        if !declaration.data.is_const {
+            interpret_array_initializer(
+                &declaration,
+                symbol_table,
+                function_scope_id_node,
+                translation_unit,
+            )?;
+        }
+    }
     for statement in &function_body.data.statements {
         interpret_statement(
             &statement, 
@@ -46,7 +71,7 @@ pub fn interpret_function<'a>(
     )?;
     let return_of_function_type: Node<'a, Value> = cast_to_type(
         return_value_node, 
-        function_node.data.return_type
+        function_node.data.return_type.clone()
     )?;
 
     // before returning, check that all variables have been assigned a value
@@ -61,7 +86,8 @@ pub fn interpret_translation_unit<'a>(
     translation_unit: &Node<'a, TranslationUnit<'a>>,
     symbol_table: &mut SymbolTable,
 ) -> Result<Node<'a, Value>, SemanticError> {
-    // interpret main function
+    // interpret main function, with the whole budget of the execution limits
+    start_execution();
     let main_function_node = &translation_unit.data.main_function;
     interpret_function(
         &main_function_node, 
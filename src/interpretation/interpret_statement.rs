@@ -1,10 +1,13 @@
-use crate::abstract_syntax_tree::nodes::{Node, Value, Expression, Identifier, TypeSpecifier, Statement, TranslationUnit};
-use crate::params::MAX_NB_OF_LOOP_ITERATIONS;
-use crate::semantic::errors::{SemanticError, SemanticErrorTrait, UnexpectedStatementParsingError, UnexpectedTypeCastError, MaxLoopIterationError};
-use crate::semantic::type_casts::cast_to_type;
+use crate::abstract_syntax_tree::nodes::{Node, Value, Expression, Identifier, TypeSpecifier, Statement, TranslationUnit, BinaryOperator};
+use crate::semantic::errors::{SemanticError, SemanticErrorTrait, UnexpectedStatementParsingError, UnexpectedExpressionParsingError, UnexpectedTypeCastError, InvalidOperandTypeError, AssertionFailedError};
+use crate::semantic::operations::{perform_binary_operation, perform_unary_operation};
+use crate::semantic::type_casts::{cast_to_type, convert_to_type, get_index_value_from_value_node};
 use crate::symbol_table::structs::SymbolTable;
 
+use super::execution_limits::count_step;
+use super::hooks::{call_before_statement, call_after_statement, call_after_assignment, has_hooks};
 use super::interpret_expression::interpret_expression;
+use super::interpret_string::{interpret_string_copy_statement, interpret_string_function};
 
 fn interpret_assignment_statement<'a>(
     statement_node: &Node<'a, Statement<'a>>,
@@ -39,30 +42,71 @@ fn interpret_assignment_statement<'a>(
 
     let var_id_node = assignment_statement.left_var.data.identifier.clone();
     let potential_index_node = &assignment_statement.left_var.data.index;
+    let members = &assignment_statement.left_var.data.members;
 
-    // We need to know if the assignment operation is on a normal variable or an array.
-    if let Some(index_expr_node) = potential_index_node {
-        // We want to assign a value into an array. We need to interpret the index-expression to get a usable index.
-        let index_value_node = interpret_expression(
+    // For an array element, possibly with struct members, we need to interpret the index-expression to get a usable index.
+    let potential_index_value_node = match potential_index_node {
+        Some(index_expr_node) => Some(interpret_expression(
             &index_expr_node, symbol_table, current_scope_node_id, translation_unit
-        )?;
+        )?),
+        None => None,
+    };
+
+    // the hooks get the assigned value before and after the assignment
+    let potential_old_value = match has_hooks() {
+        true => symbol_table.get_scope(current_scope_node_id).unwrap()
+            .get_variable_value(&var_id_node, potential_index_value_node.clone(), members)
+            .ok(),
+        false => None,
+    };
+
+    // NOTE: after the use of immutable borrow for symbol_table, we need to use mutable borrow for current_scope
+    let current_scope = symbol_table.get_mut_scope(current_scope_node_id).unwrap();
 
+    // We need to know if the assignment operation is on a struct member, a normal variable or an array.
+    if !members.is_empty() {
+        // We want to assign a value into a struct member, potentially of an array element.
+        current_scope.set_struct_member_value(
+            &var_id_node,
+            potential_index_value_node.clone(),
+            members,
+            assignment_value_node,
+        )?;
+    } else if let Some(index_value_node) = potential_index_value_node.clone() {
         // try to set the value of the array
-        // NOTE: after the use of immutable borrow for symbol_table, we need to use mutable borrow for current_scope
-        let current_scope = symbol_table.get_mut_scope(current_scope_node_id).unwrap(); 
-        return current_scope.set_array_variable_value(
+        current_scope.set_array_variable_value(
             &var_id_node,
             index_value_node,
             assignment_value_node,
-        );
+        )?;
     } else {
         // we are working with a normal variable. We can just set the value.
-        let current_scope = symbol_table.get_mut_scope(current_scope_node_id).unwrap();
-        return current_scope.set_normal_variable_value(
+        current_scope.set_normal_variable_value(
             &var_id_node,
             assignment_value_node,
-        );
+        )?;
     }
+
+    if has_hooks() {
+        let new_value_node = current_scope.get_variable_value(
+            &var_id_node, potential_index_value_node.clone(), members
+        )?;
+        // the assigned element or member, like `t[1].x`
+        let mut target = var_id_node.data.name.clone();
+        if let Some(index) = potential_index_value_node.and_then(|index_node| index_node.data.as_integer()) {
+            target.push_str(&format!("[{}]", index));
+        }
+        for member in members {
+            target.push_str(&format!(".{}", member.data.name));
+        }
+        call_after_assignment(
+            statement_node,
+            &target,
+            potential_old_value.as_ref().map(|old_value_node| &old_value_node.data),
+            &new_value_node.data,
+        )?;
+    }
+    Ok(())
 }
 
 fn get_bool_from_condition_interpretation<'a>(
@@ -193,21 +237,8 @@ fn interpret_while_statement<'a>(
     )?;
 
     // then, interpret what should be done according to the condition
-    let mut loop_number: u32 = 0;
+    // NOTE: the loops that don't terminate exhaust the step budget, see `execution_limits`
     while real_condition {
-        // check if the iteration limit has been reached
-        if loop_number >= *MAX_NB_OF_LOOP_ITERATIONS {
-            return Err(SemanticError::MaxLoopIteration(
-                MaxLoopIterationError::init(
-                    while_statement_node.sp,
-                    format!(
-                        "Maximum number of loop iteration reached (max: {}).",
-                        *MAX_NB_OF_LOOP_ITERATIONS
-                    ).as_str(),
-                )
-            ));
-        }
-
         // interpret the while block
         let while_body = &while_statement.body;
         for statement_node in while_body {
@@ -226,19 +257,328 @@ fn interpret_while_statement<'a>(
             current_scope_node_id,
             translation_unit,
         )?;
-        loop_number += 1;
     }
 
     // once all the statements of all loops have been interpreted, we can return
     Ok(())
 }
 
-/// Interpret a statement and returns a value as result.
+fn interpret_switch_statement<'a>(
+    switch_statement_node: &Node<'a, Statement<'a>>,
+    symbol_table: &mut SymbolTable,
+    current_scope_node_id: &Node<'a, Identifier>,
+    translation_unit: &TranslationUnit<'a>,
+) -> Result<(), SemanticError> {
+    let switch_statement = match &switch_statement_node.data {
+        Statement::Switch(switch_statement) => {
+            switch_statement
+        },
+        _ => {
+            return Err(SemanticError::UnexpectedStatementParsing(
+                UnexpectedStatementParsingError::init(
+                    switch_statement_node.sp,
+                    format!(
+                        "interpret_switch_statement called on a non SwitchStatement expression: {:?}", 
+                        switch_statement_node.data
+                    ).as_str(),
+                )
+            ));
+        },
+    };
+
+    // first, interpret the switch expression, which must be an integer (enums included)
+    let switch_value_node = interpret_expression(
+        &switch_statement.expression, 
+        symbol_table, 
+        current_scope_node_id,
+        translation_unit,
+    )?;
+    if switch_value_node.data.as_integer().is_none() {
+        return Err(SemanticError::InvalidOperandType(
+            InvalidOperandTypeError::init(
+                switch_value_node.sp,
+                format!(
+                    "Switch expression must be an integer, got {}", 
+                    switch_value_node.data
+                ).as_str(),
+            )
+        ));
+    }
+
+    // then, find the matching case (labels are checked to be distinct constants before interpretation)
+    // NOTE: there is no fall through, only the body of the matching case is interpreted
+    let mut matching_body = switch_statement.default_body.as_ref();
+    for case in &switch_statement.cases {
+        let label_value_node = interpret_expression(
+            &case.data.label, 
+            symbol_table, 
+            current_scope_node_id,
+            translation_unit,
+        )?;
+        let is_matching_case = perform_binary_operation(
+            &switch_value_node, &label_value_node, &BinaryOperator::Equal
+        )?;
+        if is_matching_case.data == Value::Bool(true) {
+            matching_body = Some(&case.data.body);
+            break;
+        }
+    }
+
+    if let Some(body) = matching_body {
+        for statement_node in body {
+            interpret_statement(
+                statement_node, 
+                symbol_table, 
+                current_scope_node_id,
+                translation_unit,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// The same access is only reported once.
+fn push_variable_access<'a, 'b>(
+    expression_node: &'b Node<'a, Expression<'a>>,
+    variable_accesses: &mut Vec<&'b Node<'a, Expression<'a>>>,
+) {
+    if !variable_accesses.iter().any(|access| access.sp.as_str().trim() == expression_node.sp.as_str().trim()) {
+        variable_accesses.push(expression_node);
+    }
+}
+
+/// Whether evaluating an expression calls a function, which could have side effects.
+fn has_function_call(expression_node: &Node<Expression>) -> bool {
+    match &expression_node.data {
+        Expression::FunctionCall(_) => true,
+        Expression::UnaryExpression(unary_expression) => has_function_call(&unary_expression.expression),
+        Expression::BinaryExpression(binary_expression) => {
+            has_function_call(&binary_expression.left) || has_function_call(&binary_expression.right)
+        },
+        Expression::TypeCast(type_cast) => has_function_call(&type_cast.expression),
+        Expression::GetOrSetValue(get_or_set_value) => {
+            get_or_set_value.index.as_ref().is_some_and(|index| has_function_call(index))
+        },
+        _ => false,
+    }
+}
+
+/// Get the variable accesses (like `x`, `a[i]`, `p.x` or `strlen(s)`) of an expression,
+/// including the ones in indexes and function call arguments.
+/// The accesses are read again to report their values, so the ones
+/// with a function call in their index, like `a[f(i)]`, are left out.
+fn collect_variable_accesses<'a, 'b>(
+    expression_node: &'b Node<'a, Expression<'a>>,
+    symbol_table: &SymbolTable,
+    variable_accesses: &mut Vec<&'b Node<'a, Expression<'a>>>,
+) {
+    match &expression_node.data {
+        // the operand of sizeof and len is not evaluated
+        Expression::Literal(_) | Expression::SizeOf(_) | Expression::Length(_) => {},
+        Expression::UnaryExpression(unary_expression) => {
+            collect_variable_accesses(&unary_expression.expression, symbol_table, variable_accesses);
+        },
+        Expression::BinaryExpression(binary_expression) => {
+            collect_variable_accesses(&binary_expression.left, symbol_table, variable_accesses);
+            collect_variable_accesses(&binary_expression.right, symbol_table, variable_accesses);
+        },
+        Expression::FunctionCall(function_call) => {
+            for argument in &function_call.arguments {
+                collect_variable_accesses(argument, symbol_table, variable_accesses);
+            }
+        },
+        Expression::TypeCast(type_cast) => {
+            collect_variable_accesses(&type_cast.expression, symbol_table, variable_accesses);
+        },
+        Expression::GetOrSetValue(get_or_set_value) => {
+            if let Some(index) = &get_or_set_value.index {
+                collect_variable_accesses(index, symbol_table, variable_accesses);
+            }
+            // enumerators are constants, not variables
+            let is_potential_enumerator = get_or_set_value.index.is_none() && get_or_set_value.members.is_empty();
+            if is_potential_enumerator && symbol_table.get_enumerator_value(&get_or_set_value.identifier).is_some() {
+                return;
+            }
+            if has_function_call(expression_node) {
+                return;
+            }
+            push_variable_access(expression_node, variable_accesses);
+        },
+        // the strings of strlen and strcmp are reported through their results
+        Expression::StringLength(_) | Expression::StringCompare(_, _) => {
+            push_variable_access(expression_node, variable_accesses);
+        },
+    }
+}
+
+/// Read the value of an expression without calling the hooks or counting steps,
+/// to report the values of an assertion without running the program further.
+fn read_expression_value<'a>(
+    expression_node: &Node<'a, Expression<'a>>,
+    symbol_table: &SymbolTable,
+    current_scope_node_id: &Node<'a, Identifier>,
+) -> Result<Node<'a, Value>, SemanticError> {
+    match &expression_node.data {
+        Expression::Literal(literal) => Ok(Node { sp: expression_node.sp, data: literal.clone() }),
+        Expression::UnaryExpression(unary_expression) => {
+            let value = read_expression_value(&unary_expression.expression, symbol_table, current_scope_node_id)?;
+            perform_unary_operation(value, &unary_expression.operator, expression_node.sp)
+        },
+        Expression::BinaryExpression(binary_expression) => {
+            let left_value = read_expression_value(&binary_expression.left, symbol_table, current_scope_node_id)?;
+            let right_value = read_expression_value(&binary_expression.right, symbol_table, current_scope_node_id)?;
+            perform_binary_operation(&left_value, &right_value, &binary_expression.operator)
+        },
+        Expression::TypeCast(type_cast) => {
+            let value = read_expression_value(&type_cast.expression, symbol_table, current_scope_node_id)?;
+            convert_to_type(value, type_cast.type_specifier.clone())
+        },
+        Expression::GetOrSetValue(get_or_set_value) => {
+            let identifier = &get_or_set_value.identifier;
+            if get_or_set_value.index.is_none() && get_or_set_value.members.is_empty() {
+                if let Some(enumerator_value) = symbol_table.get_enumerator_value(identifier) {
+                    return Ok(enumerator_value);
+                }
+            }
+            let potential_index_value = match &get_or_set_value.index {
+                Some(index) => Some(read_expression_value(index, symbol_table, current_scope_node_id)?),
+                None => None,
+            };
+            let current_scope = symbol_table.get_scope(current_scope_node_id).unwrap();
+            current_scope.get_variable_value(identifier, potential_index_value, &get_or_set_value.members)
+        },
+        Expression::SizeOf(_) | Expression::Length(_) => {
+            symbol_table.evaluate_size_expression(expression_node, current_scope_node_id)
+        },
+        Expression::StringLength(_) | Expression::StringCompare(_, _) => {
+            interpret_string_function(expression_node, symbol_table, current_scope_node_id)
+        },
+        // calling a function would run the program further
+        Expression::FunctionCall(_) => Err(SemanticError::UnexpectedExpressionParsing(
+            UnexpectedExpressionParsingError::init(
+                expression_node.sp,
+                "read_expression_value called on a FunctionCall expression",
+            )
+        )),
+    }
+}
+
+/// Whether an array access, like `a[i]`, has its index outside of the array.
+fn is_index_out_of_bounds<'a>(
+    expression_node: &Node<'a, Expression<'a>>,
+    symbol_table: &SymbolTable,
+    current_scope_node_id: &Node<'a, Identifier>,
+) -> bool {
+    let Expression::GetOrSetValue(get_or_set_value) = &expression_node.data else {
+        return false;
+    };
+    let Some(index) = &get_or_set_value.index else {
+        return false;
+    };
+    let index_value = read_expression_value(index, symbol_table, current_scope_node_id)
+        .and_then(get_index_value_from_value_node);
+    match index_value {
+        Ok(index_value) => symbol_table.get_scope(current_scope_node_id).unwrap()
+            .get_array_variable(&get_or_set_value.identifier)
+            .is_ok_and(|array_var_data| index_value >= array_var_data.size),
+        Err(SemanticError::NegativeArrayIndex(_)) => true,
+        Err(_) => false,
+    }
+}
+
+/// Describe the value of a variable access for the report of a failed assertion,
+/// or `None` if it can't be read for another reason than an unassigned value or a bad index.
+fn describe_variable_access_value<'a>(
+    variable_access: &Node<'a, Expression<'a>>,
+    symbol_table: &SymbolTable,
+    current_scope_node_id: &Node<'a, Identifier>,
+) -> Option<String> {
+    match read_expression_value(variable_access, symbol_table, current_scope_node_id) {
+        Ok(value_node) => Some(value_node.data.to_string()),
+        Err(SemanticError::UnassignedVariable(_)) => Some("<unassigned>".to_string()),
+        // the elements of an array are only stored once assigned
+        Err(SemanticError::UndeclaredVariable(_) | SemanticError::NegativeArrayIndex(_)) => {
+            if is_index_out_of_bounds(variable_access, symbol_table, current_scope_node_id) {
+                Some("<out of bounds>".to_string())
+            } else {
+                Some("<unassigned>".to_string())
+            }
+        },
+        Err(_) => None,
+    }
+}
+
+fn interpret_assert_statement<'a>(
+    assert_statement_node: &Node<'a, Statement<'a>>,
+    symbol_table: &mut SymbolTable,
+    current_scope_node_id: &Node<'a, Identifier>,
+    translation_unit: &TranslationUnit<'a>,
+) -> Result<(), SemanticError> {
+    let assert_statement = match &assert_statement_node.data {
+        Statement::Assert(assert_statement) => {
+            assert_statement
+        },
+        _ => {
+            return Err(SemanticError::UnexpectedStatementParsing(
+                UnexpectedStatementParsingError::init(
+                    assert_statement_node.sp,
+                    format!(
+                        "interpret_assert_statement called on a non AssertStatement expression: {:?}", 
+                        assert_statement_node.data
+                    ).as_str(),
+                )
+            ));
+        },
+    };
+
+    let real_condition = get_bool_from_condition_interpretation(
+        &assert_statement.condition, 
+        symbol_table, 
+        current_scope_node_id,
+        translation_unit,
+    )?;
+    if real_condition {
+        return Ok(());
+    }
+
+    // report the values of the variables used in the condition, to help understand the failure
+    let mut variable_accesses = Vec::new();
+    collect_variable_accesses(&assert_statement.condition, symbol_table, &mut variable_accesses);
+    let mut variable_values = Vec::new();
+    for variable_access in variable_accesses {
+        if let Some(variable_value) = describe_variable_access_value(variable_access, symbol_table, current_scope_node_id) {
+            variable_values.push(format!("{} = {}", variable_access.sp.as_str().trim(), variable_value));
+        }
+    }
+
+    let mut message = format!("Assertion failed: {}", assert_statement.condition.sp.as_str().trim());
+    if !variable_values.is_empty() {
+        message = format!("{} (with {})", message, variable_values.join(", "));
+    }
+    Err(SemanticError::AssertionFailed(
+        AssertionFailedError::init(assert_statement_node.sp, message.as_str())
+    ))
+}
+
+/// Interpret a statement, calling the interpretation hooks around it.
 pub fn interpret_statement<'a>(
     statement_node: &Node<'a, Statement<'a>>,
     symbol_table: &mut SymbolTable,
     current_scope_node_id: &Node<'a, Identifier>,
     translation_unit: &TranslationUnit<'a>,
+) -> Result<(), SemanticError> {
+    count_step(statement_node.sp)?;
+    call_before_statement(statement_node, symbol_table, current_scope_node_id)?;
+    let result = dispatch_statement(statement_node, symbol_table, current_scope_node_id, translation_unit);
+    call_after_statement(statement_node, &result, symbol_table, current_scope_node_id)?;
+    result
+}
+
+fn dispatch_statement<'a>(
+    statement_node: &Node<'a, Statement<'a>>,
+    symbol_table: &mut SymbolTable,
+    current_scope_node_id: &Node<'a, Identifier>,
+    translation_unit: &TranslationUnit<'a>,
 ) -> Result<(), SemanticError> {
     match &statement_node.data {
         Statement::Assignment(_) => {
@@ -265,5 +605,28 @@ pub fn interpret_statement<'a>(
                 translation_unit,
             )
         }
+        Statement::Switch(_) => {
+            interpret_switch_statement(
+                statement_node, 
+                symbol_table, 
+                current_scope_node_id,
+                translation_unit,
+            )
+        }
+        Statement::Assert(_) => {
+            interpret_assert_statement(
+                statement_node, 
+                symbol_table, 
+                current_scope_node_id,
+                translation_unit,
+            )
+        }
+        Statement::StringCopy(_) => {
+            interpret_string_copy_statement(
+                statement_node, 
+                symbol_table, 
+                current_scope_node_id,
+            )
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,132 @@
+use crate::abstract_syntax_tree::nodes::{Node, Identifier, Expression, Statement, StringOperand, TypeSpecifier, Value};
+use crate::semantic::errors::{SemanticError, SemanticErrorTrait, InvalidStringOperandError, UnexpectedExpressionParsingError, UnexpectedStatementParsingError};
+use crate::semantic::type_casts::cast_to_type;
+use crate::symbol_table::structs::{SymbolTable, Variable, ArrayVarData};
+
+use super::undo_log::record_writes;
+
+fn make_invalid_string_operand_error<'a>(
+    operand_node: &Node<'a, Identifier>,
+    message: &str,
+) -> SemanticError {
+    SemanticError::InvalidStringOperand(
+        InvalidStringOperandError::init(operand_node.sp, message)
+    )
+}
+
+/// Strings are only held by char arrays.
+fn check_is_char_array<'a>(
+    array_var_data: &ArrayVarData,
+    array_identifier: &Node<'a, Identifier>,
+) -> Result<(), SemanticError> {
+    if array_var_data.type_specifier != TypeSpecifier::Char {
+        return Err(make_invalid_string_operand_error(
+            array_identifier,
+            &format!(
+                "Array {} is an array of {}, only char arrays can hold strings",
+                array_identifier.data.name, array_var_data.type_specifier
+            )
+        ));
+    }
+    Ok(())
+}
+
+/// Get the characters of a string operand, without its terminating NUL.
+fn get_string_operand_value<'a>(
+    operand_node: &Node<'a, StringOperand<'a>>,
+    symbol_table: &SymbolTable,
+    current_scope_node_id: &Node<'a, Identifier>,
+) -> Result<Vec<u8>, SemanticError> {
+    match &operand_node.data {
+        StringOperand::Literal(string) => Ok(string.clone()),
+        StringOperand::Array(array_identifier) => {
+            let current_scope = symbol_table.get_scope(current_scope_node_id).unwrap();
+            let array_var_data = current_scope.get_array_variable(array_identifier)?;
+            check_is_char_array(array_var_data, array_identifier)?;
+            array_var_data.get_string(array_identifier.sp)
+        },
+    }
+}
+
+/// Interpret a `strlen(s)` or `strcmp(s1, s2)` expression.
+/// Like in C, `strcmp` returns the difference between the first different characters
+/// of its operands (the terminating NUL included), so 0 if they are equal.
+pub fn interpret_string_function<'a>(
+    expression_node: &Node<'a, Expression<'a>>,
+    symbol_table: &SymbolTable,
+    current_scope_node_id: &Node<'a, Identifier>,
+) -> Result<Node<'a, Value>, SemanticError> {
+    let result = match &expression_node.data {
+        Expression::StringLength(operand_node) => {
+            let string = get_string_operand_value(operand_node, symbol_table, current_scope_node_id)?;
+            Value::UnsignedLong(string.len() as u64)
+        },
+        Expression::StringCompare(left_operand_node, right_operand_node) => {
+            let left_string = get_string_operand_value(left_operand_node, symbol_table, current_scope_node_id)?;
+            let right_string = get_string_operand_value(right_operand_node, symbol_table, current_scope_node_id)?;
+            let difference = left_string.iter().chain(std::iter::once(&0))
+                .zip(right_string.iter().chain(std::iter::once(&0)))
+                .map(|(left_char, right_char)| *left_char as i64 - *right_char as i64)
+                .find(|difference| *difference != 0)
+                .unwrap_or(0);
+            Value::Int(difference)
+        },
+        _ => return Err(SemanticError::UnexpectedExpressionParsing(
+            UnexpectedExpressionParsingError::init(
+                expression_node.sp,
+                format!(
+                    "interpret_string_function called on a non string function expression: {:?}",
+                    expression_node.data
+                ).as_str(),
+            )
+        )),
+    };
+    cast_to_type(
+        Node { sp: expression_node.sp, data: result },
+        TypeSpecifier::Int,
+    )
+}
+
+/// Interpret a `strcpy(destination, source);` statement.
+/// The source string and its terminating NUL must fit in the destination array.
+pub fn interpret_string_copy_statement<'a>(
+    string_copy_statement_node: &Node<'a, Statement<'a>>,
+    symbol_table: &mut SymbolTable,
+    current_scope_node_id: &Node<'a, Identifier>,
+) -> Result<(), SemanticError> {
+    let string_copy_statement = match &string_copy_statement_node.data {
+        Statement::StringCopy(string_copy_statement) => {
+            string_copy_statement
+        },
+        _ => {
+            return Err(SemanticError::UnexpectedStatementParsing(
+                UnexpectedStatementParsingError::init(
+                    string_copy_statement_node.sp,
+                    format!(
+                        "interpret_string_copy_statement called on a non StringCopyStatement expression: {:?}",
+                        string_copy_statement_node.data
+                    ).as_str(),
+                )
+            ));
+        },
+    };
+
+    let source_string = get_string_operand_value(
+        &string_copy_statement.source, symbol_table, current_scope_node_id
+    )?;
+    let destination = &string_copy_statement.destination;
+    let current_scope = symbol_table.get_mut_scope(current_scope_node_id).unwrap();
+    let undo_entries = current_scope.get_undo_entries(&destination.data, None);
+    match current_scope.get_mut_variable(destination)? {
+        Variable::ArrayVar(array_var_data) => {
+            check_is_char_array(array_var_data, destination)?;
+            array_var_data.set_string(&source_string, string_copy_statement_node.sp)?;
+            record_writes(undo_entries);
+            Ok(())
+        },
+        Variable::NormalVar(_) => Err(make_invalid_string_operand_error(
+            destination,
+            &format!("Variable {} is a normal variable, strings can only be copied into char arrays", destination.data.name)
+        )),
+    }
+}
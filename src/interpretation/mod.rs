@@ -1,4 +1,8 @@
 pub mod interpret_expression;
 pub mod interpret_statement;
 pub mod interpret_declaration;
-pub mod interpret_function;
\ No newline at end of file
+pub mod interpret_string;
+pub mod interpret_function;
+pub mod hooks;
+pub mod execution_limits;
+pub mod undo_log;
\ No newline at end of file
@@ -0,0 +1,65 @@
+use std::cell::RefCell;
+
+use crate::abstract_syntax_tree::nodes::Value;
+
+/// The value of a slot of a scope before it was written: a normal variable,
+/// or an element of an array. The writes to a struct member keep the whole struct value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UndoEntry {
+    pub scope: String,
+    pub variable: String,
+    pub index: Option<usize>,
+    pub old_value: Option<Value>,
+}
+
+impl UndoEntry {
+    /// The written slot, like `t[1]`.
+    pub fn get_target(&self) -> String {
+        match self.index {
+            Some(index) => format!("{}[{}]", self.variable, index),
+            None => self.variable.clone(),
+        }
+    }
+}
+
+// The undo log only records while a tool needs it, like the debugger to step backwards,
+// so that the other interpretations don't pay for it.
+thread_local! {
+    static UNDO_LOG: RefCell<Option<Vec<UndoEntry>>> = const { RefCell::new(None) };
+}
+
+/// Record the writes of all subsequent interpretation in the current thread, in a new undo log.
+pub fn start_undo_log() {
+    UNDO_LOG.with(|undo_log| *undo_log.borrow_mut() = Some(Vec::new()));
+}
+
+/// Stop recording the writes, and drop the undo log.
+pub fn stop_undo_log() {
+    UNDO_LOG.with(|undo_log| *undo_log.borrow_mut() = None);
+}
+
+pub fn is_undo_log_recording() -> bool {
+    UNDO_LOG.with(|undo_log| undo_log.borrow().is_some())
+}
+
+/// Add the previous values of written slots to the undo log, if it is recording.
+pub fn record_writes(undo_entries: Vec<UndoEntry>) {
+    UNDO_LOG.with(|undo_log| {
+        if let Some(entries) = undo_log.borrow_mut().as_mut() {
+            entries.extend(undo_entries);
+        }
+    });
+}
+
+/// The number of entries of the undo log, to find the writes done after this point.
+pub fn get_undo_log_length() -> usize {
+    UNDO_LOG.with(|undo_log| undo_log.borrow().as_ref().map_or(0, |entries| entries.len()))
+}
+
+/// Get the entries of the undo log from the given position, in the order of the writes.
+pub fn get_undo_entries_from(position: usize) -> Vec<UndoEntry> {
+    UNDO_LOG.with(|undo_log| match undo_log.borrow().as_ref() {
+        Some(entries) => entries.get(position..).unwrap_or(&[]).to_vec(),
+        None => Vec::new(),
+    })
+}
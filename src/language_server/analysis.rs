@@ -0,0 +1,417 @@
+use std::path::Path;
+
+use pest::error::{Error, LineColLocation};
+use serde_json::{json, Value as JsonValue};
+
+use crate::abstract_syntax_tree::nodes::{AST, Node, Identifier, Declaration, Function, StructDefinition, EnumDefinition, Enumerator};
+use crate::pipelines::parse_content_into_ast;
+use crate::preprocessing::{PreprocessedSource, preprocess_content};
+use crate::semantic::errors::SemanticError;
+use crate::symbol_table::build_static_symbol_table;
+use crate::syntax_parsing::Rule;
+
+use super::path_to_uri;
+
+// The document being edited is the first file of its preprocessed source,
+// the other ones being the files it includes.
+const DOCUMENT_FILE_ID: usize = 0;
+
+// LSP kinds of symbols and completion items
+const SYMBOL_KIND_ENUM: u8 = 10;
+const SYMBOL_KIND_FIELD: u8 = 8;
+const SYMBOL_KIND_FUNCTION: u8 = 12;
+const SYMBOL_KIND_VARIABLE: u8 = 13;
+const SYMBOL_KIND_ENUM_MEMBER: u8 = 22;
+const SYMBOL_KIND_STRUCT: u8 = 23;
+const COMPLETION_KIND_FUNCTION: u8 = 3;
+const COMPLETION_KIND_FIELD: u8 = 5;
+const COMPLETION_KIND_VARIABLE: u8 = 6;
+const COMPLETION_KIND_ENUM: u8 = 13;
+const COMPLETION_KIND_ENUM_MEMBER: u8 = 20;
+const COMPLETION_KIND_STRUCT: u8 = 22;
+
+/// Get the LSP position (line and UTF-16 character, from 0) of an offset of a text.
+pub fn offset_to_position(text: &str, offset: usize) -> JsonValue {
+    let offset = offset.min(text.len());
+    let line_start = text[..offset].rfind('\n').map_or(0, |newline| newline + 1);
+    json!({
+        "line": text[..offset].matches('\n').count(),
+        "character": text[line_start..offset].encode_utf16().count(),
+    })
+}
+
+/// Get the offset of an LSP position of a text. Positions past the end of a line are moved to its end.
+pub fn position_to_offset(text: &str, position: &JsonValue) -> usize {
+    let line = position["line"].as_u64().unwrap_or(0) as usize;
+    let character = position["character"].as_u64().unwrap_or(0) as usize;
+    let line_start = match line {
+        0 => 0,
+        _ => match text.match_indices('\n').nth(line - 1) {
+            Some((newline, _)) => newline + 1,
+            None => return text.len(),
+        },
+    };
+    let mut utf16_count = 0;
+    for (char_offset, current_char) in text[line_start..].char_indices() {
+        if utf16_count >= character || current_char == '\n' {
+            return line_start + char_offset;
+        }
+        utf16_count += current_char.len_utf16();
+    }
+    text.len()
+}
+
+fn make_range(text: &str, start: usize, end: usize) -> JsonValue {
+    json!({ "start": offset_to_position(text, start), "end": offset_to_position(text, end) })
+}
+
+/// Make a diagnostic out of an error. The errors are already relocated to their original file,
+/// the ones of the included files being reported at the start of the document.
+fn make_diagnostic(error: Error<Rule>, document_path: &Path) -> JsonValue {
+    let ((start_line, start_column), (end_line, end_column)) = match error.line_col {
+        LineColLocation::Pos(position) => (position, position),
+        LineColLocation::Span(start, end) => (start, end),
+    };
+    let message = error.variant.message().to_string();
+    let error_path = error.path().map(|path| path.to_string());
+    let is_in_document = error_path.as_ref().is_none_or(|path| Path::new(path) == document_path);
+    let (range, message) = if is_in_document {
+        (
+            json!({
+                "start": { "line": start_line - 1, "character": start_column - 1 },
+                "end": { "line": end_line - 1, "character": end_column - 1 },
+            }),
+            message,
+        )
+    } else {
+        (
+            json!({ "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 0 } }),
+            format!("In {}: {}", error_path.unwrap_or_default(), message),
+        )
+    };
+    json!({ "range": range, "severity": 1, "source": "ctiny", "message": message })
+}
+
+/// Run the preprocessing, the parsing and the static semantic checks of a document,
+/// and get their errors as diagnostics. The checks stop at the first error, like the interpreter.
+pub fn get_diagnostics(document_path: &Path, text: &str) -> Vec<JsonValue> {
+    let document_path = std::fs::canonicalize(document_path).unwrap_or(document_path.to_path_buf());
+    let source = match preprocess_content(&document_path, text.to_string()) {
+        Ok(source) => source,
+        Err(error) => return vec![make_diagnostic(error.get_error(), &document_path)],
+    };
+    let result = parse_content_into_ast(&source.content, None)
+        .and_then(|ast| build_static_symbol_table(&ast).map(|_| ()));
+    match result {
+        Ok(()) => Vec::new(),
+        Err(error) => vec![make_diagnostic(source.relocate_error(error.get_error()), &document_path)],
+    }
+}
+
+/// Preprocess and parse a document, for the requests about its content.
+pub fn preprocess_document(document_path: &Path, text: &str) -> Result<PreprocessedSource, SemanticError> {
+    let source = preprocess_content(document_path, text.to_string())?;
+    parse_content_into_ast(&source.content, None)?;
+    Ok(source)
+}
+
+/// Something defined in a program, that an identifier can refer to.
+enum Definition<'t, 'a> {
+    Variable(&'t Node<'a, Declaration<'a>>),
+    Field(&'t Node<'a, StructDefinition<'a>>, &'t Node<'a, Declaration<'a>>),
+    Function(&'t Node<'a, Function<'a>>),
+    Struct(&'t Node<'a, StructDefinition<'a>>),
+    Enum(&'t Node<'a, EnumDefinition<'a>>),
+    Enumerator(&'t Node<'a, EnumDefinition<'a>>, &'t Node<'a, Enumerator<'a>>),
+}
+
+impl<'t, 'a> Definition<'t, 'a> {
+    fn get_name_node(&self) -> &'t Node<'a, Identifier> {
+        match self {
+            Definition::Variable(declaration) | Definition::Field(_, declaration) => &declaration.data.identifier,
+            Definition::Function(function) => &function.data.name,
+            Definition::Struct(struct_definition) => &struct_definition.data.name,
+            Definition::Enum(enum_definition) => &enum_definition.data.name,
+            Definition::Enumerator(_, enumerator) => &enumerator.data.identifier,
+        }
+    }
+
+    fn get_name(&self) -> &'t str {
+        &self.get_name_node().data.name
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Definition::Variable(declaration) => declaration.data.describe(),
+            Definition::Field(struct_definition, declaration) => format!(
+                "{} (member of struct {})", declaration.data.describe(), struct_definition.data.name.data.name
+            ),
+            Definition::Function(function) => function.data.get_signature(),
+            Definition::Struct(struct_definition) => format!("struct {}", struct_definition.data.name.data.name),
+            Definition::Enum(enum_definition) => format!("enum {}", enum_definition.data.name.data.name),
+            Definition::Enumerator(enum_definition, enumerator) => format!(
+                "enum {} {}", enum_definition.data.name.data.name, enumerator.data.identifier.data.name
+            ),
+        }
+    }
+
+    fn get_completion_kind(&self) -> u8 {
+        match self {
+            Definition::Variable(_) => COMPLETION_KIND_VARIABLE,
+            Definition::Field(_, _) => COMPLETION_KIND_FIELD,
+            Definition::Function(_) => COMPLETION_KIND_FUNCTION,
+            Definition::Struct(_) => COMPLETION_KIND_STRUCT,
+            Definition::Enum(_) => COMPLETION_KIND_ENUM,
+            Definition::Enumerator(_, _) => COMPLETION_KIND_ENUM_MEMBER,
+        }
+    }
+}
+
+/// Get all the functions of a program, `main` included, in the order of the source.
+fn get_functions<'t, 'a>(ast: &'t AST<'a>) -> Vec<&'t Node<'a, Function<'a>>> {
+    let mut functions: Vec<&Node<Function>> = ast.data.functions.values()
+        .chain(std::iter::once(&ast.data.main_function))
+        .collect();
+    functions.sort_by_key(|function| function.sp.start());
+    functions
+}
+
+/// Get the parameters and the local variables of a function.
+fn get_function_variables<'t, 'a>(function: &'t Node<'a, Function<'a>>) -> impl Iterator<Item = &'t Node<'a, Declaration<'a>>> {
+    function.data.params.iter().flatten()
+        .chain(function.data.body.data.declarations.iter())
+}
+
+/// Get the definitions that can be used at an offset of the preprocessed content:
+/// the variables of the enclosing function, then the functions and the user types.
+/// Ctiny has no global variables, and a single scope per function.
+fn get_visible_definitions<'t, 'a>(ast: &'t AST<'a>, offset: usize) -> Vec<Definition<'t, 'a>> {
+    let functions = get_functions(ast);
+    let enclosing_function = functions.iter()
+        .find(|function| function.sp.start() <= offset && offset <= function.sp.end());
+    let mut definitions: Vec<Definition> = enclosing_function.into_iter()
+        .flat_map(|function| get_function_variables(function))
+        .map(Definition::Variable)
+        .collect();
+    definitions.extend(functions.into_iter().map(Definition::Function));
+    for struct_definition in ast.data.structs.iter().flatten() {
+        definitions.push(Definition::Struct(struct_definition));
+    }
+    for enum_definition in ast.data.enums.iter().flatten() {
+        definitions.push(Definition::Enum(enum_definition));
+        for enumerator in &enum_definition.data.enumerators {
+            definitions.push(Definition::Enumerator(enum_definition, enumerator));
+        }
+    }
+    definitions
+}
+
+fn get_struct_fields<'t, 'a>(ast: &'t AST<'a>) -> Vec<Definition<'t, 'a>> {
+    ast.data.structs.iter().flatten()
+        .flat_map(|struct_definition| struct_definition.data.fields.iter()
+            .map(move |field| Definition::Field(struct_definition, field)))
+        .collect()
+}
+
+fn is_identifier_char(current_char: char) -> bool {
+    current_char.is_ascii_alphanumeric() || current_char == '_'
+}
+
+/// The identifier at an offset of the preprocessed content, possibly only started.
+struct Word<'c> {
+    start: usize,
+    end: usize,
+    text: &'c str,
+    is_member: bool,
+}
+
+fn get_word_at(content: &str, offset: usize) -> Word<'_> {
+    let offset = offset.min(content.len());
+    let start = content[..offset].rfind(|current_char| !is_identifier_char(current_char))
+        .map_or(0, |index| index + 1);
+    let end = content[offset..].find(|current_char| !is_identifier_char(current_char))
+        .map_or(content.len(), |index| offset + index);
+    // a member access, like `p.x`, possibly with spaces around the dot
+    let is_member = content[..start].trim_end().ends_with('.');
+    Word { start, end, text: &content[start..end], is_member }
+}
+
+/// A document with its preprocessed source and its AST, to answer requests about positions.
+pub struct DocumentView<'s, 'a> {
+    pub text: &'s str,
+    pub source: &'a PreprocessedSource,
+    pub ast: &'s AST<'a>,
+}
+
+impl<'s, 'a> DocumentView<'s, 'a> {
+    /// Get the offset in the preprocessed content of an LSP position of the document.
+    fn get_content_offset(&self, position: &JsonValue) -> Option<usize> {
+        let text_offset = position_to_offset(self.text, position);
+        self.source.source_map.locate_in_content(DOCUMENT_FILE_ID, text_offset)
+    }
+
+    /// Get the file and the range of a span of the preprocessed content.
+    fn get_file_range(&self, start: usize, end: usize) -> Option<(&'a Path, JsonValue)> {
+        let (file_id, file_start) = self.source.source_map.locate(start)?;
+        let file_end = match self.source.source_map.locate_end(end) {
+            Some((end_file_id, file_end)) if end_file_id == file_id && file_end >= file_start => file_end,
+            _ => file_start,
+        };
+        let file = self.source.source_map.get_file(file_id);
+        Some((file.path.as_path(), make_range(&file.content, file_start, file_end)))
+    }
+
+    /// Get the range in the document of a span, if it comes from the document and not an included file.
+    fn get_document_range(&self, start: usize, end: usize) -> Option<JsonValue> {
+        match self.source.source_map.locate(start) {
+            Some((DOCUMENT_FILE_ID, _)) => self.get_file_range(start, end).map(|(_, range)| range),
+            _ => None,
+        }
+    }
+
+    fn find_definition(&self, position: &JsonValue) -> Option<(Word<'a>, Definition<'s, 'a>)> {
+        let offset = self.get_content_offset(position)?;
+        let word = get_word_at(&self.source.content, offset);
+        if word.text.is_empty() {
+            return None;
+        }
+        let definitions = if word.is_member {
+            get_struct_fields(self.ast)
+        } else {
+            get_visible_definitions(self.ast, offset)
+        };
+        let definition = definitions.into_iter().find(|definition| definition.get_name() == word.text)?;
+        Some((word, definition))
+    }
+
+    /// Get the location of the definition of the identifier at a position, for go-to-definition.
+    pub fn get_definition_location(&self, position: &JsonValue) -> Option<JsonValue> {
+        let (_, definition) = self.find_definition(position)?;
+        let name_node = definition.get_name_node();
+        let (path, range) = self.get_file_range(name_node.sp.start(), name_node.sp.end())?;
+        Some(json!({ "uri": path_to_uri(path), "range": range }))
+    }
+
+    /// Get the declaration of the identifier at a position, for hover.
+    pub fn get_hover(&self, position: &JsonValue) -> Option<JsonValue> {
+        let (word, definition) = self.find_definition(position)?;
+        let mut hover = json!({
+            "contents": { "kind": "markdown", "value": format!("```c\n{}\n```", definition.describe()) },
+        });
+        if let Some(range) = self.get_document_range(word.start, word.end) {
+            hover["range"] = range;
+        }
+        Some(hover)
+    }
+
+    /// Get the identifiers that can be used at a position, for completion.
+    pub fn get_completion_items(&self, position: &JsonValue) -> Vec<JsonValue> {
+        let Some(offset) = self.get_content_offset(position) else {
+            return Vec::new();
+        };
+        let word = get_word_at(&self.source.content, offset);
+        let definitions = if word.is_member {
+            get_struct_fields(self.ast)
+        } else {
+            get_visible_definitions(self.ast, offset)
+        };
+        let mut labels: Vec<&str> = Vec::new();
+        let mut items = Vec::new();
+        for definition in definitions {
+            // a variable hides a function or a type with the same name
+            if labels.contains(&definition.get_name()) {
+                continue;
+            }
+            labels.push(definition.get_name());
+            items.push(json!({
+                "label": definition.get_name(),
+                "kind": definition.get_completion_kind(),
+                "detail": definition.describe(),
+            }));
+        }
+        items
+    }
+
+    fn make_symbol(
+        &self,
+        name: &str,
+        detail: String,
+        kind: u8,
+        sp: &pest::Span,
+        name_sp: &pest::Span,
+        children: Vec<JsonValue>,
+    ) -> Option<JsonValue> {
+        let range = self.get_document_range(sp.start(), sp.end())?;
+        let selection_range = self.get_document_range(name_sp.start(), name_sp.end())
+            .unwrap_or_else(|| range.clone());
+        Some(json!({
+            "name": name,
+            "detail": detail,
+            "kind": kind,
+            "range": range,
+            "selectionRange": selection_range,
+            "children": children,
+        }))
+    }
+
+    /// Get the outline of the document: its user types and functions, with their members and variables.
+    /// The definitions of the included files are left out.
+    pub fn get_document_symbols(&self) -> Vec<JsonValue> {
+        let mut symbols: Vec<(usize, JsonValue)> = Vec::new();
+        let make_declaration_symbols = |declarations: Vec<&Node<Declaration>>, kind: u8| -> Vec<JsonValue> {
+            declarations.into_iter()
+                .filter_map(|declaration| self.make_symbol(
+                    &declaration.data.identifier.data.name,
+                    declaration.data.describe(),
+                    kind,
+                    &declaration.sp,
+                    &declaration.data.identifier.sp,
+                    Vec::new(),
+                ))
+                .collect()
+        };
+
+        for struct_definition in self.ast.data.structs.iter().flatten() {
+            let fields = make_declaration_symbols(struct_definition.data.fields.iter().collect(), SYMBOL_KIND_FIELD);
+            let name_node = &struct_definition.data.name;
+            if let Some(symbol) = self.make_symbol(
+                &name_node.data.name, format!("struct {}", name_node.data.name), SYMBOL_KIND_STRUCT,
+                &struct_definition.sp, &name_node.sp, fields,
+            ) {
+                symbols.push((struct_definition.sp.start(), symbol));
+            }
+        }
+        for enum_definition in self.ast.data.enums.iter().flatten() {
+            let enumerators: Vec<JsonValue> = enum_definition.data.enumerators.iter()
+                .filter_map(|enumerator| self.make_symbol(
+                    &enumerator.data.identifier.data.name,
+                    format!("enum {}", enum_definition.data.name.data.name),
+                    SYMBOL_KIND_ENUM_MEMBER,
+                    &enumerator.sp,
+                    &enumerator.data.identifier.sp,
+                    Vec::new(),
+                ))
+                .collect();
+            let name_node = &enum_definition.data.name;
+            if let Some(symbol) = self.make_symbol(
+                &name_node.data.name, format!("enum {}", name_node.data.name), SYMBOL_KIND_ENUM,
+                &enum_definition.sp, &name_node.sp, enumerators,
+            ) {
+                symbols.push((enum_definition.sp.start(), symbol));
+            }
+        }
+        for function in get_functions(self.ast) {
+            let variables = make_declaration_symbols(get_function_variables(function).collect(), SYMBOL_KIND_VARIABLE);
+            let name_node = &function.data.name;
+            if let Some(symbol) = self.make_symbol(
+                &name_node.data.name, function.data.get_signature(), SYMBOL_KIND_FUNCTION,
+                &function.sp, &name_node.sp, variables,
+            ) {
+                symbols.push((function.sp.start(), symbol));
+            }
+        }
+
+        symbols.sort_by_key(|(start, _)| *start);
+        symbols.into_iter().map(|(_, symbol)| symbol).collect()
+    }
+}
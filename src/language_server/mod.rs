@@ -0,0 +1,210 @@
+pub mod analysis;
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value as JsonValue};
+
+use crate::pipelines::parse_content_into_ast;
+use crate::preprocessing::PreprocessedSource;
+use crate::protocol::{read_message, write_message};
+
+use analysis::{DocumentView, get_diagnostics, preprocess_document};
+
+// JSON-RPC error code for the requests the server does not implement
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Get the path of a `file://` URI, decoding its escaped characters.
+pub fn uri_to_path(uri: &str) -> PathBuf {
+    let encoded_path = uri.strip_prefix("file://").unwrap_or(uri);
+    let mut bytes = Vec::new();
+    let mut rest = encoded_path.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped_byte = match tail {
+            [high, low, ..] if byte == b'%' => std::str::from_utf8(&[*high, *low]).ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+        match escaped_byte {
+            Some(escaped_byte) => {
+                bytes.push(escaped_byte);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Get the `file://` URI of a path, escaping the characters that cannot appear in it.
+pub fn path_to_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
+}
+
+/// A file open in the editor.
+struct Document {
+    path: PathBuf,
+    /// The last version of the content that could be parsed, with its preprocessed source,
+    /// to keep answering requests while the current content is being edited.
+    last_valid_version: Option<(String, PreprocessedSource)>,
+}
+
+/// The Language Server Protocol server, answering the requests of an editor about the open Ctiny files.
+/// The documents are checked with the same pipeline as the interpreter: preprocessing, parsing and static checks.
+struct LanguageServer {
+    input: Box<dyn BufRead>,
+    output: Box<dyn Write>,
+    documents: HashMap<String, Document>,
+}
+
+impl LanguageServer {
+    fn new(input: Box<dyn BufRead>, output: Box<dyn Write>) -> Self {
+        LanguageServer {
+            input,
+            output,
+            documents: HashMap::new(),
+        }
+    }
+
+    fn respond(&mut self, id: JsonValue, result: JsonValue) {
+        write_message(self.output.as_mut(), &json!({ "jsonrpc": "2.0", "id": id, "result": result }));
+    }
+
+    fn respond_error(&mut self, id: JsonValue, code: i64, message: String) {
+        write_message(self.output.as_mut(), &json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": code, "message": message },
+        }));
+    }
+
+    fn notify(&mut self, method: &str, params: JsonValue) {
+        write_message(self.output.as_mut(), &json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+
+    fn get_capabilities() -> JsonValue {
+        json!({
+            "capabilities": {
+                // the whole content of the documents is sent on each change
+                "textDocumentSync": 1,
+                "definitionProvider": true,
+                "hoverProvider": true,
+                "documentSymbolProvider": true,
+                "completionProvider": { "triggerCharacters": ["."] },
+            },
+            "serverInfo": { "name": "ctiny", "version": env!("CARGO_PKG_VERSION") },
+        })
+    }
+
+    /// Check the new content of a document, and publish its diagnostics.
+    fn update_document(&mut self, uri: &str, text: String) {
+        let path = uri_to_path(uri);
+        let diagnostics = get_diagnostics(&path, &text);
+        let valid_source = preprocess_document(&path, &text).ok();
+        let document = self.documents.entry(uri.to_string()).or_insert(Document {
+            path,
+            last_valid_version: None,
+        });
+        if let Some(source) = valid_source {
+            document.last_valid_version = Some((text, source));
+        }
+        self.notify("textDocument/publishDiagnostics", json!({ "uri": uri, "diagnostics": diagnostics }));
+    }
+
+    fn close_document(&mut self, uri: &str) {
+        self.documents.remove(uri);
+        self.notify("textDocument/publishDiagnostics", json!({ "uri": uri, "diagnostics": [] }));
+    }
+
+    /// Answer a request about a document, with its last version that could be parsed.
+    fn with_document_view(&self, params: &JsonValue, answer: impl FnOnce(&DocumentView) -> JsonValue) -> JsonValue {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let Some(document) = self.documents.get(uri) else {
+            log::warn!("Request about a document that is not open: {}", uri);
+            return JsonValue::Null;
+        };
+        let Some((text, source)) = &document.last_valid_version else {
+            return JsonValue::Null;
+        };
+        match parse_content_into_ast(&source.content, document.path.to_str()) {
+            Ok(ast) => answer(&DocumentView { text, source, ast: &ast }),
+            Err(_) => JsonValue::Null,
+        }
+    }
+
+    /// Handle the messages of the client, until it asks the server to exit or closes the input.
+    fn run(&mut self) {
+        while let Some(message) = read_message(self.input.as_mut()) {
+            // the responses of the client to server requests have no method
+            let Some(method) = message["method"].as_str() else {
+                continue;
+            };
+            let params = &message["params"];
+            let id = message.get("id").cloned();
+            let result = match method {
+                "initialize" => Some(Self::get_capabilities()),
+                "textDocument/didOpen" => {
+                    let text_document = &params["textDocument"];
+                    let text = text_document["text"].as_str().unwrap_or_default().to_string();
+                    self.update_document(text_document["uri"].as_str().unwrap_or_default(), text);
+                    None
+                }
+                "textDocument/didChange" => {
+                    // with a full synchronization, the last change is the whole content
+                    let changes = params["contentChanges"].as_array();
+                    if let Some(change) = changes.and_then(|changes| changes.last()) {
+                        let text = change["text"].as_str().unwrap_or_default().to_string();
+                        self.update_document(params["textDocument"]["uri"].as_str().unwrap_or_default(), text);
+                    }
+                    None
+                }
+                "textDocument/didClose" => {
+                    self.close_document(params["textDocument"]["uri"].as_str().unwrap_or_default());
+                    None
+                }
+                "textDocument/definition" => Some(self.with_document_view(params, |view| {
+                    view.get_definition_location(&params["position"]).unwrap_or(JsonValue::Null)
+                })),
+                "textDocument/hover" => Some(self.with_document_view(params, |view| {
+                    view.get_hover(&params["position"]).unwrap_or(JsonValue::Null)
+                })),
+                "textDocument/documentSymbol" => Some(self.with_document_view(params, |view| {
+                    json!(view.get_document_symbols())
+                })),
+                "textDocument/completion" => Some(self.with_document_view(params, |view| {
+                    json!(view.get_completion_items(&params["position"]))
+                })),
+                "shutdown" => Some(JsonValue::Null),
+                "exit" => return,
+                _ => {
+                    // unknown notifications are ignored
+                    if let Some(id) = id {
+                        self.respond_error(id, METHOD_NOT_FOUND, format!("Unsupported method: {}", method));
+                    }
+                    continue;
+                }
+            };
+            if let (Some(id), Some(result)) = (id, result) {
+                self.respond(id, result);
+            }
+        }
+    }
+}
+
+/// Run a Language Server Protocol server, for editors to show the errors of Ctiny files,
+/// go to definitions, hover identifiers, list the symbols of a file and complete identifiers.
+pub fn run_language_server(input: Box<dyn BufRead>, output: Box<dyn Write>) {
+    LanguageServer::new(input, output).run();
+}
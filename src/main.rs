@@ -5,20 +5,66 @@ extern crate pest_derive;
 mod params;
 mod errors;
 mod data_loading;
+mod preprocessing;
 mod syntax_parsing;
 mod abstract_syntax_tree;
 mod semantic;
 mod pipelines;
 mod symbol_table;
 mod interpretation;
+mod repl;
+mod debugger;
+mod protocol;
+mod language_server;
+mod formatting;
+mod trace;
+mod profiler;
+mod coverage;
 
 #[cfg(test)]
 mod tests;
 
 
 fn main() {
+    // the interpreter recurses on the Rust stack for each call of the interpreted programs
+    interpretation::execution_limits::run_with_interpreter_stack(run);
+}
+
+fn run() {
     crate::params::init();
 
+    // select the data model before any literal is parsed
+    semantic::data_model::set_int_width(params::ARGV.int_width);
+    semantic::overflow_policy::set_overflow_policy(params::ARGV.overflow_policy);
+    interpretation::execution_limits::set_execution_limits(params::ARGV.get_execution_limits());
+
+    // run a command instead of a pipeline
+    if let Some(command) = &params::ARGV.command {
+        match command {
+            params::argv::Command::Repl => repl::run_repl(),
+            params::argv::Command::Debug { file } => debugger::run_debugger(file),
+            params::argv::Command::Dap => debugger::dap::run_dap_server(
+                Box::new(std::io::stdin().lock()),
+                Box::new(std::io::stdout()),
+            ),
+            params::argv::Command::Lsp => language_server::run_language_server(
+                Box::new(std::io::stdin().lock()),
+                Box::new(std::io::stdout()),
+            ),
+            params::argv::Command::Fmt { files, check, indent, brace_style, one_declaration_per_line } => {
+                let options = formatting::FormatOptions {
+                    indent_width: *indent,
+                    brace_style: *brace_style,
+                    one_declaration_per_line: *one_declaration_per_line,
+                };
+                if !formatting::run_formatter(files, *check, &options) {
+                    std::process::exit(1);
+                }
+            },
+        }
+        return;
+    }
+
     // get input file paths
     //let input_paths: Vec<PathBuf> = Vec::new();
     let input_paths = data_loading::get_input_files_from_params();
@@ -1,4 +1,11 @@
-use clap::{Parser, ValueEnum, arg};
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand, ValueEnum, arg};
+
+use crate::formatting::BraceStyle;
+use crate::interpretation::execution_limits::{ExecutionLimits, DEFAULT_MAX_STEPS, DEFAULT_MAX_CALL_DEPTH, MAX_CALL_DEPTH};
+use crate::semantic::data_model::IntWidth;
+use crate::semantic::overflow_policy::OverflowPolicy;
 
 // NOTE: 'group = "file_input_group"' means that only one of the options in the group can be used
 // the result is stored always in 'files_input', and on the option used (the other is None)
@@ -9,6 +16,10 @@ use clap::{Parser, ValueEnum, arg};
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Argv {
+    /// The command to run instead of a pipeline
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// File path to input files
     #[arg(short, long, required = false, group = "file_input_group")]
     pub files: Option<Vec<String>>,
@@ -24,6 +35,98 @@ pub struct Argv {
     /// whether to print the AST or not
     #[arg(long, default_value = "false")]
     pub display_ast: bool,
+
+    /// The width in bits of the int type, to check programs under different target assumptions
+    #[arg(value_enum, long, default_value = "16")]
+    pub int_width: IntWidth,
+
+    /// What to do on signed overflows and narrowing casts out of range
+    #[arg(value_enum, long, default_value = "trap")]
+    pub overflow_policy: OverflowPolicy,
+
+    /// The maximum number of statements and expressions interpreted per program, 0 for no limit
+    #[arg(long, default_value_t = DEFAULT_MAX_STEPS)]
+    pub max_steps: u64,
+
+    /// The maximum number of nested function calls, up to 5000, 0 for this maximum
+    #[arg(long, default_value_t = DEFAULT_MAX_CALL_DEPTH,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(0..=MAX_CALL_DEPTH as u64))]
+    pub max_call_depth: usize,
+
+    /// The maximum duration of the interpretation of each program, in milliseconds
+    #[arg(long, required = false)]
+    pub timeout: Option<u64>,
+
+    /// File path to write the execution trace of the interpreted programs to, as JSON Lines
+    #[arg(long, required = false)]
+    pub trace: Option<PathBuf>,
+
+    /// whether to print how many times each line, function and loop of the interpreted programs was run
+    #[arg(long, default_value = "false")]
+    pub profile: bool,
+
+    /// File path to write the call stacks of the interpreted programs to, in the folded format of flame graphs
+    #[arg(long, required = false)]
+    pub profile_folded: Option<PathBuf>,
+
+    /// File path to write the line, function and branch coverage of the interpreted programs to, in the LCOV format
+    #[arg(long, required = false)]
+    pub coverage: Option<PathBuf>,
+}
+
+#[derive(Clone, PartialEq, Eq, Subcommand, Debug)]
+pub enum Command {
+    /// run an interactive session (REPL)
+    Repl,
+    /// interpret a program step by step, with breakpoints and variable inspection
+    Debug {
+        /// File path to the program to debug
+        file: PathBuf,
+    },
+    /// run a Debug Adapter Protocol server over the standard input and output, for editors
+    Dap,
+    /// run a Language Server Protocol server over the standard input and output, for editors
+    Lsp,
+    /// format programs in place, keeping their comments and directives
+    Fmt {
+        /// File paths to the programs to format
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// only check that the programs are formatted, and exit with an error if one is not
+        #[arg(long, default_value = "false")]
+        check: bool,
+
+        /// The number of spaces of each indentation level
+        #[arg(long, default_value = "4")]
+        indent: usize,
+
+        /// Where to put the opening braces of blocks
+        #[arg(value_enum, long, default_value = "same-line")]
+        brace_style: BraceStyle,
+
+        /// split declarations like `int a, b;` into one declaration per line
+        #[arg(long, default_value = "false")]
+        one_declaration_per_line: bool,
+    },
+}
+
+impl Argv {
+    /// Get the execution limits of the options, where 0 means no limit.
+    pub fn get_execution_limits(&self) -> ExecutionLimits {
+        ExecutionLimits {
+            max_steps: Some(self.max_steps).filter(|max_steps| *max_steps > 0),
+            max_call_depth: Some(self.max_call_depth).filter(|max_call_depth| *max_call_depth > 0),
+            timeout: self.timeout.map(std::time::Duration::from_millis),
+        }
+    }
+}
+
+impl Command {
+    /// Whether the command talks to its client over the standard input and output.
+    pub fn is_protocol_server(&self) -> bool {
+        matches!(self, Command::Dap | Command::Lsp)
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
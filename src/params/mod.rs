@@ -39,7 +39,12 @@ fn init_logger() {
         .level(log_level)
         .chain(file_out)
         .chain(fern::Output::call(|record| {
-            println!("{}", record.args());
+            // the standard output of a protocol server is reserved for its messages
+            if is_stdout_reserved() {
+                eprintln!("{}", record.args());
+            } else {
+                println!("{}", record.args());
+            }
         }))
         .apply();
 
@@ -50,6 +55,11 @@ fn init_logger() {
     log::info!(" 🚀 starting program...");
 }
 
+/// Whether the standard output is reserved for the messages of a protocol, like DAP.
+fn is_stdout_reserved() -> bool {
+    ARGV.command.as_ref().is_some_and(|command| command.is_protocol_server())
+}
+
 static INIT: Once = Once::new();
 
 /// Initialize things that need to be initialized only once. 
@@ -57,9 +67,10 @@ static INIT: Once = Once::new();
 pub fn init() {
     INIT.call_once(|| {
         // initialization code here
-        print!("Loading .env file... ");
+        let print_step = |step: &str| if is_stdout_reserved() { eprint!("{}", step) } else { print!("{}", step) };
+        print_step("Loading .env file... ");
         dotenv().expect("Failed to load .env file");
-        print!("Initializing logger... ");
+        print_step("Initializing logger... ");
         init_logger();
     });
 }
@@ -80,26 +91,6 @@ lazy_static! {
         }
     };
 
-    // constants
-    pub static ref MAX_NB_OF_LOOP_ITERATIONS: u32 = {
-        let max_nb_of_loop_iterations = std::env::var("MAX_NB_OF_LOOP_ITERATIONS");
-        match max_nb_of_loop_iterations {
-            Ok(max_nb_of_loop_iterations) => {
-                match max_nb_of_loop_iterations.parse::<u32>() {
-                    Ok(max_nb_of_loop_iterations) => max_nb_of_loop_iterations,
-                    Err(_) => {
-                        println!("MAX_NB_OF_LOOP_ITERATIONS environment variable is not a valid u32. Defaulting to 1000.");
-                        return 1000;
-                    },
-                }
-            },
-            Err(_) => {
-                println!("MAX_NB_OF_LOOP_ITERATIONS environment variable not set. Defaulting to 1000.");
-                return 1000;
-            },
-        }
-    };
-
     // paths
     // NOTE: remember to test path existence
     pub static ref LOG_DIR: PathBuf = {
@@ -1,9 +1,21 @@
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::coverage::{Coverage, CoverageCollector};
+use crate::interpretation::hooks::{add_hook, clear_hooks};
 use crate::interpretation::interpret_function::interpret_translation_unit;
 use crate::params;
+use crate::preprocessing::preprocess_file;
+use crate::profiler::{Profile, Profiler};
 use crate::semantic::errors::{SemanticError, SyntaxParsingError, ASTBuildingError};
+use crate::semantic::warnings::take_warnings;
 use crate::symbol_table::build_static_symbol_table;
 use crate::syntax_parsing; // self allows to use the module name
 use crate::syntax_parsing::Rule;
+use crate::trace::Tracer;
 use crate::abstract_syntax_tree::build_translation_unit;
 use crate::abstract_syntax_tree::nodes::AST;
 
@@ -43,8 +55,9 @@ pub fn parse_content_into_ast<'a>(
 }
 
 /// For all input files:
-///     1. Perform syntax parsing
-///     2. Construct an AST
+///     1. Include the other files
+///     2. Perform syntax parsing
+///     3. Construct an AST
 pub fn pipeline_syntax_and_ast(
     input_files: Vec<std::path::PathBuf>
 ) {
@@ -54,9 +67,14 @@ pub fn pipeline_syntax_and_ast(
     for file in &input_files {
         log::info!("Parsing file: {}", file.to_str().unwrap());
         let file_name = file.file_name().unwrap().to_str().unwrap();
-        let file_content = std::fs::read_to_string(file).unwrap();
-        let file_content_str = file_content.as_str();
-        let ast = parse_content_into_ast(file_content_str, Some(file_name));
+        let source = match preprocess_file(file) {
+            Ok(source) => source,
+            Err(error) => {
+                log::error!("🚧 Preprocessing ERROR: \n {}\n", error);
+                continue;
+            },
+        };
+        let ast = parse_content_into_ast(&source.content, Some(file_name));
         match ast {
             Ok(ast) => {
                 if params::ARGV.display_ast {
@@ -64,7 +82,7 @@ pub fn pipeline_syntax_and_ast(
                 }
             },
             Err(error) => {
-                log::error!("🚧 Syntax Parsing ERROR: \n {}\n", error);
+                log::error!("🚧 Syntax Parsing ERROR: \n {}\n", source.relocate_error(error.get_error()));
                 continue;
             },
         }
@@ -72,21 +90,36 @@ pub fn pipeline_syntax_and_ast(
 }
 
 /// For all input files:
-///     1. Perform syntax parsing
-///     2. Construct an AST
-///     3. Perform interpretation
+///     1. Include the other files
+///     2. Perform syntax parsing
+///     3. Construct an AST
+///     4. Perform interpretation
 pub fn pipeline_syntax_ast_interpretation(input_files: Vec<std::path::PathBuf>) {
     println!("Pipeline: {:#?}", params::argv::Pipeline::SyntaxASTAndInterpretation);
 
+    // the traces and folded stacks of all the programs are written to the same files, one after the other
+    let trace_file = params::ARGV.trace.as_ref().and_then(|trace_path| create_output_file(trace_path, "Trace"));
+    let mut folded_stacks_file = params::ARGV.profile_folded.as_ref()
+        .and_then(|folded_stacks_path| create_output_file(folded_stacks_path, "Profile"));
+    // the coverage of the files included by several programs is merged
+    let coverage = params::ARGV.coverage.as_ref().map(|_| Rc::new(RefCell::new(Coverage::default())));
+
     // run syntax parsing on all input files
     for file in &input_files {
         log::info!("Parsing file: {}", file.to_str().unwrap());
 
-        // syntax parsing into AST
+        // include the other files, keeping track of where each part comes from
         let file_name = file.file_name().unwrap().to_str().unwrap();
-        let file_content = std::fs::read_to_string(file).unwrap();
-        let file_content_str = file_content.as_str();
-        let ast = match parse_content_into_ast(file_content_str, Some(file_name)) {
+        let source = match preprocess_file(file) {
+            Ok(source) => Rc::new(source),
+            Err(error) => {
+                log::error!("🚧 Preprocessing ERROR: \n {}\n", error);
+                continue;
+            },
+        };
+
+        // syntax parsing into AST
+        let ast = match parse_content_into_ast(&source.content, Some(file_name)) {
             Ok(ast) => {
                 if params::ARGV.display_ast {
                     log::info!("AST: {:#?}", ast);
@@ -94,27 +127,97 @@ pub fn pipeline_syntax_ast_interpretation(input_files: Vec<std::path::PathBuf>)
                 ast
             },
             Err(error) => {
-                log::error!("🚧 Syntax or AST Parsing ERROR: \n {}\n", error);
+                log::error!("🚧 Syntax or AST Parsing ERROR: \n {}\n", source.relocate_error(error.get_error()));
                 continue;
             },
         };
 
-        // build symbol table
-        let mut symbol_table = build_static_symbol_table(&ast);
+        // build symbol table, evaluating constant expressions
+        let mut symbol_table = match build_static_symbol_table(&ast) {
+            Ok(symbol_table) => symbol_table,
+            Err(error) => {
+                log::error!("🚧 Static semantic ERROR: \n {}\n", source.relocate_error(error.get_error()));
+                continue;
+            },
+        };
 
-        // interpretation
+        // interpretation, followed by the tracer if a trace is written
+        if let Some(trace_output) = trace_file.as_ref().and_then(|trace_file| trace_file.try_clone().ok()) {
+            let mut tracer = Tracer::new(Box::new(BufWriter::new(trace_output)), Some(source.clone()));
+            tracer.start_program(file);
+            add_hook(Box::new(tracer));
+        }
+        let profile = match params::ARGV.profile || folded_stacks_file.is_some() {
+            true => Some(Rc::new(RefCell::new(Profile::new(source.clone())))),
+            false => None,
+        };
+        if let Some(profile) = &profile {
+            add_hook(Box::new(Profiler::new(profile.clone())));
+        }
+        if let Some(coverage) = &coverage {
+            add_hook(Box::new(CoverageCollector::new(coverage.clone(), &ast, &source)));
+        }
         let res = interpret_translation_unit(
             &ast,
             &mut symbol_table,
         );
+        // the tracer is dropped, flushing its output
+        clear_hooks();
+        if let Some(profile) = profile {
+            write_profile(&profile.borrow(), file_name, folded_stacks_file.as_mut());
+        }
+        // warnings don't stop the interpretation, they are reported once it is over
+        for warning in take_warnings() {
+            log::warn!("⚠️ Interpretation WARNING: \n {}\n", source.relocate_error(warning));
+        }
         match res {
             Ok(program_return_value) => {
                 log::info!(")Program return value: {}", program_return_value.data);
             },
             Err(error) => {
-                log::error!("🚧 Interpretation ERROR: \n {}\n", error);
+                log::error!("🚧 Interpretation ERROR: \n {}\n", source.relocate_error(error.get_error()));
                 continue;
             },
         }
     }
-}
\ No newline at end of file
+
+    // the coverage is written once all the programs have been interpreted
+    if let (Some(coverage), Some(coverage_path)) = (coverage, &params::ARGV.coverage) {
+        if let Some(coverage_file) = create_output_file(coverage_path, "Coverage") {
+            let mut coverage_output = BufWriter::new(coverage_file);
+            let result = coverage.borrow().write_lcov(&mut coverage_output)
+                .and_then(|_| coverage_output.flush());
+            if let Err(error) = result {
+                log::error!("🚧 Coverage ERROR: \n {}\n", error);
+            }
+        }
+    }
+}
+
+/// Create a file to write an output of the interpretation to, logging the error if it cannot be created.
+fn create_output_file(path: &Path, output_name: &str) -> Option<File> {
+    match File::create(path) {
+        Ok(file) => Some(file),
+        Err(error) => {
+            log::error!("🚧 {} ERROR: cannot create {}: {}\n", output_name, path.display(), error);
+            None
+        },
+    }
+}
+
+/// Print the report of a profiled program if asked, and add its call stacks to the folded stacks file.
+fn write_profile(profile: &Profile, file_name: &str, folded_stacks_file: Option<&mut File>) {
+    let report_result = match params::ARGV.profile {
+        true => profile.write_report(&mut std::io::stdout().lock()),
+        false => Ok(()),
+    };
+    // the stacks of each program are under its own root frame
+    let folded_stacks_result = match folded_stacks_file {
+        Some(folded_stacks_file) => profile.write_folded_stacks(folded_stacks_file, Some(file_name))
+            .and_then(|_| folded_stacks_file.flush()),
+        None => Ok(()),
+    };
+    if let Err(error) = report_result.and(folded_stacks_result) {
+        log::error!("🚧 Profile ERROR: \n {}\n", error);
+    }
+}
@@ -0,0 +1,316 @@
+use std::collections::{HashMap, HashSet};
+
+/// A `#define` macro. Function-like macros have parameters,
+/// object-like macros don't.
+#[derive(Debug, PartialEq)]
+struct Macro {
+    params: Option<Vec<String>>,
+    body: String,
+}
+
+/// A part `start..end` of a line, replaced by its `expansion` if it is a macro invocation.
+#[derive(Debug)]
+pub struct LinePart {
+    pub start: usize,
+    pub end: usize,
+    pub expansion: Option<String>,
+}
+
+/// The macros defined so far, while preprocessing a program.
+#[derive(Debug)]
+pub struct MacroTable {
+    macros: HashMap<String, Macro>,
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn is_identifier(text: &str) -> bool {
+    text.starts_with(is_identifier_start) && text.chars().all(is_identifier_char)
+}
+
+/// Get the end of the token starting at `position`, and whether it is an identifier.
+/// Comments, char and string literals and numbers are single tokens,
+/// so that macros are not expanded inside them.
+/// A block comment can continue on the next lines, hence `in_block_comment`.
+fn next_token(text: &str, position: usize, in_block_comment: &mut bool) -> (usize, bool) {
+    let rest = &text[position..];
+    if *in_block_comment {
+        return match rest.find("*/") {
+            Some(comment_end) => {
+                *in_block_comment = false;
+                (position + comment_end + 2, false)
+            },
+            None => (text.len(), false),
+        };
+    }
+    if rest.starts_with("//") {
+        return (text.len(), false);
+    }
+    if rest.starts_with("/*") {
+        *in_block_comment = true;
+        let (comment_end, _) = next_token(text, position + 2, in_block_comment);
+        return (comment_end, false);
+    }
+
+    let first_char = rest.chars().next().unwrap();
+    let token_length = if first_char == '\'' || first_char == '"' {
+        let mut escaped = false;
+        rest.char_indices().skip(1)
+            .find(|(_, c)| {
+                let is_end = !escaped && (*c == first_char || *c == '\n');
+                escaped = !escaped && *c == '\\';
+                is_end
+            })
+            .map_or(rest.len(), |(index, c)| index + c.len_utf8())
+    } else if is_identifier_start(first_char) {
+        rest.find(|c: char| !is_identifier_char(c)).unwrap_or(rest.len())
+    } else if first_char.is_ascii_digit() {
+        rest.find(|c: char| !(is_identifier_char(c) || c == '.')).unwrap_or(rest.len())
+    } else {
+        first_char.len_utf8()
+    };
+    (position + token_length, is_identifier_start(first_char))
+}
+
+/// Remove the comments of a macro body, so that they don't
+/// comment out the rest of the line where the macro is expanded.
+fn remove_comments(text: &str) -> String {
+    let mut result = String::new();
+    let mut position = 0;
+    let mut in_block_comment = false;
+    while position < text.len() {
+        let (token_end, _) = next_token(text, position, &mut in_block_comment);
+        let token = &text[position..token_end];
+        if token.starts_with("//") || token.starts_with("/*") {
+            result.push(' ');
+        } else {
+            result.push_str(token);
+        }
+        position = token_end;
+    }
+    result.trim().to_string()
+}
+
+/// Get the arguments of a function-like macro invocation, if `position` is followed by `(`,
+/// and the end of the invocation.
+fn parse_arguments<'a>(
+    text: &'a str,
+    position: usize,
+    macro_name: &str,
+) -> Result<Option<(Vec<&'a str>, usize)>, String> {
+    let open_parenthesis = position + text[position..].len() - text[position..].trim_start().len();
+    if !text[open_parenthesis..].starts_with('(') {
+        return Ok(None);
+    }
+
+    let mut arguments = Vec::new();
+    let mut argument_start = open_parenthesis + 1;
+    let mut depth = 1;
+    let mut current = argument_start;
+    let mut in_block_comment = false;
+    while current < text.len() {
+        let (token_end, _) = next_token(text, current, &mut in_block_comment);
+        match &text[current..token_end] {
+            "(" => depth += 1,
+            ")" if depth == 1 => {
+                arguments.push(text[argument_start..current].trim());
+                return Ok(Some((arguments, token_end)));
+            },
+            ")" => depth -= 1,
+            "," if depth == 1 => {
+                arguments.push(text[argument_start..current].trim());
+                argument_start = token_end;
+            },
+            _ => {},
+        }
+        current = token_end;
+    }
+    Err(format!("Unterminated call of macro {}, the arguments must be on the same line", macro_name))
+}
+
+/// Replace the parameters by the arguments in the body of a function-like macro.
+fn substitute_arguments(body: &str, params: &[String], arguments: &[String]) -> String {
+    let mut result = String::new();
+    let mut position = 0;
+    let mut in_block_comment = false;
+    while position < body.len() {
+        let (token_end, is_identifier) = next_token(body, position, &mut in_block_comment);
+        let token = &body[position..token_end];
+        match params.iter().position(|param| is_identifier && param == token) {
+            Some(param_index) => result.push_str(&arguments[param_index]),
+            None => result.push_str(token),
+        }
+        position = token_end;
+    }
+    result
+}
+
+impl MacroTable {
+    pub fn new() -> Self {
+        Self {
+            macros: HashMap::new(),
+        }
+    }
+
+    pub fn is_defined(&self, name: &str) -> bool {
+        self.macros.contains_key(name)
+    }
+
+    /// Define a macro from the text following `#define`,
+    /// like `N 10` or `MAX(a, b) ...`.
+    /// A macro can only be defined again with the same parameters and body.
+    pub fn define(&mut self, definition: &str) -> Result<(), String> {
+        let definition = definition.trim();
+        let name_end = definition.find(|c: char| !is_identifier_char(c))
+            .unwrap_or(definition.len());
+        let name = &definition[..name_end];
+        if !is_identifier(name) {
+            return Err(format!("Invalid macro name in #define {}", definition));
+        }
+
+        // like in C, a function-like macro has its parenthesis right after its name
+        let rest = &definition[name_end..];
+        let (params, body) = if let Some(params_and_body) = rest.strip_prefix('(') {
+            let params_end = params_and_body.find(')')
+                .ok_or(format!("Missing ) in the parameters of macro {}", name))?;
+            let params_text = params_and_body[..params_end].trim();
+            let params: Vec<String> = if params_text.is_empty() {
+                Vec::new()
+            } else {
+                params_text.split(',').map(|param| param.trim().to_string()).collect()
+            };
+            for (index, param) in params.iter().enumerate() {
+                if !is_identifier(param) || params[..index].contains(param) {
+                    return Err(format!("Invalid parameter {} of macro {}", param, name));
+                }
+            }
+            (Some(params), &params_and_body[params_end + 1..])
+        } else {
+            (None, rest)
+        };
+
+        let new_macro = Macro {
+            params,
+            body: remove_comments(body),
+        };
+        match self.macros.get(name) {
+            Some(existing_macro) if *existing_macro != new_macro => {
+                Err(format!("Macro {} is already defined differently", name))
+            },
+            _ => {
+                self.macros.insert(name.to_string(), new_macro);
+                Ok(())
+            },
+        }
+    }
+
+    pub fn undefine(&mut self, name: &str) {
+        self.macros.remove(name);
+    }
+
+    /// Expand the macro invocation starting at `start` with the identifier `start..name_end`.
+    /// Return the expansion and the end of the invocation,
+    /// or None if the identifier is not a macro to expand here.
+    /// The macros being expanded are `disabled`, so that they are not expanded recursively.
+    fn expand_invocation(
+        &self,
+        text: &str,
+        start: usize,
+        name_end: usize,
+        disabled: &HashSet<String>,
+    ) -> Result<Option<(String, usize)>, String> {
+        let name = &text[start..name_end];
+        let expanded_macro = match self.macros.get(name) {
+            Some(expanded_macro) if !disabled.contains(name) => expanded_macro,
+            _ => return Ok(None),
+        };
+        let mut inner_disabled = disabled.clone();
+        inner_disabled.insert(name.to_string());
+
+        match &expanded_macro.params {
+            None => Ok(Some((self.expand_text(&expanded_macro.body, &inner_disabled)?, name_end))),
+            Some(params) => {
+                // a function-like macro name without arguments is left as is
+                let (mut arguments, invocation_end) = match parse_arguments(text, name_end, name)? {
+                    Some(arguments) => arguments,
+                    None => return Ok(None),
+                };
+                if params.is_empty() && arguments == [""] {
+                    arguments.clear();
+                }
+                if arguments.len() != params.len() {
+                    return Err(format!(
+                        "Macro {} expects {} arguments, but got {}",
+                        name, params.len(), arguments.len()
+                    ));
+                }
+                let expanded_arguments = arguments.iter()
+                    .map(|argument| self.expand_text(argument, disabled))
+                    .collect::<Result<Vec<String>, String>>()?;
+                let substituted_body = substitute_arguments(
+                    &expanded_macro.body, params, &expanded_arguments
+                );
+                Ok(Some((self.expand_text(&substituted_body, &inner_disabled)?, invocation_end)))
+            },
+        }
+    }
+
+    /// Expand all the macros of a text, except the `disabled` ones.
+    fn expand_text(&self, text: &str, disabled: &HashSet<String>) -> Result<String, String> {
+        let mut result = String::new();
+        let mut position = 0;
+        let mut in_block_comment = false;
+        while position < text.len() {
+            let (token_end, is_identifier) = next_token(text, position, &mut in_block_comment);
+            if is_identifier {
+                if let Some((expansion, invocation_end)) = self.expand_invocation(
+                    text, position, token_end, disabled
+                )? {
+                    result.push_str(&expansion);
+                    position = invocation_end;
+                    continue;
+                }
+            }
+            result.push_str(&text[position..token_end]);
+            position = token_end;
+        }
+        Ok(result)
+    }
+
+    /// Expand the macros of a line of a source file, keeping track of which parts
+    /// of the line are copied as is, and which are macro invocations.
+    /// An error gives the range of the invocation where it happened.
+    pub fn expand_line(
+        &self,
+        line: &str,
+        in_block_comment: &mut bool,
+    ) -> Result<Vec<LinePart>, (usize, usize, String)> {
+        let mut parts: Vec<LinePart> = Vec::new();
+        let mut position = 0;
+        let mut copied_part_start = 0;
+        while position < line.len() {
+            let (token_end, is_identifier) = next_token(line, position, in_block_comment);
+            if is_identifier {
+                let potential_expansion = self.expand_invocation(
+                    line, position, token_end, &HashSet::new()
+                ).map_err(|message| (position, line.trim_end().len().max(token_end), message))?;
+                if let Some((expansion, invocation_end)) = potential_expansion {
+                    parts.push(LinePart { start: copied_part_start, end: position, expansion: None });
+                    parts.push(LinePart { start: position, end: invocation_end, expansion: Some(expansion) });
+                    position = invocation_end;
+                    copied_part_start = invocation_end;
+                    continue;
+                }
+            }
+            position = token_end;
+        }
+        parts.push(LinePart { start: copied_part_start, end: line.len(), expansion: None });
+        Ok(parts)
+    }
+}
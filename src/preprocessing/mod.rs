@@ -0,0 +1,303 @@
+mod macros;
+pub mod source_map;
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use pest::error::Error;
+use pest::Span;
+
+use crate::errors::make_preprocessing_error;
+use crate::semantic::errors::{SemanticError, PreprocessingError};
+use crate::syntax_parsing::Rule;
+
+use macros::MacroTable;
+use source_map::{SourceMap, FileId};
+
+/// The content of a program once its files have been included and its macros expanded,
+/// with the source map giving back the original file and position of each part.
+#[derive(Debug)]
+pub struct PreprocessedSource {
+    pub content: String,
+    pub source_map: SourceMap,
+}
+
+impl PreprocessedSource {
+    /// Make an error over the preprocessed content point to its original file.
+    pub fn relocate_error(&self, error: Error<Rule>) -> Error<Rule> {
+        self.source_map.relocate_error(error, &self.content)
+    }
+
+    /// Get the original file and line (starting at 1) of a position of the preprocessed content.
+    pub fn get_source_line(&self, offset: usize) -> Option<(&Path, usize)> {
+        self.source_map.locate(offset).map(|(file_id, file_offset)| {
+            let file = self.source_map.get_file(file_id);
+            let line = file.content[..file_offset].matches('\n').count() + 1;
+            (file.path.as_path(), line)
+        })
+    }
+
+    /// Get the original file, line and column (starting at 1) of a position of the preprocessed content.
+    pub fn get_source_position(&self, offset: usize) -> Option<(&Path, usize, usize)> {
+        self.source_map.locate(offset).map(|(file_id, file_offset)| {
+            let file = self.source_map.get_file(file_id);
+            let line_start = file.content[..file_offset].rfind('\n').map_or(0, |index| index + 1);
+            let line = file.content[..file_offset].matches('\n').count() + 1;
+            let column = file.content[line_start..file_offset].chars().count() + 1;
+            (file.path.as_path(), line, column)
+        })
+    }
+}
+
+/// An `#ifdef` or `#ifndef` directive, until its `#endif`.
+struct Conditional {
+    line_start: usize,
+    line_end: usize,
+    is_active: bool,
+    is_parent_active: bool,
+    has_else: bool,
+}
+
+struct Preprocessor {
+    content: String,
+    source_map: SourceMap,
+    macros: MacroTable,
+    include_stack: Vec<PathBuf>,
+    included_files: HashSet<PathBuf>,
+}
+
+fn make_directive_error(
+    file_path: &Path,
+    file_content: &str,
+    start: usize,
+    end: usize,
+    message: &str,
+) -> SemanticError {
+    let span = Span::new(file_content, start, end).unwrap();
+    SemanticError::Preprocessing(
+        PreprocessingError::from(
+            make_preprocessing_error(span, message)
+                .with_path(file_path.to_string_lossy().as_ref())
+        )
+    )
+}
+
+/// Split a directive line like `#define N 10` into its name and its argument.
+/// Return None if the line is not a directive.
+fn get_directive(line: &str) -> Option<(&str, &str)> {
+    let directive = line.trim().strip_prefix('#')?.trim_start();
+    let name_end = directive.find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(directive.len());
+    Some((&directive[..name_end], directive[name_end..].trim()))
+}
+
+fn is_macro_name(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl Preprocessor {
+    /// Append a part of a source file to the preprocessed content.
+    fn copy_file_part(&mut self, file_id: FileId, file_content: &str, start: usize, end: usize) {
+        let content_start = self.content.len();
+        self.content.push_str(&file_content[start..end]);
+        self.source_map.add_segment(content_start, self.content.len(), file_id, start);
+    }
+
+    /// Append a line of a source file to the preprocessed content, expanding its macros.
+    fn expand_file_line(
+        &mut self,
+        file_id: FileId,
+        file_content: &str,
+        line_start: usize,
+        line_end: usize,
+        in_block_comment: &mut bool,
+    ) -> Result<(), (usize, usize, String)> {
+        let line = &file_content[line_start..line_end];
+        for part in self.macros.expand_line(line, in_block_comment)? {
+            match part.expansion {
+                Some(expansion) => {
+                    let content_start = self.content.len();
+                    self.content.push_str(&expansion);
+                    self.source_map.add_expansion_segment(
+                        content_start, self.content.len(),
+                        file_id, line_start + part.start, line_start + part.end,
+                    );
+                },
+                None => self.copy_file_part(
+                    file_id, file_content, line_start + part.start, line_start + part.end
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    /// Append the content of the file included by an `#include "file.ctiny"` directive.
+    fn include_directive_file(
+        &mut self,
+        path: &Path,
+        argument: &str,
+        make_error: impl Fn(&str) -> SemanticError,
+    ) -> Result<(), SemanticError> {
+        let included_file_name = argument
+            .strip_prefix('"')
+            .and_then(|rest| rest.strip_suffix('"'))
+            .filter(|file_name| !file_name.is_empty() && !file_name.contains('"'))
+            .ok_or_else(|| make_error("Invalid #include directive, expected #include \"file.ctiny\""))?;
+
+        // included files are resolved relative to the including file
+        let included_path = path.parent()
+            .unwrap_or(Path::new(""))
+            .join(included_file_name);
+        let included_path = std::fs::canonicalize(&included_path)
+            .map_err(|error| make_error(
+                format!("Cannot include file {}: {}", included_path.display(), error).as_str()
+            ))?;
+        if self.include_stack.contains(&included_path) {
+            let cycle = self.include_stack.iter()
+                .skip_while(|stacked_path| **stacked_path != included_path)
+                .chain(std::iter::once(&included_path))
+                .map(|stacked_path| stacked_path.display().to_string())
+                .collect::<Vec<String>>()
+                .join(" -> ");
+            return Err(make_error(format!("Include cycle: {}", cycle).as_str()));
+        }
+        // a file is only included once, like with include guards
+        if self.included_files.contains(&included_path) {
+            return Ok(());
+        }
+        let included_content = std::fs::read_to_string(&included_path)
+            .map_err(|error| make_error(
+                format!("Cannot read file {}: {}", included_path.display(), error).as_str()
+            ))?;
+        self.include_file(included_path, included_content)
+    }
+
+    /// Interpret a directive line of a file, updating the conditionals of this file.
+    fn interpret_directive(
+        &mut self,
+        path: &Path,
+        name: &str,
+        argument: &str,
+        conditionals: &mut Vec<Conditional>,
+        make_error: impl Fn(&str) -> SemanticError,
+        (line_start, line_end): (usize, usize),
+    ) -> Result<(), SemanticError> {
+        let is_active = conditionals.last().is_none_or(|conditional| conditional.is_active);
+        match name {
+            "ifdef" | "ifndef" => {
+                if is_active && !is_macro_name(argument) {
+                    return Err(make_error(format!("Invalid macro name in #{} {}", name, argument).as_str()));
+                }
+                let is_defined = self.macros.is_defined(argument);
+                conditionals.push(Conditional {
+                    line_start,
+                    line_end,
+                    is_active: is_active && (is_defined == (name == "ifdef")),
+                    is_parent_active: is_active,
+                    has_else: false,
+                });
+            },
+            "else" => {
+                let conditional = conditionals.last_mut()
+                    .ok_or_else(|| make_error("#else without #ifdef or #ifndef"))?;
+                if conditional.has_else {
+                    return Err(make_error("#else after #else"));
+                }
+                conditional.has_else = true;
+                conditional.is_active = conditional.is_parent_active && !conditional.is_active;
+            },
+            "endif" => {
+                conditionals.pop()
+                    .ok_or_else(|| make_error("#endif without #ifdef or #ifndef"))?;
+            },
+            // the other directives are ignored in the skipped parts
+            _ if !is_active => {},
+            "include" => self.include_directive_file(path, argument, make_error)?,
+            "define" => self.macros.define(argument)
+                .map_err(|message| make_error(message.as_str()))?,
+            "undef" => {
+                if !is_macro_name(argument) {
+                    return Err(make_error(format!("Invalid macro name in #undef {}", argument).as_str()));
+                }
+                self.macros.undefine(argument);
+            },
+            _ => return Err(make_error(format!("Unknown preprocessing directive #{}", name).as_str())),
+        }
+        Ok(())
+    }
+
+    /// Append a source file to the preprocessed content, interpreting its directives
+    /// and expanding its macros.
+    /// Directive lines and skipped lines are replaced by empty lines.
+    fn include_file(&mut self, path: PathBuf, file_content: String) -> Result<(), SemanticError> {
+        let file_id = self.source_map.add_file(path.clone(), file_content.clone());
+        self.include_stack.push(path.clone());
+        self.included_files.insert(path.clone());
+
+        let mut conditionals: Vec<Conditional> = Vec::new();
+        let mut in_block_comment = false;
+        let mut line_start = 0;
+        for line in file_content.split_inclusive('\n') {
+            let line_end = line_start + line.trim_end().len();
+            let potential_directive = if in_block_comment { None } else { get_directive(line) };
+            if let Some((name, argument)) = potential_directive {
+                self.interpret_directive(
+                    &path, name, argument, &mut conditionals,
+                    |message| make_directive_error(&path, &file_content, line_start, line_end, message),
+                    (line_start, line_end),
+                )?;
+                self.copy_file_part(file_id, &file_content, line_end, line_start + line.len());
+            } else if conditionals.last().is_none_or(|conditional| conditional.is_active) {
+                self.expand_file_line(file_id, &file_content, line_start, line_end, &mut in_block_comment)
+                    .map_err(|(start, end, message)| make_directive_error(
+                        &path, &file_content, line_start + start, line_start + end, message.as_str()
+                    ))?;
+                self.copy_file_part(file_id, &file_content, line_end, line_start + line.len());
+            } else {
+                self.copy_file_part(file_id, &file_content, line_end, line_start + line.len());
+            }
+            line_start += line.len();
+        }
+        if let Some(conditional) = conditionals.last() {
+            return Err(make_directive_error(
+                &path, &file_content, conditional.line_start, conditional.line_end,
+                "Unterminated conditional directive, missing #endif",
+            ));
+        }
+
+        self.include_stack.pop();
+        Ok(())
+    }
+}
+
+/// Read a Ctiny file, include the files given by its `#include "file.ctiny"` directives,
+/// and expand its `#define` macros.
+pub fn preprocess_file(path: &Path) -> Result<PreprocessedSource, SemanticError> {
+    // there is no content to point to, so the error points to the start of an empty one
+    let file_content = std::fs::read_to_string(path)
+        .map_err(|error| make_directive_error(
+            path, "", 0, 0,
+            format!("Cannot read file {}: {}", path.display(), error).as_str()
+        ))?;
+    preprocess_content(path, file_content)
+}
+
+/// Like `preprocess_file`, but with the content of the file given,
+/// like the unsaved content of a file open in an editor.
+/// The included files are still read from the disk, relative to `path`.
+pub fn preprocess_content(path: &Path, file_content: String) -> Result<PreprocessedSource, SemanticError> {
+    let path = std::fs::canonicalize(path).unwrap_or(path.to_path_buf());
+    let mut preprocessor = Preprocessor {
+        content: String::new(),
+        source_map: SourceMap::new(),
+        macros: MacroTable::new(),
+        include_stack: Vec::new(),
+        included_files: HashSet::new(),
+    };
+    preprocessor.include_file(path, file_content)?;
+    Ok(PreprocessedSource {
+        content: preprocessor.content,
+        source_map: preprocessor.source_map,
+    })
+}
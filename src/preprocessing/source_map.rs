@@ -0,0 +1,171 @@
+use std::path::PathBuf;
+
+use pest::error::{Error, InputLocation};
+use pest::{Position, Span};
+
+use crate::syntax_parsing::Rule;
+
+pub type FileId = usize;
+
+/// A source file of the program, with its original content.
+#[derive(Debug)]
+pub struct SourceFile {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// A part of the preprocessed content coming from a single source file.
+/// `start..end` is the range in the preprocessed content,
+/// and `file_start` the offset of `start` in the source file.
+/// The expansion of a macro doesn't match its source text character by character,
+/// so all its positions are mapped to the macro invocation `file_start..expansion_end`.
+#[derive(Debug)]
+struct SourceSegment {
+    start: usize,
+    end: usize,
+    file_id: FileId,
+    file_start: usize,
+    expansion_end: Option<usize>,
+}
+
+impl SourceSegment {
+    /// Get the offset in the source file of a position of the segment.
+    /// The end of a span inside a macro expansion is the end of the macro invocation.
+    fn get_file_offset(&self, offset: usize, is_span_end: bool) -> usize {
+        match self.expansion_end {
+            Some(expansion_end) if is_span_end || offset == self.end => expansion_end,
+            Some(_) => self.file_start,
+            None => self.file_start + offset - self.start,
+        }
+    }
+}
+
+/// Map each position of the preprocessed content back to its source file.
+/// The AST spans are over the preprocessed content, so diagnostics are relocated
+/// through the source map before being reported.
+#[derive(Debug)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+    segments: Vec<SourceSegment>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self {
+            files: Vec::new(),
+            segments: Vec::new(),
+        }
+    }
+
+    pub fn add_file(&mut self, path: PathBuf, content: String) -> FileId {
+        self.files.push(SourceFile { path, content });
+        self.files.len() - 1
+    }
+
+    pub fn get_file(&self, file_id: FileId) -> &SourceFile {
+        &self.files[file_id]
+    }
+
+    /// Get the source files, in the order they were included, their index being their id.
+    pub fn get_files(&self) -> &[SourceFile] {
+        &self.files
+    }
+
+    /// Record that `start..end` of the preprocessed content comes from the given file,
+    /// from offset `file_start`.
+    pub fn add_segment(&mut self, start: usize, end: usize, file_id: FileId, file_start: usize) {
+        if start < end {
+            self.segments.push(SourceSegment { start, end, file_id, file_start, expansion_end: None });
+        }
+    }
+
+    /// Record that `start..end` of the preprocessed content is the expansion
+    /// of the macro invocation `file_start..file_end` of the given file.
+    pub fn add_expansion_segment(
+        &mut self,
+        start: usize,
+        end: usize,
+        file_id: FileId,
+        file_start: usize,
+        file_end: usize,
+    ) {
+        if start < end {
+            self.segments.push(SourceSegment {
+                start, end, file_id, file_start, expansion_end: Some(file_end)
+            });
+        }
+    }
+
+    /// Get the file and the offset in this file of a position of the preprocessed content.
+    /// The end of a segment is also mapped, for spans ending at the end of a file.
+    pub fn locate(&self, offset: usize) -> Option<(FileId, usize)> {
+        self.segments.iter()
+            .find(|segment| segment.start <= offset && offset < segment.end)
+            .or_else(|| self.segments.iter().rev().find(|segment| segment.end == offset))
+            .map(|segment| (segment.file_id, segment.get_file_offset(offset, false)))
+    }
+
+    /// Like `locate`, but for the (excluded) end of a span,
+    /// which belongs to the segment before it.
+    pub fn locate_end(&self, offset: usize) -> Option<(FileId, usize)> {
+        self.segments.iter()
+            .find(|segment| segment.start < offset && offset <= segment.end)
+            .map(|segment| (segment.file_id, segment.get_file_offset(offset, true)))
+            .or_else(|| self.locate(offset))
+    }
+
+    /// Get the position in the preprocessed content of an offset of a source file,
+    /// the inverse of `locate`. None if this part of the file is not in the content,
+    /// like a directive, or if it is a macro invocation.
+    pub fn locate_in_content(&self, file_id: FileId, file_offset: usize) -> Option<usize> {
+        self.segments.iter()
+            .find(|segment| {
+                segment.file_id == file_id
+                    && segment.expansion_end.is_none()
+                    && segment.file_start <= file_offset
+                    && file_offset <= segment.file_start + segment.end - segment.start
+            })
+            .map(|segment| segment.start + file_offset - segment.file_start)
+    }
+
+    /// Rebuild an error located in the preprocessed content so that it points
+    /// to the right line of the right file, and names this file.
+    pub fn relocate_error(&self, error: Error<Rule>, preprocessed_content: &str) -> Error<Rule> {
+        let (start, end) = match error.location {
+            InputLocation::Pos(position) => (position, position),
+            InputLocation::Span((start, end)) => (start, end),
+        };
+
+        // errors built from spans outside of the program content (like the ones
+        // reporting unassigned variables at the end of a function) are left as is
+        let potential_line = preprocessed_content.get(..start)
+            .map(|before| before.rfind('\n').map_or(0, |line_start| line_start + 1))
+            .and_then(|line_start| preprocessed_content[line_start..].lines().next());
+        match potential_line {
+            Some(line) if line.trim_end_matches('\r') == error.line().trim_end_matches(['\r', '\n']) => {},
+            _ => return error,
+        }
+
+        let (file_id, file_start) = match self.locate(start) {
+            Some(location) => location,
+            None => return error,
+        };
+        let file = self.get_file(file_id);
+        let file_path = file.path.to_string_lossy().to_string();
+        match error.location {
+            InputLocation::Pos(_) => {
+                let position = Position::new(&file.content, file_start).unwrap();
+                Error::new_from_pos(error.variant, position).with_path(&file_path)
+            },
+            InputLocation::Span(_) => {
+                // a span covering several files is cut at the end of the first one
+                let file_end = match self.locate_end(end) {
+                    Some((end_file_id, file_end)) if end_file_id == file_id && file_end >= file_start => file_end,
+                    _ => file.content.len(),
+                };
+                let span = Span::new(&file.content, file_start, file_end).unwrap();
+                Error::new_from_span(error.variant, span).with_path(&file_path)
+            },
+        }
+    }
+}
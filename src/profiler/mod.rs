@@ -0,0 +1,288 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::rc::Rc;
+
+use crate::abstract_syntax_tree::nodes::{Node, Identifier, Expression, Statement, Function, Value, TypeSpecifier};
+use crate::interpretation::hooks::InterpretationHook;
+use crate::preprocessing::PreprocessedSource;
+use crate::preprocessing::source_map::FileId;
+use crate::semantic::errors::SemanticError;
+use crate::semantic::type_casts::convert_to_type;
+use crate::symbol_table::structs::SymbolTable;
+
+/// A line of a source file of the program, its id being its index in the source map.
+pub type LineKey = (FileId, usize);
+
+/// How many statements and expressions of a line were interpreted.
+#[derive(Debug, Default, PartialEq)]
+pub struct LineCounts {
+    pub statements: u64,
+    pub expressions: u64,
+}
+
+/// A step is the interpretation of a statement or an expression.
+/// The self steps of a function are the ones of its own body,
+/// and its total steps also count the steps of the functions it calls.
+#[derive(Debug, Default, PartialEq)]
+pub struct FunctionCounts {
+    pub calls: u64,
+    pub self_steps: u64,
+    pub total_steps: u64,
+}
+
+/// How many times a `while` statement was interpreted, and how many times its body was entered.
+#[derive(Debug, Default, PartialEq)]
+pub struct LoopCounts {
+    pub executions: u64,
+    pub iterations: u64,
+}
+
+/// The counts of the interpretation of a program, filled by a `Profiler`.
+pub struct Profile {
+    source: Rc<PreprocessedSource>,
+    pub lines: BTreeMap<LineKey, LineCounts>,
+    pub functions: BTreeMap<String, FunctionCounts>,
+    pub loops: BTreeMap<LineKey, LoopCounts>,
+    /// The steps of each call stack, like `main;square`, as in the folded stacks of flame graphs.
+    pub stacks: BTreeMap<String, u64>,
+    pub total_steps: u64,
+    call_stack: Vec<String>,
+    /// The number of frames of each function in the call stack, to count the steps of a recursive function once.
+    active_frames: HashMap<String, usize>,
+    /// The call stack joined as in `stacks`, updated when a function is entered or exited.
+    stack_key: String,
+    /// The lines of the offsets of the preprocessed content, not to count the lines before each step.
+    line_cache: HashMap<usize, Option<LineKey>>,
+    /// The conditions of the `while` statements being interpreted, by the address of their node.
+    loop_conditions: HashMap<usize, LineKey>,
+}
+
+impl Profile {
+    pub fn new(source: Rc<PreprocessedSource>) -> Self {
+        Self {
+            source,
+            lines: BTreeMap::new(),
+            functions: BTreeMap::new(),
+            loops: BTreeMap::new(),
+            stacks: BTreeMap::new(),
+            total_steps: 0,
+            call_stack: Vec::new(),
+            active_frames: HashMap::new(),
+            stack_key: String::new(),
+            line_cache: HashMap::new(),
+            loop_conditions: HashMap::new(),
+        }
+    }
+
+    fn get_line_key(&mut self, offset: usize) -> Option<LineKey> {
+        let source = &self.source;
+        *self.line_cache.entry(offset).or_insert_with(|| {
+            source.source_map.locate(offset).map(|(file_id, file_offset)| {
+                let file = source.source_map.get_file(file_id);
+                (file_id, file.content[..file_offset].matches('\n').count() + 1)
+            })
+        })
+    }
+
+    fn get_line_name(&self, (file_id, line): LineKey) -> String {
+        let path = &self.source.source_map.get_file(file_id).path;
+        let file_name = path.file_name().map_or(path.to_string_lossy(), |file_name| file_name.to_string_lossy());
+        format!("{}:{}", file_name, line)
+    }
+
+    fn push_frame(&mut self, function_name: String) {
+        if !self.stack_key.is_empty() {
+            self.stack_key.push(';');
+        }
+        self.stack_key.push_str(&function_name);
+        *self.active_frames.entry(function_name.clone()).or_default() += 1;
+        self.functions.entry(function_name.clone()).or_default().calls += 1;
+        self.call_stack.push(function_name);
+    }
+
+    fn pop_frame(&mut self) {
+        let Some(function_name) = self.call_stack.pop() else {
+            return;
+        };
+        let separator_length = if self.call_stack.is_empty() { 0 } else { 1 };
+        self.stack_key.truncate(self.stack_key.len() - function_name.len() - separator_length);
+        if let Some(frames) = self.active_frames.get_mut(&function_name) {
+            *frames -= 1;
+            if *frames == 0 {
+                self.active_frames.remove(&function_name);
+            }
+        }
+    }
+
+    /// Count a step for all the functions of the call stack.
+    fn count_step(&mut self) {
+        self.total_steps += 1;
+        let Some(current_function_name) = self.call_stack.last() else {
+            return;
+        };
+        if let Some(counts) = self.functions.get_mut(current_function_name) {
+            counts.self_steps += 1;
+        }
+        // a recursive function is counted once
+        for function_name in self.active_frames.keys() {
+            if let Some(counts) = self.functions.get_mut(function_name) {
+                counts.total_steps += 1;
+            }
+        }
+        match self.stacks.get_mut(&self.stack_key) {
+            Some(steps) => *steps += 1,
+            None => {
+                self.stacks.insert(self.stack_key.clone(), 1);
+            },
+        }
+    }
+
+    /// Write the source files with the counts of each line, followed by the counts of the functions and loops.
+    pub fn write_report(&self, output: &mut dyn Write) -> std::io::Result<()> {
+        for (file_id, file) in self.source.source_map.get_files().iter().enumerate() {
+            writeln!(output, "Profile of {}:", file.path.display())?;
+            writeln!(output, "{:>10} {:>11} | {:>5} | source", "statements", "expressions", "line")?;
+            for (line_index, line) in file.content.lines().enumerate() {
+                let line_number = line_index + 1;
+                let (statements, expressions) = match self.lines.get(&(file_id, line_number)) {
+                    Some(counts) => (counts.statements.to_string(), counts.expressions.to_string()),
+                    None => (String::new(), String::new()),
+                };
+                writeln!(output, "{:>10} {:>11} | {:>5} | {}", statements, expressions, line_number, line)?;
+            }
+            writeln!(output)?;
+        }
+
+        // the most expensive functions first
+        let mut functions: Vec<(&String, &FunctionCounts)> = self.functions.iter().collect();
+        functions.sort_by(|(first_name, first_counts), (second_name, second_counts)| {
+            second_counts.total_steps.cmp(&first_counts.total_steps).then(first_name.cmp(second_name))
+        });
+        let name_width = functions.iter().map(|(name, _)| name.len()).max().unwrap_or(0).max("function".len());
+        writeln!(output, "{:<name_width$} {:>10} {:>12} {:>12}", "function", "calls", "self steps", "total steps")?;
+        for (name, counts) in functions {
+            writeln!(
+                output, "{:<name_width$} {:>10} {:>12} {:>12}",
+                name, counts.calls, counts.self_steps, counts.total_steps
+            )?;
+        }
+
+        if !self.loops.is_empty() {
+            let loop_names: Vec<(String, &LoopCounts)> = self.loops.iter()
+                .map(|(line_key, counts)| (self.get_line_name(*line_key), counts))
+                .collect();
+            let location_width = loop_names.iter().map(|(name, _)| name.len()).max().unwrap_or(0).max("while".len());
+            writeln!(output)?;
+            writeln!(output, "{:<location_width$} {:>10} {:>12}", "while", "executions", "iterations")?;
+            for (name, counts) in loop_names {
+                writeln!(output, "{:<location_width$} {:>10} {:>12}", name, counts.executions, counts.iterations)?;
+            }
+        }
+
+        writeln!(output)?;
+        writeln!(output, "Total steps: {}", self.total_steps)
+    }
+
+    /// Write the steps of each call stack in the folded format of flame graph tools,
+    /// like `main;square 12`, under a root frame if given, like the name of the program.
+    pub fn write_folded_stacks(&self, output: &mut dyn Write, root_frame: Option<&str>) -> std::io::Result<()> {
+        for (stack, steps) in &self.stacks {
+            match root_frame {
+                Some(root_frame) => writeln!(output, "{};{} {}", root_frame, stack, steps)?,
+                None => writeln!(output, "{} {}", stack, steps)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An interpretation hook counting the steps of a program in a shared `Profile`,
+/// to report them once the interpretation is over.
+pub struct Profiler {
+    profile: Rc<RefCell<Profile>>,
+}
+
+impl Profiler {
+    pub fn new(profile: Rc<RefCell<Profile>>) -> Self {
+        Self { profile }
+    }
+}
+
+impl InterpretationHook for Profiler {
+    fn before_statement<'a>(
+        &mut self,
+        statement_node: &Node<'a, Statement<'a>>,
+        _symbol_table: &SymbolTable,
+        _current_scope_node_id: &Node<'a, Identifier>,
+    ) -> Result<(), SemanticError> {
+        let mut profile = self.profile.borrow_mut();
+        profile.count_step();
+        let Some(line_key) = profile.get_line_key(statement_node.sp.start()) else {
+            return Ok(());
+        };
+        profile.lines.entry(line_key).or_default().statements += 1;
+        if let Statement::While(while_statement) = &statement_node.data {
+            profile.loops.entry(line_key).or_default().executions += 1;
+            let condition_address = &while_statement.condition as *const _ as usize;
+            profile.loop_conditions.insert(condition_address, line_key);
+        }
+        Ok(())
+    }
+
+    fn before_expression<'a>(
+        &mut self,
+        expression_node: &Node<'a, Expression<'a>>,
+        _symbol_table: &SymbolTable,
+        _current_scope_node_id: &Node<'a, Identifier>,
+    ) -> Result<(), SemanticError> {
+        let mut profile = self.profile.borrow_mut();
+        profile.count_step();
+        if let Some(line_key) = profile.get_line_key(expression_node.sp.start()) {
+            profile.lines.entry(line_key).or_default().expressions += 1;
+        }
+        Ok(())
+    }
+
+    fn after_expression<'a>(
+        &mut self,
+        expression_node: &Node<'a, Expression<'a>>,
+        result: &Result<Node<'a, Value>, SemanticError>,
+        _symbol_table: &SymbolTable,
+        _current_scope_node_id: &Node<'a, Identifier>,
+    ) -> Result<(), SemanticError> {
+        let mut profile = self.profile.borrow_mut();
+        let expression_address = expression_node as *const _ as usize;
+        let Some(&line_key) = profile.loop_conditions.get(&expression_address) else {
+            return Ok(());
+        };
+        // the body is entered each time the condition is true
+        let is_condition_true = result.as_ref().ok()
+            .and_then(|value_node| convert_to_type(value_node.clone(), TypeSpecifier::Bool).ok())
+            .is_some_and(|bool_node| bool_node.data == Value::Bool(true));
+        if is_condition_true {
+            profile.loops.entry(line_key).or_default().iterations += 1;
+        }
+        Ok(())
+    }
+
+    fn enter_function<'a>(
+        &mut self,
+        function_node: &Node<'a, Function<'a>>,
+        _symbol_table: &SymbolTable,
+    ) -> Result<(), SemanticError> {
+        let function_name = function_node.data.name.data.name.clone();
+        self.profile.borrow_mut().push_frame(function_name);
+        Ok(())
+    }
+
+    fn exit_function<'a>(
+        &mut self,
+        _function_node: &Node<'a, Function<'a>>,
+        _result: &Result<Node<'a, Value>, SemanticError>,
+        _symbol_table: &SymbolTable,
+    ) -> Result<(), SemanticError> {
+        self.profile.borrow_mut().pop_frame();
+        Ok(())
+    }
+}
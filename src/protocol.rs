@@ -0,0 +1,44 @@
+use std::io::{BufRead, Write};
+
+use serde_json::Value as JsonValue;
+
+// The Debug Adapter Protocol and the Language Server Protocol share the same framing:
+// each JSON message is preceded by a `Content-Length` header and an empty line.
+
+/// Read the next message, or None at the end of the input.
+/// Messages that are not valid JSON are skipped.
+pub fn read_message(input: &mut dyn BufRead) -> Option<JsonValue> {
+    loop {
+        let mut content_length: Option<usize> = None;
+        loop {
+            let mut header = String::new();
+            if input.read_line(&mut header).ok()? == 0 {
+                return None;
+            }
+            let header = header.trim();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("Content-Length") {
+                    content_length = value.trim().parse().ok();
+                }
+            }
+        }
+        let Some(content_length) = content_length else {
+            continue;
+        };
+        let mut content = vec![0; content_length];
+        input.read_exact(&mut content).ok()?;
+        match serde_json::from_slice(&content) {
+            Ok(message) => return Some(message),
+            Err(error) => log::warn!("Ignoring an invalid protocol message: {}", error),
+        }
+    }
+}
+
+pub fn write_message(output: &mut dyn Write, message: &JsonValue) {
+    let content = message.to_string();
+    let _ = write!(output, "Content-Length: {}\r\n\r\n{}", content.len(), content);
+    let _ = output.flush();
+}
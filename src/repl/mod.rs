@@ -0,0 +1,397 @@
+use indexmap::IndexMap;
+use std::io::{BufRead, Write};
+
+use pest::error::{Error, InputLocation};
+use pest::iterators::Pair;
+
+use crate::abstract_syntax_tree::declarations::{build_multi_declaration, build_struct_definition, build_enum_definition};
+use crate::abstract_syntax_tree::expressions::build_expression;
+use crate::abstract_syntax_tree::functions::{build_entry_point_function, build_function_definition, build_function_prototype};
+use crate::abstract_syntax_tree::nodes::{AST, Node, Identifier, TranslationUnit};
+use crate::abstract_syntax_tree::statements::build_statement;
+use crate::errors::make_ast_error_from_pair;
+use crate::interpretation::execution_limits::start_execution;
+use crate::interpretation::interpret_declaration::interpret_array_initializer;
+use crate::interpretation::interpret_expression::interpret_expression;
+use crate::interpretation::interpret_statement::interpret_statement;
+use crate::semantic::errors::{SemanticError, SemanticErrorTrait, ASTBuildingError, SyntaxParsingError, RedeclarationError};
+use crate::semantic::warnings::take_warnings;
+use crate::symbol_table::build_static_symbol_table;
+use crate::symbol_table::structs::{SymbolTable, Variable};
+use crate::syntax_parsing::{self, Rule};
+
+const PROMPT: &str = "ctiny> ";
+const CONTINUATION_PROMPT: &str = "  ...> ";
+
+const HELP: &str = "\
+Enter struct, enum and function definitions, declarations, statements or expressions.
+Expressions are evaluated and their value is printed.
+Commands:
+    :vars          list the variables of the session, with their values
+    :funcs         list the functions defined in the session
+    :ast <input>   print the AST of an input, without running it
+    :reset         forget all definitions and variables
+    :help          print this help
+    :quit          exit the REPL";
+
+// The inputs that can be entered in the REPL, tried in this order.
+// Each input must match one of these rules entirely.
+const INPUT_RULES: [Rule; 8] = [
+    Rule::struct_definition,
+    Rule::enum_definition,
+    Rule::function_prototype,
+    Rule::function_definition,
+    Rule::entry_point_function_definition,
+    Rule::multi_declaration,
+    Rule::statement,
+    Rule::expression,
+];
+
+// The variables of the session are the variables of this function.
+// Its body holds the declarations of the session, and the statement being run.
+const SESSION_FUNCTION: &str = "int main() {\n    return 0;\n}";
+
+fn make_ast_building_error(error: Error<Rule>) -> SemanticError {
+    SemanticError::ASTBuilding(ASTBuildingError::from(error))
+}
+
+fn get_error_position(error: &Error<Rule>) -> usize {
+    match error.location {
+        InputLocation::Pos(position) => position,
+        InputLocation::Span((start, _)) => start,
+    }
+}
+
+/// Parse an input with the first rule it matches entirely.
+/// If none matches, report the syntax error that went the furthest into the input.
+fn parse_input(input: &str) -> Result<Pair<'_, Rule>, SemanticError> {
+    let mut furthest_error: Option<Error<Rule>> = None;
+    for rule in INPUT_RULES {
+        match syntax_parsing::parse(rule, input) {
+            Ok(mut pairs) => {
+                let pair = pairs.next().unwrap();
+                if pair.as_str() == input {
+                    return Ok(pair);
+                }
+            },
+            Err(error) => {
+                let is_further = furthest_error.as_ref()
+                    .is_none_or(|furthest_error| get_error_position(&error) > get_error_position(furthest_error));
+                if is_further {
+                    furthest_error = Some(error);
+                }
+            },
+        }
+    }
+    let error = furthest_error.unwrap_or_else(|| {
+        // all the rules matched a part of the input only: complain about the expression
+        let pair = syntax_parsing::parse(Rule::expression, input).unwrap().next().unwrap();
+        let rest = pest::Position::new(input, pair.as_span().end()).unwrap();
+        Error::new_from_pos(
+            pest::error::ErrorVariant::CustomError {
+                message: "Unexpected input after the end of the expression".to_string(),
+            },
+            rest,
+        )
+    });
+    Err(SemanticError::SyntaxParsing(SyntaxParsingError::from(error)))
+}
+
+/// Check if an input is not finished yet, like a function definition
+/// whose closing brace is on a following line.
+/// The brackets of strings, characters and comments are not counted.
+pub fn is_incomplete(input: &str) -> bool {
+    let mut depth: i32 = 0;
+    let mut quote: Option<char> = None;
+    let mut is_escaped = false;
+    let mut chars = input.chars().peekable();
+    while let Some(current_char) = chars.next() {
+        match quote {
+            Some(quote_char) => {
+                if !is_escaped && current_char == quote_char {
+                    quote = None;
+                }
+                is_escaped = !is_escaped && current_char == '\\';
+            },
+            None => match (current_char, chars.peek()) {
+                ('"' | '\'', _) => quote = Some(current_char),
+                ('/', Some('/')) => {
+                    // skip the rest of the line
+                    chars.find(|&comment_char| comment_char == '\n');
+                },
+                ('/', Some('*')) => {
+                    // skip until the end of the comment, an unterminated comment is not finished yet
+                    chars.next();
+                    let mut previous_char = ' ';
+                    let is_terminated = chars.any(|comment_char| {
+                        let is_end = previous_char == '*' && comment_char == '/';
+                        previous_char = comment_char;
+                        is_end
+                    });
+                    if !is_terminated {
+                        return true;
+                    }
+                },
+                ('{' | '(' | '[', _) => depth += 1,
+                ('}' | ')' | ']', _) => depth -= 1,
+                _ => {},
+            },
+        }
+    }
+    depth > 0
+}
+
+/// An interactive session: the definitions, declarations and variable values
+/// of the previous inputs are kept for the following ones.
+pub struct ReplSession {
+    ast: AST<'static>,
+    symbol_table: SymbolTable,
+}
+
+impl ReplSession {
+    pub fn new() -> Self {
+        let session_function_pair = syntax_parsing::parse(Rule::entry_point_function_definition, SESSION_FUNCTION)
+            .unwrap().next().unwrap();
+        let session_function = build_entry_point_function(session_function_pair.clone()).unwrap();
+        let ast = Node {
+            sp: session_function_pair.as_span(),
+            data: TranslationUnit {
+                structs: None,
+                enums: None,
+                prototypes: None,
+                functions: IndexMap::new(),
+                main_function: session_function,
+            },
+        };
+        let symbol_table = build_static_symbol_table(&ast).unwrap();
+        Self {
+            ast,
+            symbol_table,
+        }
+    }
+
+    fn get_session_scope_id(&self) -> Node<'static, Identifier> {
+        self.ast.data.main_function.data.name.clone()
+    }
+
+    /// Build the symbol table again, with all the static checks, after a change of the definitions
+    /// or declarations of the session. The values of the variables of the session are kept.
+    fn rebuild_symbol_table(&mut self) -> Result<(), SemanticError> {
+        let mut symbol_table = build_static_symbol_table(&self.ast)?;
+        let session_scope_id = self.get_session_scope_id();
+        if let Some(previous_session_scope) = self.symbol_table.remove_scope(&session_scope_id.data) {
+            symbol_table.get_mut_scope(&session_scope_id).unwrap()
+                .keep_variables_from(previous_session_scope);
+        }
+        self.symbol_table = symbol_table;
+        Ok(())
+    }
+
+    fn define_function(&mut self, pair: Pair<'static, Rule>) -> Result<String, SemanticError> {
+        let function = build_function_definition(pair).map_err(make_ast_building_error)?;
+        let function_id = function.data.name.data.clone();
+        let previous_function = self.ast.data.functions.insert(function_id.clone(), function);
+        if let Err(error) = self.rebuild_symbol_table() {
+            match previous_function {
+                Some(previous_function) => self.ast.data.functions.insert(function_id, previous_function),
+                None => self.ast.data.functions.shift_remove(&function_id),
+            };
+            return Err(error);
+        }
+        let verb = if previous_function.is_some() { "Redefined" } else { "Defined" };
+        Ok(format!("{} {}", verb, self.ast.data.functions[&function_id].data.get_signature()))
+    }
+
+    fn define_type(&mut self, pair: Pair<'static, Rule>) -> Result<String, SemanticError> {
+        let is_struct = pair.as_rule() == Rule::struct_definition;
+        if is_struct {
+            let struct_definition = build_struct_definition(pair).map_err(make_ast_building_error)?;
+            self.ast.data.structs.get_or_insert_with(Vec::new).push(struct_definition);
+        } else {
+            let enum_definition = build_enum_definition(pair).map_err(make_ast_building_error)?;
+            self.ast.data.enums.get_or_insert_with(Vec::new).push(enum_definition);
+        }
+        if let Err(error) = self.rebuild_symbol_table() {
+            if is_struct {
+                self.ast.data.structs.as_mut().unwrap().pop();
+            } else {
+                self.ast.data.enums.as_mut().unwrap().pop();
+            }
+            return Err(error);
+        }
+        Ok(String::new())
+    }
+
+    fn declare_variables(&mut self, pair: Pair<'static, Rule>) -> Result<String, SemanticError> {
+        let declarations = build_multi_declaration(pair).map_err(make_ast_building_error)?;
+        let session_declarations = &mut self.ast.data.main_function.data.body.data.declarations;
+        for (index, declaration) in declarations.iter().enumerate() {
+            let identifier = &declaration.data.identifier;
+            let is_declared = session_declarations.iter()
+                .chain(declarations[..index].iter())
+                .any(|session_declaration| session_declaration.data.identifier.data == identifier.data);
+            if is_declared {
+                return Err(SemanticError::Redeclaration(
+                    RedeclarationError::init(
+                        identifier.sp,
+                        &format!("Variable {} is already declared in this session", identifier.data.name)
+                    )
+                ));
+            }
+        }
+
+        let nb_of_previous_declarations = session_declarations.len();
+        session_declarations.extend(declarations);
+        if let Err(error) = self.rebuild_symbol_table() {
+            self.ast.data.main_function.data.body.data.declarations.truncate(nb_of_previous_declarations);
+            return Err(error);
+        }
+
+        // like when a function is called, the initializer lists of non-const arrays are evaluated
+        let session_scope_id = self.get_session_scope_id();
+        for declaration in &self.ast.data.main_function.data.body.data.declarations[nb_of_previous_declarations..] {
+            if !declaration.data.is_const {
+                interpret_array_initializer(declaration, &mut self.symbol_table, &session_scope_id, &self.ast.data)?;
+            }
+        }
+        Ok(String::new())
+    }
+
+    fn run_statement(&mut self, pair: Pair<'static, Rule>) -> Result<String, SemanticError> {
+        let statement = build_statement(pair).map_err(make_ast_building_error)?;
+        // the statement is checked like the statements of a function
+        self.ast.data.main_function.data.body.data.statements = vec![statement];
+        let session_scope_id = self.get_session_scope_id();
+        let result = self.rebuild_symbol_table().and_then(|_| interpret_statement(
+            &self.ast.data.main_function.data.body.data.statements[0],
+            &mut self.symbol_table,
+            &session_scope_id,
+            &self.ast.data,
+        ));
+        self.ast.data.main_function.data.body.data.statements.clear();
+        result.map(|_| String::new())
+    }
+
+    fn evaluate_expression(&mut self, pair: Pair<'static, Rule>) -> Result<String, SemanticError> {
+        let expression = build_expression(pair).map_err(make_ast_building_error)?;
+        let session_scope_id = self.get_session_scope_id();
+        let value = interpret_expression(&expression, &mut self.symbol_table, &session_scope_id, &self.ast.data)?;
+        Ok(value.data.to_string())
+    }
+
+    /// Build the AST of an input, without running it.
+    fn describe_ast(&self, input: &str) -> Result<String, SemanticError> {
+        let pair = parse_input(input)?;
+        let description = match pair.as_rule() {
+            Rule::struct_definition => format!("{:#?}", build_struct_definition(pair)),
+            Rule::enum_definition => format!("{:#?}", build_enum_definition(pair)),
+            Rule::function_prototype => format!("{:#?}", build_function_prototype(pair)),
+            Rule::function_definition => format!("{:#?}", build_function_definition(pair)),
+            Rule::entry_point_function_definition => format!("{:#?}", build_entry_point_function(pair)),
+            Rule::multi_declaration => format!("{:#?}", build_multi_declaration(pair)),
+            Rule::statement => format!("{:#?}", build_statement(pair)),
+            _ => format!("{:#?}", build_expression(pair)),
+        };
+        Ok(description)
+    }
+
+    fn describe_variables(&self) -> String {
+        let session_scope = self.symbol_table.get_scope(&self.get_session_scope_id()).unwrap();
+        let mut variables: Vec<&Variable> = session_scope.get_variables().collect();
+        variables.sort_by_key(|variable| &variable.get_id().name);
+        variables.into_iter().map(Variable::describe).collect::<Vec<String>>().join("\n")
+    }
+
+    fn describe_functions(&self) -> String {
+        let mut descriptions: Vec<(&String, String)> = self.ast.data.functions.values()
+            .map(|function| (&function.data.name.data.name, function.data.get_signature()))
+            .collect();
+        descriptions.sort();
+        descriptions.into_iter().map(|(_, description)| description).collect::<Vec<String>>().join("\n")
+    }
+
+    /// Run a REPL command, like `:vars`.
+    fn run_command(&mut self, command: &str) -> Result<String, SemanticError> {
+        let (command_name, argument) = command.split_once(char::is_whitespace).unwrap_or((command, ""));
+        match command_name {
+            ":vars" => Ok(self.describe_variables()),
+            ":funcs" => Ok(self.describe_functions()),
+            ":ast" => self.describe_ast(argument.trim()),
+            ":reset" => {
+                *self = ReplSession::new();
+                Ok("Session reset".to_string())
+            },
+            ":help" => Ok(HELP.to_string()),
+            _ => Ok(format!("Unknown command {}, see :help", command_name)),
+        }
+    }
+
+    /// Interpret an input of the session, and get the text to print:
+    /// the value of an expression, the description of a definition, or the output of a command.
+    pub fn eval_input(&mut self, input: &str) -> Result<String, SemanticError> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Ok(String::new());
+        }
+        if input.starts_with(':') {
+            return self.run_command(input);
+        }
+
+        // the AST borrows the text of the inputs, which are small and typed by hand, so they are never freed
+        let input: &'static str = Box::leak(input.into());
+        let pair = parse_input(input)?;
+        // each input has the whole budget of the execution limits
+        start_execution();
+        match pair.as_rule() {
+            Rule::struct_definition | Rule::enum_definition => self.define_type(pair),
+            Rule::function_definition => self.define_function(pair),
+            Rule::function_prototype => Err(make_ast_building_error(make_ast_error_from_pair(
+                pair, "🟠 Function prototypes are not needed in the REPL, a function can be called once it is defined"
+            ))),
+            Rule::entry_point_function_definition => Err(make_ast_building_error(make_ast_error_from_pair(
+                pair, "🟠 The main function can't be defined in the REPL, its statements can be entered directly"
+            ))),
+            Rule::multi_declaration => self.declare_variables(pair),
+            Rule::statement => self.run_statement(pair),
+            _ => self.evaluate_expression(pair),
+        }
+    }
+}
+
+/// Run an interactive session on the standard input, until `:quit` or the end of the input.
+pub fn run_repl() {
+    println!("Ctiny REPL, enter :help for help and :quit to exit");
+    let mut session = ReplSession::new();
+    let mut lines = std::io::stdin().lock().lines();
+    loop {
+        // an input can span several lines, until its braces and parentheses are closed
+        let mut input = String::new();
+        loop {
+            print!("{}", if input.is_empty() { PROMPT } else { CONTINUATION_PROMPT });
+            std::io::stdout().flush().unwrap();
+            match lines.next() {
+                Some(Ok(line)) => {
+                    input.push_str(&line);
+                    input.push('\n');
+                },
+                _ => return,
+            }
+            if !is_incomplete(&input) {
+                break;
+            }
+        }
+        if matches!(input.trim(), ":quit" | ":q") {
+            return;
+        }
+
+        let result = session.eval_input(&input);
+        for warning in take_warnings() {
+            println!("{}", warning);
+        }
+        match result {
+            Ok(output) if output.is_empty() => {},
+            Ok(output) => println!("{}", output),
+            Err(error) => println!("{}", error),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use crate::abstract_syntax_tree::nodes::{Node, Value, Expression, Identifier, SizeOfOperand, TypeSpecifier};
+
+use super::data_model::get_scalar_type_size;
+use super::errors::{SemanticError, SemanticErrorTrait, NonConstantExpressionError, InvalidArraySizeError};
+use super::operations::{perform_binary_operation, perform_unary_operation};
+use super::type_casts::{cast_to_type, convert_to_type};
+
+fn make_non_constant_expression_error<'a>(
+    expression_node: &Node<'a, Expression<'a>>,
+    reason: &str,
+) -> SemanticError {
+    SemanticError::NonConstantExpression(
+        NonConstantExpressionError::init(
+            expression_node.sp,
+            format!("Expected a constant expression, but {}", reason).as_str(),
+        )
+    )
+}
+
+/// Evaluate a constant expression, at compile time.
+/// A constant expression is made of literals, const variables declared before it,
+/// unary and binary operations and type casts.
+/// Operations are performed like at runtime, so an overflow is reported
+/// with the span of the constant expression.
+pub fn evaluate_constant_expression<'a>(
+    expression_node: &Node<'a, Expression<'a>>,
+    constants: &HashMap<Identifier, Value>,
+) -> Result<Node<'a, Value>, SemanticError> {
+    match &expression_node.data {
+        Expression::Literal(value) => Ok(Node {
+            sp: expression_node.sp,
+            data: value.clone(),
+        }),
+        Expression::UnaryExpression(unary_expression) => {
+            let evaluated_expression = evaluate_constant_expression(
+                &unary_expression.expression, constants
+            )?;
+            perform_unary_operation(evaluated_expression, &unary_expression.operator, expression_node.sp)
+        },
+        Expression::BinaryExpression(binary_expression) => {
+            let evaluated_left_expression = evaluate_constant_expression(
+                &binary_expression.left, constants
+            )?;
+            let evaluated_right_expression = evaluate_constant_expression(
+                &binary_expression.right, constants
+            )?;
+            perform_binary_operation(
+                &evaluated_left_expression,
+                &evaluated_right_expression,
+                &binary_expression.operator,
+            )
+        },
+        Expression::TypeCast(type_cast) => {
+            let evaluated_expression = evaluate_constant_expression(
+                &type_cast.expression, constants
+            )?;
+            convert_to_type(evaluated_expression, type_cast.type_specifier.clone())
+        },
+        Expression::GetOrSetValue(get_or_set_value) => {
+            if get_or_set_value.index.is_some() {
+                return Err(make_non_constant_expression_error(
+                    expression_node, "array elements are not constant expressions"
+                ));
+            }
+            if !get_or_set_value.members.is_empty() {
+                return Err(make_non_constant_expression_error(
+                    expression_node, "struct members are not constant expressions"
+                ));
+            }
+            let identifier = &get_or_set_value.identifier.data;
+            match constants.get(identifier) {
+                Some(value) => Ok(Node {
+                    sp: expression_node.sp,
+                    data: value.clone(),
+                }),
+                None => Err(make_non_constant_expression_error(
+                    expression_node,
+                    format!("{} is not a const variable declared before", identifier.name).as_str(),
+                )),
+            }
+        },
+        Expression::FunctionCall(_) => Err(make_non_constant_expression_error(
+            expression_node, "function calls are not constant expressions"
+        )),
+        Expression::SizeOf(SizeOfOperand::Type(type_specifier)) => {
+            match get_scalar_type_size(type_specifier) {
+                Some(size) => cast_to_type(
+                    Node { sp: expression_node.sp, data: Value::UnsignedLong(size as u64) },
+                    TypeSpecifier::Int,
+                ),
+                None => Err(make_non_constant_expression_error(
+                    expression_node, "sizeof of a struct type is not a constant expression"
+                )),
+            }
+        },
+        Expression::SizeOf(SizeOfOperand::Variable(_)) | Expression::Length(_) => Err(make_non_constant_expression_error(
+            expression_node, "sizeof and len of variables are not constant expressions"
+        )),
+        Expression::StringLength(_) | Expression::StringCompare(_, _) => Err(make_non_constant_expression_error(
+            expression_node, "strlen and strcmp are not constant expressions"
+        )),
+    }
+}
+
+/// Evaluate the constant expression giving the size of an array.
+/// The size must be a non-negative integer.
+pub fn evaluate_array_size<'a>(
+    size_expression_node: &Node<'a, Expression<'a>>,
+    constants: &HashMap<Identifier, Value>,
+) -> Result<usize, SemanticError> {
+    let size_value = evaluate_constant_expression(size_expression_node, constants)?.data;
+    match size_value.as_integer().map(usize::try_from) {
+        Some(Ok(size)) => Ok(size),
+        _ => Err(SemanticError::InvalidArraySize(
+            InvalidArraySizeError::init(
+                size_expression_node.sp,
+                format!("Invalid array size (must be a positive integer): {}", size_value).as_str(),
+            )
+        )),
+    }
+}
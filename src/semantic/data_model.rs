@@ -0,0 +1,125 @@
+use std::cell::Cell;
+
+use clap::ValueEnum;
+
+use crate::abstract_syntax_tree::nodes::TypeSpecifier;
+
+/// Width of the Ctiny `int` type.
+///
+/// Values of type int are always stored as i64 by the interpreter,
+/// but every literal, operation and cast is checked against the bounds
+/// of the selected width, so the same program can be checked under
+/// different target assumptions (e.g. 16-bit embedded vs 32-bit desktop).
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
+pub enum IntWidth {
+    #[value(name = "16")]
+    W16,
+    #[value(name = "32")]
+    W32,
+    #[value(name = "64")]
+    W64,
+}
+
+impl IntWidth {
+    pub fn bits(&self) -> u32 {
+        match self {
+            IntWidth::W16 => 16,
+            IntWidth::W32 => 32,
+            IntWidth::W64 => 64,
+        }
+    }
+}
+
+// The data model, like the other settings and state of the interpreter, is per-thread,
+// so that tests running in parallel can use different settings without interfering with each other.
+thread_local! {
+    static INT_WIDTH: Cell<IntWidth> = const { Cell::new(IntWidth::W16) };
+}
+
+/// Select the int width used for all subsequent AST building and interpretation
+/// in the current thread. The default is 16 bits.
+pub fn set_int_width(int_width: IntWidth) {
+    INT_WIDTH.with(|current| current.set(int_width));
+}
+
+pub fn get_int_width() -> IntWidth {
+    INT_WIDTH.with(|current| current.get())
+}
+
+/// Width of the Ctiny `long` type, derived from the int width:
+/// at least 32 bits and never smaller than int, like in C.
+/// A 32 bits int goes with a 64 bits long, as in the LP64 data model of desktop targets.
+pub fn get_long_width() -> IntWidth {
+    match get_int_width() {
+        IntWidth::W16 => IntWidth::W32,
+        IntWidth::W32 | IntWidth::W64 => IntWidth::W64,
+    }
+}
+
+/// Width and signedness of an integer type in the current data model.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct IntegerTypeInfo {
+    pub bits: u32,
+    pub is_signed: bool,
+}
+
+impl IntegerTypeInfo {
+    pub fn min_value(&self) -> i128 {
+        if self.is_signed {
+            -(1i128 << (self.bits - 1))
+        } else {
+            0
+        }
+    }
+
+    pub fn max_value(&self) -> i128 {
+        if self.is_signed {
+            (1i128 << (self.bits - 1)) - 1
+        } else {
+            (1i128 << self.bits) - 1
+        }
+    }
+
+    pub fn contains(&self, value: i128) -> bool {
+        self.min_value() <= value && value <= self.max_value()
+    }
+
+    /// Reduce a value modulo 2^bits, like unsigned C integers do.
+    pub fn wrap(&self, value: i128) -> i128 {
+        let wrapped = value.rem_euclid(1i128 << self.bits);
+        if self.is_signed && wrapped > self.max_value() {
+            wrapped - (1i128 << self.bits)
+        } else {
+            wrapped
+        }
+    }
+}
+
+/// Get the width and signedness of an integer type (char included).
+/// Returns None for non-integer types.
+pub fn get_integer_type_info(type_specifier: &TypeSpecifier) -> Option<IntegerTypeInfo> {
+    let (bits, is_signed) = match type_specifier {
+        TypeSpecifier::Char => (8, false), // Ctiny chars are stored as u8
+        TypeSpecifier::Short => (16, true),
+        TypeSpecifier::UnsignedShort => (16, false),
+        TypeSpecifier::Int | TypeSpecifier::Enum(_) => (get_int_width().bits(), true), // enums are stored as int
+        TypeSpecifier::UnsignedInt => (get_int_width().bits(), false),
+        TypeSpecifier::Long => (get_long_width().bits(), true),
+        TypeSpecifier::UnsignedLong => (get_long_width().bits(), false),
+        TypeSpecifier::Bool | TypeSpecifier::Float | TypeSpecifier::Double
+            | TypeSpecifier::Struct(_) => return None,
+    };
+    Some(IntegerTypeInfo { bits, is_signed })
+}
+
+/// Size in bytes of a scalar type in the current data model, like C `sizeof`.
+/// Returns None for struct types, whose size depends on their fields.
+pub fn get_scalar_type_size(type_specifier: &TypeSpecifier) -> Option<usize> {
+    match type_specifier {
+        TypeSpecifier::Bool => Some(1),
+        TypeSpecifier::Float => Some(4),
+        TypeSpecifier::Double => Some(8),
+        TypeSpecifier::Struct(_) => None,
+        integer_type => get_integer_type_info(integer_type).map(|info| info.bits as usize / 8),
+    }
+}
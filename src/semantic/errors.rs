@@ -67,20 +67,52 @@ pub enum SemanticError {
     IntToCharCastOverflow(IntToCharCastOverflowError),
     FloatToCharCastOverflow(FloatToCharCastOverflowError),
     FloatToIntCastOverflow(FloatToIntCastOverflowError),
+    IntegerCastOverflow(IntegerCastOverflowError),
 
     // operation overflow 
     IntOverflow(IntOverflowError),
     FloatOverflow(FloatOverflowError),
 
-    // loop
-    MaxLoopIteration(MaxLoopIterationError),
+    // execution limits
+    StepLimit(StepLimitError),
+    StackOverflow(StackOverflowError),
+    Timeout(TimeoutError),
 
     // inherited from previous errors
     ASTBuilding(ASTBuildingError), // not direct semantic error, but used in semantic analysis
     SyntaxParsing(SyntaxParsingError),
+    Preprocessing(PreprocessingError),
+    AssertionFailed(AssertionFailedError),
 
     // declaration
     Redeclaration(RedeclarationError),
+    InvalidArraySize(InvalidArraySizeError),
+    InitializerListSize(InitializerListSizeError),
+
+    // const
+    ConstAssignment(ConstAssignmentError),
+    NonConstantExpression(NonConstantExpressionError),
+
+    // struct
+    UndeclaredStruct(UndeclaredStructError),
+    InvalidMemberAccess(InvalidMemberAccessError),
+    InvalidOperandType(InvalidOperandTypeError),
+
+    // enum
+    UndeclaredEnum(UndeclaredEnumError),
+    InvalidEnumeratorValue(InvalidEnumeratorValueError),
+    InvalidCaseLabel(InvalidCaseLabelError),
+
+    // function prototype
+    PrototypeMismatch(PrototypeMismatchError),
+    MissingFunctionDefinition(MissingFunctionDefinitionError),
+
+    // string
+    BufferOverflow(BufferOverflowError),
+    InvalidStringOperand(InvalidStringOperandError),
+
+    // tools
+    InterpretationAborted(InterpretationAbortedError),
 }
 
 define_and_implement_semantic_error!(UndeclaredVariableError);
@@ -92,6 +124,7 @@ define_and_implement_semantic_error!(UnexpectedLiteralTypeError);
 define_and_implement_semantic_error!(IntToCharCastOverflowError);
 define_and_implement_semantic_error!(FloatToCharCastOverflowError);
 define_and_implement_semantic_error!(FloatToIntCastOverflowError);
+define_and_implement_semantic_error!(IntegerCastOverflowError);
 define_and_implement_semantic_error!(ASTBuildingError);
 define_and_implement_semantic_error!(UnexpectedTypeCastError);
 define_and_implement_semantic_error!(IntOverflowError);
@@ -101,32 +134,78 @@ define_and_implement_semantic_error!(BoolOverflowError);
 define_and_implement_semantic_error!(DivisionByZeroError);
 define_and_implement_semantic_error!(UnexpectedStatementParsingError);
 define_and_implement_semantic_error!(SyntaxParsingError);
-define_and_implement_semantic_error!(MaxLoopIterationError);
+define_and_implement_semantic_error!(StepLimitError);
+define_and_implement_semantic_error!(StackOverflowError);
+define_and_implement_semantic_error!(TimeoutError);
 define_and_implement_semantic_error!(RedeclarationError);
 define_and_implement_semantic_error!(UnassignedVariableError);
+define_and_implement_semantic_error!(InvalidArraySizeError);
+define_and_implement_semantic_error!(InitializerListSizeError);
+define_and_implement_semantic_error!(ConstAssignmentError);
+define_and_implement_semantic_error!(NonConstantExpressionError);
+define_and_implement_semantic_error!(UndeclaredStructError);
+define_and_implement_semantic_error!(InvalidMemberAccessError);
+define_and_implement_semantic_error!(InvalidOperandTypeError);
+define_and_implement_semantic_error!(UndeclaredEnumError);
+define_and_implement_semantic_error!(InvalidEnumeratorValueError);
+define_and_implement_semantic_error!(InvalidCaseLabelError);
+define_and_implement_semantic_error!(PrototypeMismatchError);
+define_and_implement_semantic_error!(MissingFunctionDefinitionError);
+define_and_implement_semantic_error!(PreprocessingError);
+define_and_implement_semantic_error!(AssertionFailedError);
+define_and_implement_semantic_error!(BufferOverflowError);
+define_and_implement_semantic_error!(InvalidStringOperandError);
+define_and_implement_semantic_error!(InterpretationAbortedError);
 
-impl fmt::Display for SemanticError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl SemanticError {
+    /// Get the underlying pest error, holding the message and the location of the error.
+    pub fn get_error(&self) -> Error<Rule> {
         match self {
-            SemanticError::UndeclaredVariable(error) => write!(f, "{}", error),
-            SemanticError::UndeclaredFunction(error) => write!(f, "{}", error),
-            SemanticError::ArgumentNumberMismatch(error) => write!(f, "{}", error),
-            SemanticError::UnexpectedExpressionParsing(error) => write!(f, "{}", error),
-            SemanticError::NegativeArrayIndex(error) => write!(f, "{}", error),
-            SemanticError::UnexpectedLiteralType(error) => write!(f, "{}", error),
-            SemanticError::IntToCharCastOverflow(error) => write!(f, "{}", error),
-            SemanticError::FloatToCharCastOverflow(error) => write!(f, "{}", error),
-            SemanticError::FloatToIntCastOverflow(error) => write!(f, "{}", error),
-            SemanticError::ASTBuilding(error) => write!(f, "{}", error),
-            SemanticError::UnexpectedTypeCast(error) => write!(f, "{}", error),
-            SemanticError::IntOverflow(error) => write!(f, "{}", error),
-            SemanticError::FloatOverflow(error) => write!(f, "{}", error),
-            SemanticError::DivisionByZero(error) => write!(f, "{}", error),
-            SemanticError::UnexpectedStatementParsing(error) => write!(f, "{}", error),
-            SemanticError::SyntaxParsing(error) => write!(f, "{}", error),
-            SemanticError::MaxLoopIteration(error) => write!(f, "{}", error),
-            SemanticError::Redeclaration(error) => write!(f, "{}", error),
-            SemanticError::UnassignedVariable(error) => write!(f, "{}", error),
+            SemanticError::UndeclaredVariable(error) => error.get_error(),
+            SemanticError::UndeclaredFunction(error) => error.get_error(),
+            SemanticError::ArgumentNumberMismatch(error) => error.get_error(),
+            SemanticError::UnexpectedExpressionParsing(error) => error.get_error(),
+            SemanticError::NegativeArrayIndex(error) => error.get_error(),
+            SemanticError::UnexpectedLiteralType(error) => error.get_error(),
+            SemanticError::IntToCharCastOverflow(error) => error.get_error(),
+            SemanticError::FloatToCharCastOverflow(error) => error.get_error(),
+            SemanticError::FloatToIntCastOverflow(error) => error.get_error(),
+            SemanticError::IntegerCastOverflow(error) => error.get_error(),
+            SemanticError::ASTBuilding(error) => error.get_error(),
+            SemanticError::UnexpectedTypeCast(error) => error.get_error(),
+            SemanticError::IntOverflow(error) => error.get_error(),
+            SemanticError::FloatOverflow(error) => error.get_error(),
+            SemanticError::DivisionByZero(error) => error.get_error(),
+            SemanticError::UnexpectedStatementParsing(error) => error.get_error(),
+            SemanticError::SyntaxParsing(error) => error.get_error(),
+            SemanticError::Preprocessing(error) => error.get_error(),
+            SemanticError::AssertionFailed(error) => error.get_error(),
+            SemanticError::StepLimit(error) => error.get_error(),
+            SemanticError::StackOverflow(error) => error.get_error(),
+            SemanticError::Timeout(error) => error.get_error(),
+            SemanticError::Redeclaration(error) => error.get_error(),
+            SemanticError::UnassignedVariable(error) => error.get_error(),
+            SemanticError::InvalidArraySize(error) => error.get_error(),
+            SemanticError::InitializerListSize(error) => error.get_error(),
+            SemanticError::ConstAssignment(error) => error.get_error(),
+            SemanticError::NonConstantExpression(error) => error.get_error(),
+            SemanticError::UndeclaredStruct(error) => error.get_error(),
+            SemanticError::InvalidMemberAccess(error) => error.get_error(),
+            SemanticError::InvalidOperandType(error) => error.get_error(),
+            SemanticError::UndeclaredEnum(error) => error.get_error(),
+            SemanticError::InvalidEnumeratorValue(error) => error.get_error(),
+            SemanticError::InvalidCaseLabel(error) => error.get_error(),
+            SemanticError::PrototypeMismatch(error) => error.get_error(),
+            SemanticError::MissingFunctionDefinition(error) => error.get_error(),
+            SemanticError::BufferOverflow(error) => error.get_error(),
+            SemanticError::InvalidStringOperand(error) => error.get_error(),
+            SemanticError::InterpretationAborted(error) => error.get_error(),
         }
     }
 }
+
+impl fmt::Display for SemanticError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.get_error())
+    }
+}
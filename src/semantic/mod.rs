@@ -1,4 +1,9 @@
 pub mod errors;
 pub mod type_casts;
 pub mod overflow_checks;
-pub mod operations;
\ No newline at end of file
+pub mod operations;
+pub mod data_model;
+pub mod warnings;
+pub mod overflow_policy;
+pub mod const_evaluation;
+pub mod static_checks;
@@ -1,224 +1,206 @@
-use crate::abstract_syntax_tree::nodes::{Node, Value, TypeSpecifier, BinaryOperator};
+use pest::Span;
+
+use crate::abstract_syntax_tree::nodes::{Node, Value, TypeSpecifier, BinaryOperator, UnaryOperator};
 use crate::merge_spans_no_check;
-use crate::semantic::errors::{SemanticError, SemanticErrorTrait, UnexpectedTypeCastError};
-use crate::semantic::type_casts::cast_to_type;
+use crate::semantic::errors::{SemanticError, SemanticErrorTrait, UnexpectedTypeCastError, InvalidOperandTypeError};
+use crate::semantic::type_casts::{cast_to_type, get_common_arithmetic_type, get_promoted_type};
 
-use super::errors::DivisionByZeroError;
 use super::overflow_checks::*;
 
+/// Get the span of an operation, from its left operand to its right one.
+/// Operands from different inputs, like in the REPL, can't be merged, so the left one is used.
+fn merge_operand_spans<'a>(left_span: Span<'a>, right_span: Span<'a>) -> Span<'a> {
+    if !std::ptr::eq(left_span.get_input(), right_span.get_input()) {
+        return left_span;
+    }
+    merge_spans_no_check!(left_span, right_span).unwrap_or(left_span)
+}
+
+/// Apply the usual arithmetic conversions to both operands,
+/// returning the common type and the converted values.
+fn convert_operands_to_common_type<'a>(
+    left_value_node: &Node<'a, Value>,
+    right_value_node: &Node<'a, Value>,
+) -> Result<(TypeSpecifier, Value, Value), SemanticError> {
+    let common_type = get_common_arithmetic_type(
+        left_value_node.data.as_type_specifier(),
+        right_value_node.data.as_type_specifier(),
+    );
+    let left_value = cast_to_type(left_value_node.clone(), common_type.clone())?.data;
+    let right_value = cast_to_type(right_value_node.clone(), common_type.clone())?.data;
+    Ok((common_type, left_value, right_value))
+}
+
+/// Operators only apply to scalar values: structs can only be assigned,
+/// passed to functions and returned.
+fn check_operand_is_scalar<'a>(value_node: &Node<'a, Value>) -> Result<(), SemanticError> {
+    match &value_node.data {
+        Value::Struct(struct_value) => Err(SemanticError::InvalidOperandType(
+            InvalidOperandTypeError::init(
+                value_node.sp,
+                format!(
+                    "Invalid operand of type struct {}: operators only apply to scalar values", 
+                    struct_value.type_name.name
+                ).as_str(),
+            )
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Values of different enum types can't be mixed in an operation,
+/// even though they are both stored as int.
+fn check_enum_operands_match<'a>(
+    left_value_node: &Node<'a, Value>,
+    right_value_node: &Node<'a, Value>,
+) -> Result<(), SemanticError> {
+    match (&left_value_node.data, &right_value_node.data) {
+        (Value::Enum(left_enum), Value::Enum(right_enum)) if left_enum.type_name != right_enum.type_name => {
+            Err(SemanticError::InvalidOperandType(
+                InvalidOperandTypeError::init(
+                    merge_operand_spans(left_value_node.sp, right_value_node.sp),
+                    format!(
+                        "Invalid operands of different enum types: enum {} and enum {}", 
+                        left_enum.type_name.name, right_enum.type_name.name
+                    ).as_str(),
+                )
+            ))
+        },
+        _ => Ok(()),
+    }
+}
+
+fn make_operand_cast_error<'a>(
+    operator_str: &str,
+    common_type: TypeSpecifier,
+    left_value: Value,
+    right_value: Value,
+    common_span: pest::Span<'a>,
+) -> SemanticError {
+    SemanticError::UnexpectedTypeCast(
+        UnexpectedTypeCastError::init(
+            common_span,
+            format!(
+                "In perform_binary_operation ({}), cast to {} of {:?} or {:?} failed", 
+                operator_str, common_type, left_value, right_value
+            ).as_str(),
+        )
+    )
+}
+
+/// Performs an arithmetic operation in the common type of the operands (C usual arithmetic conversions).
+/// Integer operations use the integer type of the operation for overflow and wrap checks.
 macro_rules! basic_binary_operation {
-    ($operator_str:expr, $safe_float_operation:ident, $safe_int_operation:ident, $left_value_node:expr, $right_value_node:expr) => {{
-        let left_type = $left_value_node.data.as_type_specifier();
-        let right_type = $right_value_node.data.as_type_specifier();
-        let common_span = merge_spans_no_check!(
-            $left_value_node.sp, $right_value_node.sp
-        ).unwrap();
+    ($operator_str:expr, $safe_float_operation:ident, $safe_double_operation:ident, $safe_integer_operation:ident, $left_value_node:expr, $right_value_node:expr) => {{
+        let common_span = merge_operand_spans($left_value_node.sp, $right_value_node.sp);
+        let (common_type, left_value, right_value) = convert_operands_to_common_type(
+            $left_value_node, $right_value_node
+        )?;
 
-        if left_type == TypeSpecifier::Float || right_type == TypeSpecifier::Float {
-            let left_float = cast_to_type(
-                $left_value_node.clone(), TypeSpecifier::Float
-            )?;
-            let right_float = cast_to_type(
-                $right_value_node.clone(), TypeSpecifier::Float
-            )?;
-            match (left_float.data.clone(), right_float.data.clone()) {
-                (Value::Float(left_float), Value::Float(right_float)) => {
-                    let result = $safe_float_operation(
-                        left_float, 
-                        right_float,
-                        common_span,
-                    )?;
-                    Ok(Node {
-                        sp: common_span,
-                        data: Value::Float(result),
-                    })
-                },
-                _ => {
-                    return Err(SemanticError::UnexpectedTypeCast(
-                        UnexpectedTypeCastError::init(
-                            common_span,
-                            format!(
-                                "In perform_binary_operation ({}), cast to float of {:?} or {:?} failed", 
-                                $operator_str, left_float.data, right_float.data
-                            ).as_str(),
-                        )
-                    ));
-                },
-            }
-        } else {
-            let left_int = cast_to_type(
-                $left_value_node.clone(), TypeSpecifier::Int
-            )?;
-            let right_int = cast_to_type(
-                $right_value_node.clone(), TypeSpecifier::Int
-            )?;
-            match (left_int.data, right_int.data) {
-                (Value::Int(left_int), Value::Int(right_int)) => {
-                    // We need to convert integers to floats before passing to the operation closure
-                    let result = $safe_int_operation(
-                        left_int, 
-                        right_int,
-                        common_span,
-                    )?;
-                    Ok(Node {
-                        sp: common_span,
-                        data: Value::Int(result),
-                    })
-                },
-                (left, right) => {
-                    return Err(SemanticError::UnexpectedTypeCast(
-                        UnexpectedTypeCastError::init(
+        let result = match (common_type, left_value, right_value) {
+            (TypeSpecifier::Double, Value::Double(left_double), Value::Double(right_double)) => {
+                Value::Double($safe_double_operation(left_double, right_double, common_span)?)
+            },
+            (TypeSpecifier::Float, Value::Float(left_float), Value::Float(right_float)) => {
+                Value::Float($safe_float_operation(left_float, right_float, common_span)?)
+            },
+            (integer_type, left_value, right_value) => {
+                match (left_value.as_integer(), right_value.as_integer()) {
+                    (Some(left_integer), Some(right_integer)) => {
+                        let result = $safe_integer_operation(
+                            left_integer, 
+                            right_integer,
+                            integer_type.clone(),
                             common_span,
-                            format!(
-                                "In perform_binary_operation ({}), cast to int of {:?} or {:?} failed", 
-                                $operator_str, left, right
-                            ).as_str(),
-                        )
-                    ));
-                },
-            }
-        }
+                        )?;
+                        Value::from_integer(integer_type, result).unwrap()
+                    },
+                    _ => return Err(make_operand_cast_error(
+                        $operator_str, integer_type, left_value, right_value, common_span
+                    )),
+                }
+            },
+        };
+        Ok(Node {
+            sp: common_span,
+            data: result,
+        })
     }}
 }
 
+/// Comparisons are performed in the common type of the operands, like in C.
+/// So comparing a negative int with an unsigned int converts it to unsigned first.
 macro_rules! comparison_operation {
     ($operator_str:expr, $compare_operator:tt, $left_value_node:expr, $right_value_node:expr) => {{
-        let left_type = $left_value_node.data.as_type_specifier();
-        let right_type = $right_value_node.data.as_type_specifier();
-        let common_span = merge_spans_no_check!(
-            $left_value_node.sp, $right_value_node.sp
-        ).unwrap();
+        let common_span = merge_operand_spans($left_value_node.sp, $right_value_node.sp);
+        let (common_type, left_value, right_value) = convert_operands_to_common_type(
+            $left_value_node, $right_value_node
+        )?;
 
-        // We choose to perform comparisons as floats if any of the operands are floats.
-        if left_type == TypeSpecifier::Float || right_type == TypeSpecifier::Float {
-            let left_float = cast_to_type(
-                $left_value_node.clone(), TypeSpecifier::Float
-            )?;
-            let right_float = cast_to_type(
-                $right_value_node.clone(), TypeSpecifier::Float
-            )?;
-            match (left_float.data.clone(), right_float.data.clone()) {
-                (Value::Float(left_float), Value::Float(right_float)) => {
-                    let result = left_float $compare_operator right_float;
-                    Ok(Node {
-                        sp: common_span,
-                        data: Value::Bool(result),
-                    })
-                },
-                _ => {
-                    return Err(SemanticError::UnexpectedTypeCast(
-                        UnexpectedTypeCastError::init(
-                            common_span,
-                            format!(
-                                "In perform_binary_operation ({}), cast to float of {:?} or {:?} failed", 
-                                $operator_str, left_float.data, right_float.data
-                            ).as_str(),
-                        )
-                    ));
-                },
-            }
-        } else {
-            let left_int = cast_to_type(
-                $left_value_node.clone(), TypeSpecifier::Int
-            )?;
-            let right_int = cast_to_type(
-                $right_value_node.clone(), TypeSpecifier::Int
-            )?;
-            match (left_int.data, right_int.data) {
-                (Value::Int(left_int), Value::Int(right_int)) => {
-                    let result = left_int $compare_operator right_int;
-                    Ok(Node {
-                        sp: common_span,
-                        data: Value::Bool(result),
-                    })
-                },
-                (left, right) => {
-                    return Err(SemanticError::UnexpectedTypeCast(
-                        UnexpectedTypeCastError::init(
-                            common_span,
-                            format!(
-                                "In perform_binary_operation ({}), cast to int of {:?} or {:?} failed", 
-                                $operator_str, left, right
-                            ).as_str(),
-                        )
-                    ));
-                },
-            }
-        }
+        let result = match (common_type, left_value, right_value) {
+            (TypeSpecifier::Double, Value::Double(left_double), Value::Double(right_double)) => {
+                left_double $compare_operator right_double
+            },
+            (TypeSpecifier::Float, Value::Float(left_float), Value::Float(right_float)) => {
+                left_float $compare_operator right_float
+            },
+            (integer_type, left_value, right_value) => {
+                match (left_value.as_integer(), right_value.as_integer()) {
+                    (Some(left_integer), Some(right_integer)) => {
+                        left_integer $compare_operator right_integer
+                    },
+                    _ => return Err(make_operand_cast_error(
+                        $operator_str, integer_type, left_value, right_value, common_span
+                    )),
+                }
+            },
+        };
+        Ok(Node {
+            sp: common_span,
+            data: Value::Bool(result),
+        })
     }}
 }
 
 /// Computes the modulo operation. Both operands must be integers.
-/// So this function will cast any non-integer operand to integer first.
+/// So this function will cast any floating operand to int first.
 /// If the right operand is zero, a division by zero error is returned.
 pub fn perform_modulo_operation<'a>(
     left_value_node: &Node<'a, Value>,
     right_value_node: &Node<'a, Value>,
 ) -> Result<Node<'a, Value>, SemanticError> {
-    // cast left operand to int
-    let left_casted = cast_to_type(
-        left_value_node.clone(), TypeSpecifier::Int
-    )?.data;
-    let left_int = match left_casted {
-        Value::Int(left_int) => left_int,
-        non_int => {
-            return Err(SemanticError::UnexpectedTypeCast(
-                UnexpectedTypeCastError::init(
-                    left_value_node.sp,
-                    format!(
-                        "In perform_modulo_operation, cast to int of {:?} failed", 
-                        non_int
-                    ).as_str(),
-                )
-            ));
-        },
-    };
+    let common_span = merge_operand_spans(left_value_node.sp, right_value_node.sp);
 
-    // cast right operand to int
-    let right_casted = cast_to_type(
-        right_value_node.clone(), TypeSpecifier::Int
-    )?.data;
-    let right_int = match right_casted {
-        Value::Int(right_int) => right_int,
-        non_int => {
-            return Err(SemanticError::UnexpectedTypeCast(
-                UnexpectedTypeCastError::init(
-                    right_value_node.sp,
-                    format!(
-                        "In perform_modulo_operation, cast to int of {:?} failed", 
-                        non_int
-                    ).as_str(),
-                )
-            ));
-        },
-    };
-
-    // check that right operand is not zero
-    if right_int == 0 {
-        return Err(SemanticError::DivisionByZero(
-            DivisionByZeroError::init(
-                right_value_node.sp,
-                "In perform_modulo_operation, right operand is zero."
-            )
-        ));
+    // floating operands are truncated to int
+    let mut common_type = get_common_arithmetic_type(
+        left_value_node.data.as_type_specifier(),
+        right_value_node.data.as_type_specifier(),
+    );
+    if common_type.is_floating() {
+        common_type = TypeSpecifier::Int;
     }
+    let left_value = cast_to_type(left_value_node.clone(), common_type.clone())?.data;
+    let right_value = cast_to_type(right_value_node.clone(), common_type.clone())?.data;
 
-    // compute modulo
-    let common_span = merge_spans_no_check!(
-        left_value_node.sp, right_value_node.sp
-    ).unwrap();
-    let result = safe_int_modulo(left_int, right_int, common_span)?;
-    Ok(Node {
-        sp: common_span,
-        data: Value::Int(result),
-    })
+    match (left_value.as_integer(), right_value.as_integer()) {
+        (Some(left_integer), Some(right_integer)) => {
+            let result = safe_integer_modulo(
+                left_integer, right_integer, common_type.clone(), common_span
+            )?;
+            Ok(Node {
+                sp: common_span,
+                data: Value::from_integer(common_type, result).unwrap(),
+            })
+        },
+        _ => Err(make_operand_cast_error(
+            "%", common_type, left_value, right_value, common_span
+        )),
+    }
 }
 
 macro_rules! logical_operation {
     ($operator_str:expr, $logical_operator:tt, $left_value_node:expr, $right_value_node:expr) => {{
-        let common_span = merge_spans_no_check!(
-            $left_value_node.sp, $right_value_node.sp
-        ).unwrap();
+        let common_span = merge_operand_spans($left_value_node.sp, $right_value_node.sp);
 
         let left_bool = cast_to_type(
             $left_value_node.clone(), TypeSpecifier::Bool
@@ -254,13 +236,17 @@ pub fn perform_binary_operation<'a>(
     right_value_node: &Node<'a, Value>,
     operator: &BinaryOperator,
 ) -> Result<Node<'a, Value>, SemanticError> {
+    check_operand_is_scalar(left_value_node)?;
+    check_operand_is_scalar(right_value_node)?;
+    check_enum_operands_match(left_value_node, right_value_node)?;
     match operator {
         // basic binary operations
         BinaryOperator::Plus => {
             basic_binary_operation!(
                 "+",
                 safe_float_add,
-                safe_int_add,
+                safe_double_add,
+                safe_integer_add,
                 left_value_node,
                 right_value_node
             )
@@ -269,7 +255,8 @@ pub fn perform_binary_operation<'a>(
             basic_binary_operation!(
                 "-",
                 safe_float_subtract,
-                safe_int_subtract,
+                safe_double_subtract,
+                safe_integer_subtract,
                 left_value_node,
                 right_value_node
             )
@@ -278,7 +265,8 @@ pub fn perform_binary_operation<'a>(
             basic_binary_operation!(
                 "*",
                 safe_float_multiply,
-                safe_int_multiply,
+                safe_double_multiply,
+                safe_integer_multiply,
                 left_value_node,
                 right_value_node
             )
@@ -287,7 +275,8 @@ pub fn perform_binary_operation<'a>(
             basic_binary_operation!(
                 "/",
                 safe_float_divide,
-                safe_int_divide,
+                safe_double_divide,
+                safe_integer_divide,
                 left_value_node,
                 right_value_node
             )
@@ -366,4 +355,92 @@ pub fn perform_binary_operation<'a>(
             )
         },
     }
-}
\ No newline at end of file
+}
+
+pub fn perform_unary_operation<'a>(
+    value_node: Node<'a, Value>,
+    operator: &UnaryOperator,
+    span: Span<'a>,
+) -> Result<Node<'a, Value>, SemanticError> {
+    check_operand_is_scalar(&value_node)?;
+    match operator {
+        UnaryOperator::Negation => {
+            match value_node.data {
+                Value::Float(float) => {
+                    Ok(Node {
+                        sp: span,
+                        data: Value::Float(-float),
+                    })
+                },
+                Value::Double(double) => {
+                    Ok(Node {
+                        sp: span,
+                        data: Value::Double(-double),
+                    })
+                },
+                // like in C, the operand is promoted first (e.g. a char, bool or short becomes an int)
+                integer_value => {
+                    let promoted_type = get_promoted_type(integer_value.as_type_specifier());
+                    let promoted_value = cast_to_type(
+                        Node {
+                            sp: span,
+                            data: integer_value,
+                        },
+                        promoted_type.clone(),
+                    )?;
+                    match promoted_value.data.as_integer() {
+                        Some(integer) => {
+                            let negated_integer = safe_integer_negate(
+                                integer, promoted_type.clone(), span
+                            )?;
+                            Ok(Node {
+                                sp: span,
+                                data: Value::from_integer(promoted_type, negated_integer).unwrap(),
+                            })
+                        },
+                        None => {
+                            panic!(
+                                "In perform_unary_operation, promotion of {:?} to an integer type failed", 
+                                promoted_value.data
+                            );
+                        },
+                    }
+                },
+            }
+        },
+        UnaryOperator::Not => {
+            match value_node.data {
+                Value::Bool(bool) => {
+                    Ok(Node {
+                        sp: span,
+                        data: Value::Bool(!bool),
+                    })
+                },
+                // if Value is not a bool, convert to bool and then negate
+                not_bool_value => {
+                    let casted_bool_value = cast_to_type(
+                        Node {
+                            sp: span,
+                            data: not_bool_value,
+                        },
+                        TypeSpecifier::Bool,
+                    )?;
+                    match casted_bool_value.data {
+                        Value::Bool(bool) => {
+                            Ok(Node {
+                                sp: span,
+                                data: Value::Bool(!bool),
+                            })
+                        },
+                        unexpected_non_bool_value => {
+                            panic!(
+                                "In perform_unary_operation, cast to bool of {:?} failed in ", 
+                                unexpected_non_bool_value
+                            );
+                        },
+                    }
+                }
+            }
+        },
+    }
+}
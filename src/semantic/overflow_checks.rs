@@ -1,164 +1,205 @@
 use pest::Span;
 
+use crate::abstract_syntax_tree::nodes::TypeSpecifier;
+
+use super::data_model::get_integer_type_info;
 use super::errors::{SemanticError, IntOverflowError, SemanticErrorTrait, FloatOverflowError, DivisionByZeroError};
+use super::overflow_policy::{handle_integer_overflow, handle_float_overflow};
+use super::warnings::record_warning;
 
-pub fn safe_int_add<'a>(left: i16, right: i16, common_span: Span<'a>) -> Result<i16, SemanticError> {
-    let result = left.checked_add(right);
+/// Operations on integer types are computed on i128, which holds the values of all of them,
+/// then checked against the bounds of the type of the operation, following C rules:
+/// signed overflow is handled by the overflow policy (an error by default),
+/// while unsigned arithmetic wraps around (with a warning).
+fn check_integer_result<'a>(
+    result: Option<i128>,
+    integer_type: TypeSpecifier,
+    operation_name: &str,
+    operation_str: String,
+    span: Span<'a>,
+) -> Result<i128, SemanticError> {
+    let type_info = get_integer_type_info(&integer_type).unwrap();
     match result {
-        Some(result) => Ok(result),
-        None => Err(SemanticError::IntOverflow(
-            IntOverflowError::init(
-                common_span,
+        Some(result) if type_info.contains(result) => Ok(result),
+        Some(result) if !type_info.is_signed => {
+            let wrapped_result = type_info.wrap(result);
+            record_warning(
+                span,
                 format!(
-                    "Int addition overflow: {} + {}", left, right
+                    "Unsigned {} wraps around ({} bits {}): {} = {}", 
+                    operation_name, type_info.bits, integer_type, operation_str, wrapped_result
                 ).as_str()
-            )
-        )),
+            );
+            Ok(wrapped_result)
+        },
+        _ => {
+            // keep the historical capitalized message, e.g. "Int addition overflow"
+            let type_name = integer_type.to_string();
+            let capitalized_type_name = type_name[..1].to_uppercase() + &type_name[1..];
+            let message = format!(
+                "{} {} overflow: {}", 
+                capitalized_type_name, operation_name, operation_str
+            );
+            let make_overflow_error = || SemanticError::IntOverflow(
+                IntOverflowError::init(span, message.as_str())
+            );
+            match result {
+                Some(result) => handle_integer_overflow(
+                    result, type_info, span, &message, make_overflow_error
+                ),
+                // unreachable in practice, since i128 holds any operation on 64 bits values
+                None => Err(make_overflow_error()),
+            }
+        },
     }
 }
 
-pub fn safe_float_add<'a>(left: f32, right: f32, common_span: Span<'a>) -> Result<f32, SemanticError> {
-    let result = left + right;
-    match result.is_finite() {
-        true => Ok(result),
-        false => Err(SemanticError::FloatOverflow(
-            FloatOverflowError::init(
-                common_span,
-                format!(
-                    "Float addition overflow: {} + {} would result in infinite value.", 
-                    left, right
-                ).as_str()
-            )
-        )),
+/// Float operations only overflow to infinity, which is handled by the overflow policy.
+fn check_float_result<'a>(
+    result: f32,
+    operation_name: &str,
+    operation_str: String,
+    span: Span<'a>,
+) -> Result<f32, SemanticError> {
+    if result.is_finite() {
+        return Ok(result);
     }
+    let message = format!(
+        "Float {} overflow: {} would result in infinite value.",
+        operation_name, operation_str
+    );
+    handle_float_overflow(
+        result as f64, std::f32::MAX as f64, span, &message,
+        || SemanticError::FloatOverflow(FloatOverflowError::init(span, message.as_str()))
+    ).map(|result| result as f32)
 }
 
-pub fn safe_int_subtract<'a>(left: i16, right: i16, common_span: Span<'a>) -> Result<i16, SemanticError> {
-    let result = left.checked_sub(right);
-    match result {
-        Some(result) => Ok(result),
-        None => Err(SemanticError::IntOverflow(
-            IntOverflowError::init(
-                common_span,
-                format!("Int subtraction overflow: {} - {}", left, right).as_str(),
-            ),
-        )),
+/// Division by zero is an error for every integer type.
+fn check_integer_divisor<'a>(right: i128, span: Span<'a>, message: &str) -> Result<(), SemanticError> {
+    if right == 0 {
+        Err(SemanticError::DivisionByZero(
+            DivisionByZeroError::init(span, message)
+        ))
+    } else {
+        Ok(())
     }
 }
 
-pub fn safe_float_subtract<'a>(left: f32, right: f32, common_span: Span<'a>) -> Result<f32, SemanticError> {
-    let result = left - right;
-    match result.is_finite() {
-        true => Ok(result),
-        false => Err(SemanticError::FloatOverflow(
-            FloatOverflowError::init(
-                common_span,
-                format!(
-                    "Float subtraction overflow: {} - {} would result in infinite value.",
-                    left, right
-                )
-                .as_str(),
-            ),
-        )),
-    }
+pub fn safe_integer_add<'a>(left: i128, right: i128, integer_type: TypeSpecifier, common_span: Span<'a>) -> Result<i128, SemanticError> {
+    check_integer_result(
+        left.checked_add(right), integer_type, "addition",
+        format!("{} + {}", left, right), common_span
+    )
 }
 
-pub fn safe_int_multiply<'a>(left: i16, right: i16, common_span: Span<'a>) -> Result<i16, SemanticError> {
-    let result = left.checked_mul(right);
-    match result {
-        Some(result) => Ok(result),
-        None => Err(SemanticError::IntOverflow(
-            IntOverflowError::init(
-                common_span,
-                format!("Int multiplication overflow: {} * {}", left, right).as_str(),
-            ),
-        )),
-    }
+pub fn safe_integer_subtract<'a>(left: i128, right: i128, integer_type: TypeSpecifier, common_span: Span<'a>) -> Result<i128, SemanticError> {
+    check_integer_result(
+        left.checked_sub(right), integer_type, "subtraction",
+        format!("{} - {}", left, right), common_span
+    )
 }
 
-pub fn safe_float_multiply<'a>(left: f32, right: f32, common_span: Span<'a>) -> Result<f32, SemanticError> {
-    let result = left * right;
-    match result.is_finite() {
-        true => Ok(result),
-        false => Err(SemanticError::FloatOverflow(
-            FloatOverflowError::init(
-                common_span,
-                format!(
-                    "Float multiplication overflow: {} * {} would result in infinite value.",
-                    left, right
-                )
-                .as_str(),
-            ),
-        )),
-    }
+pub fn safe_integer_multiply<'a>(left: i128, right: i128, integer_type: TypeSpecifier, common_span: Span<'a>) -> Result<i128, SemanticError> {
+    check_integer_result(
+        left.checked_mul(right), integer_type, "multiplication",
+        format!("{} * {}", left, right), common_span
+    )
 }
 
-pub fn safe_int_divide<'a>(left: i16, right: i16, common_span: Span<'a>) -> Result<i16, SemanticError> {
-    if right == 0 {
-        Err(SemanticError::DivisionByZero(
-            DivisionByZeroError::init(
-                common_span,
-                "Division by zero",
-            ),
-        ))
+pub fn safe_integer_divide<'a>(left: i128, right: i128, integer_type: TypeSpecifier, common_span: Span<'a>) -> Result<i128, SemanticError> {
+    check_integer_divisor(right, common_span, "Division by zero")?;
+    check_integer_result(
+        left.checked_div(right), integer_type, "division",
+        format!("{} / {}", left, right), common_span
+    )
+}
+
+pub fn safe_integer_modulo<'a>(left: i128, right: i128, integer_type: TypeSpecifier, common_span: Span<'a>) -> Result<i128, SemanticError> {
+    check_integer_divisor(right, common_span, "Modulo by zero")?;
+    // like the division, signed min % -1 overflows (the remainder is computed from the quotient)
+    let type_info = get_integer_type_info(&integer_type).unwrap();
+    let result = if type_info.is_signed && left == type_info.min_value() && right == -1 {
+        None
     } else {
-        let result = left.checked_div(right);
-        match result {
-            Some(result) => Ok(result),
-            None => Err(SemanticError::IntOverflow(
-                IntOverflowError::init(
-                    common_span,
-                    format!("Int division overflow: {} / {}", left, right).as_str(),
-                ),
-            )),
-        }
-    }
+        left.checked_rem(right)
+    };
+    check_integer_result(
+        result, integer_type, "modulo",
+        format!("{} % {}", left, right), common_span
+    )
+}
+
+pub fn safe_integer_negate<'a>(value: i128, integer_type: TypeSpecifier, span: Span<'a>) -> Result<i128, SemanticError> {
+    check_integer_result(
+        value.checked_neg(), integer_type, "negation",
+        format!("-({})", value), span
+    )
+}
+
+pub fn safe_float_add<'a>(left: f32, right: f32, common_span: Span<'a>) -> Result<f32, SemanticError> {
+    check_float_result(left + right, "addition", format!("{} + {}", left, right), common_span)
+}
+
+pub fn safe_float_subtract<'a>(left: f32, right: f32, common_span: Span<'a>) -> Result<f32, SemanticError> {
+    check_float_result(left - right, "subtraction", format!("{} - {}", left, right), common_span)
+}
+
+pub fn safe_float_multiply<'a>(left: f32, right: f32, common_span: Span<'a>) -> Result<f32, SemanticError> {
+    check_float_result(left * right, "multiplication", format!("{} * {}", left, right), common_span)
 }
 
 pub fn safe_float_divide<'a>(left: f32, right: f32, common_span: Span<'a>) -> Result<f32, SemanticError> {
     if right == 0.0 {
-        Err(SemanticError::DivisionByZero(
+        return Err(SemanticError::DivisionByZero(
             DivisionByZeroError::init(
                 common_span,
                 "Division by zero",
             ),
-        ))
-    } else {
-        let result = left / right;
-        match result.is_finite() {
-            true => Ok(result),
-            false => Err(SemanticError::FloatOverflow(
-                FloatOverflowError::init(
-                    common_span,
-                    format!(
-                        "Float division overflow: {} / {} would result in infinite value.",
-                        left, right
-                    )
-                    .as_str(),
-                ),
-            )),
-        }
+        ));
     }
+    check_float_result(left / right, "division", format!("{} / {}", left, right), common_span)
 }
 
-pub fn safe_int_modulo<'a>(left: i16, right: i16, common_span: Span<'a>) -> Result<i16, SemanticError> {
-    if right == 0 {
-        Err(SemanticError::DivisionByZero(
+/// Double operations are computed on f64, and only overflow to infinity.
+fn check_double_result<'a>(
+    result: f64,
+    operation_name: &str,
+    operation_str: String,
+    span: Span<'a>,
+) -> Result<f64, SemanticError> {
+    if result.is_finite() {
+        return Ok(result);
+    }
+    let message = format!(
+        "Double {} overflow: {} would result in infinite value.",
+        operation_name, operation_str
+    );
+    handle_float_overflow(
+        result, std::f64::MAX, span, &message,
+        || SemanticError::FloatOverflow(FloatOverflowError::init(span, message.as_str()))
+    )
+}
+
+pub fn safe_double_add<'a>(left: f64, right: f64, common_span: Span<'a>) -> Result<f64, SemanticError> {
+    check_double_result(left + right, "addition", format!("{} + {}", left, right), common_span)
+}
+
+pub fn safe_double_subtract<'a>(left: f64, right: f64, common_span: Span<'a>) -> Result<f64, SemanticError> {
+    check_double_result(left - right, "subtraction", format!("{} - {}", left, right), common_span)
+}
+
+pub fn safe_double_multiply<'a>(left: f64, right: f64, common_span: Span<'a>) -> Result<f64, SemanticError> {
+    check_double_result(left * right, "multiplication", format!("{} * {}", left, right), common_span)
+}
+
+pub fn safe_double_divide<'a>(left: f64, right: f64, common_span: Span<'a>) -> Result<f64, SemanticError> {
+    if right == 0.0 {
+        return Err(SemanticError::DivisionByZero(
             DivisionByZeroError::init(
                 common_span,
-                "Modulo by zero",
+                "Division by zero",
             ),
-        ))
-    } else {
-        let result = left.checked_rem(right);
-        match result {
-            Some(result) => Ok(result),
-            None => Err(SemanticError::IntOverflow(
-                IntOverflowError::init(
-                    common_span,
-                    format!("Int modulo overflow: {} % {}", left, right).as_str(),
-                ),
-            )),
-        }
+        ));
     }
+    check_double_result(left / right, "division", format!("{} / {}", left, right), common_span)
 }
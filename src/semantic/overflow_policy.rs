@@ -0,0 +1,106 @@
+use std::cell::Cell;
+
+use clap::ValueEnum;
+use pest::Span;
+
+use crate::errors::make_semantic_warning;
+
+use super::data_model::IntegerTypeInfo;
+use super::errors::SemanticError;
+use super::warnings::record_warning;
+
+/// What to do when a value overflows the bounds of its type,
+/// either in an operation or in a narrowing cast.
+///
+/// Unsigned arithmetic is not concerned: it always wraps around, like in C.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
+pub enum OverflowPolicy {
+    /// abort the interpretation with an overflow error
+    Trap,
+    /// wrap around like two's-complement hardware, and record a warning
+    Wrap,
+    /// clamp to the bounds of the type, and record a warning
+    Saturate,
+    /// wrap around like two's-complement hardware, and only log the overflow
+    Warn,
+}
+
+thread_local! {
+    static OVERFLOW_POLICY: Cell<OverflowPolicy> = const { Cell::new(OverflowPolicy::Trap) };
+}
+
+/// Select the overflow policy used for all subsequent interpretation
+/// in the current thread. The default is to trap.
+pub fn set_overflow_policy(overflow_policy: OverflowPolicy) {
+    OVERFLOW_POLICY.with(|current| current.set(overflow_policy));
+}
+
+pub fn get_overflow_policy() -> OverflowPolicy {
+    OVERFLOW_POLICY.with(|current| current.get())
+}
+
+fn report_overflow<'a>(
+    overflow_policy: OverflowPolicy,
+    span: Span<'a>,
+    message: &str,
+    replaced_value: String,
+) {
+    match overflow_policy {
+        OverflowPolicy::Wrap => record_warning(
+            span, &format!("{}, wrapped around to {}", message, replaced_value)
+        ),
+        OverflowPolicy::Saturate => record_warning(
+            span, &format!("{}, saturated to {}", message, replaced_value)
+        ),
+        OverflowPolicy::Warn => log::warn!(
+            "{}", make_semantic_warning(span, &format!("{}, wrapped around to {}", message, replaced_value))
+        ),
+        OverflowPolicy::Trap => {},
+    }
+}
+
+/// Handle an integer value out of the bounds of its type, according to the current overflow policy.
+/// The error is only built when trapping.
+pub fn handle_integer_overflow<'a>(
+    value: i128,
+    type_info: IntegerTypeInfo,
+    span: Span<'a>,
+    message: &str,
+    make_overflow_error: impl FnOnce() -> SemanticError,
+) -> Result<i128, SemanticError> {
+    let overflow_policy = get_overflow_policy();
+    let replaced_value = match overflow_policy {
+        OverflowPolicy::Trap => return Err(make_overflow_error()),
+        OverflowPolicy::Wrap | OverflowPolicy::Warn => type_info.wrap(value),
+        OverflowPolicy::Saturate => value.clamp(type_info.min_value(), type_info.max_value()),
+    };
+    report_overflow(overflow_policy, span, message, replaced_value.to_string());
+    Ok(replaced_value)
+}
+
+/// Handle a floating value out of the range of its type (infinite or too big), according to the current overflow policy.
+/// Since floats have no wrapping behaviour, wrapping keeps the IEEE result (an infinity).
+pub fn handle_float_overflow<'a>(
+    value: f64,
+    max_value: f64,
+    span: Span<'a>,
+    message: &str,
+    make_overflow_error: impl FnOnce() -> SemanticError,
+) -> Result<f64, SemanticError> {
+    let overflow_policy = get_overflow_policy();
+    let replaced_value = match overflow_policy {
+        OverflowPolicy::Trap => return Err(make_overflow_error()),
+        OverflowPolicy::Wrap | OverflowPolicy::Warn => {
+            if !value.is_finite() {
+                value
+            } else if value > 0.0 {
+                f64::INFINITY
+            } else {
+                f64::NEG_INFINITY
+            }
+        },
+        OverflowPolicy::Saturate => value.clamp(-max_value, max_value),
+    };
+    report_overflow(overflow_policy, span, message, replaced_value.to_string());
+    Ok(replaced_value)
+}
@@ -0,0 +1,280 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::abstract_syntax_tree::nodes::{Node, Function, Identifier, Statement, Value, Expression, Declaration, TranslationUnit};
+
+use crate::symbol_table::structs::SymbolTable;
+
+use super::const_evaluation::evaluate_constant_expression;
+use super::errors::{SemanticError, SemanticErrorTrait, ConstAssignmentError, InvalidCaseLabelError, PrototypeMismatchError, MissingFunctionDefinitionError};
+
+fn check_statements_do_not_assign_constants<'a>(
+    statements: &Vec<Node<'a, Statement<'a>>>,
+    const_identifiers: &HashSet<&Identifier>,
+) -> Result<(), SemanticError> {
+    for statement in statements {
+        match &statement.data {
+            Statement::Assignment(assignment) => {
+                let identifier = &assignment.left_var.data.identifier.data;
+                if const_identifiers.contains(identifier) {
+                    return Err(SemanticError::ConstAssignment(
+                        ConstAssignmentError::init(
+                            assignment.left_var.sp,
+                            format!("Cannot assign to constant {}", identifier.name).as_str(),
+                        )
+                    ));
+                }
+            },
+            Statement::If(if_statement) => {
+                check_statements_do_not_assign_constants(&if_statement.if_body, const_identifiers)?;
+                if let Some(else_body) = &if_statement.else_body {
+                    check_statements_do_not_assign_constants(else_body, const_identifiers)?;
+                }
+            },
+            Statement::While(while_statement) => {
+                check_statements_do_not_assign_constants(&while_statement.body, const_identifiers)?;
+            },
+            Statement::Assert(_) => {},
+            Statement::StringCopy(string_copy_statement) => {
+                let identifier = &string_copy_statement.destination.data;
+                if const_identifiers.contains(identifier) {
+                    return Err(SemanticError::ConstAssignment(
+                        ConstAssignmentError::init(
+                            string_copy_statement.destination.sp,
+                            format!("Cannot copy a string into constant {}", identifier.name).as_str(),
+                        )
+                    ));
+                }
+            },
+            Statement::Switch(switch_statement) => {
+                for case in &switch_statement.cases {
+                    check_statements_do_not_assign_constants(&case.data.body, const_identifiers)?;
+                }
+                if let Some(default_body) = &switch_statement.default_body {
+                    check_statements_do_not_assign_constants(default_body, const_identifiers)?;
+                }
+            },
+        }
+    }
+    Ok(())
+}
+
+/// Statically reject any assignment to a const variable or parameter of the function,
+/// or to an enumerator.
+pub fn check_no_assignment_to_constants<'a>(
+    function: &Function<'a>,
+    enumerators: &HashMap<Identifier, Value>,
+) -> Result<(), SemanticError> {
+    let params = function.params.iter().flatten();
+    let declarations = function.body.data.declarations.iter();
+    let const_identifiers: HashSet<&Identifier> = params.chain(declarations)
+        .filter(|declaration| declaration.data.is_const)
+        .map(|declaration| &declaration.data.identifier.data)
+        .chain(enumerators.keys())
+        .collect();
+
+    check_statements_do_not_assign_constants(&function.body.data.statements, &const_identifiers)
+}
+
+fn make_invalid_case_label_error<'a>(
+    label_node: &Node<'a, Expression<'a>>,
+    message: &str,
+) -> SemanticError {
+    SemanticError::InvalidCaseLabel(
+        InvalidCaseLabelError::init(label_node.sp, message)
+    )
+}
+
+fn check_statements_case_labels<'a>(
+    statements: &Vec<Node<'a, Statement<'a>>>,
+    constants: &HashMap<Identifier, Value>,
+) -> Result<(), SemanticError> {
+    for statement in statements {
+        match &statement.data {
+            Statement::Assignment(_) | Statement::Assert(_) | Statement::StringCopy(_) => {},
+            Statement::If(if_statement) => {
+                check_statements_case_labels(&if_statement.if_body, constants)?;
+                if let Some(else_body) = &if_statement.else_body {
+                    check_statements_case_labels(else_body, constants)?;
+                }
+            },
+            Statement::While(while_statement) => {
+                check_statements_case_labels(&while_statement.body, constants)?;
+            },
+            Statement::Switch(switch_statement) => {
+                let mut label_values: HashSet<i128> = HashSet::new();
+                for case in &switch_statement.cases {
+                    let label = &case.data.label;
+                    let label_value = evaluate_constant_expression(label, constants)?.data;
+                    match label_value.as_integer() {
+                        Some(integer) => {
+                            if !label_values.insert(integer) {
+                                return Err(make_invalid_case_label_error(
+                                    label, 
+                                    format!("Duplicate case value: {}", integer).as_str()
+                                ));
+                            }
+                        },
+                        None => return Err(make_invalid_case_label_error(
+                            label, 
+                            format!("Case labels must be integers, got {}", label_value).as_str()
+                        )),
+                    }
+                    check_statements_case_labels(&case.data.body, constants)?;
+                }
+                if let Some(default_body) = &switch_statement.default_body {
+                    check_statements_case_labels(default_body, constants)?;
+                }
+            },
+        }
+    }
+    Ok(())
+}
+
+/// Statically check that the case labels of all switch statements of the function
+/// are distinct integer constant expressions.
+pub fn check_switch_case_labels<'a>(
+    function: &Function<'a>,
+    constants: &HashMap<Identifier, Value>,
+) -> Result<(), SemanticError> {
+    check_statements_case_labels(&function.body.data.statements, constants)
+}
+
+/// Call `check` on each expression of the statements, sub-expressions included.
+fn check_statements_expressions<'a>(
+    statements: &Vec<Node<'a, Statement<'a>>>,
+    check: &mut impl FnMut(&Node<'a, Expression<'a>>) -> Result<(), SemanticError>,
+) -> Result<(), SemanticError> {
+    for statement in statements {
+        match &statement.data {
+            Statement::Assignment(assignment) => {
+                if let Some(index) = &assignment.left_var.data.index {
+                    check_expression_and_sub_expressions(index, check)?;
+                }
+                check_expression_and_sub_expressions(&assignment.right_expr, check)?;
+            },
+            Statement::If(if_statement) => {
+                check_expression_and_sub_expressions(&if_statement.condition, check)?;
+                check_statements_expressions(&if_statement.if_body, check)?;
+                if let Some(else_body) = &if_statement.else_body {
+                    check_statements_expressions(else_body, check)?;
+                }
+            },
+            Statement::While(while_statement) => {
+                check_expression_and_sub_expressions(&while_statement.condition, check)?;
+                check_statements_expressions(&while_statement.body, check)?;
+            },
+            Statement::Switch(switch_statement) => {
+                check_expression_and_sub_expressions(&switch_statement.expression, check)?;
+                for case in &switch_statement.cases {
+                    check_expression_and_sub_expressions(&case.data.label, check)?;
+                    check_statements_expressions(&case.data.body, check)?;
+                }
+                if let Some(default_body) = &switch_statement.default_body {
+                    check_statements_expressions(default_body, check)?;
+                }
+            },
+            Statement::Assert(assert_statement) => {
+                check_expression_and_sub_expressions(&assert_statement.condition, check)?;
+            },
+            Statement::StringCopy(_) => {},
+        }
+    }
+    Ok(())
+}
+
+fn check_expression_and_sub_expressions<'a>(
+    expression: &Node<'a, Expression<'a>>,
+    check: &mut impl FnMut(&Node<'a, Expression<'a>>) -> Result<(), SemanticError>,
+) -> Result<(), SemanticError> {
+    check(expression)?;
+    match &expression.data {
+        Expression::Literal(_) | Expression::SizeOf(_) | Expression::Length(_) 
+            | Expression::StringLength(_) | Expression::StringCompare(_, _) => Ok(()),
+        Expression::UnaryExpression(unary_expression) => {
+            check_expression_and_sub_expressions(&unary_expression.expression, check)
+        },
+        Expression::BinaryExpression(binary_expression) => {
+            check_expression_and_sub_expressions(&binary_expression.left, check)?;
+            check_expression_and_sub_expressions(&binary_expression.right, check)
+        },
+        Expression::FunctionCall(function_call) => {
+            for argument in &function_call.arguments {
+                check_expression_and_sub_expressions(argument, check)?;
+            }
+            Ok(())
+        },
+        Expression::TypeCast(type_cast) => {
+            check_expression_and_sub_expressions(&type_cast.expression, check)
+        },
+        Expression::GetOrSetValue(get_or_set_value) => match &get_or_set_value.index {
+            Some(index) => check_expression_and_sub_expressions(index, check),
+            None => Ok(()),
+        },
+    }
+}
+
+/// Statically evaluate all the `sizeof(...)` and `len(...)` expressions of the function,
+/// so that an invalid operand (like `len` of a normal variable) is reported before interpretation.
+pub fn check_size_expressions<'a>(
+    function: &Function<'a>,
+    symbol_table: &SymbolTable,
+) -> Result<(), SemanticError> {
+    let mut check = |expression: &Node<'a, Expression<'a>>| match &expression.data {
+        Expression::SizeOf(_) | Expression::Length(_) => {
+            symbol_table.evaluate_size_expression(expression, &function.name).map(|_| ())
+        },
+        _ => Ok(()),
+    };
+    check_statements_expressions(&function.body.data.statements, &mut check)?;
+    check_expression_and_sub_expressions(&function.body.data.function_return, &mut check)
+}
+
+fn describe_parameter_type(parameter: &Declaration) -> String {
+    if parameter.is_array() {
+        format!("{}[]", parameter.type_specifier)
+    } else {
+        parameter.type_specifier.to_string()
+    }
+}
+
+/// Statically check that each function prototype has a definition with the same signature:
+/// same return type, and same parameter types (parameter names can differ).
+pub fn check_function_prototypes<'a>(translation_unit: &TranslationUnit<'a>) -> Result<(), SemanticError> {
+    let prototypes = match &translation_unit.prototypes {
+        Some(prototypes) => prototypes,
+        None => return Ok(()),
+    };
+
+    for prototype in prototypes {
+        let function_name = &prototype.data.name.data.name;
+        let function = match translation_unit.functions.get(&prototype.data.name.data) {
+            Some(function) => &function.data,
+            None => return Err(SemanticError::MissingFunctionDefinition(
+                MissingFunctionDefinitionError::init(
+                    prototype.sp,
+                    format!("Function {} is declared, but never defined", function_name).as_str(),
+                )
+            )),
+        };
+
+        let prototype_params: Vec<String> = prototype.data.params.iter().flatten()
+            .map(|param| describe_parameter_type(&param.data))
+            .collect();
+        let function_params: Vec<String> = function.params.iter().flatten()
+            .map(|param| describe_parameter_type(&param.data))
+            .collect();
+        if prototype.data.return_type != function.return_type || prototype_params != function_params {
+            return Err(SemanticError::PrototypeMismatch(
+                PrototypeMismatchError::init(
+                    prototype.sp,
+                    format!(
+                        "Prototype {} {}({}) doesn't match the definition {} {}({})", 
+                        prototype.data.return_type, function_name, prototype_params.join(", "),
+                        function.return_type, function_name, function_params.join(", "),
+                    ).as_str(),
+                )
+            ));
+        }
+    }
+    Ok(())
+}
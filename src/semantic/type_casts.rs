@@ -1,6 +1,10 @@
 use crate::abstract_syntax_tree::nodes::{Value, Node, TypeSpecifier};
 
-use super::errors::{SemanticError, NegativeArrayIndexError, UnexpectedLiteralTypeError, SemanticErrorTrait, IntToCharCastOverflowError, FloatToCharCastOverflowError, FloatToIntCastOverflowError};
+use super::data_model::get_integer_type_info;
+use super::errors::{SemanticError, NegativeArrayIndexError, UnexpectedLiteralTypeError, UnexpectedTypeCastError, SemanticErrorTrait, IntToCharCastOverflowError, FloatToCharCastOverflowError, FloatToIntCastOverflowError, IntegerCastOverflowError, FloatOverflowError};
+use super::overflow_policy::{handle_integer_overflow, handle_float_overflow};
+use super::warnings::record_warning;
+use crate::interpretation::hooks::{has_hooks, call_on_implicit_cast};
 
 // given a Literal, check that it is a positive integer, and return the value as usize
 pub fn get_index_value_from_value_node<'a>(input_node: Node<'a, Value>) -> Result<usize, SemanticError> {
@@ -51,26 +55,16 @@ fn cast_to_bool<'a>(
 ) -> Result<Node<'a, Value>, SemanticError> {
     match input_node.data {
         Value::Bool(_) => Ok(input_node),
-        Value::Int(integer) => {
-            if integer == 0 {
-                ok_value_node!(Bool, input_node.sp, false)
-            } else {
-                ok_value_node!(Bool, input_node.sp, true)
-            }
-        },
         Value::Float(float) => {
-            if float == 0.0 {
-                ok_value_node!(Bool, input_node.sp, false)
-            } else {
-                ok_value_node!(Bool, input_node.sp, true)
-            }
+            ok_value_node!(Bool, input_node.sp, float != 0.0)
         },
-        Value::Char(character) => {
-            if character == b'\0' {
-                ok_value_node!(Bool, input_node.sp, false)
-            } else {
-                ok_value_node!(Bool, input_node.sp, true)
-            }
+        Value::Double(double) => {
+            ok_value_node!(Bool, input_node.sp, double != 0.0)
+        },
+        // chars and all integer types
+        ref integer_value => {
+            let integer = integer_value.as_integer().unwrap();
+            ok_value_node!(Bool, input_node.sp, integer != 0)
         },
     }
 }
@@ -86,12 +80,48 @@ fn cast_to_float<'a>(
                 ok_value_node!(Float, input_node.sp, 0.0)
             }
         },
-        Value::Int(integer) => {
+        Value::Float(_) => Ok(input_node),
+        Value::Double(double) => {
+            if double.is_finite() && double.abs() > std::f32::MAX as f64 {
+                let message = format!("Double value out of range for float: {}", double);
+                let float = handle_float_overflow(
+                    double, std::f32::MAX as f64, input_node.sp, &message,
+                    || SemanticError::FloatOverflow(
+                        FloatOverflowError::init(input_node.sp, &message)
+                    )
+                )?;
+                ok_value_node!(Float, input_node.sp, float as f32)
+            } else {
+                ok_value_node!(Float, input_node.sp, double as f32)
+            }
+        },
+        // chars and all integer types
+        ref integer_value => {
+            let integer = integer_value.as_integer().unwrap();
             ok_value_node!(Float, input_node.sp, integer as f32)
         },
-        Value::Float(_) => Ok(input_node),
-        Value::Char(character) => {
-            ok_value_node!(Float, input_node.sp, character as f32)
+    }
+}
+
+fn cast_to_double<'a>(
+    input_node: Node<'a, Value>
+) -> Result<Node<'a, Value>, SemanticError> {
+    match input_node.data {
+        Value::Bool(boolean) => {
+            if boolean {
+                ok_value_node!(Double, input_node.sp, 1.0)
+            } else {
+                ok_value_node!(Double, input_node.sp, 0.0)
+            }
+        },
+        Value::Float(float) => {
+            ok_value_node!(Double, input_node.sp, float as f64)
+        },
+        Value::Double(_) => Ok(input_node),
+        // chars and all integer types
+        ref integer_value => {
+            let integer = integer_value.as_integer().unwrap();
+            ok_value_node!(Double, input_node.sp, integer as f64)
         },
     }
 }
@@ -99,6 +129,7 @@ fn cast_to_float<'a>(
 fn cast_to_char<'a>(
     input_node: Node<'a, Value>
 ) -> Result<Node<'a, Value>, SemanticError> {
+    let char_info = get_integer_type_info(&TypeSpecifier::Char).unwrap();
     match input_node.data {
         Value::Bool(boolean) => {
             if boolean {
@@ -107,77 +138,170 @@ fn cast_to_char<'a>(
                 ok_value_node!(Char, input_node.sp, b'\0')
             }
         },
-        Value::Int(integer) => {
-            if integer < 0 || integer > 255 {
-                Err(
-                    SemanticError::IntToCharCastOverflow(
-                        IntToCharCastOverflowError::init(
-                            input_node.sp,
-                            &format!("Int literal out of range for char: {}", integer)
-                        )
+        Value::Char(_) => Ok(input_node),
+        Value::Float(_) | Value::Double(_) => {
+            let (floating, floating_type_name) = match input_node.data {
+                Value::Float(float) => (float as f64, "Float literal"),
+                Value::Double(double) => (double, "Double value"),
+                _ => unreachable!(),
+            };
+            if floating < 0.0 || floating > 255.0 {
+                let message = format!("{} out of range for char: {}", floating_type_name, floating);
+                let character = handle_integer_overflow(
+                    floating as i128, char_info, input_node.sp, &message,
+                    || SemanticError::FloatToCharCastOverflow(
+                        FloatToCharCastOverflowError::init(input_node.sp, &message)
                     )
-                )
+                )?;
+                ok_value_node!(Char, input_node.sp, character as u8)
             } else {
-                ok_value_node!(Char, input_node.sp, integer as u8)
+                ok_value_node!(Char, input_node.sp, floating as u8)
             }
         },
-        Value::Float(float) => {
-            if float < 0.0 || float > 255.0 {
-                Err(
-                    SemanticError::FloatToCharCastOverflow(
-                        FloatToCharCastOverflowError::init(
-                            input_node.sp,
-                            &format!("Float literal out of range for char: {}", float)
-                        )
+        // all integer types
+        ref integer_value => {
+            let integer = integer_value.as_integer().unwrap();
+            if integer < 0 || integer > 255 {
+                let message = format!("Int literal out of range for char: {}", integer);
+                let character = handle_integer_overflow(
+                    integer, char_info, input_node.sp, &message,
+                    || SemanticError::IntToCharCastOverflow(
+                        IntToCharCastOverflowError::init(input_node.sp, &message)
                     )
-                )
+                )?;
+                ok_value_node!(Char, input_node.sp, character as u8)
             } else {
-                ok_value_node!(Char, input_node.sp, float as u8)
+                ok_value_node!(Char, input_node.sp, integer as u8)
             }
         },
-        Value::Char(_) => Ok(input_node),
     }
 }
 
-fn cast_to_int<'a>(
-    input_node: Node<'a, Value>
+/// Cast to one of the integer types (except char), following C rules:
+/// - a value that fits in the target type is kept as is
+/// - an out of range value wraps around for unsigned target types (with a warning)
+/// - an out of range value is handled by the overflow policy for signed target types (an error by default)
+/// - a float is truncated toward zero, and must fit in the target type (or is handled by the overflow policy)
+fn cast_to_integer<'a>(
+    input_node: Node<'a, Value>,
+    target_type: TypeSpecifier,
 ) -> Result<Node<'a, Value>, SemanticError> {
-    match input_node.data {
-        Value::Bool(boolean) => {
-            if boolean {
-                ok_value_node!(Int, input_node.sp, 1)
+    let type_info = get_integer_type_info(&target_type).unwrap();
+    let floating_value = match input_node.data {
+        Value::Float(float) => Some(float as f64),
+        Value::Double(double) => Some(double),
+        _ => None,
+    };
+
+    let integer = match (&input_node.data, floating_value) {
+        (Value::Bool(boolean), _) => *boolean as i128,
+        (_, Some(floating)) => {
+            // the conversion truncates toward zero, so anything strictly between min - 1 and max + 1 fits
+            if !(floating < type_info.max_value() as f64 + 1.0 
+                && floating > type_info.min_value() as f64 - 1.0) {
+                let message = format!(
+                    "Float literal out of range for {} ({} bits): {}", 
+                    target_type, type_info.bits, floating
+                );
+                // NOTE: NaN is truncated to 0
+                handle_integer_overflow(
+                    floating as i128, type_info, input_node.sp, &message,
+                    || SemanticError::FloatToIntCastOverflow(
+                        FloatToIntCastOverflowError::init(input_node.sp, &message)
+                    )
+                )?
             } else {
-                ok_value_node!(Int, input_node.sp, 0)
+                floating as i128
             }
         },
-        Value::Int(_) => Ok(input_node),
-        Value::Float(float) => {
-            if float > i16::MAX as f32 || float < i16::MIN as f32 {
-                Err(
-                    SemanticError::FloatToIntCastOverflow(
-                        FloatToIntCastOverflowError::init(
-                            input_node.sp,
-                            &format!("Float literal out of range for int: {}", float)
-                        )
+        (integer_value, None) => {
+            let integer = integer_value.as_integer().unwrap();
+            if type_info.contains(integer) {
+                integer
+            } else if !type_info.is_signed {
+                let wrapped_integer = type_info.wrap(integer);
+                record_warning(
+                    input_node.sp,
+                    &format!(
+                        "Conversion of {} to {} ({} bits) wraps around to {}", 
+                        integer, target_type, type_info.bits, wrapped_integer
                     )
-                )
+                );
+                wrapped_integer
             } else {
-                ok_value_node!(Int, input_node.sp, float as i16)
+                let message = format!(
+                    "Integer value out of range for {} ({} bits): {}", 
+                    target_type, type_info.bits, integer
+                );
+                handle_integer_overflow(
+                    integer, type_info, input_node.sp, &message,
+                    || SemanticError::IntegerCastOverflow(
+                        IntegerCastOverflowError::init(input_node.sp, &message)
+                    )
+                )?
             }
         },
-        Value::Char(character) => {
-            ok_value_node!(Int, input_node.sp, character as i16)
-        },
+    };
+
+    Ok(Node {
+        sp: input_node.sp,
+        data: Value::from_integer(target_type, integer).unwrap(),
+    })
+}
+
+/// this function is used to cast a literal to a given type, like the implicit conversions of C
+/// (arithmetic conversions, assignments, conditions and returns)
+///
+/// The conversions changing the type of the value are reported to the interpretation hooks.
+pub fn cast_to_type<'a>(
+    input_node: Node<'a, Value>,
+    target_type: TypeSpecifier,
+) -> Result<Node<'a, Value>, SemanticError> {
+    if !has_hooks() || input_node.data.as_type_specifier() == target_type {
+        return convert_to_type(input_node, target_type);
     }
+    let output_node = convert_to_type(input_node.clone(), target_type)?;
+    call_on_implicit_cast(&input_node, &output_node.data)?;
+    Ok(output_node)
 }
 
-/// this function is used to cast a literal to a given type
+/// Convert a value to a given type, without reporting it like `cast_to_type`,
+/// for explicit casts like `(int) x`.
 /// 
 /// It checks overflows and underflows, as well as invalid values
-pub fn cast_to_type<'a>(
+pub fn convert_to_type<'a>(
     input_node: Node<'a, Value>,
     target_type: TypeSpecifier,
 ) -> Result<Node<'a, Value>, SemanticError> {
+    // structs and enums can only be "converted" to their own type
+    match (&input_node.data, &target_type) {
+        (Value::Struct(struct_value), TypeSpecifier::Struct(struct_name)) 
+            if struct_value.type_name == *struct_name => return Ok(input_node),
+        (Value::Enum(enum_value), TypeSpecifier::Enum(enum_name)) 
+            if enum_value.type_name == *enum_name => return Ok(input_node),
+        (Value::Struct(_), _) | (_, TypeSpecifier::Struct(_)) | (_, TypeSpecifier::Enum(_)) => return Err(
+            SemanticError::UnexpectedTypeCast(
+                UnexpectedTypeCastError::init(
+                    input_node.sp,
+                    &format!(
+                        "Cannot convert a value of type {} to {}", 
+                        input_node.data.as_type_specifier(), target_type
+                    )
+                )
+            )
+        ),
+        _ => {},
+    }
+
+    // an enum value is converted to other types like the int it is stored as
+    let input_node = match input_node.data {
+        Value::Enum(enum_value) => Node {
+            sp: input_node.sp,
+            data: Value::Int(enum_value.value),
+        },
+        _ => input_node,
+    };
+
     match target_type {
         TypeSpecifier::Bool => {
             cast_to_bool(input_node)
@@ -185,11 +309,104 @@ pub fn cast_to_type<'a>(
         TypeSpecifier::Float => {
             cast_to_float(input_node)
         },
+        TypeSpecifier::Double => {
+            cast_to_double(input_node)
+        },
         TypeSpecifier::Char => {
             cast_to_char(input_node)
         },
-        TypeSpecifier::Int => {
-            cast_to_int(input_node)
+        integer_type => {
+            cast_to_integer(input_node, integer_type)
         },
     }
-}
\ No newline at end of file
+}
+
+/// Integer conversion rank of C, used by the usual arithmetic conversions.
+/// Signed and unsigned versions of a type have the same rank.
+fn get_integer_conversion_rank(type_specifier: &TypeSpecifier) -> u8 {
+    match type_specifier {
+        TypeSpecifier::Bool => 0,
+        TypeSpecifier::Char => 1,
+        TypeSpecifier::Short | TypeSpecifier::UnsignedShort => 2,
+        TypeSpecifier::Int | TypeSpecifier::UnsignedInt | TypeSpecifier::Enum(_) => 3,
+        TypeSpecifier::Long | TypeSpecifier::UnsignedLong => 4,
+        TypeSpecifier::Float | TypeSpecifier::Double => 5,
+        TypeSpecifier::Struct(_) => unreachable!("structs are rejected before any arithmetic conversion"),
+    }
+}
+
+fn get_unsigned_type(type_specifier: &TypeSpecifier) -> TypeSpecifier {
+    match type_specifier {
+        TypeSpecifier::Short => TypeSpecifier::UnsignedShort,
+        TypeSpecifier::Int => TypeSpecifier::UnsignedInt,
+        TypeSpecifier::Long => TypeSpecifier::UnsignedLong,
+        other => other.clone(),
+    }
+}
+
+/// C integer promotions: any type with a rank lower than int is promoted to int
+/// if int can represent all of its values, and to unsigned int otherwise
+/// (e.g. an unsigned short with a 16 bits int).
+/// Enums are promoted to int.
+/// Other types are left untouched.
+pub fn get_promoted_type(type_specifier: TypeSpecifier) -> TypeSpecifier {
+    if let TypeSpecifier::Enum(_) = type_specifier {
+        return TypeSpecifier::Int; // enums are stored as int
+    }
+    if type_specifier.is_floating() 
+        || get_integer_conversion_rank(&type_specifier) >= get_integer_conversion_rank(&TypeSpecifier::Int) {
+        return type_specifier;
+    }
+    let int_info = get_integer_type_info(&TypeSpecifier::Int).unwrap();
+    match get_integer_type_info(&type_specifier) {
+        Some(type_info) if type_info.max_value() > int_info.max_value() => TypeSpecifier::UnsignedInt,
+        _ => TypeSpecifier::Int, // bools fit in an int
+    }
+}
+
+/// C usual arithmetic conversions: get the common type in which
+/// a binary operation on the two given operand types is performed.
+pub fn get_common_arithmetic_type(
+    left_type: TypeSpecifier,
+    right_type: TypeSpecifier,
+) -> TypeSpecifier {
+    // floating types first
+    if left_type == TypeSpecifier::Double || right_type == TypeSpecifier::Double {
+        return TypeSpecifier::Double;
+    }
+    if left_type == TypeSpecifier::Float || right_type == TypeSpecifier::Float {
+        return TypeSpecifier::Float;
+    }
+
+    // then integer types, once promoted
+    let left_type = get_promoted_type(left_type);
+    let right_type = get_promoted_type(right_type);
+    if left_type == right_type {
+        return left_type;
+    }
+    let left_info = get_integer_type_info(&left_type).unwrap();
+    let right_info = get_integer_type_info(&right_type).unwrap();
+    let left_rank = get_integer_conversion_rank(&left_type);
+    let right_rank = get_integer_conversion_rank(&right_type);
+
+    // same signedness: the type with the greater rank wins
+    if left_info.is_signed == right_info.is_signed {
+        return if left_rank >= right_rank { left_type } else { right_type };
+    }
+
+    let (signed_type, signed_info, unsigned_type, unsigned_info) = if left_info.is_signed {
+        (left_type, left_info, right_type, right_info)
+    } else {
+        (right_type, right_info, left_type, left_info)
+    };
+    if get_integer_conversion_rank(&unsigned_type) >= get_integer_conversion_rank(&signed_type) {
+        // e.g. int and unsigned int give unsigned int
+        unsigned_type
+    } else if signed_info.max_value() >= unsigned_info.max_value() {
+        // e.g. long and unsigned int give long, when long is wider than int
+        signed_type
+    } else {
+        // e.g. long and unsigned int give unsigned long, when long and int have the same width
+        get_unsigned_type(&signed_type)
+    }
+}
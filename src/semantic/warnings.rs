@@ -0,0 +1,24 @@
+use std::cell::RefCell;
+
+use pest::error::Error;
+use pest::Span;
+
+use crate::errors::make_semantic_warning;
+use crate::syntax_parsing::Rule;
+
+// Warnings don't stop the interpretation, so they are collected on the side
+// and reported once the program has been interpreted.
+thread_local! {
+    static WARNINGS: RefCell<Vec<Error<Rule>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Record a warning pointing to the given span.
+pub fn record_warning<'a>(span: Span<'a>, message: &str) {
+    let warning = make_semantic_warning(span, message);
+    WARNINGS.with(|warnings| warnings.borrow_mut().push(warning));
+}
+
+/// Get all the warnings recorded so far in the current thread, and clear them.
+pub fn take_warnings() -> Vec<Error<Rule>> {
+    WARNINGS.with(|warnings| warnings.borrow_mut().drain(..).collect())
+}
@@ -1,35 +1,153 @@
 use std::collections::HashMap;
 
-use crate::abstract_syntax_tree::nodes::{AST, Identifier, Declaration, Function};
+use crate::abstract_syntax_tree::nodes::{AST, Identifier, Declaration, Function, Initializer, Node, Value, TypeSpecifier};
+use crate::semantic::const_evaluation::{evaluate_array_size, evaluate_constant_expression};
+use crate::semantic::errors::{SemanticError, SemanticErrorTrait, InitializerListSizeError, RedeclarationError, BufferOverflowError};
+use crate::semantic::static_checks::{check_no_assignment_to_constants, check_switch_case_labels, check_function_prototypes, check_size_expressions};
 
 
 use structs::*;
+use user_types::UserDefinedTypes;
 
 pub mod structs;
+pub mod user_types;
 
-fn build_variable(declaration: &Declaration) -> Variable {
-    if declaration.is_array() {
-        Variable::ArrayVar(ArrayVarData::new(
-            declaration.identifier.data.clone(),
-            declaration.type_specifier,
-            declaration.array_size.unwrap(),
-        ))
-    } else {
-        Variable::NormalVar(NormalVarData::new(
+fn build_variable(
+    declaration: &Declaration,
+    constants: &HashMap<Identifier, Value>,
+    user_types: &UserDefinedTypes,
+) -> Result<Variable, SemanticError> {
+    user_types.check_type_is_declared(&declaration.type_specifier, declaration.identifier.sp)?;
+    if user_types.enumerators.contains_key(&declaration.identifier.data) {
+        return Err(SemanticError::Redeclaration(
+            RedeclarationError::init(
+                declaration.identifier.sp,
+                &format!("Variable {} is already declared as an enumerator", declaration.identifier.data.name)
+            )
+        ));
+    }
+
+    let mut variable = match &declaration.array_size {
+        Some(size_expression) => {
+            let size = evaluate_array_size(size_expression, constants)?;
+            Variable::ArrayVar(ArrayVarData::new(
+                declaration.identifier.data.clone(),
+                declaration.type_specifier.clone(),
+                size,
+            ))
+        },
+        None => Variable::NormalVar(NormalVarData::new(
             declaration.identifier.data.clone(),
-            declaration.type_specifier,
-        ))
+            declaration.type_specifier.clone(),
+        )),
+    };
+
+    // struct variables start with all of their fields unassigned
+    if let TypeSpecifier::Struct(struct_name) = &declaration.type_specifier {
+        let template = user_types.struct_templates[struct_name].clone();
+        match &mut variable {
+            Variable::NormalVar(normal_var) => normal_var.set_struct_template(template),
+            Variable::ArrayVar(array_var) => array_var.set_struct_template(template),
+        }
+    }
+    Ok(variable)
+}
+
+/// Like in C, an array can be initialized with fewer elements than its size,
+/// the other elements being zero, but not with more.
+/// A string literal must fit in its char array with its terminating NUL.
+fn check_initializer_list_size<'a>(
+    declaration: &Declaration<'a>,
+    variable: &Variable,
+) -> Result<(), SemanticError> {
+    let (array_var, initializer) = match (variable, &declaration.initializer) {
+        (Variable::ArrayVar(array_var), Some(initializer)) => (array_var, initializer),
+        _ => return Ok(()),
+    };
+    match &initializer.data {
+        Initializer::List(elements) if elements.len() > array_var.size => {
+            Err(SemanticError::InitializerListSize(
+                InitializerListSizeError::init(
+                    initializer.sp,
+                    format!(
+                        "Array {} of size {} is initialized with {} elements",
+                        declaration.identifier.data.name, array_var.size, elements.len()
+                    ).as_str(),
+                )
+            ))
+        },
+        Initializer::String(string) if string.len() + 1 > array_var.size => {
+            Err(SemanticError::BufferOverflow(
+                BufferOverflowError::init(
+                    initializer.sp,
+                    format!(
+                        "A string literal of {} characters and its terminating NUL don't fit in array {} of size {}",
+                        string.len(), declaration.identifier.data.name, array_var.size
+                    ).as_str(),
+                )
+            ))
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Evaluate the initializer of a const variable and assign it.
+/// Const scalars are also added to the known constants,
+/// so that they can be used in the following constant expressions.
+fn initialize_const_variable<'a>(
+    declaration: &Declaration<'a>,
+    variable: &mut Variable,
+    constants: &mut HashMap<Identifier, Value>,
+) -> Result<(), SemanticError> {
+    let initializer = declaration.initializer.as_ref().unwrap();
+    match (variable, &initializer.data) {
+        (Variable::NormalVar(normal_var), Initializer::Expression(expression)) => {
+            let value = evaluate_constant_expression(expression, constants)?;
+            normal_var.set_value(value)?;
+            constants.insert(
+                declaration.identifier.data.clone(),
+                normal_var.get_value().unwrap().clone(),
+            );
+        },
+        (Variable::ArrayVar(array_var), _) if initializer.data.is_zero_list() => {
+            array_var.set_zero_values(0);
+        },
+        (Variable::ArrayVar(array_var), Initializer::List(elements)) => {
+            for (index, element) in elements.iter().enumerate() {
+                let value = evaluate_constant_expression(element, constants)?;
+                let index_node = Node {
+                    sp: element.sp,
+                    data: Value::Int(index as i64),
+                };
+                array_var.set_value(index_node, value)?;
+            }
+            array_var.set_zero_values(elements.len());
+        },
+        (Variable::ArrayVar(array_var), Initializer::String(string)) => {
+            array_var.set_string(string, initializer.sp)?;
+            array_var.set_zero_values(string.len() + 1);
+        },
+        // checked when building the AST
+        _ => unreachable!(),
     }
+    Ok(())
 }
 
-fn build_scope<'a>(scope_id: Identifier, scope_function: &'a Function<'a>) -> Scope {
+fn build_scope<'a>(
+    scope_id: Identifier, 
+    scope_function: &'a Function<'a>,
+    user_types: &UserDefinedTypes,
+) -> Result<Scope, SemanticError> {
     let mut scope_vars = HashMap::new();
 
+    user_types.check_type_is_declared(&scope_function.return_type, scope_function.name.sp)?;
+
     // add function parameters to the variables of the current scope
+    // NOTE: parameters are never constant expressions, even when const
     if let Some(function_params) = &scope_function.params {
         for param in function_params {
             let current_declaration = &param.data;
-            let current_var = build_variable(&current_declaration);
+            let current_var = build_variable(&current_declaration, &HashMap::new(), user_types)?;
             scope_vars.insert(current_declaration.identifier.data.clone(), current_var);
         }
     }
@@ -45,37 +163,59 @@ fn build_scope<'a>(scope_id: Identifier, scope_function: &'a Function<'a>) -> Sc
     }
 
     // add function variables to the variables of the current scope
+    // const variables are evaluated in declaration order, at compile time
+    // enumerators can be used in their constant expressions
+    let mut constants = user_types.enumerators.clone();
     let block = &scope_function.body.data;
     for declaration in &block.declarations {
         let current_declaration = &declaration.data;
-        let current_var = build_variable(&current_declaration);
+        let mut current_var = build_variable(&current_declaration, &constants, user_types)?;
+        check_initializer_list_size(current_declaration, &current_var)?;
+        // the initializers of non-const arrays are evaluated when the function is called
+        if current_declaration.is_const {
+            initialize_const_variable(current_declaration, &mut current_var, &mut constants)?;
+        }
         scope_vars.insert(current_declaration.identifier.data.clone(), current_var);
     }
 
-    Scope::new(scope_id, scope_vars, scope_args)
+    check_no_assignment_to_constants(scope_function, &user_types.enumerators)?;
+    check_switch_case_labels(scope_function, &constants)?;
+
+    Ok(Scope::new(scope_id, scope_vars, scope_args))
 }
 
-pub fn build_static_symbol_table<'a>(ast: &AST<'a>) -> SymbolTable {
+pub fn build_static_symbol_table<'a>(ast: &AST<'a>) -> Result<SymbolTable, SemanticError> {
     let translation_unit = &ast.data;
 
     let mut symbol_table = SymbolTable::new();
 
+    // struct and enum types are shared by all functions
+    let user_types = UserDefinedTypes::build(translation_unit)?;
+    symbol_table.set_enumerators(user_types.enumerators.clone());
+    symbol_table.set_struct_templates(user_types.struct_templates.clone());
+
     // in Ctiny, each function has a single scope
     // start by entry point function
     let current_scope_id = Identifier {name: "main".to_string()};
     let current_scope_function = &translation_unit.main_function.data;
-    let main_scope = build_scope(current_scope_id, current_scope_function);
+    let main_scope = build_scope(current_scope_id, current_scope_function, &user_types)?;
     symbol_table.add_scope(main_scope);
 
     // build the scopes of the other functions
-    if let Some(functions) = &translation_unit.functions {
-        for function in functions {
-            let current_scope_id = function.data.name.data.clone();
-            let current_scope_function = &function.data;
-            let current_scope = build_scope(current_scope_id, current_scope_function);
-            symbol_table.add_scope(current_scope);
-        }
+    for function in translation_unit.functions.values() {
+        let current_scope_id = function.data.name.data.clone();
+        let current_scope_function = &function.data;
+        let current_scope = build_scope(current_scope_id, current_scope_function, &user_types)?;
+        symbol_table.add_scope(current_scope);
+    }
+
+    check_function_prototypes(translation_unit)?;
+
+    // sizes only depend on declarations, so they can be checked once all scopes are built
+    check_size_expressions(&translation_unit.main_function.data, &symbol_table)?;
+    for function in translation_unit.functions.values() {
+        check_size_expressions(&function.data, &symbol_table)?;
     }
     
-    symbol_table
-}
\ No newline at end of file
+    Ok(symbol_table)
+}
@@ -2,7 +2,8 @@ use std::collections::HashMap;
 
 use pest::Span;
 
-use crate::{abstract_syntax_tree::nodes::{Identifier, TypeSpecifier, Value, Node}, semantic::{errors::{SemanticError, UndeclaredVariableError, SemanticErrorTrait, RedeclarationError, UndeclaredFunctionError, UnassignedVariableError}, type_casts::{get_index_value_from_value_node, cast_to_type}}};
+use crate::{abstract_syntax_tree::nodes::{Identifier, TypeSpecifier, Value, Node, StructValue, StructField, Expression, GetOrSetValue, SizeOfOperand}, semantic::{data_model::get_scalar_type_size, errors::{SemanticError, UndeclaredVariableError, SemanticErrorTrait, RedeclarationError, UndeclaredFunctionError, UnassignedVariableError, InvalidMemberAccessError, UnexpectedExpressionParsingError, UndeclaredStructError, BufferOverflowError}, type_casts::{get_index_value_from_value_node, cast_to_type}}};
+use crate::interpretation::undo_log::{UndoEntry, is_undo_log_recording, record_writes};
 
 #[derive(Debug)]
 pub enum Variable {
@@ -26,6 +27,45 @@ impl PartialEq for Variable {
     }
 }
 
+impl Variable {
+    pub fn get_id(&self) -> &Identifier {
+        match self {
+            Variable::NormalVar(normal_var_data) => &normal_var_data.id,
+            Variable::ArrayVar(array_var_data) => &array_var_data.id,
+        }
+    }
+
+    /// Describe the variable and its current value(s), like `int a[2] = {4 (Int), <unassigned>}`.
+    pub fn describe(&self) -> String {
+        self.describe_with(|_, value| value.cloned())
+    }
+
+    /// Describe the variable with the values given for its slots, from the index of
+    /// the element (for an array) and the current value, like the values of a previous step.
+    pub fn describe_with(&self, get_value: impl Fn(Option<usize>, Option<&Value>) -> Option<Value>) -> String {
+        let describe_value = |value: Option<Value>| {
+            value.map_or("<unassigned>".to_string(), |value| value.to_string())
+        };
+        match self {
+            Variable::NormalVar(normal_var_data) => format!(
+                "{} {} = {}",
+                normal_var_data.type_specifier,
+                normal_var_data.id.name,
+                describe_value(get_value(None, normal_var_data.get_value())),
+            ),
+            Variable::ArrayVar(array_var_data) => {
+                let values: Vec<String> = (0..array_var_data.size)
+                    .map(|index| describe_value(get_value(Some(index), array_var_data.get_value(index))))
+                    .collect();
+                format!(
+                    "{} {}[{}] = {{{}}}",
+                    array_var_data.type_specifier, array_var_data.id.name, array_var_data.size, values.join(", ")
+                )
+            },
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct NormalVarData {
     pub id: Identifier,
@@ -47,7 +87,7 @@ impl NormalVarData {
         let value_type = value_node.data.as_type_specifier();
         if self.type_specifier != value_type {
             let casted_value_node = cast_to_type(
-                value_node, self.type_specifier
+                value_node, self.type_specifier.clone()
             )?;
             self.value = Some(casted_value_node.data);
             Ok(())
@@ -60,6 +100,16 @@ impl NormalVarData {
     pub fn get_value(&self) -> Option<&Value> {
         self.value.as_ref()
     }
+
+    pub fn get_mut_value(&mut self) -> Option<&mut Value> {
+        self.value.as_mut()
+    }
+
+    /// Struct variables start with all their fields unassigned,
+    /// so that each field can be assigned individually.
+    pub fn set_struct_template(&mut self, template: StructValue) {
+        self.value = Some(Value::Struct(template));
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -88,7 +138,7 @@ impl ArrayVarData {
         let usable_index = get_index_value_from_value_node(index_node)?;
         // cast the value to the type of the array
         let casted_value_node = cast_to_type(
-            value_node, self.type_specifier
+            value_node, self.type_specifier.clone()
         )?;
         self.values.insert(usable_index, casted_value_node.data);
         Ok(())
@@ -97,18 +147,340 @@ impl ArrayVarData {
     pub fn get_value(&self, index: usize) -> Option<&Value> {
         self.values.get(&index)
     }
+
+    pub fn get_mut_value(&mut self, index: usize) -> Option<&mut Value> {
+        self.values.get_mut(&index)
+    }
+
+    /// Set the elements from `start_index` to the end of the array to zero,
+    /// like the elements not given by an initializer list.
+    pub fn set_zero_values(&mut self, start_index: usize) {
+        for index in start_index..self.size {
+            let zero_value = match self.values.get_mut(&index) {
+                // the elements of an array of structs already hold a struct value
+                Some(Value::Struct(struct_value)) => {
+                    struct_value.set_zero_fields();
+                    continue;
+                },
+                _ => Value::zero(&self.type_specifier).unwrap(),
+            };
+            self.values.insert(index, zero_value);
+        }
+    }
+
+    /// Get the string held by a char array, up to its terminating NUL (excluded).
+    /// The NUL must be inside the array, otherwise reading the string would overflow it.
+    pub fn get_string<'a>(&self, sp: Span<'a>) -> Result<Vec<u8>, SemanticError> {
+        let mut string = Vec::new();
+        for index in 0..self.size {
+            match self.values.get(&index) {
+                Some(Value::Char(0)) => return Ok(string),
+                Some(Value::Char(character)) => string.push(*character),
+                _ => return Err(SemanticError::UnassignedVariable(
+                    UnassignedVariableError::init(
+                        sp,
+                        &format!(
+                            "Array {} has not been assigned a value at index {}, before the end of its string",
+                            self.id.name, index
+                        )
+                    )
+                )),
+            }
+        }
+        Err(SemanticError::BufferOverflow(
+            BufferOverflowError::init(
+                sp,
+                &format!(
+                    "The string in array {} has no terminating NUL within its {} elements",
+                    self.id.name, self.size
+                )
+            )
+        ))
+    }
+
+    /// Store a string and its terminating NUL at the start of a char array.
+    /// Like with C `strcpy`, the elements after the NUL are left as is,
+    /// but the unassigned ones are set to NUL, so that the array is fully assigned.
+    pub fn set_string<'a>(&mut self, string: &[u8], sp: Span<'a>) -> Result<(), SemanticError> {
+        if string.len() + 1 > self.size {
+            return Err(SemanticError::BufferOverflow(
+                BufferOverflowError::init(
+                    sp,
+                    &format!(
+                        "A string of {} characters and its terminating NUL don't fit in array {} of size {}",
+                        string.len(), self.id.name, self.size
+                    )
+                )
+            ));
+        }
+        for (index, character) in string.iter().chain(std::iter::once(&0)).enumerate() {
+            self.values.insert(index, Value::Char(*character));
+        }
+        for index in string.len() + 1..self.size {
+            self.values.entry(index).or_insert(Value::Char(0));
+        }
+        Ok(())
+    }
+
+    /// Each element of an array of structs starts with all its fields unassigned.
+    pub fn set_struct_template(&mut self, template: StructValue) {
+        for index in 0..self.size {
+            self.values.insert(index, Value::Struct(template.clone()));
+        }
+    }
+}
+
+/// Get the index of an array element, without the conversions of `get_index_value_from_value_node`,
+/// which would be followed by the interpretation hooks.
+fn get_slot_index(potential_index_node: Option<&Node<Value>>) -> Option<usize> {
+    potential_index_node
+        .and_then(|index_node| index_node.data.as_integer())
+        .and_then(|index| usize::try_from(index).ok())
+}
+
+fn make_invalid_member_access_error<'a>(
+    member_node: &Node<'a, Identifier>,
+    message: &str,
+) -> SemanticError {
+    SemanticError::InvalidMemberAccess(
+        InvalidMemberAccessError::init(member_node.sp, message)
+    )
+}
+
+/// Follow the member accesses (e.g. `.position.x`) from a struct value,
+/// and get the accessed field.
+fn get_member_field<'a, 'b>(
+    value: &'b Value,
+    members: &[Node<'a, Identifier>],
+) -> Result<&'b StructField, SemanticError> {
+    let (member, remaining_members) = members.split_first().unwrap();
+    let struct_value = match value {
+        Value::Struct(struct_value) => struct_value,
+        other => return Err(make_invalid_member_access_error(
+            member,
+            &format!("Cannot access member {} of a value of type {}", member.data.name, other.as_type_specifier())
+        )),
+    };
+    let field = match struct_value.get_field(&member.data) {
+        Some(field) => field,
+        None => return Err(make_invalid_member_access_error(
+            member,
+            &format!("struct {} has no member {}", struct_value.type_name.name, member.data.name)
+        )),
+    };
+    if remaining_members.is_empty() {
+        return Ok(field);
+    }
+    match &field.value {
+        Some(field_value) => get_member_field(field_value, remaining_members),
+        // struct fields always hold a value, so this is an unassigned scalar field
+        None => Err(make_invalid_member_access_error(
+            &remaining_members[0],
+            &format!("Cannot access member {} of a value of type {}", remaining_members[0].data.name, field.type_specifier)
+        )),
+    }
+}
+
+/// Mutable version of get_member_field.
+fn get_mut_member_field<'a, 'b>(
+    value: &'b mut Value,
+    members: &[Node<'a, Identifier>],
+) -> Result<&'b mut StructField, SemanticError> {
+    let (member, remaining_members) = members.split_first().unwrap();
+    let struct_value = match value {
+        Value::Struct(struct_value) => struct_value,
+        other => return Err(make_invalid_member_access_error(
+            member,
+            &format!("Cannot access member {} of a value of type {}", member.data.name, other.as_type_specifier())
+        )),
+    };
+    let struct_name = struct_value.type_name.name.clone();
+    let field = match struct_value.get_mut_field(&member.data) {
+        Some(field) => field,
+        None => return Err(make_invalid_member_access_error(
+            member,
+            &format!("struct {} has no member {}", struct_name, member.data.name)
+        )),
+    };
+    if remaining_members.is_empty() {
+        return Ok(field);
+    }
+    let field_type = field.type_specifier.clone();
+    match &mut field.value {
+        Some(field_value) => get_mut_member_field(field_value, remaining_members),
+        None => Err(make_invalid_member_access_error(
+            &remaining_members[0],
+            &format!("Cannot access member {} of a value of type {}", remaining_members[0].data.name, field_type)
+        )),
+    }
+}
+
+/// Get a copy of an accessed value, making sure it has been assigned.
+/// For a struct, all of its fields must have been assigned.
+fn get_assigned_value<'a>(
+    potential_value: Option<&Value>,
+    access_path: &str,
+    span: Span<'a>,
+) -> Result<Node<'a, Value>, SemanticError> {
+    let unassigned_path = match potential_value {
+        None => Some(access_path.to_string()),
+        Some(Value::Struct(struct_value)) => struct_value.get_first_unassigned_field()
+            .map(|field_path| format!("{}.{}", access_path, field_path)),
+        Some(_) => None,
+    };
+    match unassigned_path {
+        Some(unassigned_path) => Err(
+            SemanticError::UnassignedVariable(
+                UnassignedVariableError::init(
+                    span,
+                    &format!("{} has not been assigned a value", unassigned_path)
+                )
+            )
+        ),
+        None => Ok(Node {
+            sp: span,
+            data: potential_value.unwrap().clone(),
+        }),
+    }
 }
 
 pub struct SymbolTable {
     scopes: HashMap<Identifier, Scope>,
+    enumerators: HashMap<Identifier, Value>, // shared by all scopes
+    struct_templates: HashMap<Identifier, StructValue>, // shared by all scopes
 }
 
 // implementations
 impl SymbolTable {
     pub fn new() -> Self {
         Self {
-            scopes: HashMap::new()
+            scopes: HashMap::new(),
+            enumerators: HashMap::new(),
+            struct_templates: HashMap::new(),
+        }
+    }
+
+    pub fn set_enumerators(&mut self, enumerators: HashMap<Identifier, Value>) {
+        self.enumerators = enumerators;
+    }
+
+    pub fn set_struct_templates(&mut self, struct_templates: HashMap<Identifier, StructValue>) {
+        self.struct_templates = struct_templates;
+    }
+
+    /// Get the size and the alignment in bytes of a type, like C `sizeof` and `_Alignof`.
+    /// Like in C, each struct field is aligned on its own alignment,
+    /// and the size of a struct is padded to a multiple of its alignment.
+    fn get_type_layout(&self, type_specifier: &TypeSpecifier) -> (usize, usize) {
+        if let Some(size) = get_scalar_type_size(type_specifier) {
+            return (size, size);
+        }
+        let struct_template = match type_specifier {
+            TypeSpecifier::Struct(struct_name) => &self.struct_templates[struct_name],
+            _ => unreachable!("only struct types have no scalar size"),
+        };
+        let mut size: usize = 0;
+        let mut alignment: usize = 1;
+        for field in &struct_template.fields {
+            let (field_size, field_alignment) = self.get_type_layout(&field.type_specifier);
+            size = size.next_multiple_of(field_alignment) + field_size;
+            alignment = alignment.max(field_alignment);
         }
+        (size.next_multiple_of(alignment), alignment)
+    }
+
+    /// Get the size in bytes of the value accessed by a variable access, like `a`, `a[i]` or `p.x`.
+    /// The access is not evaluated, only the declared types are used.
+    fn get_variable_size<'a>(
+        &self,
+        get_or_set_value: &GetOrSetValue<'a>,
+        current_scope: &Scope,
+    ) -> Result<usize, SemanticError> {
+        let identifier = &get_or_set_value.identifier;
+        let (type_specifier, array_length) = match current_scope.get_variable(identifier)? {
+            Variable::NormalVar(normal_var_data) => {
+                if get_or_set_value.index.is_some() {
+                    // makes the usual error for an index on a normal variable
+                    current_scope.get_array_variable(identifier)?;
+                }
+                (&normal_var_data.type_specifier, 1)
+            },
+            Variable::ArrayVar(array_var_data) => {
+                let array_length = match get_or_set_value.index {
+                    Some(_) => 1,
+                    None if get_or_set_value.members.is_empty() => array_var_data.size,
+                    None => return Err(make_invalid_member_access_error(
+                        &get_or_set_value.members[0],
+                        &format!("Cannot access member {} of array {}", get_or_set_value.members[0].data.name, identifier.data.name)
+                    )),
+                };
+                (&array_var_data.type_specifier, array_length)
+            },
+        };
+
+        if get_or_set_value.members.is_empty() {
+            return Ok(self.get_type_layout(type_specifier).0 * array_length);
+        }
+        let variable_template = match type_specifier {
+            TypeSpecifier::Struct(struct_name) => Value::Struct(self.struct_templates[struct_name].clone()),
+            _ => return Err(make_invalid_member_access_error(
+                &get_or_set_value.members[0],
+                &format!("Cannot access member {} of a value of type {}", get_or_set_value.members[0].data.name, type_specifier)
+            )),
+        };
+        let field = get_member_field(&variable_template, &get_or_set_value.members)?;
+        Ok(self.get_type_layout(&field.type_specifier).0)
+    }
+
+    /// Evaluate a `sizeof(...)` or `len(...)` expression of the given scope.
+    /// Their values only depend on the declared types and array sizes, so they are
+    /// checked before interpretation, and are int values like in Ctiny array indexes.
+    pub fn evaluate_size_expression<'a>(
+        &self,
+        expression_node: &Node<'a, Expression<'a>>,
+        current_scope_node_id: &Node<'a, Identifier>,
+    ) -> Result<Node<'a, Value>, SemanticError> {
+        let current_scope = self.get_scope(current_scope_node_id).unwrap();
+        let size = match &expression_node.data {
+            Expression::SizeOf(SizeOfOperand::Type(type_specifier)) => {
+                if let TypeSpecifier::Struct(struct_name) = type_specifier {
+                    if !self.struct_templates.contains_key(struct_name) {
+                        return Err(SemanticError::UndeclaredStruct(
+                            UndeclaredStructError::init(
+                                expression_node.sp,
+                                &format!("Undeclared struct type: struct {}", struct_name.name)
+                            )
+                        ));
+                    }
+                }
+                self.get_type_layout(type_specifier).0
+            },
+            Expression::SizeOf(SizeOfOperand::Variable(get_or_set_value)) => {
+                self.get_variable_size(get_or_set_value, current_scope)?
+            },
+            Expression::Length(array_identifier) => current_scope.get_array_variable(array_identifier)?.size,
+            _ => return Err(SemanticError::UnexpectedExpressionParsing(
+                UnexpectedExpressionParsingError::init(
+                    expression_node.sp,
+                    format!(
+                        "evaluate_size_expression called on a non size expression: {:?}", 
+                        expression_node.data
+                    ).as_str(),
+                )
+            )),
+        };
+        let size_node = Node {
+            sp: expression_node.sp,
+            data: Value::UnsignedLong(size as u64),
+        };
+        cast_to_type(size_node, TypeSpecifier::Int)
+    }
+
+    pub fn get_enumerator_value<'a>(&self, enumerator_id: &Node<'a, Identifier>) -> Option<Node<'a, Value>> {
+        self.enumerators.get(&enumerator_id.data).map(|value| Node {
+            sp: enumerator_id.sp,
+            data: value.clone(),
+        })
     }
 
     pub fn get_scope(&self, scope_id: &Node<Identifier>) -> Option<&Scope> {
@@ -141,6 +513,10 @@ impl SymbolTable {
     pub fn add_scope(&mut self, scope: Scope) {
         self.scopes.insert(scope.id.clone(), scope);
     }
+
+    pub fn remove_scope(&mut self, scope_id: &Identifier) -> Option<Scope> {
+        self.scopes.remove(scope_id)
+    }
 }
 
 // in Ctiny, a scope is equivalent to a block of a function
@@ -163,6 +539,21 @@ impl Scope {
         }
     }
 
+    /// Get all the variables of the scope, in no particular order.
+    pub fn get_variables(&self) -> impl Iterator<Item = &Variable> {
+        self.variables.values()
+    }
+
+    /// Keep the variables of a previous version of this scope, with their values.
+    /// The variables that are not declared in this scope anymore are dropped.
+    pub fn keep_variables_from(&mut self, previous_scope: Scope) {
+        for (var_id, variable) in previous_scope.variables {
+            if self.variables.contains_key(&var_id) {
+                self.variables.insert(var_id, variable);
+            }
+        }
+    }
+
     // The following function is used for testing purposes
     #[cfg(test)]
     pub fn get_variable_from_id(&self, var_id: &Identifier) -> Result<&Variable, String> {
@@ -232,34 +623,61 @@ impl Scope {
         }
     }
 
+    /// Get the current values of a slot, before writing it: a normal variable, an element
+    /// of an array, or all the elements of an array without index.
+    /// Nothing is returned when the undo log is not recording.
+    pub fn get_undo_entries(&self, var_id: &Identifier, potential_index: Option<usize>) -> Vec<UndoEntry> {
+        if !is_undo_log_recording() {
+            return Vec::new();
+        }
+        let make_undo_entry = |index: Option<usize>, old_value: Option<&Value>| UndoEntry {
+            scope: self.id.name.clone(),
+            variable: var_id.name.clone(),
+            index,
+            old_value: old_value.cloned(),
+        };
+        match (self.variables.get(var_id), potential_index) {
+            (Some(Variable::NormalVar(normal_var_data)), _) => vec![make_undo_entry(None, normal_var_data.get_value())],
+            (Some(Variable::ArrayVar(array_var_data)), Some(index)) => {
+                vec![make_undo_entry(Some(index), array_var_data.get_value(index))]
+            },
+            (Some(Variable::ArrayVar(array_var_data)), None) => (0..array_var_data.size)
+                .map(|index| make_undo_entry(Some(index), array_var_data.get_value(index)))
+                .collect(),
+            (None, _) => Vec::new(),
+        }
+    }
+
     /// Get a variable value.
     /// If the variable is an array, get the value at the given index.
+    /// If members are given, get the value of the accessed struct member.
     /// 
     /// This function make use get_normal_variable and get_array_variable.
     /// 
     /// The function check that the variable is an array if the index is given.
     /// The function check that the index is a positive integer.
+    /// The function check that the accessed value (all fields for a struct) has been assigned.
     pub fn get_variable_value<'a>(
         &self, 
         var_id_node: &Node<'a, Identifier>, 
-        potential_index: Option<Node<'a, Value>>
+        potential_index: Option<Node<'a, Value>>,
+        members: &[Node<'a, Identifier>],
     ) -> Result<Node<'a, Value>, SemanticError> {
         // check if the variable is an array or a normal variable
         // if the index is given, make sure its value is a positive integer
-        match potential_index {
+        let (variable_value, access_path, span) = match potential_index {
             Some(index) => {
                 // case: array
                 let index_span = index.sp;
                 let index_value = get_index_value_from_value_node(index)?;
                 let array_var_data = self.get_array_variable(var_id_node)?;
                 match array_var_data.get_value(index_value) {
-                    Some(value) => Ok(
-                        Node {
-                            sp: index_span,
-                            data: value.clone(),
-                        }
+                    Some(value) => (
+                        value,
+                        format!("{}[{}]", var_id_node.data.name, index_value),
+                        index_span,
                     ),
-                    None => Err(
+                    None => return Err(
                         SemanticError::UndeclaredVariable(
                             UndeclaredVariableError::init(
                                 index_span,
@@ -273,13 +691,12 @@ impl Scope {
                 // case: normal variable
                 let normal_var_data = self.get_normal_variable(var_id_node)?;
                 match normal_var_data.get_value() {
-                    Some(value) => Ok(
-                        Node {
-                            sp: var_id_node.sp,
-                            data: value.clone(),
-                        }
+                    Some(value) => (
+                        value,
+                        var_id_node.data.name.clone(),
+                        var_id_node.sp,
                     ),
-                    None => Err(
+                    None => return Err(
                         SemanticError::UndeclaredVariable(
                             UndeclaredVariableError::init(
                                 var_id_node.sp,
@@ -289,6 +706,21 @@ impl Scope {
                     ),
                 }
             },
+        };
+
+        match members.last() {
+            None => get_assigned_value(Some(variable_value), &access_path, span),
+            Some(last_member) => {
+                let field = get_member_field(variable_value, members)?;
+                let member_path: Vec<&str> = members.iter()
+                    .map(|member| member.data.name.as_str())
+                    .collect();
+                get_assigned_value(
+                    field.value.as_ref(),
+                    &format!("{}.{}", access_path, member_path.join(".")),
+                    last_member.sp,
+                )
+            },
         }
     }
 
@@ -297,10 +729,12 @@ impl Scope {
         var_id_node: &Node<'a, Identifier>, 
         value_node: Node<'a, Value>,
     ) -> Result<(), SemanticError> {
+        let undo_entries = self.get_undo_entries(&var_id_node.data, None);
         match self.get_mut_variable(var_id_node)? {
             Variable::NormalVar(normal_var_data) => {
                 // set the value
                 normal_var_data.set_value(value_node)?;
+                record_writes(undo_entries);
                 Ok(())
             },
             Variable::ArrayVar(_) => Err(
@@ -320,6 +754,10 @@ impl Scope {
         index_node: Node<'a, Value>,
         value_node: Node<'a, Value>,
     ) -> Result<(), SemanticError> {
+        let undo_entries = match get_slot_index(Some(&index_node)) {
+            Some(index) => self.get_undo_entries(&var_id_node.data, Some(index)),
+            None => Vec::new(),
+        };
         match self.get_mut_variable(var_id_node)? {
             Variable::NormalVar(_) => Err(
                 SemanticError::UndeclaredVariable(
@@ -332,11 +770,74 @@ impl Scope {
             Variable::ArrayVar(array_var_data) => {
                 // set the value
                 array_var_data.set_value(index_node, value_node)?;
+                record_writes(undo_entries);
                 Ok(())
             },
         }
     }
 
+    /// Set the value of a struct member (e.g. `p.x` or `points[1].position.x`).
+    /// The value is converted to the type of the accessed field.
+    pub fn set_struct_member_value<'a>(
+        &mut self,
+        var_id_node: &Node<'a, Identifier>,
+        potential_index: Option<Node<'a, Value>>,
+        members: &[Node<'a, Identifier>],
+        value_node: Node<'a, Value>,
+    ) -> Result<(), SemanticError> {
+        // the whole struct value of the variable or element is kept in the undo log
+        let undo_entries = self.get_undo_entries(&var_id_node.data, get_slot_index(potential_index.as_ref()));
+        let variable_value = match (potential_index, self.get_mut_variable(var_id_node)?) {
+            (Some(index), Variable::ArrayVar(array_var_data)) => {
+                let index_span = index.sp;
+                let index_value = get_index_value_from_value_node(index)?;
+                match array_var_data.get_mut_value(index_value) {
+                    Some(value) => value,
+                    None => return Err(
+                        SemanticError::UndeclaredVariable(
+                            UndeclaredVariableError::init(
+                                index_span,
+                                &format!("Array <{}> does not have a value at index {}", var_id_node.data.name, index_value)
+                            )
+                        )
+                    ),
+                }
+            },
+            (None, Variable::NormalVar(normal_var_data)) => {
+                match normal_var_data.get_mut_value() {
+                    Some(value) => value,
+                    // struct variables always hold a value
+                    None => return Err(make_invalid_member_access_error(
+                        var_id_node,
+                        &format!("Variable {} is not a struct", var_id_node.data.name)
+                    )),
+                }
+            },
+            (Some(_), Variable::NormalVar(_)) => return Err(
+                SemanticError::UndeclaredVariable(
+                    UndeclaredVariableError::init(
+                        var_id_node.sp,
+                        &format!("Variable {} is a normal variable, not an array", var_id_node.data.name)
+                    )
+                )
+            ),
+            (None, Variable::ArrayVar(_)) => return Err(
+                SemanticError::UndeclaredVariable(
+                    UndeclaredVariableError::init(
+                        var_id_node.sp,
+                        &format!("Variable {} is an array, not a normal variable", var_id_node.data.name)
+                    )
+                )
+            ),
+        };
+
+        let field = get_mut_member_field(variable_value, members)?;
+        let casted_value_node = cast_to_type(value_node, field.type_specifier.clone())?;
+        field.value = Some(casted_value_node.data);
+        record_writes(undo_entries);
+        Ok(())
+    }
+
     // this function adds a new variable to the scope
     // It checks that the variable is not already declared in the scope
     pub fn add_variable<'a>(&mut self, variable_node: Node<'a, Variable>) -> Result<(), SemanticError> {
@@ -362,7 +863,8 @@ impl Scope {
     }
 
     pub fn get_number_of_arguments(&self) -> usize {
-        self.arguments.as_ref().unwrap().len()
+        // functions without parameters have no argument list
+        self.arguments.as_ref().map_or(0, |arguments| arguments.len())
     }
 
     pub fn get_argument_id<'a>(&self, index: usize) -> Identifier {
@@ -373,29 +875,55 @@ impl Scope {
         for (var_id, var) in &self.variables {
             match var {
                 Variable::NormalVar(normal_var_data) => {
-                    if normal_var_data.get_value().is_none() {
-                        return Err(
+                    match normal_var_data.get_value() {
+                        None => return Err(
                             SemanticError::UnassignedVariable(
                                 UnassignedVariableError::init(
                                     Span::new(&var_id.name, 0, var_id.name.len()).unwrap(),
                                     &format!("Variable {} has not been assigned a value.", var_id.name)
                                 )
                             )
-                        )
+                        ),
+                        Some(Value::Struct(struct_value)) => {
+                            if let Some(field_path) = struct_value.get_first_unassigned_field() {
+                                return Err(
+                                    SemanticError::UnassignedVariable(
+                                        UnassignedVariableError::init(
+                                            Span::new(&var_id.name, 0, var_id.name.len()).unwrap(),
+                                            &format!("Variable {} has unassigned field {}.", var_id.name, field_path)
+                                        )
+                                    )
+                                )
+                            }
+                        },
+                        Some(_) => {},
                     }
                 },
                 Variable::ArrayVar(array_var_data) => {
                     // knowing the size of the array, check that all values have been assigned from index 0 to size - 1
                     for index in 0..array_var_data.size {
-                        if array_var_data.get_value(index).is_none() {
-                            return Err(
+                        match array_var_data.get_value(index) {
+                            None => return Err(
                                 SemanticError::UnassignedVariable(
                                     UnassignedVariableError::init(
                                         Span::new(&var_id.name, 0, var_id.name.len()).unwrap(),
                                         &format!("Array {} has not been assigned a value at index {}.", var_id.name, index)
                                     )
                                 )
-                            )
+                            ),
+                            Some(Value::Struct(struct_value)) => {
+                                if let Some(field_path) = struct_value.get_first_unassigned_field() {
+                                    return Err(
+                                        SemanticError::UnassignedVariable(
+                                            UnassignedVariableError::init(
+                                                Span::new(&var_id.name, 0, var_id.name.len()).unwrap(),
+                                                &format!("Array {} has unassigned field {} at index {}.", var_id.name, field_path, index)
+                                            )
+                                        )
+                                    )
+                                }
+                            },
+                            Some(_) => {},
                         }
                     }
                 },
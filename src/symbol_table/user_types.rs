@@ -0,0 +1,216 @@
+use std::collections::{HashMap, HashSet};
+
+use pest::Span;
+
+use crate::abstract_syntax_tree::nodes::{Identifier, Value, TypeSpecifier, StructValue, StructField, EnumValue, TranslationUnit};
+use crate::semantic::const_evaluation::evaluate_constant_expression;
+use crate::semantic::data_model::get_integer_type_info;
+use crate::semantic::errors::{SemanticError, SemanticErrorTrait, RedeclarationError, UndeclaredStructError, UndeclaredEnumError, InvalidEnumeratorValueError};
+
+/// Struct and enum types defined at the top level of a program, shared by all functions.
+pub struct UserDefinedTypes {
+    pub struct_templates: HashMap<Identifier, StructValue>,
+    pub enum_names: HashSet<Identifier>,
+    pub enumerators: HashMap<Identifier, Value>,
+}
+
+impl UserDefinedTypes {
+    pub fn build<'a>(translation_unit: &TranslationUnit<'a>) -> Result<Self, SemanticError> {
+        let (enum_names, enumerators) = build_enumerators(translation_unit)?;
+        let struct_templates = build_struct_templates(translation_unit, &enum_names)?;
+        Ok(Self {
+            struct_templates,
+            enum_names,
+            enumerators,
+        })
+    }
+
+    /// Check that a struct or enum type has been defined.
+    pub fn check_type_is_declared<'a>(
+        &self, 
+        type_specifier: &TypeSpecifier, 
+        span: Span<'a>,
+    ) -> Result<(), SemanticError> {
+        match type_specifier {
+            TypeSpecifier::Struct(struct_name) if !self.struct_templates.contains_key(struct_name) => {
+                Err(make_undeclared_struct_error(struct_name, span))
+            },
+            TypeSpecifier::Enum(enum_name) if !self.enum_names.contains(enum_name) => {
+                Err(make_undeclared_enum_error(enum_name, span))
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+fn make_undeclared_struct_error<'a>(
+    struct_name: &Identifier,
+    span: Span<'a>,
+) -> SemanticError {
+    SemanticError::UndeclaredStruct(
+        UndeclaredStructError::init(
+            span,
+            &format!("Undeclared struct type: struct {}", struct_name.name)
+        )
+    )
+}
+
+/// Build the value of each struct type with all of its fields unassigned.
+/// A field can only be of a struct type defined before, so struct types are never recursive.
+/// Nested struct fields hold the template of their own struct type.
+fn build_struct_templates<'a>(
+    translation_unit: &TranslationUnit<'a>,
+    enum_names: &HashSet<Identifier>,
+) -> Result<HashMap<Identifier, StructValue>, SemanticError> {
+    let mut struct_templates: HashMap<Identifier, StructValue> = HashMap::new();
+    let struct_definitions = match &translation_unit.structs {
+        Some(struct_definitions) => struct_definitions,
+        None => return Ok(struct_templates),
+    };
+
+    for struct_definition in struct_definitions {
+        let struct_name = &struct_definition.data.name;
+        if struct_templates.contains_key(&struct_name.data) {
+            return Err(SemanticError::Redeclaration(
+                RedeclarationError::init(
+                    struct_name.sp,
+                    &format!("struct {} is already defined", struct_name.data.name)
+                )
+            ));
+        }
+
+        let mut fields: Vec<StructField> = Vec::new();
+        for field_declaration in &struct_definition.data.fields {
+            let field_id = &field_declaration.data.identifier;
+            if fields.iter().any(|field| field.id == field_id.data) {
+                return Err(SemanticError::Redeclaration(
+                    RedeclarationError::init(
+                        field_id.sp,
+                        &format!(
+                            "Field {} is already declared in struct {}", 
+                            field_id.data.name, struct_name.data.name
+                        )
+                    )
+                ));
+            }
+            let field_type = field_declaration.data.type_specifier.clone();
+            let field_value = match &field_type {
+                TypeSpecifier::Struct(field_struct_name) => {
+                    match struct_templates.get(field_struct_name) {
+                        Some(template) => Some(Value::Struct(template.clone())),
+                        None => return Err(make_undeclared_struct_error(
+                            field_struct_name, field_declaration.sp
+                        )),
+                    }
+                },
+                TypeSpecifier::Enum(field_enum_name) if !enum_names.contains(field_enum_name) => {
+                    return Err(make_undeclared_enum_error(field_enum_name, field_declaration.sp));
+                },
+                _ => None,
+            };
+            fields.push(StructField {
+                id: field_id.data.clone(),
+                type_specifier: field_type,
+                value: field_value,
+            });
+        }
+
+        struct_templates.insert(
+            struct_name.data.clone(),
+            StructValue {
+                type_name: struct_name.data.clone(),
+                fields,
+            },
+        );
+    }
+    Ok(struct_templates)
+}
+
+fn make_undeclared_enum_error<'a>(
+    enum_name: &Identifier,
+    span: Span<'a>,
+) -> SemanticError {
+    SemanticError::UndeclaredEnum(
+        UndeclaredEnumError::init(
+            span,
+            &format!("Undeclared enum type: enum {}", enum_name.name)
+        )
+    )
+}
+
+/// Evaluate the enumerators of all enum types, in definition order.
+/// Like in C, an enumerator without value is the previous enumerator + 1 (0 for the first one),
+/// and its value must fit in an int.
+fn build_enumerators<'a>(
+    translation_unit: &TranslationUnit<'a>,
+) -> Result<(HashSet<Identifier>, HashMap<Identifier, Value>), SemanticError> {
+    let mut enum_names: HashSet<Identifier> = HashSet::new();
+    let mut enumerators: HashMap<Identifier, Value> = HashMap::new();
+    let enum_definitions = match &translation_unit.enums {
+        Some(enum_definitions) => enum_definitions,
+        None => return Ok((enum_names, enumerators)),
+    };
+
+    let int_info = get_integer_type_info(&TypeSpecifier::Int).unwrap();
+    for enum_definition in enum_definitions {
+        let enum_name = &enum_definition.data.name;
+        if !enum_names.insert(enum_name.data.clone()) {
+            return Err(SemanticError::Redeclaration(
+                RedeclarationError::init(
+                    enum_name.sp,
+                    &format!("enum {} is already defined", enum_name.data.name)
+                )
+            ));
+        }
+
+        let mut next_value: i128 = 0;
+        for enumerator in &enum_definition.data.enumerators {
+            let enumerator_id = &enumerator.data.identifier;
+            if enumerators.contains_key(&enumerator_id.data) {
+                return Err(SemanticError::Redeclaration(
+                    RedeclarationError::init(
+                        enumerator_id.sp,
+                        &format!("Enumerator {} is already declared", enumerator_id.data.name)
+                    )
+                ));
+            }
+
+            let value = match &enumerator.data.value {
+                Some(value_expression) => {
+                    let value = evaluate_constant_expression(value_expression, &enumerators)?.data;
+                    match value.as_integer() {
+                        Some(integer) => integer,
+                        None => return Err(SemanticError::InvalidEnumeratorValue(
+                            InvalidEnumeratorValueError::init(
+                                value_expression.sp,
+                                &format!("Enumerator {} must have an integer value, got {}", enumerator_id.data.name, value)
+                            )
+                        )),
+                    }
+                },
+                None => next_value,
+            };
+            if !int_info.contains(value) {
+                return Err(SemanticError::InvalidEnumeratorValue(
+                    InvalidEnumeratorValueError::init(
+                        enumerator.sp,
+                        &format!(
+                            "Enumerator {} value {} is out of the range of int ({} bits)", 
+                            enumerator_id.data.name, value, int_info.bits
+                        )
+                    )
+                ));
+            }
+
+            enumerators.insert(
+                enumerator_id.data.clone(),
+                Value::Enum(EnumValue {
+                    type_name: enum_name.data.clone(),
+                    value: value as i64,
+                }),
+            );
+            next_value = value + 1;
+        }
+    }
+    Ok((enum_names, enumerators))
+}
@@ -1,7 +1,7 @@
 use pest::Parser;
 
 use crate::syntax_parsing::{CTinyParser, Rule};
-use crate::abstract_syntax_tree::declarations::{build_declaration, build_parameter_list, build_multi_declaration};
+use crate::abstract_syntax_tree::declarations::{build_declaration, build_parameter_list, build_multi_declaration, build_struct_definition, build_enum_definition};
 
 use crate::build_test;
 
@@ -21,7 +21,10 @@ fn test_ast_declaration() {
         "float x",
         "float x[10]",
         "bool x",
-        "bool x[10]"
+        "bool x[10]",
+        "int x[N * 2]",
+        "const int x = 1",
+        "const float x[2] = {1.0, 2.5}"
     );
 }
 
@@ -57,6 +60,24 @@ fn test_ast_build_multi_declaration() {
         "int x, y[10], z;",
         "char x, y[10], z;",
         "float x[10], y, z;",
-        "bool x, y[10], z[10];"
+        "bool x, y[10], z[10];",
+        "const int N = 2, M = N * 2, x[N + M] = {1, 2, 3, 4, 5, 6};"
     );
-}
\ No newline at end of file
+}
+#[test]
+fn test_ast_struct_definition() {
+    build_test!(Rule::struct_definition, build_struct_definition,
+        "struct point { int x; float y; };",
+        "struct point { int x, y, z; };",
+        "struct segment { struct point start; struct point end; };"
+    );
+}
+
+#[test]
+fn test_ast_enum_definition() {
+    build_test!(Rule::enum_definition, build_enum_definition,
+        "enum color { RED };",
+        "enum color { RED, GREEN = 5, BLUE };",
+        "enum size { SMALL = 2, LARGE = SMALL * 4 };"
+    );
+}
@@ -62,9 +62,23 @@ fn test_ast_literal_int_overflow_max() {
     build_test_expression_error!(rule, test_string);
 }
 
-// NOTE: Note that we cannot test the overflow of the min value
-// this is because the parser cannot parse the minus sign in the Literal rule
-// (it actually make it a unary minus operator, not a Literal).
+// NOTE: the minus sign is not part of the Literal rule (it is a unary minus operator).
+// The AST building folds a minus sign directly followed by an integer literal into
+// a single negative literal, so the min value is tested at the factor level.
+#[test]
+fn test_ast_literal_int_min() {
+    build_test_expression!(Rule::factor, "-32768", "- 32768", "-0");
+}
+
+#[test]
+fn test_ast_literal_int_overflow_min() {
+    build_test_expression_error!(Rule::factor, "-32769");
+}
+
+#[test]
+fn test_ast_literal_int_overflow_huge() {
+    build_test_expression_error!(Rule::literal, "99999999999999999999999");
+}
 
 #[test]
 fn test_ast_type_cast() {
@@ -178,10 +192,17 @@ fn test_ast_equality() {
     build_test_expression!(Rule::equality, 
         "1 == 2", 
         "1 != 2", 
-        "1 == 2 != 3"
+        "(1 == 2) != 3",
+        "1 < 2 == 3 > 4"
     );
 }
 
+#[test]
+fn test_ast_chained_equality() {
+    build_test_expression_error!(Rule::equality, "1 == 2 != 3");
+    build_test_expression_error!(Rule::equality, "a == b == c");
+}
+
 #[test]
 fn test_ast_relation() {
     build_test_expression!(Rule::relation,
@@ -191,10 +212,26 @@ fn test_ast_relation() {
         "1 > 2", 
         "1 <= 2", 
         "1 >= 2",
-        "(1 <= 2)"
+        "(1 <= 2)",
+        "(1 < 2) < 3"
     );
 }
 
+#[test]
+fn test_ast_chained_relation() {
+    build_test_expression_error!(Rule::relation, "1 < 2 < 3");
+    build_test_expression_error!(Rule::relation, "a <= b > c");
+}
+
+#[test]
+fn test_ast_chained_relation_error_message() {
+    let pair = CTinyParser::parse(Rule::expression, "0 < x + 1 <= 10")
+        .unwrap().next().unwrap();
+    let message = build_expression(pair).unwrap_err().to_string();
+    print!("{}\n", message);
+    assert!(message.contains("Did you mean (0 < x + 1) && (x + 1 <= 10)?"));
+}
+
 #[test]
 fn test_ast_addition() {
     build_test_expression!(Rule::addition,
@@ -1,7 +1,7 @@
 use pest::Parser;
 
 use crate::syntax_parsing::{CTinyParser, Rule};
-use crate::abstract_syntax_tree::functions::{build_block, build_function_definition, build_entry_point_function};
+use crate::abstract_syntax_tree::functions::{build_block, build_function_definition, build_entry_point_function, build_function_prototype};
 
 use crate::build_test;
 
@@ -59,4 +59,14 @@ fn test_ast_entry_point_function() {
         "int main() { int x; char y[10]; x = 3; y[0] = 4; return x + y[0]; }",
         "int main() { int x; char y[10]; x = 3; y[0] = 4; if (x == y[0]) { y[1] = y[0]; } return x + y[0]; }"
     );
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_ast_function_prototype() {
+    build_test!(Rule::function_prototype, build_function_prototype,
+        "int foo();",
+        "int foo(int a);",
+        "float foo(int a, char b[10], bool c);",
+        "struct point foo(struct point p, enum color c);"
+    );
+}
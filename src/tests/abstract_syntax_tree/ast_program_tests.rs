@@ -2,6 +2,8 @@ use pest::Parser;
 
 use crate::syntax_parsing::{CTinyParser, Rule};
 use crate::abstract_syntax_tree::build_translation_unit;
+use crate::pipelines::parse_content_into_ast;
+use crate::semantic::errors::SemanticError;
 
 use crate::build_test;
 
@@ -17,10 +19,43 @@ fn test_ast_translation_unit() {
         "int main () { return 0; }",
         "char foo() { return 'a'; } int bar() { return 10; } int main () { return foo() + bar(); }",
         "int foo() { int x; char y[10]; x = 3; y[0] = 4; return x + y[0]; } int main() { int x; char y[10]; x = 3; y[0] = 4; return x + y[0] + foo(); }",
-        "int main() { int x; char y[10]; x = 3; y[0] = 4; if (x == y[0]) { y[1] = y[0]; } return 0; }"
+        "int main() { int x; char y[10]; x = 3; y[0] = 4; if (x == y[0]) { y[1] = y[0]; } return 0; }",
+        "int foo(int a); int main() { return foo(1); } int foo(int a) { return a; }",
+        "int main() { return bar(); } int bar() { return 2; }"
     );
 }
 
+#[test]
+fn test_ast_translation_unit_main_errors() {
+    let invalid_programs = vec![
+        "int foo() { return 0; }", // missing main
+        "int main() { return 0; } int main() { return 1; }", // main defined twice
+        "int foo() { return 0; } int foo() { return 1; } int main() { return 0; }", // foo defined twice
+    ];
+    for test_str in invalid_programs {
+        let pairs = CTinyParser::parse(Rule::translation_unit, test_str).unwrap();
+        let first_pair = pairs.into_iter().next().unwrap();
+        assert!(build_translation_unit(first_pair).is_err(), "Expected AST error for {}", test_str);
+    }
+}
 
-
-
+#[test]
+fn test_ast_program_chained_comparison_errors() {
+    let invalid_programs = vec![
+        "int main() { int x; x = 1 < 2 < 3; return x; }",
+        "int main() { int x; x = 0; if (1 < 2 < 3) { x = 1; } return x; }",
+        "int main() { int x; x = 0; while (x == 1 == 0) { x = 1; } return x; }",
+        "int foo(int a) { return 0 < a <= 10; } int main() { return foo(1); }",
+        "int main() { int x; x = 0; if (x == 0) { if (x < 1 > 0) { x = 1; } } return x; }",
+    ];
+    for test_str in invalid_programs {
+        match parse_content_into_ast(test_str, None) {
+            Err(SemanticError::ASTBuilding(error)) => {
+                let message = error.to_string();
+                print!("Expected error: {}\n", message);
+                assert!(message.contains("can't be chained"));
+            },
+            _ => panic!("Expected a chained comparison error for {}", test_str),
+        }
+    }
+}
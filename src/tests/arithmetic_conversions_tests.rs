@@ -0,0 +1,359 @@
+use crate::abstract_syntax_tree::nodes::{TypeSpecifier, Value};
+use crate::semantic::data_model::{IntWidth, set_int_width, get_long_width};
+use crate::semantic::type_casts::{get_common_arithmetic_type, get_promoted_type};
+use crate::semantic::warnings::take_warnings;
+use crate::tests::interpretation::expressions::interpret_expression_type_casts::interpret_expression_to_value_for_testing;
+use crate::tests::macros::interpret_program_for_testing;
+
+/// Set the int width of the test thread, interpret the expression,
+/// then check the resulting value and the number of warnings recorded.
+macro_rules! build_arithmetic_conversion_test {
+    ($test_name:ident, $int_width:expr, $test_str:expr, $test_value:expr, $nb_of_warnings:expr) => {
+        #[test]
+        fn $test_name() {
+            set_int_width($int_width);
+            take_warnings();
+            let interpreted_value = interpret_expression_to_value_for_testing($test_str)
+                .unwrap_or_else(|error| {
+                    panic!("Error interpreting expression <{}>: {}", $test_str, error);
+                });
+            assert_eq!(interpreted_value.data, $test_value);
+            assert_eq!(take_warnings().len(), $nb_of_warnings);
+        }
+    };
+    ($test_name:ident, $int_width:expr, $test_str:expr) => {
+        #[test]
+        fn $test_name() {
+            set_int_width($int_width);
+            let interpreted_value = interpret_expression_to_value_for_testing($test_str);
+            match interpreted_value {
+                Ok(value) => panic!(
+                    "Expected error for <{}>, got value {}", $test_str, value.data
+                ),
+                Err(error) => print!("Expected error for <{}>: {}\n", $test_str, error),
+            }
+        }
+    };
+}
+
+// data model
+#[test]
+fn test_long_width_follows_int_width() {
+    set_int_width(IntWidth::W16);
+    assert_eq!(get_long_width(), IntWidth::W32);
+    set_int_width(IntWidth::W32);
+    assert_eq!(get_long_width(), IntWidth::W64);
+    set_int_width(IntWidth::W64);
+    assert_eq!(get_long_width(), IntWidth::W64);
+}
+
+// integer promotions
+#[test]
+fn test_integer_promotions() {
+    set_int_width(IntWidth::W16);
+    assert_eq!(get_promoted_type(TypeSpecifier::Bool), TypeSpecifier::Int);
+    assert_eq!(get_promoted_type(TypeSpecifier::Char), TypeSpecifier::Int);
+    assert_eq!(get_promoted_type(TypeSpecifier::Short), TypeSpecifier::Int);
+    // a 16 bits int can't hold all unsigned short values
+    assert_eq!(get_promoted_type(TypeSpecifier::UnsignedShort), TypeSpecifier::UnsignedInt);
+    assert_eq!(get_promoted_type(TypeSpecifier::Long), TypeSpecifier::Long);
+    assert_eq!(get_promoted_type(TypeSpecifier::Float), TypeSpecifier::Float);
+
+    set_int_width(IntWidth::W32);
+    assert_eq!(get_promoted_type(TypeSpecifier::UnsignedShort), TypeSpecifier::Int);
+}
+
+// usual arithmetic conversions
+#[test]
+fn test_common_arithmetic_type() {
+    set_int_width(IntWidth::W16);
+    assert_eq!(get_common_arithmetic_type(TypeSpecifier::Char, TypeSpecifier::Short), TypeSpecifier::Int);
+    assert_eq!(get_common_arithmetic_type(TypeSpecifier::Int, TypeSpecifier::UnsignedInt), TypeSpecifier::UnsignedInt);
+    assert_eq!(get_common_arithmetic_type(TypeSpecifier::Long, TypeSpecifier::Float), TypeSpecifier::Float);
+    assert_eq!(get_common_arithmetic_type(TypeSpecifier::Float, TypeSpecifier::Double), TypeSpecifier::Double);
+    // a 32 bits long can hold all 16 bits unsigned int values
+    assert_eq!(get_common_arithmetic_type(TypeSpecifier::Long, TypeSpecifier::UnsignedInt), TypeSpecifier::Long);
+
+    set_int_width(IntWidth::W64);
+    // long and int have the same width
+    assert_eq!(get_common_arithmetic_type(TypeSpecifier::Long, TypeSpecifier::UnsignedInt), TypeSpecifier::UnsignedLong);
+}
+
+// literals
+build_arithmetic_conversion_test!(
+    test_unsigned_int_literal,
+    IntWidth::W16,
+    "65535u",
+    Value::UnsignedInt(65535),
+    0
+);
+
+build_arithmetic_conversion_test!(
+    test_unsigned_int_literal_overflow,
+    IntWidth::W16,
+    "65536u"
+);
+
+build_arithmetic_conversion_test!(
+    test_long_literal,
+    IntWidth::W16,
+    "2147483647L",
+    Value::Long(2147483647),
+    0
+);
+
+build_arithmetic_conversion_test!(
+    test_long_literal_min,
+    IntWidth::W16,
+    "-2147483648l",
+    Value::Long(-2147483648),
+    0
+);
+
+build_arithmetic_conversion_test!(
+    test_long_literal_overflow,
+    IntWidth::W16,
+    "2147483648l"
+);
+
+build_arithmetic_conversion_test!(
+    test_long_literal_64_bits,
+    IntWidth::W32,
+    "2147483648l",
+    Value::Long(2147483648),
+    0
+);
+
+build_arithmetic_conversion_test!(
+    test_unsigned_long_literal,
+    IntWidth::W16,
+    "4294967295ul",
+    Value::UnsignedLong(4294967295),
+    0
+);
+
+// operations
+build_arithmetic_conversion_test!(
+    test_short_addition_is_int,
+    IntWidth::W16,
+    "(short) 1 + (short) 2",
+    Value::Int(3),
+    0
+);
+
+build_arithmetic_conversion_test!(
+    test_signed_overflow_is_error,
+    IntWidth::W16,
+    "(short) 32767 + (short) 1"
+);
+
+build_arithmetic_conversion_test!(
+    test_unsigned_addition_wraps,
+    IntWidth::W16,
+    "65535u + 1u",
+    Value::UnsignedInt(0),
+    1
+);
+
+build_arithmetic_conversion_test!(
+    test_unsigned_subtraction_wraps,
+    IntWidth::W32,
+    "0u - 1u",
+    Value::UnsignedInt(4294967295),
+    1
+);
+
+build_arithmetic_conversion_test!(
+    test_unsigned_negation_wraps,
+    IntWidth::W16,
+    "-1u",
+    Value::UnsignedInt(65535),
+    1
+);
+
+build_arithmetic_conversion_test!(
+    test_int_and_long_addition_is_long,
+    IntWidth::W16,
+    "32767 + 1l",
+    Value::Long(32768),
+    0
+);
+
+build_arithmetic_conversion_test!(
+    test_long_multiplication_overflow,
+    IntWidth::W16,
+    "65536l * 65536l"
+);
+
+build_arithmetic_conversion_test!(
+    test_unsigned_short_multiplication_promoted,
+    IntWidth::W32,
+    "(unsigned short) 65535 * (unsigned short) 2",
+    Value::Int(131070),
+    0
+);
+
+build_arithmetic_conversion_test!(
+    test_double_addition,
+    IntWidth::W16,
+    "(double) 1 + 1.5",
+    Value::Double(2.5),
+    0
+);
+
+build_arithmetic_conversion_test!(
+    test_double_division_by_zero,
+    IntWidth::W16,
+    "(double) 1 / 0"
+);
+
+build_arithmetic_conversion_test!(
+    test_unsigned_modulo,
+    IntWidth::W16,
+    "65535u % 10",
+    Value::UnsignedInt(5),
+    0
+);
+
+// comparisons
+build_arithmetic_conversion_test!(
+    test_negative_int_less_than_unsigned,
+    IntWidth::W16,
+    "-1 < 1u",
+    Value::Bool(false), // -1 is converted to 65535u, like in C
+    1
+);
+
+build_arithmetic_conversion_test!(
+    test_negative_int_less_than_long,
+    IntWidth::W16,
+    "-1 < 1l",
+    Value::Bool(true),
+    0
+);
+
+// casts
+build_arithmetic_conversion_test!(
+    test_cast_to_unsigned_short_wraps,
+    IntWidth::W16,
+    "(unsigned short) 70000l",
+    Value::UnsignedShort(4464),
+    1
+);
+
+build_arithmetic_conversion_test!(
+    test_cast_negative_to_unsigned_wraps,
+    IntWidth::W16,
+    "(unsigned) (-1)",
+    Value::UnsignedInt(65535),
+    1
+);
+
+build_arithmetic_conversion_test!(
+    test_cast_to_short_overflow,
+    IntWidth::W32,
+    "(short) 40000"
+);
+
+build_arithmetic_conversion_test!(
+    test_cast_float_to_unsigned_negative,
+    IntWidth::W16,
+    "(unsigned int) (-1.5)"
+);
+
+build_arithmetic_conversion_test!(
+    test_cast_double_to_float,
+    IntWidth::W16,
+    "(float) (double) 2.5",
+    Value::Float(2.5),
+    0
+);
+
+build_arithmetic_conversion_test!(
+    test_cast_long_to_char,
+    IntWidth::W16,
+    "(char) 97l",
+    Value::Char(b'a'),
+    0
+);
+
+// programs
+#[test]
+fn test_program_unsigned_counter_wraps() {
+    set_int_width(IntWidth::W16);
+    take_warnings();
+    let test_str = "
+    int main() {
+        unsigned   short counter;
+        int i;
+        counter = 65534u;
+        i = 0;
+        while (i < 3) {
+            counter = counter + 1;
+            i = i + 1;
+        }
+        return counter;
+    }
+    ";
+    // the addition is done in unsigned int and wraps once, then the result fits in the unsigned short
+    assert_eq!(interpret_program_for_testing(test_str).unwrap(), Value::Int(1));
+    assert_eq!(take_warnings().len(), 1);
+}
+
+#[test]
+fn test_program_long_accumulator() {
+    set_int_width(IntWidth::W16);
+    let test_str = "
+    long sum(int n) {
+        long total;
+        int i;
+        total = 0;
+        i = 0;
+        while (i < n) {
+            total = total + 1000;
+            i = i + 1;
+        }
+        return total;
+    }
+
+    int main() {
+        long total;
+        total = sum(100);
+        return total / 1000;
+    }
+    ";
+    // 100 000 overflows a 16 bits int, but not a 32 bits long
+    assert_eq!(interpret_program_for_testing(test_str).unwrap(), Value::Int(100));
+}
+
+#[test]
+fn test_program_signed_short_overflow() {
+    set_int_width(IntWidth::W32);
+    let test_str = "
+    int main() {
+        short s;
+        s = 32767;
+        s = s + 1;
+        return s;
+    }
+    ";
+    // the addition is done in int without overflow, but the result doesn't fit back into the short
+    assert!(interpret_program_for_testing(test_str).is_err());
+}
+
+#[test]
+fn test_program_identifiers_starting_with_keywords() {
+    set_int_width(IntWidth::W16);
+    let test_str = "
+    int main() {
+        int shortest, integer, unsignedness;
+        double doubled;
+        shortest = 1;
+        integer = 2;
+        unsignedness = 3;
+        doubled = 1.5;
+        return shortest + integer + unsignedness + (int) (doubled * 2.0);
+    }
+    ";
+    assert_eq!(interpret_program_for_testing(test_str).unwrap(), Value::Int(9));
+}
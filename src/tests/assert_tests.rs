@@ -0,0 +1,192 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::abstract_syntax_tree::nodes::{Node, Value, Expression, Identifier};
+use crate::interpretation::hooks::{InterpretationHook, add_hook, clear_hooks};
+use crate::semantic::errors::SemanticError;
+use crate::symbol_table::structs::SymbolTable;
+use crate::build_program_test;
+use crate::tests::macros::interpret_program_for_testing;
+
+build_program_test!(
+    test_assert_passes,
+    "
+    int main() {
+        int x;
+        x = 2;
+        assert(x == 2);
+        assert(x);
+        assert(x > 1 && x < 3);
+        return x;
+    }
+    ",
+    Value::Int(2)
+);
+
+build_program_test!(
+    test_assert_fails,
+    "
+    int main() {
+        int x;
+        x = 2;
+        assert(x == 3);
+        return x;
+    }
+    ",
+    error SemanticError::AssertionFailed(_)
+);
+
+build_program_test!(
+    test_assert_zero_fails,
+    "
+    int main() {
+        float f;
+        f = 0.0;
+        assert(f);
+        return 0;
+    }
+    ",
+    error SemanticError::AssertionFailed(_)
+);
+
+build_program_test!(
+    test_assert_stops_interpretation,
+    "
+    int main() {
+        int x;
+        x = 1;
+        assert(x < 0);
+        x = 1 / 0;
+        return x;
+    }
+    ",
+    error SemanticError::AssertionFailed(_)
+);
+
+build_program_test!(
+    test_assert_in_called_function,
+    "
+    int checked_divide(int x, int y) {
+        assert(y != 0);
+        return x / y;
+    }
+
+    int main() {
+        return checked_divide(6, 0);
+    }
+    ",
+    error SemanticError::AssertionFailed(_)
+);
+
+build_program_test!(
+    test_assert_condition_error_is_reported,
+    "
+    int main() {
+        int x;
+        x = 1;
+        assert(x / 0);
+        return x;
+    }
+    ",
+    error SemanticError::DivisionByZero(_)
+);
+
+#[test]
+fn test_assert_failure_reports_variable_values() {
+    let test_str = "
+    struct point { int x; int y; };
+    enum color { RED, GREEN };
+
+    int main() {
+        int a[3];
+        int i;
+        struct point p;
+        enum color c;
+        a[0] = 1; a[1] = 2; a[2] = 3;
+        i = 1;
+        p.x = 4; p.y = 5;
+        c = GREEN;
+        assert(a[i] + p.x > a[i] * 10 || c == RED);
+        return 0;
+    }
+    ";
+    match interpret_program_for_testing(test_str) {
+        Err(SemanticError::AssertionFailed(error)) => {
+            let message = error.to_string();
+            print!("{}\n", message);
+            assert!(message.contains(
+                "Assertion failed: a[i] + p.x > a[i] * 10 || c == RED (with i = 1 (Int), a[i] = 2 (Int), p.x = 4 (Int), c = 1 (enum color))"
+            ));
+        },
+        other => panic!("Expected an assertion failure, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_assert_failure_does_not_call_functions_again() {
+    // the access with a call in its index is not evaluated again to report its value
+    let test_str = "
+    int next(int n) {
+        return n + 1;
+    }
+    int main() {
+        int a[3];
+        int i;
+        a[0] = 1; a[1] = 2; a[2] = 3;
+        i = 0;
+        assert(a[next(i)] == 1);
+        return 0;
+    }
+    ";
+    match interpret_program_for_testing(test_str) {
+        Err(SemanticError::AssertionFailed(error)) => {
+            let message = error.to_string();
+            print!("{}\n", message);
+            assert!(message.contains("Assertion failed: a[next(i)] == 1 (with i = 0 (Int))"));
+        },
+        other => panic!("Expected an assertion failure, got {:?}", other),
+    }
+}
+
+/// Count the expressions evaluated by the interpreter.
+struct ExpressionCounter(Rc<RefCell<usize>>);
+
+impl InterpretationHook for ExpressionCounter {
+    fn before_expression<'a>(
+        &mut self,
+        _expression_node: &Node<'a, Expression<'a>>,
+        _symbol_table: &SymbolTable,
+        _current_scope_node_id: &Node<'a, Identifier>,
+    ) -> Result<(), SemanticError> {
+        *self.0.borrow_mut() += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_assert_failure_does_not_evaluate_values_again() {
+    // the report reads the values without the interpretation hooks
+    let test_str = "
+    int main() {
+        int a[3];
+        int i;
+        i = 1;
+        a[i] = 2;
+        assert(a[i] == 3);
+        return 0;
+    }
+    ";
+    let expression_count = Rc::new(RefCell::new(0));
+    clear_hooks();
+    add_hook(Box::new(ExpressionCounter(expression_count.clone())));
+    let result = interpret_program_for_testing(test_str);
+    clear_hooks();
+    match result {
+        Err(SemanticError::AssertionFailed(error)) => {
+            assert!(error.to_string().contains("(with i = 1 (Int), a[i] = 2 (Int))"));
+        },
+        other => panic!("Expected an assertion failure, got {:?}", other),
+    }
+    // 1 for i = 1, 2 for a[i] = 2 and 4 for the condition of the assertion
+    assert_eq!(*expression_count.borrow(), 7);
+}
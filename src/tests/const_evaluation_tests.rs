@@ -0,0 +1,339 @@
+use crate::abstract_syntax_tree::nodes::{Identifier, Value};
+use crate::pipelines::parse_content_into_ast;
+use crate::semantic::data_model::{IntWidth, set_int_width};
+use crate::semantic::errors::SemanticError;
+use crate::symbol_table::build_static_symbol_table;
+use crate::symbol_table::structs::Variable;
+use crate::build_program_test;
+
+build_program_test!(
+    test_const_scalar,
+    "
+    int main() {
+        const int N = 3;
+        int x;
+        x = N + 1;
+        return x;
+    }
+    ",
+    Value::Int(4)
+);
+
+build_program_test!(
+    test_const_array_size_expression,
+    "
+    int main() {
+        const int N = 2;
+        int a[N * 2], i;
+        i = 0;
+        while (i < N * 2) {
+            a[i] = i;
+            i = i + 1;
+        }
+        return a[3];
+    }
+    ",
+    Value::Int(3)
+);
+
+build_program_test!(
+    test_const_followup_declaration_uses_previous_const,
+    "
+    int main() {
+        const int N = 2, M = N * 3;
+        return M;
+    }
+    ",
+    Value::Int(6)
+);
+
+build_program_test!(
+    test_const_value_is_cast_to_declared_type,
+    "
+    int main() {
+        const char C = 97;
+        const float HALF = 1 / 2.0;
+        return (int) C + (int) (HALF * 2.0);
+    }
+    ",
+    Value::Int(98)
+);
+
+build_program_test!(
+    test_const_array,
+    "
+    int main() {
+        const int N = 3;
+        const int primes[N] = {2, 3, N + 2};
+        return primes[0] * primes[1] * primes[2];
+    }
+    ",
+    Value::Int(30)
+);
+
+build_program_test!(
+    test_const_parameter_is_read_only,
+    "
+    int twice(const int x) {
+        return x * 2;
+    }
+
+    int main() {
+        int y;
+        y = twice(21);
+        return y;
+    }
+    ",
+    Value::Int(42)
+);
+
+// assignments to constants
+build_program_test!(
+    test_const_assignment_error,
+    "
+    int main() {
+        const int N = 3;
+        N = 4;
+        return N;
+    }
+    ",
+    error SemanticError::ConstAssignment(_)
+);
+
+build_program_test!(
+    test_const_array_element_assignment_error,
+    "
+    int main() {
+        const int a[2] = {1, 2};
+        int i;
+        i = 0;
+        while (i < 2) {
+            if (i == 1) {
+                a[i] = 0;
+            }
+            i = i + 1;
+        }
+        return a[1];
+    }
+    ",
+    error SemanticError::ConstAssignment(_)
+);
+
+build_program_test!(
+    test_const_parameter_assignment_error,
+    "
+    int f(const int x) {
+        x = x + 1;
+        return x;
+    }
+
+    int main() {
+        int y;
+        y = f(1);
+        return y;
+    }
+    ",
+    error SemanticError::ConstAssignment(_)
+);
+
+#[test]
+fn test_const_assignment_is_rejected_statically() {
+    // the function is never called, but the assignment is still rejected
+    let test_str = "
+    int never_called() {
+        const int N = 3;
+        N = 4;
+        return N;
+    }
+
+    int main() {
+        return 0;
+    }
+    ";
+    let ast = parse_content_into_ast(test_str, None).unwrap();
+    assert!(matches!(
+        build_static_symbol_table(&ast),
+        Err(SemanticError::ConstAssignment(_))
+    ));
+}
+
+// constant expressions
+build_program_test!(
+    test_const_expression_overflow,
+    "
+    int main() {
+        const int N = 200 * 200;
+        return 0;
+    }
+    ",
+    error SemanticError::IntOverflow(_)
+);
+
+build_program_test!(
+    test_const_array_size_overflow,
+    "
+    int main() {
+        const int N = 20000;
+        int a[N * 2];
+        return 0;
+    }
+    ",
+    error SemanticError::IntOverflow(_)
+);
+
+build_program_test!(
+    test_const_array_size_non_constant_variable,
+    "
+    int main() {
+        int n;
+        int a[n];
+        n = 1;
+        return n;
+    }
+    ",
+    error SemanticError::NonConstantExpression(_)
+);
+
+build_program_test!(
+    test_const_array_size_const_declared_after,
+    "
+    int main() {
+        int a[N];
+        const int N = 2;
+        return N;
+    }
+    ",
+    error SemanticError::NonConstantExpression(_)
+);
+
+build_program_test!(
+    test_const_initializer_function_call,
+    "
+    int one() {
+        return 1;
+    }
+
+    int main() {
+        const int N = one();
+        return N;
+    }
+    ",
+    error SemanticError::NonConstantExpression(_)
+);
+
+build_program_test!(
+    test_const_array_size_negative,
+    "
+    int main() {
+        const int N = 1;
+        int a[N - 2];
+        return 0;
+    }
+    ",
+    error SemanticError::InvalidArraySize(_)
+);
+
+build_program_test!(
+    test_const_array_size_float,
+    "
+    int main() {
+        int a[2.0];
+        return 0;
+    }
+    ",
+    error SemanticError::InvalidArraySize(_)
+);
+
+build_program_test!(
+    test_const_array_initializer_list_too_short,
+    "
+    int main() {
+        const int a[3] = {1, 2};
+        return a[1] * 10 + a[2];
+    }
+    ",
+    Value::Int(20)
+);
+
+build_program_test!(
+    test_const_array_initializer_list_too_long,
+    "
+    int main() {
+        const int a[2] = {1, 2, 3};
+        return 0;
+    }
+    ",
+    error SemanticError::InitializerListSize(_)
+);
+
+// declarations
+build_program_test!(
+    test_const_without_initializer,
+    "
+    int main() {
+        const int N;
+        return 0;
+    }
+    ",
+    error SemanticError::ASTBuilding(_)
+);
+
+build_program_test!(
+    test_non_const_with_initializer,
+    "
+    int main() {
+        int x = 1;
+        return x;
+    }
+    ",
+    error SemanticError::ASTBuilding(_)
+);
+
+build_program_test!(
+    test_const_array_with_scalar_initializer,
+    "
+    int main() {
+        const int a[2] = 1;
+        return 0;
+    }
+    ",
+    error SemanticError::ASTBuilding(_)
+);
+
+build_program_test!(
+    test_parameter_with_initializer,
+    "
+    int f(const int x = 1) {
+        return x;
+    }
+
+    int main() {
+        return 0;
+    }
+    ",
+    error SemanticError::ASTBuilding(_)
+);
+
+#[test]
+fn test_const_is_evaluated_in_symbol_table() {
+    set_int_width(IntWidth::W16);
+    let test_str = "
+    int main() {
+        const int N = 2 + 3;
+        int a[N * 2];
+        return N;
+    }
+    ";
+    let ast = parse_content_into_ast(test_str, None).unwrap();
+    let symbol_table = build_static_symbol_table(&ast).unwrap();
+    let main_scope = symbol_table.get_scope(&ast.data.main_function.data.name).unwrap();
+
+    match main_scope.get_variable_from_id(&Identifier {name: "N".to_string()}).unwrap() {
+        Variable::NormalVar(n_var) => assert_eq!(n_var.get_value(), Some(&Value::Int(5))),
+        other => panic!("Expected a normal variable, got {:?}", other),
+    }
+    match main_scope.get_variable_from_id(&Identifier {name: "a".to_string()}).unwrap() {
+        Variable::ArrayVar(a_var) => assert_eq!(a_var.size, 10),
+        other => panic!("Expected an array variable, got {:?}", other),
+    }
+}
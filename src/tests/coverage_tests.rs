@@ -0,0 +1,174 @@
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::coverage::{Coverage, CoverageCollector, BranchBlock, FunctionCoverage};
+use crate::interpretation::hooks::{add_hook, clear_hooks};
+use crate::interpretation::interpret_function::interpret_translation_unit;
+use crate::pipelines::parse_content_into_ast;
+use crate::preprocessing::preprocess_file;
+use crate::semantic::data_model::{IntWidth, set_int_width};
+use crate::semantic::overflow_policy::{OverflowPolicy, set_overflow_policy};
+use crate::symbol_table::build_static_symbol_table;
+
+/// Write the given files in a new directory, and return the directory.
+fn write_test_files(test_name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let directory = std::env::temp_dir().join(
+        format!("ctiny_coverage_{}_{}", std::process::id(), test_name)
+    );
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+    for (file_name, content) in files {
+        std::fs::write(directory.join(file_name), content).unwrap();
+    }
+    std::fs::canonicalize(directory).unwrap()
+}
+
+/// Interpret a program, adding its coverage to the given one.
+fn collect_coverage_for_testing(coverage: &Rc<RefCell<Coverage>>, program_path: &Path) {
+    set_int_width(IntWidth::W16);
+    set_overflow_policy(OverflowPolicy::Trap);
+    let source = preprocess_file(program_path).unwrap();
+    let ast = parse_content_into_ast(&source.content, None).unwrap();
+    let mut symbol_table = build_static_symbol_table(&ast).unwrap();
+
+    clear_hooks();
+    add_hook(Box::new(CoverageCollector::new(coverage.clone(), &ast, &source)));
+    interpret_translation_unit(&ast, &mut symbol_table).unwrap();
+    clear_hooks();
+}
+
+const HELPER_FILE: &str = "int clamp(int x) {
+    int r;
+    r = x;
+    if (x < 0 || x > 9) {
+        r = 0;
+    }
+    return r;
+}
+int unused(int x) {
+    return x;
+}
+";
+
+#[test]
+fn test_coverage_of_a_program() {
+    let directory = write_test_files("program", &[
+        ("helper.ctiny", HELPER_FILE),
+        ("program.ctiny", "#include \"helper.ctiny\"
+int main() {
+    int i;
+    int t;
+    i = 0;
+    t = 0;
+    while (i < 3) {
+        t = clamp(i);
+        i = i + 1;
+    }
+    return t;
+}"),
+    ]);
+    let coverage = Rc::new(RefCell::new(Coverage::default()));
+    collect_coverage_for_testing(&coverage, &directory.join("program.ctiny"));
+    let coverage = coverage.borrow();
+
+    let helper_coverage = &coverage.files[&directory.join("helper.ctiny")];
+    assert_eq!(helper_coverage.lines.iter().map(|(line, hits)| (*line, *hits)).collect::<Vec<_>>(), vec![
+        (3, 3), (4, 3), (5, 0), (7, 3), (10, 0),
+    ]);
+    assert_eq!(helper_coverage.functions["clamp"], FunctionCoverage { line: 1, calls: 3 });
+    assert_eq!(helper_coverage.functions["unused"], FunctionCoverage { line: 9, calls: 0 });
+    // `x < 0`, the whole condition and `x > 9` are always false
+    let branches: Vec<&BranchBlock> = helper_coverage.branches.values().collect();
+    assert_eq!(branches.len(), 3);
+    assert_eq!(*branches[0], BranchBlock { line: 4, evaluations: 3, true_count: 0, false_count: 3 });
+    assert_eq!(*branches[1], BranchBlock { line: 4, evaluations: 3, true_count: 0, false_count: 3 });
+    assert_eq!(*branches[2], BranchBlock { line: 4, evaluations: 3, true_count: 0, false_count: 3 });
+
+    let program_coverage = &coverage.files[&directory.join("program.ctiny")];
+    let while_block = program_coverage.branches.values().next().unwrap();
+    assert_eq!(*while_block, BranchBlock { line: 7, evaluations: 4, true_count: 3, false_count: 1 });
+}
+
+#[test]
+fn test_coverage_merged_across_programs() {
+    let directory = write_test_files("merged", &[
+        ("helper.ctiny", HELPER_FILE),
+        ("first.ctiny", "#include \"helper.ctiny\"
+int main() {
+    return clamp(-1);
+}"),
+        ("second.ctiny", "#include \"helper.ctiny\"
+int main() {
+    return clamp(4);
+}"),
+    ]);
+    let coverage = Rc::new(RefCell::new(Coverage::default()));
+    collect_coverage_for_testing(&coverage, &directory.join("first.ctiny"));
+    collect_coverage_for_testing(&coverage, &directory.join("second.ctiny"));
+    let coverage = coverage.borrow();
+    assert_eq!(coverage.files.len(), 3);
+
+    let helper_coverage = &coverage.files[&directory.join("helper.ctiny")];
+    assert_eq!(helper_coverage.functions["clamp"].calls, 2);
+    assert_eq!(helper_coverage.lines[&5], 1);
+    // the blocks are ordered by range: `x < 0`, the whole condition, then `x > 9`
+    let condition_block = helper_coverage.branches.values().nth(1).unwrap();
+    assert_eq!((condition_block.true_count, condition_block.false_count), (1, 1));
+
+    let mut lcov = Vec::new();
+    coverage.write_lcov(&mut lcov).unwrap();
+    let lcov = String::from_utf8(lcov).unwrap();
+    print!("LCOV:\n{}", lcov);
+    let helper_record = lcov.split("end_of_record\n")
+        .find(|record| record.contains("helper.ctiny"))
+        .unwrap();
+    assert_eq!(helper_record, format!("TN:
+SF:{}
+FN:1,clamp
+FN:9,unused
+FNDA:2,clamp
+FNDA:0,unused
+FNF:2
+FNH:1
+BRDA:4,0,0,1
+BRDA:4,0,1,1
+BRDA:4,1,0,1
+BRDA:4,1,1,1
+BRDA:4,2,0,0
+BRDA:4,2,1,2
+BRF:6
+BRH:5
+DA:3,2
+DA:4,2
+DA:5,1
+DA:7,2
+DA:10,0
+LF:5
+LH:4
+", directory.join("helper.ctiny").display()));
+}
+
+#[test]
+fn test_coverage_of_branches_never_reached() {
+    let directory = write_test_files("unreached", &[
+        ("program.ctiny", "int main() {
+    int a;
+    a = 0;
+    if (a > 0) {
+        if (a > 1) {
+            a = 2;
+        }
+    }
+    return a;
+}"),
+    ]);
+    let coverage = Rc::new(RefCell::new(Coverage::default()));
+    collect_coverage_for_testing(&coverage, &directory.join("program.ctiny"));
+
+    let mut lcov = Vec::new();
+    coverage.borrow().write_lcov(&mut lcov).unwrap();
+    let lcov = String::from_utf8(lcov).unwrap();
+    assert!(lcov.contains("BRDA:4,0,0,0\nBRDA:4,0,1,1\nBRDA:5,1,0,-\nBRDA:5,1,1,-\nBRF:4\nBRH:1\n"));
+    assert!(lcov.contains("DA:5,0\nDA:6,0\n"));
+}
@@ -0,0 +1,255 @@
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use serde_json::{json, Value as JsonValue};
+
+use crate::debugger::dap::run_dap_server;
+use crate::semantic::data_model::{IntWidth, set_int_width};
+use crate::semantic::overflow_policy::{OverflowPolicy, set_overflow_policy};
+use crate::tests::shared_output::SharedOutput;
+
+fn write_test_program(test_name: &str, content: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(
+        format!("ctiny_dap_{}_{}", std::process::id(), test_name)
+    );
+    std::fs::create_dir_all(&directory).unwrap();
+    let program_path = directory.join("program.ctiny");
+    std::fs::write(&program_path, content).unwrap();
+    std::fs::canonicalize(program_path).unwrap()
+}
+
+fn parse_messages(output: &str) -> Vec<JsonValue> {
+    let mut messages = Vec::new();
+    let mut rest = output;
+    while let Some(header_end) = rest.find("\r\n\r\n") {
+        let content_length: usize = rest[..header_end].trim_start_matches("Content-Length: ").parse().unwrap();
+        let content_start = header_end + 4;
+        messages.push(serde_json::from_str(&rest[content_start..content_start + content_length]).unwrap());
+        rest = &rest[content_start + content_length..];
+    }
+    messages
+}
+
+/// Run a DAP session for a program with a scripted transcript of requests,
+/// and get all the messages sent by the server.
+fn run_dap_transcript(test_name: &str, program: &str, requests: Vec<JsonValue>) -> (PathBuf, Vec<JsonValue>) {
+    set_int_width(IntWidth::W16);
+    set_overflow_policy(OverflowPolicy::Trap);
+    let program_path = write_test_program(test_name, program);
+    let mut input = String::new();
+    for (index, mut request) in requests.into_iter().enumerate() {
+        request["seq"] = json!(index + 1);
+        request["type"] = json!("request");
+        let request = request.to_string().replace("$PROGRAM", &program_path.to_string_lossy());
+        input.push_str(&format!("Content-Length: {}\r\n\r\n{}", request.len(), request));
+    }
+
+    let output = SharedOutput::new();
+    run_dap_server(Box::new(Cursor::new(input)), Box::new(output.clone()));
+    let output = output.get_text();
+    print!("DAP output:\n{}\n", output);
+    (program_path, parse_messages(&output))
+}
+
+fn launch_requests(stop_on_entry: bool, breakpoint_lines: &[usize]) -> Vec<JsonValue> {
+    let breakpoints: Vec<JsonValue> = breakpoint_lines.iter().map(|line| json!({ "line": line })).collect();
+    vec![
+        json!({ "command": "initialize", "arguments": { "adapterID": "ctiny" } }),
+        json!({ "command": "launch", "arguments": { "program": "$PROGRAM", "stopOnEntry": stop_on_entry } }),
+        json!({ "command": "setBreakpoints", "arguments": { "source": { "path": "$PROGRAM" }, "breakpoints": breakpoints } }),
+        json!({ "command": "configurationDone" }),
+    ]
+}
+
+fn get_events<'m>(messages: &'m [JsonValue], event: &str) -> Vec<&'m JsonValue> {
+    messages.iter().filter(|message| message["event"] == event).collect()
+}
+
+fn get_response<'m>(messages: &'m [JsonValue], command: &str) -> &'m JsonValue {
+    messages.iter()
+        .find(|message| message["type"] == "response" && message["command"] == command)
+        .unwrap_or_else(|| panic!("No response to {}", command))
+}
+
+// the lines of the statements: 3 in square, and 9, 10 and 11 in main
+const SQUARE_PROGRAM: &str = "int square(int x) {
+    int y;
+    y = x * x;
+    return y;
+}
+int main() {
+    int a;
+    int t[2];
+    a = 3;
+    t[0] = square(a);
+    t[1] = a;
+    return t[0];
+}";
+
+#[test]
+fn test_dap_breakpoint_stack_trace_and_variables() {
+    let mut requests = launch_requests(false, &[3]);
+    requests.extend([
+        json!({ "command": "threads" }),
+        json!({ "command": "stackTrace", "arguments": { "threadId": 1 } }),
+        json!({ "command": "scopes", "arguments": { "frameId": 1 } }),
+        // the scope of the frame gets the first reference of the pause
+        json!({ "command": "variables", "arguments": { "variablesReference": 1 } }),
+        json!({ "command": "continue", "arguments": { "threadId": 1 } }),
+        json!({ "command": "disconnect" }),
+    ]);
+    let (program_path, messages) = run_dap_transcript("breakpoint", SQUARE_PROGRAM, requests);
+
+    assert_eq!(get_events(&messages, "initialized").len(), 1);
+    assert_eq!(get_response(&messages, "setBreakpoints")["body"]["breakpoints"][0]["verified"], true);
+    let stopped_events = get_events(&messages, "stopped");
+    assert_eq!(stopped_events.len(), 1);
+    assert_eq!(stopped_events[0]["body"]["reason"], "breakpoint");
+
+    let frames = &get_response(&messages, "stackTrace")["body"]["stackFrames"];
+    assert_eq!(frames[0]["name"], "square");
+    assert_eq!(frames[0]["line"], 3);
+    assert_eq!(frames[0]["source"]["path"], program_path.to_string_lossy().as_ref());
+    assert_eq!(frames[1]["name"], "main");
+    assert_eq!(frames[1]["line"], 10);
+
+    let variables = &get_response(&messages, "variables")["body"]["variables"];
+    assert_eq!(variables[0]["name"], "x");
+    assert_eq!(variables[0]["value"], "3 (Int)");
+    assert_eq!(variables[1]["name"], "y");
+    assert_eq!(variables[1]["value"], "<unassigned>");
+
+    let outputs = get_events(&messages, "output");
+    assert_eq!(outputs.last().unwrap()["body"]["output"], "Program return value: 9 (Int)\n");
+    assert_eq!(get_events(&messages, "exited")[0]["body"]["exitCode"], 0);
+    assert_eq!(get_events(&messages, "terminated").len(), 1);
+}
+
+#[test]
+fn test_dap_stepping() {
+    let mut requests = launch_requests(true, &[]);
+    requests.extend([
+        json!({ "command": "next", "arguments": { "threadId": 1 } }),
+        json!({ "command": "stepIn", "arguments": { "threadId": 1 } }),
+        json!({ "command": "stackTrace", "arguments": { "threadId": 1 } }),
+        json!({ "command": "stepOut", "arguments": { "threadId": 1 } }),
+        json!({ "command": "continue", "arguments": { "threadId": 1 } }),
+        json!({ "command": "disconnect" }),
+    ]);
+    let (_, messages) = run_dap_transcript("stepping", SQUARE_PROGRAM, requests);
+
+    let reasons: Vec<&JsonValue> = get_events(&messages, "stopped").iter()
+        .map(|event| &event["body"]["reason"])
+        .collect();
+    assert_eq!(reasons, ["entry", "step", "step", "step"]);
+    let frames = &get_response(&messages, "stackTrace")["body"]["stackFrames"];
+    assert_eq!(frames[0]["name"], "square");
+    assert_eq!(get_events(&messages, "exited")[0]["body"]["exitCode"], 0);
+}
+
+#[test]
+fn test_dap_array_variables() {
+    let mut requests = launch_requests(false, &[11]);
+    requests.extend([
+        json!({ "command": "scopes", "arguments": { "frameId": 0 } }),
+        json!({ "command": "variables", "arguments": { "variablesReference": 1 } }),
+        // the array is the first variable that can be expanded
+        json!({ "command": "variables", "arguments": { "variablesReference": 2 } }),
+        json!({ "command": "evaluate", "arguments": { "expression": "t", "frameId": 0 } }),
+        json!({ "command": "continue", "arguments": { "threadId": 1 } }),
+        json!({ "command": "disconnect" }),
+    ]);
+    let (_, messages) = run_dap_transcript("array", SQUARE_PROGRAM, requests);
+
+    let variables_responses: Vec<&JsonValue> = messages.iter()
+        .filter(|message| message["command"] == "variables")
+        .collect();
+    let frame_variables = &variables_responses[0]["body"]["variables"];
+    assert_eq!(frame_variables[1]["name"], "t");
+    assert_eq!(frame_variables[1]["indexedVariables"], 2);
+    let array_elements = &variables_responses[1]["body"]["variables"];
+    assert_eq!(array_elements[0]["name"], "[0]");
+    assert_eq!(array_elements[0]["value"], "9 (Int)");
+    assert_eq!(array_elements[1]["value"], "<unassigned>");
+    assert_eq!(
+        get_response(&messages, "evaluate")["body"]["result"],
+        "int t[2] = {9 (Int), <unassigned>}"
+    );
+}
+
+#[test]
+fn test_dap_pauses_on_error() {
+    let program = "int main() {
+    int a;
+    a = 32767;
+    a = a + 1;
+    return a;
+}";
+    let mut requests = launch_requests(false, &[]);
+    requests.extend([
+        json!({ "command": "scopes", "arguments": { "frameId": 0 } }),
+        json!({ "command": "variables", "arguments": { "variablesReference": 1 } }),
+        json!({ "command": "continue", "arguments": { "threadId": 1 } }),
+        json!({ "command": "disconnect" }),
+    ]);
+    let (_, messages) = run_dap_transcript("error", program, requests);
+
+    let stopped_event = get_events(&messages, "stopped")[0];
+    assert_eq!(stopped_event["body"]["reason"], "exception");
+    assert!(stopped_event["body"]["text"].as_str().unwrap().contains("overflow"));
+    assert_eq!(get_response(&messages, "variables")["body"]["variables"][0]["value"], "32767 (Int)");
+    let outputs = get_events(&messages, "output");
+    assert_eq!(outputs.last().unwrap()["body"]["category"], "stderr");
+    assert_eq!(get_events(&messages, "exited")[0]["body"]["exitCode"], 1);
+}
+
+#[test]
+fn test_dap_disconnect_while_paused() {
+    let mut requests = launch_requests(true, &[]);
+    requests.push(json!({ "command": "disconnect" }));
+    let (_, messages) = run_dap_transcript("disconnect", SQUARE_PROGRAM, requests);
+
+    assert_eq!(get_events(&messages, "stopped").len(), 1);
+    assert_eq!(get_response(&messages, "disconnect")["success"], true);
+    assert_eq!(get_events(&messages, "exited")[0]["body"]["exitCode"], 1);
+    // the program has ended when the disconnection is answered
+    let kinds: Vec<&JsonValue> = messages.iter()
+        .rev()
+        .take(3)
+        .map(|message| message.get("event").unwrap_or(&message["command"]))
+        .collect();
+    assert_eq!(kinds, vec!["disconnect", "terminated", "exited"]);
+}
+
+#[test]
+fn test_dap_launch_of_a_missing_program() {
+    let requests = vec![
+        json!({ "command": "initialize", "arguments": { "adapterID": "ctiny" } }),
+        json!({ "command": "launch", "arguments": { "program": "$PROGRAM.missing" } }),
+        json!({ "command": "configurationDone" }),
+        json!({ "command": "disconnect" }),
+    ];
+    let (_, messages) = run_dap_transcript("missing_program", SQUARE_PROGRAM, requests);
+
+    let launch_response = get_response(&messages, "launch");
+    assert_eq!(launch_response["success"], false);
+    assert!(launch_response["message"].as_str().unwrap().contains("Cannot find the program"));
+    assert_eq!(get_response(&messages, "disconnect")["success"], true);
+    assert!(get_events(&messages, "exited").is_empty());
+}
+
+#[test]
+fn test_dap_requests_needing_a_paused_program() {
+    let requests = vec![
+        json!({ "command": "initialize", "arguments": { "adapterID": "ctiny" } }),
+        json!({ "command": "stackTrace", "arguments": { "threadId": 1 } }),
+        json!({ "command": "restartFrame", "arguments": { "frameId": 0 } }),
+        json!({ "command": "disconnect" }),
+    ];
+    let (_, messages) = run_dap_transcript("not_paused", SQUARE_PROGRAM, requests);
+
+    assert_eq!(get_response(&messages, "initialize")["body"]["supportsConfigurationDoneRequest"], true);
+    assert_eq!(get_response(&messages, "stackTrace")["success"], false);
+    assert_eq!(get_response(&messages, "restartFrame")["success"], false);
+    assert!(get_events(&messages, "exited").is_empty());
+}
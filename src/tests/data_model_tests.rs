@@ -0,0 +1,262 @@
+use pest::Span;
+
+use crate::abstract_syntax_tree::nodes::{Node, TypeSpecifier, Value};
+use crate::interpretation::interpret_function::interpret_translation_unit;
+use crate::pipelines::parse_content_into_ast;
+use crate::semantic::data_model::{IntWidth, set_int_width, get_int_width, get_integer_type_info};
+use crate::semantic::errors::SemanticError;
+use crate::semantic::overflow_checks::*;
+use crate::semantic::type_casts::cast_to_type;
+use crate::symbol_table::build_static_symbol_table;
+use crate::tests::interpretation::expressions::interpret_expression_type_casts::interpret_expression_to_value_for_testing;
+
+fn interpret_program_with_int_width(
+    test_str: &str,
+    int_width: IntWidth,
+) -> Result<Value, SemanticError> {
+    set_int_width(int_width);
+    let ast = parse_content_into_ast(test_str, None)?;
+    let mut symbol_table = build_static_symbol_table(&ast)?;
+    interpret_translation_unit(&ast, &mut symbol_table)
+        .map(|value_node| value_node.data)
+}
+
+/// Set the int width of the test thread, then interpret the expression
+macro_rules! build_int_width_expression_test {
+    ($test_name:ident, $int_width:expr, $test_str:expr, $test_value:expr) => {
+        #[test]
+        fn $test_name() {
+            set_int_width($int_width);
+            let interpreted_value = interpret_expression_to_value_for_testing($test_str)
+                .unwrap_or_else(|error| {
+                    panic!("Error interpreting expression <{}>: {}", $test_str, error);
+                });
+            assert_eq!(interpreted_value.data, Value::Int($test_value));
+        }
+    };
+    ($test_name:ident, $int_width:expr, $test_str:expr) => {
+        #[test]
+        fn $test_name() {
+            set_int_width($int_width);
+            let interpreted_value = interpret_expression_to_value_for_testing($test_str);
+            match interpreted_value {
+                Ok(value) => panic!(
+                    "Expected error for <{}>, got value {}", $test_str, value.data
+                ),
+                Err(error) => print!("Expected error for <{}>: {}\n", $test_str, error),
+            }
+        }
+    };
+}
+
+#[test]
+fn test_data_model_default_int_width() {
+    assert_eq!(get_int_width(), IntWidth::W16);
+}
+
+#[test]
+fn test_data_model_int_width_bounds() {
+    set_int_width(IntWidth::W16);
+    let int_info = get_integer_type_info(&TypeSpecifier::Int).unwrap();
+    assert_eq!(int_info.min_value(), i16::MIN as i128);
+    assert_eq!(int_info.max_value(), i16::MAX as i128);
+    assert!(!int_info.contains(32768));
+
+    set_int_width(IntWidth::W32);
+    let int_info = get_integer_type_info(&TypeSpecifier::Int).unwrap();
+    assert_eq!(int_info.min_value(), i32::MIN as i128);
+    assert_eq!(int_info.max_value(), i32::MAX as i128);
+    assert!(int_info.contains(32768));
+
+    set_int_width(IntWidth::W64);
+    let int_info = get_integer_type_info(&TypeSpecifier::Int).unwrap();
+    assert_eq!(int_info.min_value(), i64::MIN as i128);
+    assert_eq!(int_info.max_value(), i64::MAX as i128);
+}
+
+// literals
+build_int_width_expression_test!(
+    test_data_model_literal_16_bits_overflow,
+    IntWidth::W16,
+    "32768"
+);
+
+build_int_width_expression_test!(
+    test_data_model_literal_32_bits,
+    IntWidth::W32,
+    "32768",
+    32768
+);
+
+build_int_width_expression_test!(
+    test_data_model_literal_32_bits_max,
+    IntWidth::W32,
+    "2147483647",
+    i32::MAX as i64
+);
+
+build_int_width_expression_test!(
+    test_data_model_literal_32_bits_min,
+    IntWidth::W32,
+    "-2147483648",
+    i32::MIN as i64
+);
+
+build_int_width_expression_test!(
+    test_data_model_literal_32_bits_overflow,
+    IntWidth::W32,
+    "2147483648"
+);
+
+build_int_width_expression_test!(
+    test_data_model_literal_64_bits_max,
+    IntWidth::W64,
+    "9223372036854775807",
+    i64::MAX
+);
+
+build_int_width_expression_test!(
+    test_data_model_literal_64_bits_min,
+    IntWidth::W64,
+    "-9223372036854775808",
+    i64::MIN
+);
+
+build_int_width_expression_test!(
+    test_data_model_literal_64_bits_overflow,
+    IntWidth::W64,
+    "9223372036854775808"
+);
+
+// operations
+build_int_width_expression_test!(
+    test_data_model_addition_16_bits_overflow,
+    IntWidth::W16,
+    "32767 + 1"
+);
+
+build_int_width_expression_test!(
+    test_data_model_addition_32_bits,
+    IntWidth::W32,
+    "32767 + 1",
+    32768
+);
+
+build_int_width_expression_test!(
+    test_data_model_multiplication_32_bits_overflow,
+    IntWidth::W32,
+    "65536 * 32768"
+);
+
+build_int_width_expression_test!(
+    test_data_model_multiplication_64_bits,
+    IntWidth::W64,
+    "65536 * 32768",
+    2147483648
+);
+
+build_int_width_expression_test!(
+    test_data_model_negation_32_bits_overflow,
+    IntWidth::W32,
+    "-(-2147483648)"
+);
+
+build_int_width_expression_test!(
+    test_data_model_subtraction_64_bits_overflow,
+    IntWidth::W64,
+    "-9223372036854775807 - 2"
+);
+
+build_int_width_expression_test!(
+    test_data_model_division_32_bits_overflow,
+    IntWidth::W32,
+    "-2147483648 / -1"
+);
+
+build_int_width_expression_test!(
+    test_data_model_modulo_16_bits_overflow,
+    IntWidth::W16,
+    "-32768 % -1"
+);
+
+#[test]
+fn test_data_model_safe_int_add_bounds() {
+    let debug_str = "safe_integer_add";
+    let span = Span::new(debug_str, 0, debug_str.len()).unwrap();
+
+    set_int_width(IntWidth::W16);
+    assert!(safe_integer_add(i16::MAX as i128, 1, TypeSpecifier::Int, span).is_err());
+
+    set_int_width(IntWidth::W32);
+    assert_eq!(safe_integer_add(i16::MAX as i128, 1, TypeSpecifier::Int, span).unwrap(), 32768);
+    assert!(safe_integer_add(i32::MAX as i128, 1, TypeSpecifier::Int, span).is_err());
+
+    set_int_width(IntWidth::W64);
+    assert_eq!(safe_integer_add(i32::MAX as i128, 1, TypeSpecifier::Int, span).unwrap(), 2147483648);
+    assert!(safe_integer_add(i64::MAX as i128, 1, TypeSpecifier::Int, span).is_err());
+}
+
+// casts
+#[test]
+fn test_data_model_float_to_int_cast_bounds() {
+    let test_str = "(int) 40000.0";
+    let float_node = Node {
+        sp: Span::new(test_str, 0, test_str.len()).unwrap(),
+        data: Value::Float(40000.0),
+    };
+
+    set_int_width(IntWidth::W16);
+    assert!(cast_to_type(float_node.clone(), TypeSpecifier::Int).is_err());
+
+    set_int_width(IntWidth::W32);
+    assert_eq!(
+        cast_to_type(float_node, TypeSpecifier::Int).unwrap().data,
+        Value::Int(40000)
+    );
+}
+
+build_int_width_expression_test!(
+    test_data_model_float_to_int_cast_truncation_16_bits,
+    IntWidth::W16,
+    "(int) 32767.9",
+    32767
+);
+
+build_int_width_expression_test!(
+    test_data_model_float_to_int_cast_overflow_32_bits,
+    IntWidth::W32,
+    "(int) 3000000000.0"
+);
+
+build_int_width_expression_test!(
+    test_data_model_float_to_int_cast_64_bits,
+    IntWidth::W64,
+    "(int) 3000000000.0",
+    3000000000
+);
+
+// programs
+#[test]
+fn test_data_model_program_depends_on_int_width() {
+    let test_str = "
+    int square(int x) {
+        return x * x;
+    }
+
+    int main() {
+        int a;
+        a = square(200);
+        return a;
+    }
+    ";
+    // 200 * 200 = 40000 overflows a 16 bits int
+    assert!(interpret_program_with_int_width(test_str, IntWidth::W16).is_err());
+    assert_eq!(
+        interpret_program_with_int_width(test_str, IntWidth::W32).unwrap(),
+        Value::Int(40000)
+    );
+    assert_eq!(
+        interpret_program_with_int_width(test_str, IntWidth::W64).unwrap(),
+        Value::Int(40000)
+    );
+}
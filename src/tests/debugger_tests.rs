@@ -0,0 +1,227 @@
+use std::io::Cursor;
+
+use crate::abstract_syntax_tree::nodes::Value;
+use crate::debugger::Debugger;
+use crate::interpretation::hooks::{add_hook, clear_hooks};
+use crate::interpretation::interpret_function::interpret_translation_unit;
+use crate::interpretation::undo_log::is_undo_log_recording;
+use crate::pipelines::parse_content_into_ast;
+use crate::semantic::data_model::{IntWidth, set_int_width};
+use crate::semantic::errors::SemanticError;
+use crate::semantic::overflow_policy::{OverflowPolicy, set_overflow_policy};
+use crate::symbol_table::build_static_symbol_table;
+use crate::tests::shared_output::SharedOutput;
+
+/// Interpret a program under the debugger, with the given commands,
+/// and get the result of the program and the output of the debugger.
+fn debug_program_for_testing(test_str: &str, commands: &str) -> (Result<Value, SemanticError>, String) {
+    set_int_width(IntWidth::W16);
+    set_overflow_policy(OverflowPolicy::Trap);
+    let ast = parse_content_into_ast(test_str, None).unwrap();
+    let mut symbol_table = build_static_symbol_table(&ast).unwrap();
+
+    let output = SharedOutput::new();
+    clear_hooks();
+    add_hook(Box::new(Debugger::new(
+        Box::new(Cursor::new(commands.to_string())),
+        Box::new(output.clone()),
+        None,
+    )));
+    let result = interpret_translation_unit(&ast, &mut symbol_table)
+        .map(|value_node| value_node.data);
+    clear_hooks();
+
+    let output = output.get_text();
+    print!("Debugger output:\n{}\n", output);
+    (result, output)
+}
+
+// the lines of the statements: 3 in square, and 8 and 9 in main
+const SQUARE_PROGRAM: &str = "int square(int x) {
+    int y;
+    y = x * x;
+    return y;
+}
+int main() {
+    int a;
+    a = 3;
+    a = square(a);
+    return a;
+}";
+
+#[test]
+fn test_debugger_pauses_at_first_statement() {
+    let (result, output) = debug_program_for_testing(SQUARE_PROGRAM, "continue\n");
+    assert_eq!(result.unwrap(), Value::Int(9));
+    assert!(output.starts_with("Paused at line 8 in main: a = 3;\n"));
+    assert_eq!(output.matches("Paused").count(), 1);
+}
+
+#[test]
+fn test_debugger_breakpoint_and_backtrace() {
+    let (result, output) = debug_program_for_testing(
+        SQUARE_PROGRAM,
+        "break 3\ncontinue\nprint x\nbacktrace\ncontinue\n",
+    );
+    assert_eq!(result.unwrap(), Value::Int(9));
+    assert!(output.contains("Breakpoint set at line 3"));
+    assert!(output.contains("Paused at line 3 in square: y = x * x;"));
+    assert!(output.contains("int x = 3 (Int)"));
+    assert!(output.contains("#0 square at line 3\n#1 main at line 9\n"));
+}
+
+#[test]
+fn test_debugger_step_over() {
+    let (result, output) = debug_program_for_testing(
+        SQUARE_PROGRAM,
+        "next\nnext\nprint a\n",
+    );
+    assert_eq!(result.unwrap(), Value::Int(9));
+    assert!(output.contains("Paused at line 9 in main"));
+    assert!(!output.contains("in square"));
+}
+
+#[test]
+fn test_debugger_step_into_and_out() {
+    let (result, output) = debug_program_for_testing(
+        SQUARE_PROGRAM,
+        "next\nstep\nlocals\nout\n",
+    );
+    assert_eq!(result.unwrap(), Value::Int(9));
+    assert!(output.contains("Paused at line 3 in square"));
+    assert!(output.contains("int x = 3 (Int)\nint y = <unassigned>\n"));
+    assert_eq!(output.matches("Paused").count(), 3);
+}
+
+#[test]
+fn test_debugger_array_inspection() {
+    let (result, output) = debug_program_for_testing(
+        "int main() {
+    int t[3];
+    t[0] = 1;
+    t[1] = 2;
+    t[2] = 3;
+    return t[0];
+}",
+        "break 4\ncontinue\nprint t\ncontinue\n",
+    );
+    assert_eq!(result.unwrap(), Value::Int(1));
+    assert!(output.contains("int t[3] = {1 (Int), <unassigned>, <unassigned>}"));
+}
+
+#[test]
+fn test_debugger_stops_on_error() {
+    let (result, output) = debug_program_for_testing(
+        "int main() {
+    int a;
+    a = 32767;
+    a = a + 1;
+    return a;
+}",
+        "continue\nprint a\ncontinue\n",
+    );
+    assert!(matches!(result, Err(SemanticError::IntOverflow(_))));
+    assert!(output.contains("Stopped on error in main at line 4"));
+    assert!(output.contains("int a = 32767 (Int)"));
+}
+
+#[test]
+fn test_debugger_quit() {
+    let (result, _) = debug_program_for_testing(SQUARE_PROGRAM, "quit\n");
+    assert!(matches!(result, Err(SemanticError::InterpretationAborted(_))));
+}
+
+#[test]
+fn test_debugger_quit_in_function_called_from_index() {
+    let (result, output) = debug_program_for_testing("int f(int i) {
+    int j;
+    j = i + 1;
+    return j;
+}
+int main() {
+    int t[2];
+    int x;
+    t[0] = 0;
+    t[1] = 5;
+    x = t[f(0)];
+    return x;
+}", "break 3\ncontinue\nquit\n");
+    assert!(output.contains("Paused at line 3 in f: j = i + 1;"));
+    assert!(matches!(result, Err(SemanticError::InterpretationAborted(_))));
+}
+
+// the lines of the statements: 3 in square, and 10 to 15 in main
+const LOOP_PROGRAM: &str = "int square(int x) {
+    int y;
+    y = x * x;
+    return y;
+}
+int main() {
+    int i;
+    int s;
+    int t[3];
+    i = 0;
+    s = 0;
+    while (i < 3) {
+        t[i] = square(i);
+        s = s + t[i];
+        i = i + 1;
+    }
+    return s;
+}";
+
+#[test]
+fn test_debugger_reverse_step() {
+    let (result, output) = debug_program_for_testing(
+        LOOP_PROGRAM,
+        "break 15\ncontinue\ncontinue\nreverse-step\nlocals\nrs\nprint x\nbacktrace\nrs\nprint t\nstep\nstep\nstep\nprint s\ncontinue\n",
+    );
+    assert_eq!(result.unwrap(), Value::Int(5));
+    assert!(output.contains("Rewound to line 14 in main: s = s + t[i];"));
+    assert!(output.contains("int i = 1 (Int)\nint s = 0 (Int)\nint t[3] = {0 (Int), 1 (Int), <unassigned>}\n"));
+    assert!(output.contains("Rewound to line 3 in square: y = x * x;\n(ctiny-debug) int x = 1 (Int)\n"));
+    assert!(output.contains("#0 square at line 3\n#1 main at line 13\n"));
+    assert!(output.contains("int t[3] = {0 (Int), <unassigned>, <unassigned>}"));
+    // stepping forward goes back to the present, where the program is paused
+    assert!(output.contains("Back at line 15 in main: i = i + 1;\n(ctiny-debug) int s = 1 (Int)\n"));
+}
+
+#[test]
+fn test_debugger_reverse_to_last_write() {
+    let (result, output) = debug_program_for_testing(
+        LOOP_PROGRAM,
+        "break 15\ncontinue\ncontinue\nlast-write s\nlw t[1]\nlw s\nlw s\nlocals\nlw x\ncontinue\n",
+    );
+    assert_eq!(result.unwrap(), Value::Int(5));
+    assert!(output.contains("Rewound to line 14 in main: s = s + t[i];\ns written: 0 (Int) -> 1 (Int)\n"));
+    // the element is written by the call of the previous statement
+    assert!(output.contains("Rewound to line 13 in main: t[i] = square(i);\nt[1] written: <unassigned> -> 1 (Int)\n"));
+    assert!(output.contains("Rewound to line 14 in main: s = s + t[i];\ns written: 0 (Int) -> 0 (Int)\n"));
+    assert!(output.contains("Rewound to line 11 in main: s = 0;\ns written: <unassigned> -> 0 (Int)\n"));
+    assert!(output.contains("int i = 0 (Int)\nint s = <unassigned>\nint t[3] = {<unassigned>, <unassigned>, <unassigned>}\n"));
+    assert!(output.contains("No write of x in function main before this statement"));
+}
+
+#[test]
+fn test_debugger_reverse_from_error() {
+    let (result, output) = debug_program_for_testing(
+        "int main() {
+    int a;
+    int n;
+    a = 1;
+    n = 0;
+    while (n < 20) {
+        a = a * 2;
+        n = n + 1;
+    }
+    return a;
+}",
+        "continue\nlast-write a\nprint n\nlw n\ncontinue\n",
+    );
+    assert!(matches!(result, Err(SemanticError::IntOverflow(_))));
+    // the last successful doubling, before the one overflowing
+    assert!(output.contains("Rewound to line 7 in main: a = a * 2;\na written: 8192 (Int) -> 16384 (Int)\n"));
+    assert!(output.contains("int n = 13 (Int)"));
+    assert!(output.contains("Rewound to line 8 in main: n = n + 1;\nn written: 12 (Int) -> 13 (Int)\n"));
+    assert!(!is_undo_log_recording());
+}
@@ -0,0 +1,365 @@
+use crate::abstract_syntax_tree::nodes::Value;
+use crate::semantic::errors::SemanticError;
+use crate::build_program_test;
+
+build_program_test!(
+    test_enumerator_values,
+    "
+    enum color { RED, GREEN = 5, BLUE };
+
+    int main() {
+        return RED + GREEN * 10 + BLUE * 100;
+    }
+    ",
+    Value::Int(650)
+);
+
+build_program_test!(
+    test_enumerator_value_uses_previous_enumerators,
+    "
+    enum size { SMALL = 2, MEDIUM = SMALL * 2, LARGE = MEDIUM * 2 };
+
+    int main() {
+        return LARGE;
+    }
+    ",
+    Value::Int(8)
+);
+
+build_program_test!(
+    test_enum_variable,
+    "
+    enum color { RED, GREEN, BLUE };
+
+    int main() {
+        enum color c;
+        int x;
+        c = BLUE;
+        x = c;
+        return x;
+    }
+    ",
+    Value::Int(2)
+);
+
+build_program_test!(
+    test_enum_in_if_condition,
+    "
+    enum color { RED, GREEN, BLUE };
+
+    int main() {
+        enum color c;
+        int x;
+        c = GREEN;
+        if (c == GREEN) {
+            x = 1;
+        } else {
+            x = 2;
+        }
+        return x;
+    }
+    ",
+    Value::Int(1)
+);
+
+build_program_test!(
+    test_enum_parameter_and_return_value,
+    "
+    enum color { RED, GREEN, BLUE };
+
+    enum color next_color(enum color c) {
+        enum color next;
+        next = RED;
+        if (c == RED) {
+            next = GREEN;
+        }
+        if (c == GREEN) {
+            next = BLUE;
+        }
+        return next;
+    }
+
+    int main() {
+        return next_color(GREEN);
+    }
+    ",
+    Value::Int(2)
+);
+
+build_program_test!(
+    test_enum_struct_field,
+    "
+    enum color { RED, GREEN, BLUE };
+    struct pixel { enum color c; int x; };
+
+    int main() {
+        struct pixel p;
+        p.c = BLUE;
+        p.x = 3;
+        return p.c + p.x;
+    }
+    ",
+    Value::Int(5)
+);
+
+build_program_test!(
+    test_enumerator_in_constant_expression,
+    "
+    enum limits { SIZE = 3 };
+
+    int main() {
+        const int N = SIZE + 1;
+        int a[SIZE], i;
+        i = 0;
+        while (i < SIZE) {
+            a[i] = i * N;
+            i = i + 1;
+        }
+        return a[2];
+    }
+    ",
+    Value::Int(8)
+);
+
+build_program_test!(
+    test_int_assigned_to_enum_variable,
+    "
+    enum color { RED, GREEN, BLUE };
+
+    int main() {
+        enum color c;
+        c = 1;
+        return 0;
+    }
+    ",
+    error SemanticError::UnexpectedTypeCast(_)
+);
+
+build_program_test!(
+    test_enumerator_of_other_enum_assigned,
+    "
+    enum color { RED, GREEN, BLUE };
+    enum fruit { APPLE, BANANA };
+
+    int main() {
+        enum color c;
+        c = BANANA;
+        return 0;
+    }
+    ",
+    error SemanticError::UnexpectedTypeCast(_)
+);
+
+build_program_test!(
+    test_enums_of_different_types_compared,
+    "
+    enum color { RED, GREEN, BLUE };
+    enum fruit { APPLE, BANANA };
+
+    int main() {
+        int x;
+        x = 0;
+        if (RED == APPLE) {
+            x = 1;
+        }
+        return x;
+    }
+    ",
+    error SemanticError::InvalidOperandType(_)
+);
+
+build_program_test!(
+    test_enumerator_out_of_int_range,
+    "
+    enum big { LAST = 32767, OVER };
+
+    int main() {
+        return 0;
+    }
+    ",
+    error SemanticError::InvalidEnumeratorValue(_)
+);
+
+build_program_test!(
+    test_enumerator_float_value,
+    "
+    enum bad { HALF = 0.5 };
+
+    int main() {
+        return 0;
+    }
+    ",
+    error SemanticError::InvalidEnumeratorValue(_)
+);
+
+build_program_test!(
+    test_duplicate_enumerator,
+    "
+    enum color { RED, GREEN };
+    enum light { GREEN, ORANGE };
+
+    int main() {
+        return 0;
+    }
+    ",
+    error SemanticError::Redeclaration(_)
+);
+
+build_program_test!(
+    test_variable_named_as_enumerator,
+    "
+    enum color { RED, GREEN };
+
+    int main() {
+        int RED;
+        RED = 1;
+        return RED;
+    }
+    ",
+    error SemanticError::Redeclaration(_)
+);
+
+build_program_test!(
+    test_undeclared_enum_type,
+    "
+    int main() {
+        enum color c;
+        return 0;
+    }
+    ",
+    error SemanticError::UndeclaredEnum(_)
+);
+
+build_program_test!(
+    test_switch_on_enum,
+    "
+    enum color { RED, GREEN, BLUE };
+
+    int main() {
+        enum color c;
+        int x;
+        c = BLUE;
+        x = 0;
+        switch (c) {
+            case RED:
+                x = 1;
+            case GREEN:
+                x = 2;
+            case BLUE:
+                x = 3;
+        }
+        return x;
+    }
+    ",
+    Value::Int(3)
+);
+
+build_program_test!(
+    test_switch_default_case,
+    "
+    int main() {
+        int x, y;
+        x = 7;
+        switch (x) {
+            case 1:
+                y = 10;
+            default:
+                y = 20;
+        }
+        return y;
+    }
+    ",
+    Value::Int(20)
+);
+
+build_program_test!(
+    test_switch_no_fall_through,
+    "
+    int main() {
+        int x, y;
+        x = 1;
+        y = 0;
+        switch (x) {
+            case 1:
+                y = y + 1;
+            case 2:
+                y = y + 10;
+        }
+        return y;
+    }
+    ",
+    Value::Int(1)
+);
+
+build_program_test!(
+    test_switch_duplicate_case,
+    "
+    enum color { RED, GREEN = 0 };
+
+    int main() {
+        enum color c;
+        int x;
+        c = RED;
+        x = 0;
+        switch (c) {
+            case RED:
+                x = 1;
+            case GREEN:
+                x = 2;
+        }
+        return x;
+    }
+    ",
+    error SemanticError::InvalidCaseLabel(_)
+);
+
+build_program_test!(
+    test_switch_non_constant_case,
+    "
+    int main() {
+        int x, y;
+        x = 1;
+        y = 1;
+        switch (x) {
+            case y:
+                x = 2;
+        }
+        return x;
+    }
+    ",
+    error SemanticError::NonConstantExpression(_)
+);
+
+build_program_test!(
+    test_switch_case_of_other_enum,
+    "
+    enum color { RED, GREEN };
+    enum fruit { APPLE, BANANA };
+
+    int main() {
+        enum color c;
+        int x;
+        c = RED;
+        x = 0;
+        switch (c) {
+            case APPLE:
+                x = 1;
+        }
+        return x;
+    }
+    ",
+    error SemanticError::InvalidOperandType(_)
+);
+
+build_program_test!(
+    test_assignment_to_enumerator,
+    "
+    enum color { RED, GREEN };
+
+    int main() {
+        RED = 1;
+        return 0;
+    }
+    ",
+    error SemanticError::ConstAssignment(_)
+);
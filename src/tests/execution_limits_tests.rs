@@ -0,0 +1,200 @@
+use std::time::Duration;
+
+use crate::abstract_syntax_tree::nodes::Value;
+use crate::interpretation::execution_limits::{
+    ExecutionLimits, MAX_CALL_DEPTH, set_execution_limits, get_execution_limits, run_with_interpreter_stack
+};
+use crate::interpretation::interpret_function::interpret_translation_unit;
+use crate::pipelines::parse_content_into_ast;
+use crate::semantic::data_model::{IntWidth, set_int_width};
+use crate::semantic::errors::SemanticError;
+use crate::semantic::overflow_policy::{OverflowPolicy, set_overflow_policy};
+use crate::symbol_table::build_static_symbol_table;
+
+/// Set the execution limits of the test thread, and interpret a program.
+fn interpret_with_limits_for_testing(test_str: &str, execution_limits: ExecutionLimits) -> Result<Value, SemanticError> {
+    set_int_width(IntWidth::W16);
+    set_overflow_policy(OverflowPolicy::Trap);
+    set_execution_limits(execution_limits);
+    let ast = parse_content_into_ast(test_str, None).unwrap();
+    let mut symbol_table = build_static_symbol_table(&ast).unwrap();
+    interpret_translation_unit(&ast, &mut symbol_table)
+        .map(|value_node| value_node.data)
+}
+
+// 3 nested loops of 20 iterations: no loop is long, but the program runs 8000 times its inner body
+const NESTED_LOOPS_PROGRAM: &str = "int main() {
+    int i;
+    int j;
+    int k;
+    int n;
+    n = 0;
+    i = 0;
+    while (i < 20) {
+        j = 0;
+        while (j < 20) {
+            k = 0;
+            while (k < 20) {
+                n = n + 1;
+                k = k + 1;
+            }
+            j = j + 1;
+        }
+        i = i + 1;
+    }
+    return n;
+}";
+
+const RECURSION_PROGRAM: &str = "int f(int n) {
+    int r;
+    r = 0;
+    if (n > 0) {
+        r = f(n - 1);
+    }
+    return r;
+}
+int main() {
+    return f(10);
+}";
+
+#[test]
+fn test_execution_limits_default() {
+    assert_eq!(get_execution_limits(), ExecutionLimits::default());
+    assert_eq!(get_execution_limits().timeout, None);
+}
+
+#[test]
+fn test_step_limit_bounds_nested_loops() {
+    let small_budget = ExecutionLimits { max_steps: Some(10_000), ..ExecutionLimits::default() };
+    let result = interpret_with_limits_for_testing(NESTED_LOOPS_PROGRAM, small_budget);
+    assert!(matches!(result, Err(SemanticError::StepLimit(_))));
+
+    let result = interpret_with_limits_for_testing(NESTED_LOOPS_PROGRAM, ExecutionLimits::default());
+    assert_eq!(result.unwrap(), Value::Int(8000));
+}
+
+#[test]
+fn test_limits_reached_in_an_index() {
+    let index_loop = "int main() {
+    int a[2];
+    int i;
+    a[0] = 1;
+    a[1] = 0;
+    i = 0;
+    while (1 > 0) {
+        i = a[a[i] + 0];
+    }
+    return i;
+}";
+    // whatever the budget, some of them run out in an index expression
+    for max_steps in 1..80 {
+        let budget = ExecutionLimits { max_steps: Some(max_steps), ..ExecutionLimits::default() };
+        let result = interpret_with_limits_for_testing(index_loop, budget);
+        assert!(matches!(result, Err(SemanticError::StepLimit(_))), "{} steps: {:?}", max_steps, result);
+    }
+
+    let index_recursion = "int f(int n) {
+    int a[1];
+    a[0] = 0;
+    return a[f(n + 1)];
+}
+int main() {
+    return f(0);
+}";
+    let limits = ExecutionLimits { max_call_depth: Some(20), ..ExecutionLimits::default() };
+    let result = interpret_with_limits_for_testing(index_recursion, limits);
+    assert!(matches!(result, Err(SemanticError::StackOverflow(_))));
+}
+
+#[test]
+fn test_step_limit_is_reset_for_each_program() {
+    // a run of the program takes more than 100 steps, so the runs together exceed the budget
+    let budget = ExecutionLimits { max_steps: Some(300), ..ExecutionLimits::default() };
+    for _ in 0..10 {
+        let result = interpret_with_limits_for_testing(RECURSION_PROGRAM, budget);
+        assert_eq!(result.unwrap(), Value::Int(0));
+    }
+}
+
+#[test]
+fn test_max_call_depth() {
+    let shallow_limits = ExecutionLimits { max_call_depth: Some(5), ..ExecutionLimits::default() };
+    let result = interpret_with_limits_for_testing(RECURSION_PROGRAM, shallow_limits);
+    match result {
+        Err(SemanticError::StackOverflow(error)) => {
+            let message = error.to_string();
+            print!("Expected error: {}\n", message);
+            assert!(message.contains("when calling f"));
+        },
+        _ => panic!("Expected a stack overflow, got {:?}", result),
+    }
+
+    // `f(10)` makes 11 nested calls of `f`
+    let limits = ExecutionLimits { max_call_depth: Some(11), ..ExecutionLimits::default() };
+    assert_eq!(interpret_with_limits_for_testing(RECURSION_PROGRAM, limits).unwrap(), Value::Int(0));
+}
+
+// each call is nested in a loop, a condition and an expression, which takes more of the Rust stack
+const DEEP_RECURSION_PROGRAM: &str = "int f(int n) {
+    int r;
+    r = 0;
+    if (n > 0) {
+        while (r < 1) {
+            r = 1 + (1 * (f(n - 1) + 0));
+        }
+    }
+    return r;
+}
+int main() {
+    return f(N);
+}";
+
+#[test]
+fn test_max_call_depth_fits_in_the_interpreter_stack() {
+    // `f(N)` makes N + 1 nested calls of `f`
+    let program_at_max = DEEP_RECURSION_PROGRAM.replace("f(N)", &format!("f({})", MAX_CALL_DEPTH - 1));
+    let program_over_max = DEEP_RECURSION_PROGRAM.replace("f(N)", &format!("f({})", MAX_CALL_DEPTH));
+    let (result_at_max, result_over_max) = run_with_interpreter_stack(move || {
+        // no limit is the maximum call depth, and a larger limit is lowered to it
+        let unlimited = ExecutionLimits { max_steps: None, max_call_depth: None, timeout: None };
+        let too_deep = ExecutionLimits { max_call_depth: Some(MAX_CALL_DEPTH * 2), ..unlimited };
+        (
+            interpret_with_limits_for_testing(&program_at_max, unlimited),
+            interpret_with_limits_for_testing(&program_over_max, too_deep),
+        )
+    });
+    assert_eq!(result_at_max.unwrap(), Value::Int(MAX_CALL_DEPTH as i64 - 1));
+    assert!(matches!(result_over_max, Err(SemanticError::StackOverflow(_))));
+}
+
+#[test]
+fn test_recursion_without_end_is_a_stack_overflow() {
+    let result = interpret_with_limits_for_testing("int f(int n) {
+    int r;
+    r = f(n + 1);
+    return r;
+}
+int main() {
+    return f(0);
+}", ExecutionLimits { max_call_depth: Some(20), ..ExecutionLimits::default() });
+    assert!(matches!(result, Err(SemanticError::StackOverflow(_))));
+}
+
+#[test]
+fn test_timeout() {
+    let infinite_loop = "int main() {
+    int i;
+    i = 1;
+    while (i > 0) {
+        i = 2;
+    }
+    return i;
+}";
+    let limits = ExecutionLimits {
+        max_steps: None,
+        max_call_depth: None,
+        timeout: Some(Duration::from_millis(50)),
+    };
+    let result = interpret_with_limits_for_testing(infinite_loop, limits);
+    assert!(matches!(result, Err(SemanticError::Timeout(_))));
+}
@@ -0,0 +1,226 @@
+use std::path::PathBuf;
+
+use crate::formatting::{BraceStyle, FormatOptions, format_source, run_formatter};
+use crate::semantic::data_model::{IntWidth, set_int_width};
+use crate::semantic::errors::SemanticError;
+
+fn format_for_testing(content: &str, options: &FormatOptions) -> Result<String, SemanticError> {
+    set_int_width(IntWidth::W16);
+    format_source(content, options)
+}
+
+/// Format a program with the given options, check the result,
+/// and check that formatting it again doesn't change it.
+macro_rules! build_format_test {
+    ($test_name:ident, $options:expr, $content:expr, $expected:expr) => {
+        #[test]
+        fn $test_name() {
+            let options = $options;
+            let formatted = format_for_testing($content, &options).unwrap();
+            print!("Formatted:\n{}", formatted);
+            assert_eq!(formatted, $expected);
+            assert_eq!(format_for_testing(&formatted, &options).unwrap(), formatted);
+        }
+    };
+}
+
+build_format_test!(
+    test_format_indentation_and_spacing,
+    FormatOptions::default(),
+    "int f(int a){return (a+1)*2-(3-a);}
+int main(){
+  int t[3] = {1,2,3}, n;   char s[6]=\"a\\\"b\";
+  n=0;
+  while(n<3&&!(n==2)){ t[n]=t[n]*(int) (-t[0]); n=n+1; }
+  if((n<3)==(t[0]<0)){n=1;}else{n=2;}
+  switch(n){case 1: n=2; case 2: n=3; default: n=4;}
+  assert(strcmp(s,\"a\") != 0 || len(t) == 3);
+  return f(n) - - 1;
+}",
+    "int f(int a) {
+    return (a + 1) * 2 - (3 - a);
+}
+
+int main() {
+    int t[3] = {1, 2, 3}, n;
+    char s[6] = \"a\\\"b\";
+    n = 0;
+    while (n < 3 && !(n == 2)) {
+        t[n] = t[n] * (int) (-t[0]);
+        n = n + 1;
+    }
+    if ((n < 3) == (t[0] < 0)) {
+        n = 1;
+    } else {
+        n = 2;
+    }
+    switch (n) {
+        case 1:
+            n = 2;
+        case 2:
+            n = 3;
+        default:
+            n = 4;
+    }
+    assert(strcmp(s, \"a\") != 0 || len(t) == 3);
+    return f(n) - -1;
+}
+"
+);
+
+build_format_test!(
+    test_format_keeps_comments_and_directives,
+    FormatOptions::default(),
+    "// header comment
+#define SIZE 3
+
+struct point {int x;   int y; // the ordinate
+};
+enum color {RED,GREEN=5,BLUE};
+int f(int a);
+/* the
+   entry point */
+int main(){
+  int t[SIZE];
+
+
+  // first
+  t[0]=1;
+  if(t[0]>0){t[1]=2;}else{ /* nothing */ t[1]=3;}
+  return t[0]; // done
+}
+// end",
+    "// header comment
+#define SIZE 3
+
+struct point {
+    int x;
+    int y; // the ordinate
+};
+
+enum color {
+    RED,
+    GREEN = 5,
+    BLUE
+};
+
+int f(int a);
+
+/* the
+   entry point */
+int main() {
+    int t[SIZE];
+
+    // first
+    t[0] = 1;
+    if (t[0] > 0) {
+        t[1] = 2;
+    } else {
+        /* nothing */
+        t[1] = 3;
+    }
+    return t[0]; // done
+}
+// end
+"
+);
+
+build_format_test!(
+    test_format_next_line_braces,
+    FormatOptions { indent_width: 2, brace_style: BraceStyle::NextLine, one_declaration_per_line: false },
+    "struct point { int x; };
+int main() {
+    int a;
+    a = 1;
+    if (a > 0) { a = 2; } else { a = 3; }
+    return a;
+}",
+    "struct point
+{
+  int x;
+};
+
+int main()
+{
+  int a;
+  a = 1;
+  if (a > 0)
+  {
+    a = 2;
+  }
+  else
+  {
+    a = 3;
+  }
+  return a;
+}
+"
+);
+
+build_format_test!(
+    test_format_one_declaration_per_line,
+    FormatOptions { one_declaration_per_line: true, ..FormatOptions::default() },
+    "int main() {
+    const int a = 1, b = 2; // constants
+    int t[2] = {a, b}, i;
+    return t[0];
+}",
+    "int main() {
+    const int a = 1;
+    const int b = 2; // constants
+    int t[2] = {a, b};
+    int i;
+    return t[0];
+}
+"
+);
+
+build_format_test!(
+    test_format_parentheses_follow_precedence,
+    FormatOptions::default(),
+    "int main() {
+    int a;
+    a = ((1 - (2 - 3)) - 4) * -(5 + 6) / (7 % 2);
+    a = (a < 2) == (3 > a) || (a != 1 && (a == 2 || a == 3));
+    return (long) (a * 2.50) + (char) 'b' + sizeof(unsigned int) + 10u;
+}",
+    "int main() {
+    int a;
+    a = (1 - (2 - 3) - 4) * -(5 + 6) / (7 % 2);
+    a = (a < 2) == (3 > a) || a != 1 && (a == 2 || a == 3);
+    return (long) (a * 2.50) + (char) 'b' + sizeof(unsigned int) + 10u;
+}
+"
+);
+
+#[test]
+fn test_format_invalid_program() {
+    let result = format_for_testing("int main() { int a return a; }", &FormatOptions::default());
+    assert!(matches!(result, Err(SemanticError::SyntaxParsing(_))));
+}
+
+fn write_test_file(test_name: &str, content: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(
+        format!("ctiny_formatting_{}_{}", std::process::id(), test_name)
+    );
+    std::fs::create_dir_all(&directory).unwrap();
+    let file_path = directory.join("program.ctiny");
+    std::fs::write(&file_path, content).unwrap();
+    file_path
+}
+
+#[test]
+fn test_formatter_check_mode() {
+    set_int_width(IntWidth::W16);
+    let unformatted = "int main(){return 0;}";
+    let file_path = write_test_file("check", unformatted);
+    let files = vec![file_path.clone()];
+
+    // checking leaves the file as it is
+    assert!(!run_formatter(&files, true, &FormatOptions::default()));
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), unformatted);
+
+    assert!(run_formatter(&files, false, &FormatOptions::default()));
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "int main() {\n    return 0;\n}\n");
+    assert!(run_formatter(&files, true, &FormatOptions::default()));
+}
@@ -0,0 +1,191 @@
+use crate::abstract_syntax_tree::nodes::Value;
+use crate::semantic::errors::SemanticError;
+use crate::build_program_test;
+
+build_program_test!(
+    test_array_initializer_list,
+    "
+    int main() {
+        int a[3] = {1, 2, 3};
+        return a[0] * 100 + a[1] * 10 + a[2];
+    }
+    ",
+    Value::Int(123)
+);
+
+build_program_test!(
+    test_array_partial_initializer_list,
+    "
+    int main() {
+        int a[5] = {1, 2, 3};
+        return a[2] * 10 + a[3] + a[4];
+    }
+    ",
+    Value::Int(30)
+);
+
+build_program_test!(
+    test_array_zero_initializer,
+    "
+    int main() {
+        float a[4] = {0};
+        char c[2] = {0};
+        return a[3] + c[1];
+    }
+    ",
+    Value::Int(0)
+);
+
+build_program_test!(
+    test_array_initializer_with_expressions,
+    "
+    int square(int x) {
+        return x * x;
+    }
+
+    int sum_of_elements(int n) {
+        int a[3] = {n, square(n), n + 1};
+        return a[0] + a[1] + a[2];
+    }
+
+    int main() {
+        return sum_of_elements(3);
+    }
+    ",
+    Value::Int(16)
+);
+
+build_program_test!(
+    test_array_initializer_is_evaluated_at_each_call,
+    "
+    int first_plus_last(int x) {
+        int a[3] = {x};
+        a[2] = a[0] + a[2];
+        return a[2];
+    }
+
+    int main() {
+        return first_plus_last(1) * 10 + first_plus_last(2);
+    }
+    ",
+    Value::Int(12)
+);
+
+build_program_test!(
+    test_array_initializer_casts_elements,
+    "
+    int main() {
+        double a[2] = {1, 'a'};
+        int b[2] = {2.5, 3.9};
+        return a[0] + a[1] + b[0] + b[1];
+    }
+    ",
+    Value::Int(103)
+);
+
+build_program_test!(
+    test_array_initializer_element_overflow,
+    "
+    int main() {
+        char a[3] = {1, 300, 2};
+        return 0;
+    }
+    ",
+    error SemanticError::IntToCharCastOverflow(_)
+);
+
+build_program_test!(
+    test_array_initializer_too_many_elements,
+    "
+    int main() {
+        int a[2] = {1, 2, 3};
+        return 0;
+    }
+    ",
+    error SemanticError::InitializerListSize(_)
+);
+
+build_program_test!(
+    test_array_initializer_too_many_elements_in_uncalled_function,
+    "
+    int f() {
+        int a[1] = {1, 2};
+        return a[0];
+    }
+
+    int main() {
+        return 0;
+    }
+    ",
+    error SemanticError::InitializerListSize(_)
+);
+
+build_program_test!(
+    test_struct_array_zero_initializer,
+    "
+    struct point { int x; int y; };
+    struct segment { struct point start; struct point end; };
+
+    int main() {
+        struct segment s[2] = {0};
+        s[1].end.x = 4;
+        return s[0].start.y + s[1].end.x + s[1].end.y;
+    }
+    ",
+    Value::Int(4)
+);
+
+build_program_test!(
+    test_struct_array_partial_initializer_list,
+    "
+    struct point { int x; int y; };
+
+    int first_y_and_last_x(struct point p) {
+        struct point points[3] = {p};
+        return points[0].y * 10 + points[2].x;
+    }
+
+    int main() {
+        struct point p;
+        p.x = 1;
+        p.y = 2;
+        return first_y_and_last_x(p);
+    }
+    ",
+    Value::Int(20)
+);
+
+build_program_test!(
+    test_enum_array_partial_initializer_list,
+    "
+    enum color { RED, GREEN, BLUE };
+
+    int main() {
+        enum color colors[3] = {BLUE};
+        return colors[0] * 10 + colors[1];
+    }
+    ",
+    Value::Int(20)
+);
+
+build_program_test!(
+    test_scalar_with_initializer_list,
+    "
+    int main() {
+        int x = {1};
+        return 0;
+    }
+    ",
+    error SemanticError::ASTBuilding(_)
+);
+
+build_program_test!(
+    test_array_with_scalar_initializer,
+    "
+    int main() {
+        int a[2] = 1;
+        return 0;
+    }
+    ",
+    error SemanticError::ASTBuilding(_)
+);
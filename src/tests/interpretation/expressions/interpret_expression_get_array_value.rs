@@ -92,7 +92,7 @@ fn interpret_expression_get_value_array_var_for_testing<'a>(
         },
         Node {
             sp: test_str_span.clone(),
-            data: Value::Int(get_index as i16),
+            data: Value::Int(get_index as i64),
         },
         Node {
             sp: test_str_span.clone(),
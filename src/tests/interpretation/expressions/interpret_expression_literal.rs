@@ -71,7 +71,7 @@ fn test_interpret_expression_literal() {
         });
     let main_id_node = &ast.data.main_function.data.name;
 
-    let mut symbol_table = build_static_symbol_table(&ast);
+    let mut symbol_table = build_static_symbol_table(&ast).unwrap();
     let literal_1_statement_node = &ast.data.main_function.data.body.data.statements[0];
     let literal_1_statement = &literal_1_statement_node.data;
 
@@ -113,7 +113,7 @@ fn test_interpret_expression_literal() {
 fn test_interpret_expression_literal_int() {
     // this test is a simpler version of the preceding test
     let test_str = "1";
-    let test_value: i16 = 1;
+    let test_value: i64 = 1;
     let rule = Rule::expression;
 
     // Syntax parsing
@@ -84,11 +84,12 @@ build_interpret_expression_to_value_test!(
     Float
 );
 
+// like in C, chars and bools are promoted to int before negation
 build_interpret_expression_to_value_test!(
     test_interpret_expression_unary_negation_from_char,
     "-'a'",
-    b'a',
-    Char
+    -97,
+    Int
 );
 
 // WARN: cannot test "-'\0'", since the grammar does not allow it
@@ -96,13 +97,50 @@ build_interpret_expression_to_value_test!(
 build_interpret_expression_to_value_test!(
     test_interpret_expression_unary_negation_from_bool,
     "-true",
-    true,
-    Bool
+    -1,
+    Int
 );
 
 build_interpret_expression_to_value_test!(
     test_interpret_expression_unary_negation_from_bool_false,
     "-false",
-    false,
-    Bool
-);
\ No newline at end of file
+    0,
+    Int
+);
+
+// negative literals and negation overflow
+build_interpret_expression_to_value_test!(
+    test_interpret_expression_unary_negation_int_min_literal,
+    "-32768",
+    i16::MIN as i64,
+    Int
+);
+
+build_interpret_expression_to_value_test!(
+    test_interpret_expression_unary_negation_int_min_plus_one_literal,
+    "-32767",
+    -32767,
+    Int
+);
+
+build_interpret_expression_to_value_test!(
+    test_interpret_expression_unary_negation_int_min_in_operation,
+    "-32768 + 1",
+    -32767,
+    Int
+);
+
+build_interpret_expression_to_value_test!(
+    test_interpret_expression_unary_negation_int_min_overflow,
+    "-(-32768)"
+);
+
+build_interpret_expression_to_value_test!(
+    test_interpret_expression_unary_negation_int_min_cast_overflow,
+    "-(int) (-32768.0)"
+);
+
+build_interpret_expression_to_value_test!(
+    test_interpret_expression_unary_negation_parenthesized_literal_overflow,
+    "-(32768)"
+);
@@ -1,5 +1,5 @@
 #[cfg(test)]
-mod interpret_expression_type_casts;
+pub mod interpret_expression_type_casts;
 #[cfg(test)]
 mod interpret_expression_get_normal_value;
 #[cfg(test)]
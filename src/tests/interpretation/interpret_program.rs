@@ -48,7 +48,7 @@ fn interpret_program_to_value_for_testing<'a>(
     print!("AST for string \"{}\": \n {:#?} \n\n", test_str, ast);
 
     // build symbol table
-    let mut symbol_table = build_static_symbol_table(&ast);
+    let mut symbol_table = build_static_symbol_table(&ast)?;
 
     // interpretation
     interpret_translation_unit(
@@ -1,5 +1,5 @@
 #[cfg(test)]
-mod expressions;
+pub mod expressions;
 #[cfg(test)]
 mod statements;
 #[cfg(test)]
@@ -8,6 +8,7 @@ mod interpret_declaration;
 mod interpret_program;
 
 use std::collections::HashMap;
+use indexmap::IndexMap;
 use pest::Span;
 
 use crate::abstract_syntax_tree::nodes::{Identifier, Node, TranslationUnit, TypeSpecifier, Value, Expression, Block, Function};
@@ -42,7 +43,10 @@ pub fn create_pseudo_translation_unit() -> TranslationUnit<'static> {
         data: Identifier {name: "main".to_string()},
     };
     let pseudo_translation_unit = TranslationUnit {
-        functions: None,
+        structs: None,
+        enums: None,
+        prototypes: None,
+        functions: IndexMap::new(),
         main_function: Node {
             sp: pseudo_span,
             data: Function {
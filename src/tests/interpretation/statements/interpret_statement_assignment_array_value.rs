@@ -123,7 +123,7 @@ macro_rules! build_test_assignment_to_array_value {
 
             let result = interpret_statement_assignment_array_var(
                 test_str,
-                var_type,
+                var_type.clone(),
             );
 
             match result {
@@ -101,7 +101,7 @@ macro_rules! build_test_assignment_to_normal_value {
 
             let result = interpret_statement_assignment_normal_var(
                 test_str,
-                var_type,
+                var_type.clone(),
             );
 
             match result {
@@ -0,0 +1,284 @@
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use serde_json::{json, Value as JsonValue};
+
+use crate::language_server::{path_to_uri, run_language_server};
+use crate::tests::shared_output::SharedOutput;
+
+fn get_test_directory(test_name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(
+        format!("ctiny_lsp_{}_{}", std::process::id(), test_name)
+    );
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::canonicalize(directory).unwrap()
+}
+
+fn parse_messages(output: &str) -> Vec<JsonValue> {
+    let mut messages = Vec::new();
+    let mut rest = output;
+    while let Some(header_end) = rest.find("\r\n\r\n") {
+        let content_length: usize = rest[..header_end].trim_start_matches("Content-Length: ").parse().unwrap();
+        let content_start = header_end + 4;
+        messages.push(serde_json::from_str(&rest[content_start..content_start + content_length]).unwrap());
+        rest = &rest[content_start + content_length..];
+    }
+    messages
+}
+
+/// Run an LSP session with a scripted transcript of messages, the ones with an id being requests,
+/// and get all the messages sent by the server.
+fn run_lsp_transcript(messages: Vec<JsonValue>) -> Vec<JsonValue> {
+    let mut input = String::new();
+    for mut message in messages {
+        message["jsonrpc"] = json!("2.0");
+        let message = message.to_string();
+        input.push_str(&format!("Content-Length: {}\r\n\r\n{}", message.len(), message));
+    }
+
+    let output = SharedOutput::new();
+    run_language_server(Box::new(Cursor::new(input)), Box::new(output.clone()));
+    let output = output.get_text();
+    print!("LSP output:\n{}\n", output);
+    parse_messages(&output)
+}
+
+fn did_open(uri: &str, text: &str) -> JsonValue {
+    json!({
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": { "uri": uri, "languageId": "ctiny", "version": 1, "text": text } },
+    })
+}
+
+fn did_change(uri: &str, text: &str) -> JsonValue {
+    json!({
+        "method": "textDocument/didChange",
+        "params": { "textDocument": { "uri": uri, "version": 2 }, "contentChanges": [{ "text": text }] },
+    })
+}
+
+fn position_request(id: i64, method: &str, uri: &str, line: usize, character: usize) -> JsonValue {
+    json!({
+        "id": id,
+        "method": method,
+        "params": { "textDocument": { "uri": uri }, "position": { "line": line, "character": character } },
+    })
+}
+
+fn get_result(messages: &[JsonValue], id: i64) -> &JsonValue {
+    &messages.iter()
+        .find(|message| message["id"] == id)
+        .unwrap_or_else(|| panic!("No response to request {}", id))["result"]
+}
+
+fn get_diagnostics(messages: &[JsonValue]) -> Vec<&JsonValue> {
+    messages.iter()
+        .filter(|message| message["method"] == "textDocument/publishDiagnostics")
+        .map(|message| &message["params"]["diagnostics"])
+        .collect()
+}
+
+fn get_range_start(location: &JsonValue) -> (u64, u64) {
+    let start = &location["range"]["start"];
+    (start["line"].as_u64().unwrap(), start["character"].as_u64().unwrap())
+}
+
+const POINT_PROGRAM: &str = "struct point { int x; int y; };
+enum color { RED, GREEN };
+int square(int n) {
+    return n * n;
+}
+int main() {
+    struct point p;
+    int total;
+    p.x = square(3);
+    total = p.x + GREEN;
+    return total;
+}";
+
+#[test]
+fn test_lsp_initialize_and_shutdown() {
+    let messages = run_lsp_transcript(vec![
+        json!({ "id": 1, "method": "initialize", "params": { "capabilities": {} } }),
+        json!({ "method": "initialized", "params": {} }),
+        json!({ "id": 2, "method": "textDocument/formatting", "params": {} }),
+        json!({ "id": 3, "method": "shutdown" }),
+        json!({ "method": "exit" }),
+        // never read, the server has exited
+        json!({ "id": 4, "method": "shutdown" }),
+    ]);
+
+    let capabilities = &get_result(&messages, 1)["capabilities"];
+    assert_eq!(capabilities["textDocumentSync"], 1);
+    assert_eq!(capabilities["hoverProvider"], true);
+    assert_eq!(capabilities["completionProvider"]["triggerCharacters"][0], ".");
+    let unsupported = messages.iter().find(|message| message["id"] == 2).unwrap();
+    assert_eq!(unsupported["error"]["code"], -32601);
+    assert_eq!(get_result(&messages, 3), &JsonValue::Null);
+    assert_eq!(messages.len(), 3);
+}
+
+#[test]
+fn test_lsp_diagnostics() {
+    let uri = path_to_uri(&get_test_directory("diagnostics").join("program.ctiny"));
+    let messages = run_lsp_transcript(vec![
+        did_open(&uri, "int main() {\n    int a;\n    a = 1 +;\n    return a;\n}"),
+        did_change(&uri, "int main() {\n    struct nope n;\n    return 0;\n}"),
+        did_change(&uri, "int main() {\n    int a;\n    a = 1;\n    return a;\n}"),
+        json!({ "method": "textDocument/didClose", "params": { "textDocument": { "uri": uri } } }),
+    ]);
+
+    let diagnostics = get_diagnostics(&messages);
+    assert_eq!(diagnostics.len(), 4);
+    // syntax error
+    assert_eq!(diagnostics[0].as_array().unwrap().len(), 1);
+    assert_eq!(get_range_start(&diagnostics[0][0]).0, 2);
+    assert_eq!(diagnostics[0][0]["severity"], 1);
+    // static semantic error
+    assert_eq!(get_range_start(&diagnostics[1][0]), (1, 16));
+    assert!(diagnostics[1][0]["message"].as_str().unwrap().contains("nope"));
+    // fixed, then closed
+    assert!(diagnostics[2].as_array().unwrap().is_empty());
+    assert!(diagnostics[3].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_lsp_diagnostics_of_ast_building_errors() {
+    let uri = path_to_uri(&get_test_directory("ast_building").join("program.ctiny"));
+    let messages = run_lsp_transcript(vec![
+        did_open(&uri, POINT_PROGRAM),
+        did_change(&uri, "int main() {\n    int x;\n    x = 99999999999;\n    return x;\n}"),
+        did_change(&uri, "int main() {\n    int x;\n    x = 0;\n    if (1 < 2 < 3) {\n        x = 1;\n    }\n    return x;\n}"),
+        // the server keeps answering after the errors
+        position_request(1, "textDocument/hover", &uri, 10, 12),
+        json!({ "id": 2, "method": "shutdown" }),
+    ]);
+
+    let diagnostics = get_diagnostics(&messages);
+    assert_eq!(diagnostics.len(), 3);
+    // out-of-range literal
+    assert_eq!(get_range_start(&diagnostics[1][0]), (2, 8));
+    assert!(diagnostics[1][0]["message"].as_str().unwrap().contains("too big"));
+    // chained comparison
+    assert_eq!(get_range_start(&diagnostics[2][0]), (3, 8));
+    assert!(diagnostics[2][0]["message"].as_str().unwrap().contains("can't be chained"));
+    assert_eq!(get_result(&messages, 1)["contents"]["value"], "```c\nint total\n```");
+    assert_eq!(get_result(&messages, 2), &JsonValue::Null);
+}
+
+#[test]
+fn test_lsp_definition_and_hover() {
+    let uri = path_to_uri(&get_test_directory("definition").join("program.ctiny"));
+    let messages = run_lsp_transcript(vec![
+        did_open(&uri, POINT_PROGRAM),
+        // total, in "total = p.x + GREEN;"
+        position_request(1, "textDocument/definition", &uri, 9, 6),
+        // x, a struct member
+        position_request(2, "textDocument/definition", &uri, 9, 14),
+        // GREEN, an enumerator
+        position_request(3, "textDocument/definition", &uri, 9, 20),
+        // square, a function
+        position_request(4, "textDocument/hover", &uri, 8, 10),
+        position_request(5, "textDocument/hover", &uri, 9, 14),
+        position_request(6, "textDocument/hover", &uri, 6, 18),
+        // not an identifier
+        position_request(7, "textDocument/hover", &uri, 9, 10),
+    ]);
+
+    assert_eq!(get_diagnostics(&messages)[0].as_array().unwrap().len(), 0);
+    assert_eq!(get_result(&messages, 1)["uri"], uri.as_str());
+    assert_eq!(get_range_start(get_result(&messages, 1)), (7, 8));
+    assert_eq!(get_range_start(get_result(&messages, 2)), (0, 19));
+    assert_eq!(get_range_start(get_result(&messages, 3)), (1, 18));
+    assert_eq!(get_result(&messages, 4)["contents"]["value"], "```c\nint square(int n)\n```");
+    assert_eq!(get_result(&messages, 5)["contents"]["value"], "```c\nint x (member of struct point)\n```");
+    assert_eq!(get_result(&messages, 6)["contents"]["value"], "```c\nstruct point p\n```");
+    assert_eq!(get_range_start(get_result(&messages, 6)), (6, 17));
+    assert_eq!(get_result(&messages, 7), &JsonValue::Null);
+}
+
+#[test]
+fn test_lsp_requests_use_the_last_valid_content() {
+    let uri = path_to_uri(&get_test_directory("last_valid").join("program.ctiny"));
+    let unfinished_program = POINT_PROGRAM.replace("return total;", "return tot");
+    let messages = run_lsp_transcript(vec![
+        did_open(&uri, POINT_PROGRAM),
+        did_change(&uri, &unfinished_program),
+        position_request(1, "textDocument/hover", &uri, 10, 12),
+    ]);
+
+    assert_eq!(get_diagnostics(&messages)[1].as_array().unwrap().len(), 1);
+    assert_eq!(get_result(&messages, 1)["contents"]["value"], "```c\nint total\n```");
+}
+
+#[test]
+fn test_lsp_document_symbols() {
+    let uri = path_to_uri(&get_test_directory("symbols").join("program.ctiny"));
+    let messages = run_lsp_transcript(vec![
+        did_open(&uri, POINT_PROGRAM),
+        json!({ "id": 1, "method": "textDocument/documentSymbol", "params": { "textDocument": { "uri": uri } } }),
+    ]);
+
+    let symbols = get_result(&messages, 1).as_array().unwrap();
+    let names: Vec<&str> = symbols.iter().map(|symbol| symbol["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["point", "color", "square", "main"]);
+    assert_eq!(symbols[0]["kind"], 23);
+    assert_eq!(symbols[0]["children"][1]["name"], "y");
+    assert_eq!(symbols[1]["children"][1]["name"], "GREEN");
+    assert_eq!(symbols[2]["detail"], "int square(int n)");
+    assert_eq!(symbols[2]["children"][0]["name"], "n");
+    let main_variables: Vec<&str> = symbols[3]["children"].as_array().unwrap().iter()
+        .map(|symbol| symbol["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(main_variables, ["p", "total"]);
+    assert_eq!(get_range_start(&symbols[2]), (2, 0));
+    assert_eq!(symbols[2]["selectionRange"]["start"]["character"], 4);
+}
+
+#[test]
+fn test_lsp_completion() {
+    let uri = path_to_uri(&get_test_directory("completion").join("program.ctiny"));
+    let messages = run_lsp_transcript(vec![
+        did_open(&uri, POINT_PROGRAM),
+        // in "return total;"
+        position_request(1, "textDocument/completion", &uri, 10, 13),
+        // after "p."
+        position_request(2, "textDocument/completion", &uri, 9, 14),
+    ]);
+
+    let labels = |id: i64| -> Vec<String> {
+        get_result(&messages, id).as_array().unwrap().iter()
+            .map(|item| item["label"].as_str().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(labels(1), ["p", "total", "square", "main", "point", "color", "RED", "GREEN"]);
+    assert_eq!(get_result(&messages, 1)[1]["kind"], 6);
+    assert_eq!(get_result(&messages, 1)[2]["detail"], "int square(int n)");
+    assert_eq!(labels(2), ["x", "y"]);
+}
+
+#[test]
+fn test_lsp_included_files() {
+    let directory = get_test_directory("include");
+    std::fs::write(directory.join("util.ctiny"), "int triple(int n) {\n    return 3 * n;\n}\n").unwrap();
+    std::fs::write(directory.join("broken.ctiny"), "int broken( {\n    return 0;\n}\n").unwrap();
+    let uri = path_to_uri(&directory.join("program.ctiny"));
+    let program = "#include \"util.ctiny\"\nint main() {\n    return triple(2);\n}";
+    let messages = run_lsp_transcript(vec![
+        did_open(&uri, program),
+        position_request(1, "textDocument/definition", &uri, 2, 12),
+        json!({ "id": 2, "method": "textDocument/documentSymbol", "params": { "textDocument": { "uri": uri } } }),
+        did_change(&uri, &program.replace("util.ctiny", "broken.ctiny")),
+    ]);
+
+    let definition = get_result(&messages, 1);
+    assert_eq!(definition["uri"], path_to_uri(&directory.join("util.ctiny")).as_str());
+    assert_eq!(get_range_start(definition), (0, 4));
+    // the functions of the included files are not symbols of the document
+    assert_eq!(get_result(&messages, 2).as_array().unwrap().len(), 1);
+    let diagnostics = get_diagnostics(&messages);
+    assert!(diagnostics[0].as_array().unwrap().is_empty());
+    assert_eq!(get_range_start(&diagnostics[1][0]), (0, 0));
+    assert!(diagnostics[1][0]["message"].as_str().unwrap().starts_with("In "));
+    assert!(diagnostics[1][0]["message"].as_str().unwrap().contains("broken.ctiny"));
+}
@@ -1,3 +1,9 @@
+use crate::abstract_syntax_tree::nodes::Value;
+use crate::interpretation::interpret_function::interpret_translation_unit;
+use crate::pipelines::parse_content_into_ast;
+use crate::semantic::errors::SemanticError;
+use crate::symbol_table::build_static_symbol_table;
+
 
 
 
@@ -31,4 +37,46 @@ macro_rules! build_test {
             print!("AST for string \"{}\": \n {:#?} \n\n", test_str, ast);
         }
     }
-}
\ No newline at end of file
+}
+
+/// Interpret a whole program with the settings of the test thread, and get its return value.
+pub fn interpret_program_for_testing(test_str: &str) -> Result<Value, SemanticError> {
+    let ast = parse_content_into_ast(test_str, None)?;
+    let mut symbol_table = build_static_symbol_table(&ast)?;
+    interpret_translation_unit(&ast, &mut symbol_table)
+        .map(|value_node| value_node.data)
+}
+
+/// Interpret a whole program with a 16-bit int and the trap overflow policy, then check its return value,
+/// or check that the given error is raised (before or during interpretation).
+#[macro_export]
+macro_rules! build_program_test {
+    ($test_name:ident, $test_str:expr, $test_value:expr) => {
+        #[test]
+        fn $test_name() {
+            $crate::semantic::data_model::set_int_width($crate::semantic::data_model::IntWidth::W16);
+            $crate::semantic::overflow_policy::set_overflow_policy($crate::semantic::overflow_policy::OverflowPolicy::Trap);
+            let interpreted_value = $crate::tests::macros::interpret_program_for_testing($test_str)
+                .unwrap_or_else(|error| {
+                    panic!("Error interpreting program <{}>: {}", $test_str, error);
+                });
+            assert_eq!(interpreted_value, $test_value);
+        }
+    };
+    ($test_name:ident, $test_str:expr, error $error_pattern:pat) => {
+        #[test]
+        fn $test_name() {
+            $crate::semantic::data_model::set_int_width($crate::semantic::data_model::IntWidth::W16);
+            $crate::semantic::overflow_policy::set_overflow_policy($crate::semantic::overflow_policy::OverflowPolicy::Trap);
+            match $crate::tests::macros::interpret_program_for_testing($test_str) {
+                Ok(value) => panic!(
+                    "Expected error for <{}>, got value {}", $test_str, value
+                ),
+                Err(error) => {
+                    print!("Expected error for <{}>: {}\n", $test_str, error);
+                    assert!(matches!(error, $error_pattern));
+                },
+            }
+        }
+    };
+}
@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod macros;
 #[cfg(test)]
+mod shared_output;
+#[cfg(test)]
 mod syntax_parsing_tests;
 #[cfg(test)]
 mod symbol_table_tests;
@@ -8,6 +10,48 @@ mod symbol_table_tests;
 mod type_cast_tests;
 #[cfg(test)]
 mod overflow_checks_tests;
+#[cfg(test)]
+mod data_model_tests;
+#[cfg(test)]
+mod arithmetic_conversions_tests;
+#[cfg(test)]
+mod overflow_policy_tests;
+#[cfg(test)]
+mod const_evaluation_tests;
+#[cfg(test)]
+mod struct_tests;
+#[cfg(test)]
+mod enum_tests;
+#[cfg(test)]
+mod prototype_tests;
+#[cfg(test)]
+mod preprocessing_tests;
+#[cfg(test)]
+mod assert_tests;
+#[cfg(test)]
+mod size_tests;
+#[cfg(test)]
+mod initializer_tests;
+#[cfg(test)]
+mod string_tests;
+#[cfg(test)]
+mod repl_tests;
+#[cfg(test)]
+mod debugger_tests;
+#[cfg(test)]
+mod dap_tests;
+#[cfg(test)]
+mod language_server_tests;
+#[cfg(test)]
+mod formatting_tests;
+#[cfg(test)]
+mod trace_tests;
+#[cfg(test)]
+mod profiler_tests;
+#[cfg(test)]
+mod coverage_tests;
+#[cfg(test)]
+mod execution_limits_tests;
 
 #[cfg(test)]
 mod abstract_syntax_tree;
@@ -1,7 +1,15 @@
+use crate::abstract_syntax_tree::nodes::TypeSpecifier;
 use crate::semantic::overflow_checks::*;
 
+// int operations are the integer operations performed on the int type
+macro_rules! int_operation {
+    ($function:ident) => {
+        |left, right, span| $function(left, right, TypeSpecifier::Int, span)
+    };
+}
+
 macro_rules! build_safe_operation_test {
-    ($test_name:ident, $function:ident, $left:expr, $right:expr, $debug_expr:expr) => {
+    ($test_name:ident, $function:expr, $left:expr, $right:expr, $debug_expr:expr) => {
         #[test]
         fn $test_name() {
             // positive test
@@ -10,7 +18,7 @@ macro_rules! build_safe_operation_test {
             let debug_str = stringify!($debug_str);
             let common_debug_span = pest::Span::new(debug_str, 0, debug_str.len()).unwrap();
 
-            let result = $function(left, right, common_debug_span);
+            let result = ($function)(left, right, common_debug_span);
 
             match result {
                 Ok(value) => {
@@ -32,7 +40,7 @@ macro_rules! build_safe_operation_test {
             }
         }
     };
-    ($test_name:ident, $function:ident, $left:expr, $right:expr, $debug_str:expr, false) => {
+    ($test_name:ident, $function:expr, $left:expr, $right:expr, $debug_str:expr, false) => {
         // negative case
         #[test]
         fn $test_name() {
@@ -41,7 +49,7 @@ macro_rules! build_safe_operation_test {
             let debug_str = $debug_str;
             let common_debug_span = pest::Span::new(debug_str, 0, debug_str.len()).unwrap();
 
-            let result = $function(left, right, common_debug_span);
+            let result = ($function)(left, right, common_debug_span);
 
             match result {
                 Ok(value) => {
@@ -63,7 +71,7 @@ macro_rules! build_safe_operation_test {
 // int addition tests
 build_safe_operation_test!(
     test_safe_int_add_positive,
-    safe_int_add,
+    int_operation!(safe_integer_add),
     1, 
     1,
     1+1
@@ -71,9 +79,9 @@ build_safe_operation_test!(
 
 build_safe_operation_test!(
     test_safe_int_add_overflow,
-    safe_int_add,
-    i16::MAX, 
-    i16::MAX,
+    int_operation!(safe_integer_add),
+    i16::MAX as i128, 
+    i16::MAX as i128,
     "i16::MAX+i16::MAX",
     false
 );
@@ -99,7 +107,7 @@ build_safe_operation_test!(
 // int subtraction tests
 build_safe_operation_test!(
     test_safe_int_subtract_positive,
-    safe_int_subtract,
+    int_operation!(safe_integer_subtract),
     10, 
     5,
     10-5
@@ -107,8 +115,8 @@ build_safe_operation_test!(
 
 build_safe_operation_test!(
     test_safe_int_subtract_underflow,
-    safe_int_subtract,
-    i16::MIN, 
+    int_operation!(safe_integer_subtract),
+    i16::MIN as i128, 
     1,
     "i16::MIN-1",
     false
@@ -135,7 +143,7 @@ build_safe_operation_test!(
 // int multiplication tests
 build_safe_operation_test!(
     test_safe_int_multiply_positive,
-    safe_int_multiply,
+    int_operation!(safe_integer_multiply),
     10, 
     5,
     10*5
@@ -143,8 +151,8 @@ build_safe_operation_test!(
 
 build_safe_operation_test!(
     test_safe_int_multiply_overflow,
-    safe_int_multiply,
-    i16::MAX, 
+    int_operation!(safe_integer_multiply),
+    i16::MAX as i128, 
     2,
     "i16::MAX*2",
     false
@@ -171,7 +179,7 @@ build_safe_operation_test!(
 // int division tests
 build_safe_operation_test!(
     test_safe_int_divide_positive,
-    safe_int_divide,
+    int_operation!(safe_integer_divide),
     10, 
     5,
     10/5
@@ -179,7 +187,7 @@ build_safe_operation_test!(
 
 build_safe_operation_test!(
     test_safe_int_divide_zero,
-    safe_int_divide,
+    int_operation!(safe_integer_divide),
     10, 
     0,
     "10/0",
@@ -207,7 +215,7 @@ build_safe_operation_test!(
 // int modulo tests
 build_safe_operation_test!(
     test_safe_int_modulo_positive,
-    safe_int_modulo,
+    int_operation!(safe_integer_modulo),
     10, 
     3,
     10%3
@@ -215,9 +223,38 @@ build_safe_operation_test!(
 
 build_safe_operation_test!(
     test_safe_int_modulo_zero,
-    safe_int_modulo,
+    int_operation!(safe_integer_modulo),
     10, 
     0,
     "10%0",
     false
 );
+
+// int negation tests
+#[test]
+fn test_safe_int_negate_positive() {
+    let debug_str = "-(1)";
+    let common_debug_span = pest::Span::new(debug_str, 0, debug_str.len()).unwrap();
+    assert_eq!(safe_integer_negate(1, TypeSpecifier::Int, common_debug_span).unwrap(), -1);
+}
+
+#[test]
+fn test_safe_int_negate_max() {
+    let debug_str = "-(i16::MAX)";
+    let common_debug_span = pest::Span::new(debug_str, 0, debug_str.len()).unwrap();
+    assert_eq!(safe_integer_negate(i16::MAX as i128, TypeSpecifier::Int, common_debug_span).unwrap(), -(i16::MAX as i128));
+}
+
+#[test]
+fn test_safe_int_negate_overflow() {
+    let debug_str = "-(i16::MIN)";
+    let common_debug_span = pest::Span::new(debug_str, 0, debug_str.len()).unwrap();
+    let result = safe_integer_negate(i16::MIN as i128, TypeSpecifier::Int, common_debug_span);
+    match result {
+        Ok(value) => panic!(
+            "No error occured while evaluating expression <{}>, but we expected one. Result value: <{}>",
+            debug_str, value
+        ),
+        Err(error) => println!("Expected error occured while evaluating expression <{}>: <{}>", debug_str, error),
+    }
+}
@@ -0,0 +1,210 @@
+use crate::abstract_syntax_tree::nodes::Value;
+use crate::interpretation::interpret_function::interpret_translation_unit;
+use crate::pipelines::parse_content_into_ast;
+use crate::semantic::overflow_policy::{OverflowPolicy, set_overflow_policy, get_overflow_policy};
+use crate::semantic::warnings::take_warnings;
+use crate::symbol_table::build_static_symbol_table;
+use crate::tests::interpretation::expressions::interpret_expression_type_casts::interpret_expression_to_value_for_testing;
+
+/// Set the overflow policy of the test thread, interpret the expression,
+/// then check the resulting value and the number of warnings recorded.
+macro_rules! build_overflow_policy_test {
+    ($test_name:ident, $overflow_policy:expr, $test_str:expr, $test_value:expr, $nb_of_warnings:expr) => {
+        #[test]
+        fn $test_name() {
+            set_overflow_policy($overflow_policy);
+            take_warnings();
+            let interpreted_value = interpret_expression_to_value_for_testing($test_str)
+                .unwrap_or_else(|error| {
+                    panic!("Error interpreting expression <{}>: {}", $test_str, error);
+                });
+            assert_eq!(interpreted_value.data, $test_value);
+            assert_eq!(take_warnings().len(), $nb_of_warnings);
+        }
+    };
+    ($test_name:ident, $overflow_policy:expr, $test_str:expr) => {
+        #[test]
+        fn $test_name() {
+            set_overflow_policy($overflow_policy);
+            let interpreted_value = interpret_expression_to_value_for_testing($test_str);
+            match interpreted_value {
+                Ok(value) => panic!(
+                    "Expected error for <{}>, got value {}", $test_str, value.data
+                ),
+                Err(error) => print!("Expected error for <{}>: {}\n", $test_str, error),
+            }
+        }
+    };
+}
+
+#[test]
+fn test_overflow_policy_default_is_trap() {
+    assert_eq!(get_overflow_policy(), OverflowPolicy::Trap);
+}
+
+// operations
+build_overflow_policy_test!(
+    test_overflow_policy_trap_addition,
+    OverflowPolicy::Trap,
+    "32767 + 1"
+);
+
+build_overflow_policy_test!(
+    test_overflow_policy_wrap_addition,
+    OverflowPolicy::Wrap,
+    "32767 + 1",
+    Value::Int(-32768),
+    1
+);
+
+build_overflow_policy_test!(
+    test_overflow_policy_saturate_addition,
+    OverflowPolicy::Saturate,
+    "32767 + 1",
+    Value::Int(32767),
+    1
+);
+
+build_overflow_policy_test!(
+    test_overflow_policy_warn_addition,
+    OverflowPolicy::Warn,
+    "32767 + 1",
+    Value::Int(-32768),
+    0 // only logged
+);
+
+build_overflow_policy_test!(
+    test_overflow_policy_wrap_multiplication,
+    OverflowPolicy::Wrap,
+    "200 * 200",
+    Value::Int(-25536),
+    1
+);
+
+build_overflow_policy_test!(
+    test_overflow_policy_saturate_subtraction,
+    OverflowPolicy::Saturate,
+    "-32768 - 1",
+    Value::Int(-32768),
+    1
+);
+
+build_overflow_policy_test!(
+    test_overflow_policy_wrap_negation,
+    OverflowPolicy::Wrap,
+    "-(-32768)",
+    Value::Int(-32768),
+    1
+);
+
+build_overflow_policy_test!(
+    test_overflow_policy_wrap_division_by_zero,
+    OverflowPolicy::Wrap,
+    "1 / 0"
+);
+
+build_overflow_policy_test!(
+    test_overflow_policy_saturate_unsigned_still_wraps,
+    OverflowPolicy::Saturate,
+    "65535u + 1u",
+    Value::UnsignedInt(0),
+    1
+);
+
+// casts
+build_overflow_policy_test!(
+    test_overflow_policy_trap_cast_to_short,
+    OverflowPolicy::Trap,
+    "(short) 40000l"
+);
+
+build_overflow_policy_test!(
+    test_overflow_policy_wrap_cast_to_short,
+    OverflowPolicy::Wrap,
+    "(short) 40000l",
+    Value::Short(-25536),
+    1
+);
+
+build_overflow_policy_test!(
+    test_overflow_policy_saturate_cast_to_short,
+    OverflowPolicy::Saturate,
+    "(short) 40000l",
+    Value::Short(32767),
+    1
+);
+
+build_overflow_policy_test!(
+    test_overflow_policy_wrap_cast_to_char,
+    OverflowPolicy::Wrap,
+    "(char) 353",
+    Value::Char(b'a'),
+    1
+);
+
+build_overflow_policy_test!(
+    test_overflow_policy_saturate_cast_to_char,
+    OverflowPolicy::Saturate,
+    "(char) (-5)",
+    Value::Char(0),
+    1
+);
+
+build_overflow_policy_test!(
+    test_overflow_policy_saturate_float_to_int,
+    OverflowPolicy::Saturate,
+    "(int) 1000000.0",
+    Value::Int(32767),
+    1
+);
+
+build_overflow_policy_test!(
+    test_overflow_policy_wrap_float_to_int,
+    OverflowPolicy::Wrap,
+    "(int) 32768.5",
+    Value::Int(-32768),
+    1
+);
+
+build_overflow_policy_test!(
+    test_overflow_policy_saturate_float_multiplication,
+    OverflowPolicy::Saturate,
+    "340000000000000000000000000000000000000.0 * 2.0",
+    Value::Float(f32::MAX),
+    1
+);
+
+// programs
+#[test]
+fn test_overflow_policy_program_keeps_running() {
+    let test_str = "
+    int main() {
+        int x, i;
+        x = 32000;
+        i = 0;
+        while (i < 3) {
+            x = x + 500;
+            i = i + 1;
+        }
+        return x;
+    }
+    ";
+    let ast = parse_content_into_ast(test_str, None).unwrap();
+
+    set_overflow_policy(OverflowPolicy::Trap);
+    let mut symbol_table = build_static_symbol_table(&ast).unwrap();
+    assert!(interpret_translation_unit(&ast, &mut symbol_table).is_err());
+
+    // 32000 + 1500 = 33500, wrapped around to -32036
+    set_overflow_policy(OverflowPolicy::Wrap);
+    take_warnings();
+    let mut symbol_table = build_static_symbol_table(&ast).unwrap();
+    assert_eq!(
+        interpret_translation_unit(&ast, &mut symbol_table).unwrap().data,
+        Value::Int(-32036)
+    );
+    let warnings = take_warnings();
+    assert_eq!(warnings.len(), 1);
+    // the warning points to the overflowing operation
+    assert_eq!(warnings[0].line(), "            x = x + 500;");
+}
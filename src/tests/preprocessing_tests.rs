@@ -0,0 +1,514 @@
+use std::path::PathBuf;
+
+use crate::abstract_syntax_tree::nodes::Value;
+use crate::interpretation::interpret_function::interpret_translation_unit;
+use crate::pipelines::parse_content_into_ast;
+use crate::preprocessing::{preprocess_file, PreprocessedSource};
+use crate::semantic::data_model::{IntWidth, set_int_width};
+use crate::semantic::errors::SemanticError;
+use crate::semantic::overflow_policy::{OverflowPolicy, set_overflow_policy};
+use crate::symbol_table::build_static_symbol_table;
+
+/// Write the given files in a new directory, and return the path of the first one.
+fn write_test_files(test_name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let directory = std::env::temp_dir().join(
+        format!("ctiny_preprocessing_{}_{}", std::process::id(), test_name)
+    );
+    let _ = std::fs::remove_dir_all(&directory);
+    for (file_name, content) in files {
+        let file_path = directory.join(file_name);
+        std::fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+        std::fs::write(file_path, content).unwrap();
+    }
+    directory.join(files[0].0)
+}
+
+fn interpret_preprocessed_source(source: &PreprocessedSource) -> Result<Value, SemanticError> {
+    set_int_width(IntWidth::W16);
+    set_overflow_policy(OverflowPolicy::Trap);
+    let ast = parse_content_into_ast(&source.content, None)?;
+    let mut symbol_table = build_static_symbol_table(&ast)?;
+    interpret_translation_unit(&ast, &mut symbol_table)
+        .map(|value_node| value_node.data)
+}
+
+/// Interpret a program made of several files, the first one being the main file,
+/// then check its return value, or check that the given error is raised
+/// (while including the files, before or during interpretation).
+macro_rules! build_preprocessing_program_test {
+    ($test_name:ident, $files:expr, $test_value:expr) => {
+        #[test]
+        fn $test_name() {
+            let main_path = write_test_files(stringify!($test_name), $files);
+            let interpreted_value = preprocess_file(&main_path)
+                .and_then(|source| interpret_preprocessed_source(&source))
+                .unwrap_or_else(|error| {
+                    panic!("Error interpreting program {}: {}", main_path.display(), error);
+                });
+            assert_eq!(interpreted_value, $test_value);
+        }
+    };
+    ($test_name:ident, $files:expr, error $error_pattern:pat) => {
+        #[test]
+        fn $test_name() {
+            let main_path = write_test_files(stringify!($test_name), $files);
+            match preprocess_file(&main_path).and_then(|source| interpret_preprocessed_source(&source)) {
+                Ok(value) => panic!(
+                    "Expected error for {}, got value {}", main_path.display(), value
+                ),
+                Err(error) => {
+                    print!("Expected error for {}: {}\n", main_path.display(), error);
+                    assert!(matches!(error, $error_pattern));
+                },
+            }
+        }
+    };
+}
+
+build_preprocessing_program_test!(
+    test_include_function,
+    &[
+        ("main.ctiny", "
+        #include \"util.ctiny\"
+
+        int main() {
+            return square(3);
+        }
+        "),
+        ("util.ctiny", "
+        int square(int x) {
+            return x * x;
+        }
+        "),
+    ],
+    Value::Int(9)
+);
+
+build_preprocessing_program_test!(
+    test_include_relative_to_including_file,
+    &[
+        ("main.ctiny", "
+        #include \"lib/math.ctiny\"
+
+        int main() {
+            return cube(2);
+        }
+        "),
+        ("lib/math.ctiny", "
+        #include \"square.ctiny\"
+
+        int cube(int x) {
+            return square(x) * x;
+        }
+        "),
+        ("lib/square.ctiny", "
+        int square(int x) {
+            return x * x;
+        }
+        "),
+    ],
+    Value::Int(8)
+);
+
+build_preprocessing_program_test!(
+    test_include_once,
+    &[
+        ("main.ctiny", "
+        #include \"square.ctiny\"
+        #include \"cube.ctiny\"
+        #include \"square.ctiny\"
+
+        int main() {
+            return square(2) + cube(2);
+        }
+        "),
+        ("square.ctiny", "
+        int square(int x) {
+            return x * x;
+        }
+        "),
+        ("cube.ctiny", "
+        #include \"square.ctiny\"
+
+        int cube(int x) {
+            return square(x) * x;
+        }
+        "),
+    ],
+    Value::Int(12)
+);
+
+build_preprocessing_program_test!(
+    test_include_types,
+    &[
+        ("main.ctiny", "
+        #include \"point.ctiny\"
+
+        int main() {
+            struct point p;
+            p.x = 1;
+            p.y = GREEN;
+            return p.x + p.y;
+        }
+        "),
+        ("point.ctiny", "
+        struct point { int x; int y; };
+        enum color { RED, GREEN };
+        "),
+    ],
+    Value::Int(2)
+);
+
+build_preprocessing_program_test!(
+    test_include_cycle,
+    &[
+        ("main.ctiny", "
+        #include \"a.ctiny\"
+
+        int main() {
+            return 0;
+        }
+        "),
+        ("a.ctiny", "#include \"b.ctiny\"\n"),
+        ("b.ctiny", "#include \"a.ctiny\"\n"),
+    ],
+    error SemanticError::Preprocessing(_)
+);
+
+build_preprocessing_program_test!(
+    test_include_missing_file,
+    &[
+        ("main.ctiny", "
+        #include \"missing.ctiny\"
+
+        int main() {
+            return 0;
+        }
+        "),
+    ],
+    error SemanticError::Preprocessing(_)
+);
+
+build_preprocessing_program_test!(
+    test_include_invalid_directive,
+    &[
+        ("main.ctiny", "
+        #include <util.ctiny>
+
+        int main() {
+            return 0;
+        }
+        "),
+    ],
+    error SemanticError::Preprocessing(_)
+);
+
+#[test]
+fn test_missing_file_is_a_preprocessing_error() {
+    let main_path = write_test_files(
+        "test_missing_file_is_a_preprocessing_error",
+        &[("main.ctiny", "int main() {\n    return 0;\n}\n")],
+    )
+        .with_file_name("missing.ctiny");
+    match preprocess_file(&main_path) {
+        Err(SemanticError::Preprocessing(error)) => {
+            let message = error.to_string();
+            print!("Expected error: {}\n", message);
+            assert!(message.contains("Cannot read file"));
+            assert!(message.contains("missing.ctiny"));
+        },
+        result => panic!("Expected a preprocessing error, got {:?}", result),
+    }
+}
+
+#[test]
+fn test_include_error_points_to_included_file() {
+    let main_path = write_test_files(
+        "test_include_error_points_to_included_file",
+        &[
+            ("main.ctiny", "#include \"util.ctiny\"\n\nint main() {\n    return divide(1, 0);\n}\n"),
+            ("util.ctiny", "int divide(int x, int y) {\n    return x / y;\n}\n"),
+        ],
+    );
+    let source = preprocess_file(&main_path).unwrap();
+    let error = interpret_preprocessed_source(&source).unwrap_err();
+    assert!(matches!(error, SemanticError::DivisionByZero(_)));
+
+    let relocated_error = source.relocate_error(error.get_error());
+    let message = relocated_error.to_string();
+    print!("{}\n", message);
+    assert!(message.contains("util.ctiny:2:12"));
+    assert_eq!(relocated_error.line(), "    return x / y;");
+}
+
+#[test]
+fn test_syntax_error_points_to_included_file() {
+    let main_path = write_test_files(
+        "test_syntax_error_points_to_included_file",
+        &[
+            ("main.ctiny", "#include \"util.ctiny\"\n\nint main() {\n    return 0;\n}\n"),
+            ("util.ctiny", "int f() {\n    return 1\n}\n"),
+        ],
+    );
+    let source = preprocess_file(&main_path).unwrap();
+    let error = parse_content_into_ast(&source.content, None).unwrap_err();
+
+    let message = source.relocate_error(error.get_error()).to_string();
+    print!("{}\n", message);
+    assert!(message.contains("util.ctiny:3:1"));
+}
+
+build_preprocessing_program_test!(
+    test_define_object_like_macro,
+    &[
+        ("main.ctiny", "
+        #define SIZE 4
+        #define DOUBLE_SIZE (SIZE * 2) // comments are not part of the macro
+
+        int main() {
+            int a[DOUBLE_SIZE];
+            int i;
+            i = 0;
+            while (i < DOUBLE_SIZE) {
+                a[i] = i;
+                i = i + 1;
+            }
+            return a[SIZE] + DOUBLE_SIZE;
+        }
+        "),
+    ],
+    Value::Int(12)
+);
+
+build_preprocessing_program_test!(
+    test_define_function_like_macro,
+    &[
+        ("main.ctiny", "
+        #define SQUARE(x) ((x) * (x))
+        #define MAX(a, b) max_of(a, b)
+
+        int max_of(int a, int b) {
+            int result;
+            result = b;
+            if (a > b) {
+                result = a;
+            }
+            return result;
+        }
+
+        int main() {
+            return MAX(SQUARE(1 + 2), SQUARE(2)) + MAX(1, (2 + 3));
+        }
+        "),
+    ],
+    Value::Int(14)
+);
+
+build_preprocessing_program_test!(
+    test_macro_not_expanded_recursively,
+    &[
+        ("main.ctiny", "
+        int inc(int v) {
+            return v + 1;
+        }
+
+        #define inc(v) inc((v) * 10)
+
+        int main() {
+            return inc(2);
+        }
+        "),
+    ],
+    Value::Int(21)
+);
+
+build_preprocessing_program_test!(
+    test_macro_not_expanded_in_comments_and_chars,
+    &[
+        ("main.ctiny", "
+        #define c 'b'
+
+        int main() {
+            char d;
+            /* c is not expanded here
+               nor here: c */
+            d = 'c'; // nor here: c
+            return d - c;
+        }
+        "),
+    ],
+    Value::Int(1)
+);
+
+build_preprocessing_program_test!(
+    test_ifdef_else_endif,
+    &[
+        ("main.ctiny", "
+        #define DEBUG
+
+        int main() {
+        #ifdef DEBUG
+            return 1;
+        #else
+            return 2;
+        #endif
+        }
+        "),
+    ],
+    Value::Int(1)
+);
+
+build_preprocessing_program_test!(
+    test_ifndef_and_undef,
+    &[
+        ("main.ctiny", "
+        #define DEBUG
+        #undef DEBUG
+
+        int main() {
+        #ifndef DEBUG
+            #ifdef DEBUG
+                return 1;
+            #else
+                return 2;
+            #endif
+        #else
+            return 3;
+            #unknown directives are skipped here
+        #endif
+        }
+        "),
+    ],
+    Value::Int(2)
+);
+
+build_preprocessing_program_test!(
+    test_include_guard_with_ifndef,
+    &[
+        ("main.ctiny", "
+        #include \"config.ctiny\"
+        #include \"square.ctiny\"
+
+        int main() {
+            return square(N);
+        }
+        "),
+        ("config.ctiny", "
+        #ifndef N
+        #define N 5
+        #endif
+        "),
+        ("square.ctiny", "
+        #include \"config.ctiny\"
+
+        int square(int x) {
+            return x * x;
+        }
+        "),
+    ],
+    Value::Int(25)
+);
+
+build_preprocessing_program_test!(
+    test_macro_argument_number_mismatch,
+    &[
+        ("main.ctiny", "
+        #define MAX(a, b) a
+
+        int main() {
+            return MAX(1);
+        }
+        "),
+    ],
+    error SemanticError::Preprocessing(_)
+);
+
+build_preprocessing_program_test!(
+    test_macro_redefinition,
+    &[
+        ("main.ctiny", "
+        #define N 1
+        #define N 2
+
+        int main() {
+            return N;
+        }
+        "),
+    ],
+    error SemanticError::Preprocessing(_)
+);
+
+build_preprocessing_program_test!(
+    test_unterminated_ifdef,
+    &[
+        ("main.ctiny", "
+        #ifdef DEBUG
+        int main() {
+            return 0;
+        }
+        "),
+    ],
+    error SemanticError::Preprocessing(_)
+);
+
+build_preprocessing_program_test!(
+    test_endif_without_ifdef,
+    &[
+        ("main.ctiny", "
+        int main() {
+            return 0;
+        }
+        #endif
+        "),
+    ],
+    error SemanticError::Preprocessing(_)
+);
+
+build_preprocessing_program_test!(
+    test_unknown_directive,
+    &[
+        ("main.ctiny", "
+        #pragma once
+
+        int main() {
+            return 0;
+        }
+        "),
+    ],
+    error SemanticError::Preprocessing(_)
+);
+
+#[test]
+fn test_error_in_macro_expansion_points_to_invocation() {
+    let main_path = write_test_files(
+        "test_error_in_macro_expansion_points_to_invocation",
+        &[
+            ("main.ctiny", "#define DIVIDE(x, y) ((x) / (y))\n\nint main() {\n    int zero;\n    zero = 0;\n    return 1 + DIVIDE(1, zero);\n}\n"),
+        ],
+    );
+    let source = preprocess_file(&main_path).unwrap();
+    let error = interpret_preprocessed_source(&source).unwrap_err();
+    assert!(matches!(error, SemanticError::DivisionByZero(_)));
+
+    let relocated_error = source.relocate_error(error.get_error());
+    let message = relocated_error.to_string();
+    print!("{}\n", message);
+    assert!(message.contains("main.ctiny:6:16"));
+    assert_eq!(relocated_error.line(), "    return 1 + DIVIDE(1, zero);");
+}
+
+#[test]
+fn test_syntax_error_after_macro_keeps_original_column() {
+    let main_path = write_test_files(
+        "test_syntax_error_after_macro_keeps_original_column",
+        &[
+            ("main.ctiny", "#define LONG_NAME_FOR_ONE 1\n\nint main() {\n    return LONG_NAME_FOR_ONE + ;\n}\n"),
+        ],
+    );
+    let source = preprocess_file(&main_path).unwrap();
+    let error = parse_content_into_ast(&source.content, None).unwrap_err();
+
+    let message = source.relocate_error(error.get_error()).to_string();
+    print!("{}\n", message);
+    assert!(message.contains("main.ctiny:4:32"));
+}
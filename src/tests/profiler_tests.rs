@@ -0,0 +1,103 @@
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::interpretation::hooks::{add_hook, clear_hooks};
+use crate::interpretation::interpret_function::interpret_translation_unit;
+use crate::pipelines::parse_content_into_ast;
+use crate::preprocessing::preprocess_content;
+use crate::profiler::{Profile, Profiler, FunctionCounts, LineCounts, LoopCounts};
+use crate::semantic::data_model::{IntWidth, set_int_width};
+use crate::semantic::overflow_policy::{OverflowPolicy, set_overflow_policy};
+use crate::symbol_table::build_static_symbol_table;
+
+/// Interpret a program with a profiler, and get its profile.
+fn profile_program_for_testing(test_str: &str) -> Profile {
+    set_int_width(IntWidth::W16);
+    set_overflow_policy(OverflowPolicy::Trap);
+    let source = Rc::new(preprocess_content(Path::new("program.ctiny"), test_str.to_string()).unwrap());
+    let ast = parse_content_into_ast(&source.content, None).unwrap();
+    let mut symbol_table = build_static_symbol_table(&ast).unwrap();
+
+    let profile = Rc::new(RefCell::new(Profile::new(source.clone())));
+    clear_hooks();
+    add_hook(Box::new(Profiler::new(profile.clone())));
+    interpret_translation_unit(&ast, &mut symbol_table).unwrap();
+    clear_hooks();
+
+    Rc::try_unwrap(profile).ok().unwrap().into_inner()
+}
+
+const LOOP_PROGRAM: &str = "int square(int x) {
+    return x * x;
+}
+int main() {
+    int i;
+    int s;
+    int t;
+    i = 0;
+    s = 0;
+    while (i < 3) {
+        t = square(i);
+        s = s + t;
+        i = i + 1;
+    }
+    return s;
+}";
+
+#[test]
+fn test_profile_counts() {
+    let profile = profile_program_for_testing(LOOP_PROGRAM);
+
+    // the condition is evaluated once more than the body
+    assert_eq!(profile.lines[&(0, 10)], LineCounts { statements: 1, expressions: 12 });
+    assert_eq!(profile.lines[&(0, 11)], LineCounts { statements: 3, expressions: 6 });
+    assert_eq!(profile.lines[&(0, 2)], LineCounts { statements: 0, expressions: 9 });
+    assert!(!profile.lines.contains_key(&(0, 5)));
+
+    assert_eq!(profile.loops[&(0, 10)], LoopCounts { executions: 1, iterations: 3 });
+    assert_eq!(profile.functions["square"], FunctionCounts { calls: 3, self_steps: 9, total_steps: 9 });
+    assert_eq!(profile.functions["main"], FunctionCounts { calls: 1, self_steps: 51, total_steps: 60 });
+    assert_eq!(profile.total_steps, 60);
+}
+
+#[test]
+fn test_profile_recursion_is_counted_once() {
+    let profile = profile_program_for_testing("int f(int n) {
+    int r;
+    r = 0;
+    if (n > 0) {
+        r = f(n - 1);
+    }
+    return r;
+}
+int main() {
+    return f(2);
+}");
+    let counts = &profile.functions["f"];
+    assert_eq!(counts.calls, 3);
+    assert_eq!(counts.total_steps, counts.self_steps);
+    assert_eq!(profile.functions["main"].total_steps, profile.total_steps);
+    let stacks: Vec<&String> = profile.stacks.keys().collect();
+    assert_eq!(stacks, vec!["main", "main;f", "main;f;f", "main;f;f;f"]);
+}
+
+#[test]
+fn test_profile_report_and_folded_stacks() {
+    let profile = profile_program_for_testing(LOOP_PROGRAM);
+
+    let mut report = Vec::new();
+    profile.write_report(&mut report).unwrap();
+    let report = String::from_utf8(report).unwrap();
+    print!("Report:\n{}", report);
+    assert!(report.contains("         3           6 |    11 |         t = square(i);\n"));
+    assert!(report.contains("                       |     5 |     int i;\n"));
+    assert!(report.contains("main              1           51           60\n"));
+    assert!(report.contains("square            3            9            9\n"));
+    assert!(report.contains("program.ctiny:10          1            3\n"));
+    assert!(report.ends_with("Total steps: 60\n"));
+
+    let mut folded_stacks = Vec::new();
+    profile.write_folded_stacks(&mut folded_stacks, None).unwrap();
+    assert_eq!(String::from_utf8(folded_stacks).unwrap(), "main 51\nmain;square 9\n");
+}
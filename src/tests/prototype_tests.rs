@@ -0,0 +1,205 @@
+use crate::abstract_syntax_tree::nodes::Value;
+use crate::semantic::errors::SemanticError;
+use crate::build_program_test;
+use crate::tests::macros::interpret_program_for_testing;
+
+build_program_test!(
+    test_prototype_before_main,
+    "
+    int square(int x);
+
+    int main() {
+        return square(4);
+    }
+
+    int square(int x) {
+        return x * x;
+    }
+    ",
+    Value::Int(16)
+);
+
+build_program_test!(
+    test_main_first_without_prototype,
+    "
+    int main() {
+        return twice(4);
+    }
+
+    int twice(int x) {
+        return x * 2;
+    }
+    ",
+    Value::Int(8)
+);
+
+build_program_test!(
+    test_mutually_recursive_functions,
+    "
+    bool is_even(int n);
+    bool is_odd(int n);
+
+    bool is_even(int n) {
+        bool result;
+        result = true;
+        if (n > 0) {
+            result = is_odd(n - 1);
+        }
+        return result;
+    }
+
+    bool is_odd(int n) {
+        bool result;
+        result = false;
+        if (n > 0) {
+            result = is_even(n - 1);
+        }
+        return result;
+    }
+
+    int main() {
+        return is_even(10);
+    }
+    ",
+    Value::Int(1)
+);
+
+build_program_test!(
+    test_prototype_parameter_names_can_differ,
+    "
+    int add(int a, int b);
+
+    int add(int x, int y) {
+        return x + y;
+    }
+
+    int main() {
+        return add(1, 2);
+    }
+    ",
+    Value::Int(3)
+);
+
+build_program_test!(
+    test_repeated_prototype,
+    "
+    int one();
+    int one();
+
+    int one() {
+        return 1;
+    }
+
+    int main() {
+        return one();
+    }
+    ",
+    Value::Int(1)
+);
+
+build_program_test!(
+    test_prototype_without_definition,
+    "
+    int missing(int x);
+
+    int main() {
+        return 0;
+    }
+    ",
+    error SemanticError::MissingFunctionDefinition(_)
+);
+
+build_program_test!(
+    test_prototype_return_type_mismatch,
+    "
+    float foo(int x);
+
+    int foo(int x) {
+        return x;
+    }
+
+    int main() {
+        return foo(1);
+    }
+    ",
+    error SemanticError::PrototypeMismatch(_)
+);
+
+build_program_test!(
+    test_prototype_parameter_count_mismatch,
+    "
+    int foo(int x, int y);
+
+    int foo(int x) {
+        return x;
+    }
+
+    int main() {
+        return foo(1);
+    }
+    ",
+    error SemanticError::PrototypeMismatch(_)
+);
+
+build_program_test!(
+    test_prototype_parameter_type_mismatch,
+    "
+    int foo(char x);
+
+    int foo(int x) {
+        return x;
+    }
+
+    int main() {
+        return foo(1);
+    }
+    ",
+    error SemanticError::PrototypeMismatch(_)
+);
+
+build_program_test!(
+    test_prototype_array_parameter_mismatch,
+    "
+    int foo(int x[3]);
+
+    int foo(int x) {
+        return x;
+    }
+
+    int main() {
+        return foo(1);
+    }
+    ",
+    error SemanticError::PrototypeMismatch(_)
+);
+
+build_program_test!(
+    test_call_to_undefined_function,
+    "
+    int main() {
+        return nothing(1);
+    }
+    ",
+    error SemanticError::UndeclaredFunction(_)
+);
+
+#[test]
+fn test_duplicate_main_function() {
+    let test_str = "
+    int main() {
+        return 0;
+    }
+
+    int main() {
+        return 1;
+    }
+    ";
+    match interpret_program_for_testing(test_str) {
+        Err(SemanticError::ASTBuilding(error)) => {
+            let message = error.to_string();
+            print!("{}\n", message);
+            assert!(message.contains("🟠 Function main is already defined."));
+        },
+        other => panic!("Expected an AST building error, got {:?}", other),
+    }
+}
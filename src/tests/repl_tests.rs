@@ -0,0 +1,206 @@
+use crate::repl::{ReplSession, is_incomplete};
+use crate::semantic::data_model::{IntWidth, set_int_width};
+use crate::semantic::errors::SemanticError;
+use crate::semantic::overflow_policy::{OverflowPolicy, set_overflow_policy};
+
+/// Run the inputs of a REPL session in order, and get the output of the last one.
+fn eval_inputs_for_testing(inputs: &[&str]) -> Result<String, SemanticError> {
+    set_int_width(IntWidth::W16);
+    set_overflow_policy(OverflowPolicy::Trap);
+    let mut session = ReplSession::new();
+    let (last_input, previous_inputs) = inputs.split_last().unwrap();
+    for input in previous_inputs {
+        session.eval_input(input).unwrap_or_else(|error| {
+            panic!("Error evaluating REPL input <{}>: {}", input, error);
+        });
+    }
+    session.eval_input(last_input)
+}
+
+/// Run a REPL session, then check the output of its last input,
+/// or check that its last input raises the given error.
+macro_rules! build_repl_test {
+    ($test_name:ident, $inputs:expr, $expected_output:expr) => {
+        #[test]
+        fn $test_name() {
+            let output = eval_inputs_for_testing(&$inputs)
+                .unwrap_or_else(|error| {
+                    panic!("Error evaluating REPL inputs <{:?}>: {}", $inputs, error);
+                });
+            assert_eq!(output, $expected_output);
+        }
+    };
+    ($test_name:ident, $inputs:expr, error $error_pattern:pat) => {
+        #[test]
+        fn $test_name() {
+            match eval_inputs_for_testing(&$inputs) {
+                Ok(output) => panic!(
+                    "Expected error for <{:?}>, got output {}", $inputs, output
+                ),
+                Err(error) => {
+                    print!("Expected error for <{:?}>: {}\n", $inputs, error);
+                    assert!(matches!(error, $error_pattern));
+                },
+            }
+        }
+    };
+}
+
+build_repl_test!(
+    test_repl_expression,
+    ["1 + 2 * 3"],
+    "7 (Int)"
+);
+
+build_repl_test!(
+    test_repl_variables_persist,
+    ["int x;", "x = 3;", "x = x + 1;", "x * 2"],
+    "8 (Int)"
+);
+
+build_repl_test!(
+    test_repl_function_definition,
+    ["int square(int x) { return x * x; }", "square(5)"],
+    "25 (Int)"
+);
+
+build_repl_test!(
+    test_repl_function_redefinition,
+    [
+        "int f(int x) { return x; }",
+        "int f(int x) { return x + 1; }",
+        "f(1)",
+    ],
+    "2 (Int)"
+);
+
+build_repl_test!(
+    test_repl_function_description,
+    ["int square(int x) { return x * x; }"],
+    "Defined int square(int x)"
+);
+
+build_repl_test!(
+    test_repl_struct_definition,
+    [
+        "struct Point { int x; int y; };",
+        "struct Point p;",
+        "p.x = 1;",
+        "p.y = 2;",
+        "p.x + p.y",
+    ],
+    "3 (Int)"
+);
+
+build_repl_test!(
+    test_repl_array_initializer,
+    ["int a[3] = {1, 2};", "a[0] + a[1] + a[2]"],
+    "3 (Int)"
+);
+
+build_repl_test!(
+    test_repl_vars_command,
+    ["int b;", "int a[2];", "a[0] = 4;", ":vars"],
+    "int a[2] = {4 (Int), <unassigned>}\nint b = <unassigned>"
+);
+
+build_repl_test!(
+    test_repl_funcs_command,
+    [
+        "int g(int x, char c) { return x; }",
+        "float f() { return 1.0; }",
+        ":funcs",
+    ],
+    "float f()\nint g(int x, char c)"
+);
+
+build_repl_test!(
+    test_repl_reset_command,
+    ["int x;", ":reset", ":vars"],
+    ""
+);
+
+build_repl_test!(
+    test_repl_call_in_operation,
+    ["int sq(int a) { return a * a; }", "1 + sq(2)"],
+    "5 (Int)"
+);
+
+build_repl_test!(
+    test_repl_call_in_assignment,
+    [
+        "int sq(int a) { return a * a; }",
+        "int x;",
+        "x = sq(2) * 3;",
+        "sq(x) - x",
+    ],
+    "132 (Int)"
+);
+
+build_repl_test!(
+    test_repl_functions_after_reset,
+    [
+        "int f() { return 1; }",
+        ":reset",
+        "int f() { return 2; }",
+        "f() + f()",
+    ],
+    "4 (Int)"
+);
+
+build_repl_test!(
+    test_repl_redeclaration,
+    ["int x;", "int x;"],
+    error SemanticError::Redeclaration(_)
+);
+
+build_repl_test!(
+    test_repl_undeclared_variable,
+    ["y + 1"],
+    error SemanticError::UndeclaredVariable(_)
+);
+
+build_repl_test!(
+    test_repl_prototype_rejected,
+    ["int f(int x);"],
+    error SemanticError::ASTBuilding(_)
+);
+
+build_repl_test!(
+    test_repl_syntax_error,
+    ["1 +"],
+    error SemanticError::SyntaxParsing(_)
+);
+
+#[test]
+fn test_repl_failed_definition_is_forgotten() {
+    set_int_width(IntWidth::W16);
+    set_overflow_policy(OverflowPolicy::Trap);
+    let mut session = ReplSession::new();
+    session.eval_input("int f() { return 1; }").unwrap();
+    assert!(session.eval_input("int f() { struct Nope n; return 1; }").is_err());
+    assert_eq!(session.eval_input("f()").unwrap(), "1 (Int)");
+}
+
+#[test]
+fn test_repl_ast_command_does_not_run() {
+    set_int_width(IntWidth::W16);
+    set_overflow_policy(OverflowPolicy::Trap);
+    let mut session = ReplSession::new();
+    session.eval_input("int x;").unwrap();
+    let ast = session.eval_input(":ast x = 1;").unwrap();
+    assert!(ast.contains("Assignment"));
+    assert_eq!(session.eval_input(":vars").unwrap(), "int x = <unassigned>");
+}
+
+#[test]
+fn test_repl_is_incomplete() {
+    assert!(is_incomplete("int f() {"));
+    assert!(!is_incomplete("int f() { return 1; }"));
+    assert!(!is_incomplete("char c = '{';"));
+    assert!(!is_incomplete("int x; // {"));
+    assert!(is_incomplete("int f() { // }\n"));
+    assert!(!is_incomplete("int x; /* { */"));
+    assert!(is_incomplete("int f() { /* } */"));
+    assert!(is_incomplete("int x; /* {"));
+}
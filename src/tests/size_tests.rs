@@ -0,0 +1,189 @@
+use crate::abstract_syntax_tree::nodes::Value;
+use crate::semantic::errors::SemanticError;
+use crate::build_program_test;
+
+build_program_test!(
+    test_sizeof_scalar_types,
+    "
+    int main() {
+        return sizeof(char) * 1000 + sizeof(int) * 100 + sizeof(long) * 10 + sizeof(double);
+    }
+    ",
+    Value::Int(1248)
+);
+
+build_program_test!(
+    test_sizeof_unsigned_and_bool,
+    "
+    int main() {
+        return sizeof(unsigned short) + sizeof(unsigned) + sizeof(bool) + sizeof(float);
+    }
+    ",
+    Value::Int(9)
+);
+
+build_program_test!(
+    test_sizeof_struct_with_padding,
+    "
+    struct mixed { char c; long l; short s; };
+    struct nested { char c; struct mixed m; };
+
+    int main() {
+        return sizeof(struct mixed) * 100 + sizeof(struct nested);
+    }
+    ",
+    Value::Int(1216)
+);
+
+build_program_test!(
+    test_sizeof_variables,
+    "
+    struct point { int x; float y; };
+
+    int main() {
+        int a[10];
+        struct point p;
+        struct point points[3];
+        int i, total;
+        total = sizeof(a) * 1000 + sizeof(a[0]) * 100 + sizeof(points) + sizeof(p.y) + sizeof(points[1].x);
+        i = 0;
+        while (i < len(a)) {
+            a[i] = i;
+            i = i + 1;
+        }
+        i = 0;
+        while (i < len(points)) {
+            points[i].x = i;
+            points[i].y = 0.5;
+            i = i + 1;
+        }
+        p = points[0];
+        return total;
+    }
+    ",
+    Value::Int(20230)
+);
+
+build_program_test!(
+    test_sizeof_does_not_evaluate_its_operand,
+    "
+    int main() {
+        int a[3];
+        int i;
+        i = 100;
+        a[0] = 0; a[1] = 0; a[2] = 0;
+        return sizeof(a[i]);
+    }
+    ",
+    Value::Int(2)
+);
+
+build_program_test!(
+    test_len_loop_bound,
+    "
+    int main() {
+        int a[5];
+        int i, total;
+        i = 0;
+        while (i < len(a)) {
+            a[i] = i * 2;
+            i = i + 1;
+        }
+        i = 0;
+        total = 0;
+        while (i < len(a)) {
+            total = total + a[i];
+            i = i + 1;
+        }
+        return total + len(a);
+    }
+    ",
+    Value::Int(25)
+);
+
+build_program_test!(
+    test_sizeof_type_in_constant_expression,
+    "
+    int main() {
+        const int n = sizeof(long) * 2;
+        int a[sizeof(int)];
+        a[0] = 1; a[1] = 2;
+        return n + len(a);
+    }
+    ",
+    Value::Int(10)
+);
+
+build_program_test!(
+    test_len_of_normal_variable,
+    "
+    int main() {
+        int x;
+        x = 1;
+        return len(x);
+    }
+    ",
+    error SemanticError::UndeclaredVariable(_)
+);
+
+build_program_test!(
+    test_len_checked_before_interpretation,
+    "
+    int never_called() {
+        return len(unknown);
+    }
+
+    int main() {
+        return 0;
+    }
+    ",
+    error SemanticError::UndeclaredVariable(_)
+);
+
+build_program_test!(
+    test_sizeof_unknown_member,
+    "
+    struct point { int x; int y; };
+
+    int main() {
+        struct point p;
+        p.x = 1; p.y = 2;
+        return sizeof(p.z);
+    }
+    ",
+    error SemanticError::InvalidMemberAccess(_)
+);
+
+build_program_test!(
+    test_sizeof_undeclared_struct,
+    "
+    int main() {
+        return sizeof(struct point);
+    }
+    ",
+    error SemanticError::UndeclaredStruct(_)
+);
+
+build_program_test!(
+    test_len_in_constant_expression,
+    "
+    int main() {
+        int a[3];
+        const int n = len(a);
+        a[0] = 1; a[1] = 2; a[2] = 3;
+        return n;
+    }
+    ",
+    error SemanticError::NonConstantExpression(_)
+);
+
+build_program_test!(
+    test_sizeof_does_not_fit_in_int,
+    "
+    int main() {
+        long big[20000];
+        return sizeof(big);
+    }
+    ",
+    error SemanticError::IntegerCastOverflow(_)
+);
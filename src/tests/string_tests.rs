@@ -0,0 +1,247 @@
+use crate::abstract_syntax_tree::nodes::Value;
+use crate::semantic::errors::SemanticError;
+use crate::build_program_test;
+
+build_program_test!(
+    test_string_initializer,
+    "
+    int main() {
+        char s[8] = \"hi\";
+        return s[0] + s[1] + s[2] + s[7];
+    }
+    ",
+    Value::Int(209)
+);
+
+build_program_test!(
+    test_string_initializer_with_escapes,
+    r#"
+    int main() {
+        char s[8] = "a\"\\\n";
+        return strlen(s) * 1000 + s[1] * 10 + s[3];
+    }
+    "#,
+    Value::Int(4350)
+);
+
+build_program_test!(
+    test_string_initializer_fits_exactly,
+    "
+    int main() {
+        const char s[6] = \"hello\";
+        return strlen(s);
+    }
+    ",
+    Value::Int(5)
+);
+
+build_program_test!(
+    test_string_initializer_without_room_for_nul,
+    "
+    int main() {
+        char s[5] = \"hello\";
+        return 0;
+    }
+    ",
+    error SemanticError::BufferOverflow(_)
+);
+
+build_program_test!(
+    test_string_initializer_for_int_array,
+    "
+    int main() {
+        int a[8] = \"hello\";
+        return 0;
+    }
+    ",
+    error SemanticError::ASTBuilding(_)
+);
+
+build_program_test!(
+    test_string_initializer_for_char,
+    "
+    int main() {
+        char c = \"a\";
+        return 0;
+    }
+    ",
+    error SemanticError::ASTBuilding(_)
+);
+
+build_program_test!(
+    test_strlen,
+    "
+    int main() {
+        char s[16] = \"hello\";
+        s[2] = '0';
+        return strlen(s) * 10 + strlen(\"\");
+    }
+    ",
+    Value::Int(50)
+);
+
+build_program_test!(
+    test_strlen_without_nul,
+    "
+    int main() {
+        char s[3] = \"ab\";
+        s[2] = 'c';
+        return strlen(s);
+    }
+    ",
+    error SemanticError::BufferOverflow(_)
+);
+
+build_program_test!(
+    test_strlen_of_int_array,
+    "
+    int main() {
+        int a[3] = {0};
+        return strlen(a);
+    }
+    ",
+    error SemanticError::InvalidStringOperand(_)
+);
+
+build_program_test!(
+    test_strlen_of_unassigned_char,
+    "
+    int main() {
+        char s[3];
+        s[0] = 'a';
+        return strlen(s);
+    }
+    ",
+    error SemanticError::UnassignedVariable(_)
+);
+
+build_program_test!(
+    test_strcmp,
+    "
+    int main() {
+        char a[8] = \"abc\";
+        char b[8] = \"abd\";
+        char c[8] = \"ab\";
+        int result;
+        result = 0;
+        if (strcmp(a, \"abc\") == 0) {
+            result = result + 1;
+        }
+        if (strcmp(a, b) < 0) {
+            result = result + 10;
+        }
+        if (strcmp(a, c) > 0) {
+            result = result + 100;
+        }
+        return result;
+    }
+    ",
+    Value::Int(111)
+);
+
+build_program_test!(
+    test_strcpy,
+    "
+    int main() {
+        char s[8] = \"hello\";
+        strcpy(s, \"hi\");
+        return strlen(s) * 100 + s[3];
+    }
+    ",
+    Value::Int(308)
+);
+
+build_program_test!(
+    test_strcpy_into_unassigned_array,
+    "
+    int f() {
+        char s[4];
+        strcpy(s, \"ab\");
+        return strlen(s) * 100 + s[3];
+    }
+    int main() {
+        return f();
+    }
+    ",
+    Value::Int(200)
+);
+
+build_program_test!(
+    test_strcpy_from_array,
+    "
+    int main() {
+        char source[4] = \"abc\";
+        char destination[4] = {0};
+        strcpy(destination, source);
+        return strcmp(destination, source) + strlen(destination);
+    }
+    ",
+    Value::Int(3)
+);
+
+build_program_test!(
+    test_strcpy_buffer_overflow,
+    "
+    int main() {
+        char source[16] = \"hello world\";
+        char destination[8] = {0};
+        strcpy(destination, source);
+        return 0;
+    }
+    ",
+    error SemanticError::BufferOverflow(_)
+);
+
+build_program_test!(
+    test_strcpy_into_const,
+    "
+    int main() {
+        const char s[8] = \"hello\";
+        strcpy(s, \"hi\");
+        return 0;
+    }
+    ",
+    error SemanticError::ConstAssignment(_)
+);
+
+build_program_test!(
+    test_strcpy_into_normal_variable,
+    "
+    int main() {
+        char c;
+        strcpy(c, \"a\");
+        return 0;
+    }
+    ",
+    error SemanticError::InvalidStringOperand(_)
+);
+
+build_program_test!(
+    test_string_initializer_is_evaluated_at_each_call,
+    "
+    int first_char_after_copy(int first_call) {
+        char s[4] = \"ab\";
+        if (first_call) {
+            strcpy(s, \"z\");
+        }
+        return s[0];
+    }
+
+    int main() {
+        return first_char_after_copy(1) - first_char_after_copy(0);
+    }
+    ",
+    Value::Int(25)
+);
+
+build_program_test!(
+    test_assert_reports_strlen,
+    "
+    int main() {
+        char s[8] = \"abc\";
+        assert(strlen(s) == 2);
+        return 0;
+    }
+    ",
+    error SemanticError::AssertionFailed(_)
+);
@@ -0,0 +1,320 @@
+use crate::abstract_syntax_tree::nodes::Value;
+use crate::semantic::errors::SemanticError;
+use crate::build_program_test;
+
+build_program_test!(
+    test_struct_local_variable,
+    "
+    struct point { int x; float y; };
+
+    int main() {
+        struct point p;
+        p.x = 2;
+        p.y = 1.5;
+        return p.x + (int) (p.y * 2.0);
+    }
+    ",
+    Value::Int(5)
+);
+
+build_program_test!(
+    test_struct_field_conversion,
+    "
+    struct point { int x; int y; };
+
+    int main() {
+        struct point p;
+        p.x = 2.7;
+        p.y = 'a';
+        return p.x + p.y;
+    }
+    ",
+    Value::Int(99)
+);
+
+build_program_test!(
+    test_struct_array,
+    "
+    struct point { int x; int y; };
+
+    int main() {
+        struct point points[3];
+        int i, sum;
+        i = 0;
+        while (i < 3) {
+            points[i].x = i;
+            points[i].y = i * 10;
+            i = i + 1;
+        }
+        sum = points[1].x + points[2].y;
+        return sum;
+    }
+    ",
+    Value::Int(21)
+);
+
+build_program_test!(
+    test_nested_struct_members,
+    "
+    struct point { int x; int y; };
+    struct segment { struct point start; struct point end; };
+
+    int main() {
+        struct segment s;
+        s.start.x = 1;
+        s.start.y = 2;
+        s.end.x = 4;
+        s.end.y = 6;
+        return (s.end.x - s.start.x) * (s.end.y - s.start.y);
+    }
+    ",
+    Value::Int(12)
+);
+
+build_program_test!(
+    test_struct_whole_assignment_copies,
+    "
+    struct point { int x; int y; };
+
+    int main() {
+        struct point p, q;
+        p.x = 1;
+        p.y = 2;
+        q = p;
+        p.x = 10;
+        return q.x;
+    }
+    ",
+    Value::Int(1)
+);
+
+build_program_test!(
+    test_struct_nested_whole_assignment,
+    "
+    struct point { int x; int y; };
+    struct segment { struct point start; struct point end; };
+
+    int main() {
+        struct point p;
+        struct segment s;
+        p.x = 3;
+        p.y = 4;
+        s.start = p;
+        s.end = p;
+        return s.start.y + s.end.x;
+    }
+    ",
+    Value::Int(7)
+);
+
+build_program_test!(
+    test_struct_parameter_passed_by_value,
+    "
+    struct point { int x; int y; };
+
+    int move_right(struct point p) {
+        p.x = p.x + 100;
+        return p.x;
+    }
+
+    int main() {
+        struct point p;
+        int moved_x;
+        p.x = 1;
+        p.y = 2;
+        moved_x = move_right(p);
+        return moved_x + p.x;
+    }
+    ",
+    Value::Int(102)
+);
+
+build_program_test!(
+    test_struct_return_value,
+    "
+    struct point { int x; int y; };
+
+    struct point make_point(int x, int y) {
+        struct point p;
+        p.x = x;
+        p.y = y;
+        return p;
+    }
+
+    int main() {
+        struct point p;
+        p = make_point(5, 7);
+        return p.x * p.y;
+    }
+    ",
+    Value::Int(35)
+);
+
+build_program_test!(
+    test_struct_field_overflow,
+    "
+    struct small { char c; };
+
+    int main() {
+        struct small v;
+        v.c = 300;
+        return 0;
+    }
+    ",
+    error SemanticError::IntToCharCastOverflow(_)
+);
+
+build_program_test!(
+    test_struct_unassigned_field_read,
+    "
+    struct point { int x; int y; };
+
+    int main() {
+        struct point p;
+        p.x = 1;
+        return p.y;
+    }
+    ",
+    error SemanticError::UnassignedVariable(_)
+);
+
+build_program_test!(
+    test_struct_partially_assigned_copy,
+    "
+    struct point { int x; int y; };
+
+    int main() {
+        struct point p, q;
+        p.x = 1;
+        q = p;
+        return 0;
+    }
+    ",
+    error SemanticError::UnassignedVariable(_)
+);
+
+build_program_test!(
+    test_struct_unassigned_field_at_function_end,
+    "
+    struct point { int x; int y; };
+
+    int main() {
+        struct point p;
+        p.x = 1;
+        return p.x;
+    }
+    ",
+    error SemanticError::UnassignedVariable(_)
+);
+
+build_program_test!(
+    test_struct_unknown_member,
+    "
+    struct point { int x; int y; };
+
+    int main() {
+        struct point p;
+        p.z = 1;
+        return 0;
+    }
+    ",
+    error SemanticError::InvalidMemberAccess(_)
+);
+
+build_program_test!(
+    test_member_access_on_scalar,
+    "
+    int main() {
+        int x;
+        x = 1;
+        return x.y;
+    }
+    ",
+    error SemanticError::InvalidMemberAccess(_)
+);
+
+build_program_test!(
+    test_struct_arithmetic_rejected,
+    "
+    struct point { int x; int y; };
+
+    int main() {
+        struct point p, q;
+        int x;
+        p.x = 1;
+        p.y = 2;
+        q = p;
+        x = p + q;
+        return x;
+    }
+    ",
+    error SemanticError::InvalidOperandType(_)
+);
+
+build_program_test!(
+    test_struct_assigned_to_other_struct_type,
+    "
+    struct point { int x; int y; };
+    struct vector { int x; int y; };
+
+    int main() {
+        struct point p;
+        struct vector v;
+        p.x = 1;
+        p.y = 2;
+        v = p;
+        return v.x;
+    }
+    ",
+    error SemanticError::UnexpectedTypeCast(_)
+);
+
+build_program_test!(
+    test_undeclared_struct_type,
+    "
+    int main() {
+        struct point p;
+        p.x = 1;
+        return p.x;
+    }
+    ",
+    error SemanticError::UndeclaredStruct(_)
+);
+
+build_program_test!(
+    test_struct_field_of_later_struct_type,
+    "
+    struct segment { struct point start; struct point end; };
+    struct point { int x; int y; };
+
+    int main() {
+        return 0;
+    }
+    ",
+    error SemanticError::UndeclaredStruct(_)
+);
+
+build_program_test!(
+    test_struct_redefinition,
+    "
+    struct point { int x; int y; };
+    struct point { int x; };
+
+    int main() {
+        return 0;
+    }
+    ",
+    error SemanticError::Redeclaration(_)
+);
+
+build_program_test!(
+    test_struct_duplicate_field,
+    "
+    struct point { int x; int x; };
+
+    int main() {
+        return 0;
+    }
+    ",
+    error SemanticError::Redeclaration(_)
+);
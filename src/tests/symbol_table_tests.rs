@@ -18,7 +18,7 @@ fn test_build_static_symbol_table() {
         });
     let main_id_node = &ast.data.main_function.data.name;
 
-    let symbol_table = build_static_symbol_table(&ast);
+    let symbol_table = build_static_symbol_table(&ast).unwrap();
     let main_scope: &Scope = symbol_table.get_scope(main_id_node).unwrap();
     let x_var = main_scope.get_variable_from_id(&Identifier {name: "x".to_string()}).unwrap();
     assert_eq!(x_var, &Variable::NormalVar(NormalVarData::new(
@@ -45,9 +45,9 @@ fn test_build_static_symbol_table_multi_functions() {
         }
     );
     let main_id_node = &ast.data.main_function.data.name;
-    let foo_id_node = &ast.data.functions.as_ref().unwrap()[0].data.name;
+    let foo_id_node = &ast.data.functions[&Identifier {name: "foo".to_string()}].data.name;
 
-    let symbol_table = build_static_symbol_table(&ast);
+    let symbol_table = build_static_symbol_table(&ast).unwrap();
 
     // check main scope
     let main_scope: &Scope = symbol_table.get_scope(main_id_node).unwrap();
@@ -84,9 +84,9 @@ fn test_build_static_symbol_table_array() {
             panic!("AST ERROR for {}: \n {}\n", test_string, error);
         });
     let main_id_node = &ast.data.main_function.data.name;
-    let foo_id_node = &ast.data.functions.as_ref().unwrap()[0].data.name;
+    let foo_id_node = &ast.data.functions[&Identifier {name: "foo".to_string()}].data.name;
 
-    let symbol_table = build_static_symbol_table(&ast);
+    let symbol_table = build_static_symbol_table(&ast).unwrap();
 
     // check main scope
     let main_scope: &Scope = symbol_table.get_scope(main_id_node).unwrap();
@@ -110,4 +110,25 @@ fn test_build_static_symbol_table_array() {
         TypeSpecifier::Int,
         10,
     )));
-}
\ No newline at end of file
+}
+#[test]
+fn test_build_static_symbol_table_functions_in_source_order() {
+    // every function has an error, the one of the first function is reported
+    let function_names = ["h", "g", "f", "e", "d", "c", "b", "a"];
+    let mut test_string = String::new();
+    for name in function_names {
+        test_string.push_str(&format!("int {}() {{\n    struct Unknown_{} s;\n    return 0;\n}}\n", name, name));
+    }
+    test_string.push_str("int main() {\n    return 0;\n}");
+    let ast = parse_content_into_ast(&test_string, None).unwrap();
+
+    let function_names_in_ast: Vec<&str> = ast.data.functions.keys()
+        .map(|function_id| function_id.name.as_str())
+        .collect();
+    assert_eq!(function_names_in_ast, function_names);
+    let Err(error) = build_static_symbol_table(&ast) else {
+        panic!("Expected an error for unknown structs");
+    };
+    print!("Expected error: {}\n", error);
+    assert!(error.to_string().contains("Unknown_h"));
+}
@@ -153,4 +153,207 @@ build_syntax_parsing_test!(
     test_function_call,
     "test_function(1, 2, a, 2*3)",
     Rule::expression
-);
\ No newline at end of file
+);
+build_syntax_parsing_test!(
+    test_multi_word_type_specifier,
+    "unsigned long",
+    Rule::type_specifier
+);
+
+build_syntax_parsing_test!(
+    test_declaration_with_unsigned_alone,
+    "unsigned counter;",
+    Rule::multi_declaration
+);
+
+build_syntax_parsing_test!(
+    test_declaration_with_keyword_prefixed_identifier,
+    "unsigned integer;",
+    Rule::multi_declaration
+);
+
+build_syntax_parsing_test!(
+    test_keyword_prefixed_identifier,
+    "shortest",
+    Rule::identifier
+);
+
+build_syntax_parsing_test!(
+    test_keyword_is_not_identifier,
+    "short",
+    Rule::identifier,
+    false
+);
+
+build_syntax_parsing_test!(
+    test_integer_literal_with_suffix,
+    "42UL",
+    Rule::literal
+);
+
+build_syntax_parsing_test!(
+    test_const_declaration,
+    "const int N = 4, M = N * 2;",
+    Rule::multi_declaration
+);
+
+build_syntax_parsing_test!(
+    test_const_array_declaration,
+    "const char letters[N + 1] = {'a', 'b', 'c'};",
+    Rule::multi_declaration
+);
+
+build_syntax_parsing_test!(
+    test_const_prefixed_identifier,
+    "constant",
+    Rule::identifier
+);
+
+build_syntax_parsing_test!(
+    test_const_keyword_is_not_identifier,
+    "const",
+    Rule::identifier,
+    false
+);
+
+build_syntax_parsing_test!(
+    test_struct_definition,
+    "struct point { int x; float y; };",
+    Rule::struct_definition
+);
+
+build_syntax_parsing_test!(
+    test_struct_variable_declaration,
+    "struct point p, points[3];",
+    Rule::multi_declaration
+);
+
+build_syntax_parsing_test!(
+    test_nested_struct_member_access,
+    "points[i + 1].start.x",
+    Rule::get_or_set_value
+);
+
+build_syntax_parsing_test!(
+    test_struct_keyword_is_not_identifier,
+    "struct",
+    Rule::identifier,
+    false
+);
+
+build_syntax_parsing_test!(
+    test_enum_definition,
+    "enum color { RED, GREEN = 5, BLUE };",
+    Rule::enum_definition
+);
+
+build_syntax_parsing_test!(
+    test_enum_variable_declaration,
+    "enum color c;",
+    Rule::multi_declaration
+);
+
+build_syntax_parsing_test!(
+    test_switch_statement,
+    "switch (c) { case RED: x = 1; y = 2; case GREEN: default: x = 3; }",
+    Rule::switch_statement
+);
+
+build_syntax_parsing_test!(
+    test_default_prefixed_identifier,
+    "defaultValue",
+    Rule::identifier
+);
+
+build_syntax_parsing_test!(
+    test_function_prototype,
+    "int foo(int a, float b[3]);",
+    Rule::function_prototype
+);
+
+build_syntax_parsing_test!(
+    test_main_before_other_functions,
+    "int main() { return foo(); } int foo() { return 0; }",
+    Rule::translation_unit
+);
+
+build_syntax_parsing_test!(
+    test_assert_statement,
+    "assert(a[i] > 0 && x == 2);",
+    Rule::assert_statement
+);
+
+build_syntax_parsing_test!(
+    test_assert_missing_semicolon,
+    "assert(x)",
+    Rule::assert_statement,
+    false
+);
+
+build_syntax_parsing_test!(
+    test_sizeof_type,
+    "sizeof(unsigned long)",
+    Rule::sizeof_expression
+);
+
+build_syntax_parsing_test!(
+    test_sizeof_variable,
+    "sizeof(points[i].x)",
+    Rule::sizeof_expression
+);
+
+build_syntax_parsing_test!(
+    test_length_expression,
+    "len(a)",
+    Rule::length_expression
+);
+
+build_syntax_parsing_test!(
+    test_length_prefixed_identifier,
+    "length",
+    Rule::identifier
+);
+
+build_syntax_parsing_test!(
+    test_string_literal,
+    r#""hello, \"world\"\n""#,
+    Rule::string_literal
+);
+
+build_syntax_parsing_test!(
+    test_string_literal_on_several_lines,
+    "\"hello\nworld\"",
+    Rule::string_literal,
+    false
+);
+
+build_syntax_parsing_test!(
+    test_char_array_string_initializer,
+    "char s[16] = \"hello\";",
+    Rule::multi_declaration
+);
+
+build_syntax_parsing_test!(
+    test_strlen_expression,
+    "strlen(s)",
+    Rule::strlen_expression
+);
+
+build_syntax_parsing_test!(
+    test_strcmp_expression,
+    "strcmp(s, \"hello\")",
+    Rule::strcmp_expression
+);
+
+build_syntax_parsing_test!(
+    test_strcpy_statement,
+    "strcpy(destination, \"hello\");",
+    Rule::strcpy_statement
+);
+
+build_syntax_parsing_test!(
+    test_strcpy_literal_destination,
+    "strcpy(\"hello\", s);",
+    Rule::strcpy_statement,
+    false
+);
@@ -0,0 +1,113 @@
+use serde_json::Value as JsonValue;
+
+use crate::interpretation::hooks::{add_hook, clear_hooks};
+use crate::interpretation::interpret_function::interpret_translation_unit;
+use crate::pipelines::parse_content_into_ast;
+use crate::semantic::data_model::{IntWidth, set_int_width};
+use crate::semantic::overflow_policy::{OverflowPolicy, set_overflow_policy};
+use crate::symbol_table::build_static_symbol_table;
+use crate::trace::Tracer;
+use crate::tests::shared_output::SharedOutput;
+
+/// Interpret a program with a tracer, and get the lines of the trace.
+fn trace_program_for_testing(test_str: &str) -> Vec<String> {
+    set_int_width(IntWidth::W16);
+    set_overflow_policy(OverflowPolicy::Trap);
+    let ast = parse_content_into_ast(test_str, None).unwrap();
+    let mut symbol_table = build_static_symbol_table(&ast).unwrap();
+
+    let output = SharedOutput::new();
+    clear_hooks();
+    add_hook(Box::new(Tracer::new(Box::new(output.clone()), None)));
+    let _ = interpret_translation_unit(&ast, &mut symbol_table);
+    clear_hooks();
+
+    let output = output.get_text();
+    print!("Trace:\n{}", output);
+    output.lines().map(str::to_string).collect()
+}
+
+fn get_events<'a>(events: &'a [JsonValue], event: &str) -> Vec<&'a JsonValue> {
+    events.iter().filter(|value| value["event"] == event).collect()
+}
+
+const SQUARE_PROGRAM: &str = "int square(short x) {
+    int y;
+    y = x * x;
+    return y;
+}
+int main() {
+    int a;
+    int t[1];
+    a = square(3);
+    t[0] = a;
+    t[0] = t[0] + 1;
+    return t[0];
+}";
+
+#[test]
+fn test_trace_events() {
+    let lines = trace_program_for_testing(SQUARE_PROGRAM);
+    let events: Vec<JsonValue> = lines.iter()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    let statements = get_events(&events, "statement");
+    let statement_lines: Vec<&JsonValue> = statements.iter().map(|event| &event["line"]).collect();
+    assert_eq!(statement_lines, vec![9, 3, 10, 11]);
+    assert_eq!(statements[1]["function"], "square");
+    assert_eq!(statements[1]["kind"], "assignment");
+    assert_eq!(statements[1]["text"], "y = x * x;");
+
+    let assignments = get_events(&events, "assignment");
+    assert_eq!(assignments.len(), 4);
+    assert_eq!(assignments[0]["target"], "y");
+    assert_eq!(assignments[0]["old"], JsonValue::Null);
+    assert_eq!(assignments[0]["new"], "9 (Int)");
+    assert_eq!(assignments[3]["target"], "t[0]");
+    assert_eq!(assignments[3]["old"], "9 (Int)");
+    assert_eq!(assignments[3]["new"], "10 (Int)");
+
+    // the call of square is located at the call site, with its converted argument
+    let calls = get_events(&events, "call");
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[1]["function"], "square");
+    assert_eq!((&calls[1]["line"], &calls[1]["column"]), (&JsonValue::from(9), &JsonValue::from(9)));
+    assert_eq!(calls[1]["arguments"][0]["name"], "x");
+    assert_eq!(calls[1]["arguments"][0]["value"], "3 (Short)");
+
+    let returns = get_events(&events, "return");
+    let return_values: Vec<&JsonValue> = returns.iter().map(|event| &event["value"]).collect();
+    assert_eq!(return_values, vec!["9 (Int)", "10 (Int)"]);
+
+    // the argument is converted to short, then x is promoted to int for the multiplication
+    let casts = get_events(&events, "cast");
+    assert_eq!(casts.len(), 3);
+    assert_eq!((&casts[0]["from"], &casts[0]["to"]), (&JsonValue::from("int"), &JsonValue::from("short")));
+    assert_eq!((&casts[1]["line"], &casts[1]["column"]), (&JsonValue::from(3), &JsonValue::from(9)));
+    assert_eq!(casts[1]["result"], "3 (Int)");
+}
+
+#[test]
+fn test_trace_is_deterministic() {
+    let first_trace = trace_program_for_testing(SQUARE_PROGRAM);
+    let second_trace = trace_program_for_testing(SQUARE_PROGRAM);
+    assert_eq!(first_trace, second_trace);
+    assert!(first_trace[0].starts_with("{\"event\":\"call\",\"file\":null,\"line\":6,\"column\":1,"));
+}
+
+#[test]
+fn test_trace_error_return() {
+    let lines = trace_program_for_testing("int main() {
+    int a;
+    a = 32767;
+    a = a + 1;
+    return a;
+}");
+    let last_event: JsonValue = serde_json::from_str(lines.last().unwrap()).unwrap();
+    assert_eq!(last_event["event"], "return");
+    assert_eq!(last_event["function"], "main");
+    assert!(last_event["error"].as_str().unwrap().contains("overflow"));
+    // the failed assignment is not traced
+    assert_eq!(lines.iter().filter(|line| line.contains("\"event\":\"assignment\"")).count(), 1);
+}
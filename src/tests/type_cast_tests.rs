@@ -171,7 +171,7 @@ cast_literal_test!(
 
 cast_literal_test!(
     test_overflow_when_casting_large_int_to_char,
-    Value::Int(i16::MAX),
+    Value::Int(i16::MAX as i64),
     "32767",
     TypeSpecifier::Char,
     false
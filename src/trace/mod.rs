@@ -0,0 +1,214 @@
+use std::io::Write;
+use std::path::Path;
+use std::rc::Rc;
+
+use pest::Span;
+use serde_json::{json, Value as JsonValue};
+
+use crate::abstract_syntax_tree::nodes::{Node, Identifier, Expression, Statement, Function, Value};
+use crate::interpretation::hooks::InterpretationHook;
+use crate::preprocessing::PreprocessedSource;
+use crate::semantic::errors::SemanticError;
+use crate::symbol_table::structs::SymbolTable;
+
+/// Where an event happened, in its original file when the program has been preprocessed.
+struct TraceLocation {
+    file: Option<String>,
+    line: usize,
+    column: usize,
+}
+
+fn get_statement_kind(statement: &Statement) -> &'static str {
+    match statement {
+        Statement::Assignment(_) => "assignment",
+        Statement::If(_) => "if",
+        Statement::While(_) => "while",
+        Statement::Switch(_) => "switch",
+        Statement::Assert(_) => "assert",
+        Statement::StringCopy(_) => "strcpy",
+    }
+}
+
+/// An interpretation hook writing the execution of a program as JSON Lines, one event per line:
+/// the statements, the assignments, the function calls and returns, and the implicit casts.
+///
+/// The fields of each event are always written in the same order, and the values as displayed
+/// by the interpreter, like `7 (Int)`, so that the traces of two versions can be diffed.
+pub struct Tracer {
+    output: Box<dyn Write>,
+    source: Option<Rc<PreprocessedSource>>,
+    /// The functions being interpreted, from `main`.
+    call_stack: Vec<String>,
+    /// The locations of the function calls being evaluated, until their function is entered.
+    call_sites: Vec<TraceLocation>,
+}
+
+impl Tracer {
+    /// Create a tracer writing its events to `output`.
+    /// Without a preprocessed source, the positions are the ones of the parsed content.
+    pub fn new(output: Box<dyn Write>, source: Option<Rc<PreprocessedSource>>) -> Self {
+        Self {
+            output,
+            source,
+            call_stack: Vec::new(),
+            call_sites: Vec::new(),
+        }
+    }
+
+    fn get_location(&self, sp: Span) -> TraceLocation {
+        let source_position = self.source.as_ref()
+            .and_then(|source| source.get_source_position(sp.start()));
+        match source_position {
+            Some((path, line, column)) => TraceLocation {
+                file: path.file_name().map(|file_name| file_name.to_string_lossy().to_string()),
+                line,
+                column,
+            },
+            None => {
+                let (line, column) = sp.start_pos().line_col();
+                TraceLocation { file: None, line, column }
+            },
+        }
+    }
+
+    fn get_current_function_name(&self) -> String {
+        self.call_stack.last().cloned().unwrap_or_default()
+    }
+
+    /// Write an event, with its fields in the given order after its name and location.
+    fn write_event(&mut self, event: &str, location: &TraceLocation, fields: Vec<(&str, JsonValue)>) {
+        let mut all_fields = vec![
+            ("event", json!(event)),
+            ("file", json!(location.file)),
+            ("line", json!(location.line)),
+            ("column", json!(location.column)),
+        ];
+        all_fields.extend(fields);
+        let members: Vec<String> = all_fields.iter()
+            .map(|(name, value)| format!("{}:{}", json!(name), value))
+            .collect();
+        if let Err(error) = writeln!(self.output, "{{{}}}", members.join(",")) {
+            log::error!("🚧 Trace ERROR: \n {}\n", error);
+        }
+    }
+
+    /// Write the header of the trace of a program.
+    pub fn start_program(&mut self, program_path: &Path) {
+        let location = TraceLocation {
+            file: program_path.file_name().map(|file_name| file_name.to_string_lossy().to_string()),
+            line: 1,
+            column: 1,
+        };
+        self.write_event("program", &location, Vec::new());
+    }
+}
+
+impl InterpretationHook for Tracer {
+    fn before_statement<'a>(
+        &mut self,
+        statement_node: &Node<'a, Statement<'a>>,
+        _symbol_table: &SymbolTable,
+        _current_scope_node_id: &Node<'a, Identifier>,
+    ) -> Result<(), SemanticError> {
+        let location = self.get_location(statement_node.sp);
+        let statement_line = statement_node.sp.as_str().lines().next().unwrap_or("").trim().to_string();
+        self.write_event("statement", &location, vec![
+            ("function", json!(self.get_current_function_name())),
+            ("kind", json!(get_statement_kind(&statement_node.data))),
+            ("text", json!(statement_line)),
+        ]);
+        Ok(())
+    }
+
+    fn before_expression<'a>(
+        &mut self,
+        expression_node: &Node<'a, Expression<'a>>,
+        _symbol_table: &SymbolTable,
+        _current_scope_node_id: &Node<'a, Identifier>,
+    ) -> Result<(), SemanticError> {
+        if let Expression::FunctionCall(_) = expression_node.data {
+            let location = self.get_location(expression_node.sp);
+            self.call_sites.push(location);
+        }
+        Ok(())
+    }
+
+    fn after_assignment<'a>(
+        &mut self,
+        statement_node: &Node<'a, Statement<'a>>,
+        target: &str,
+        old_value: Option<&Value>,
+        new_value: &Value,
+    ) -> Result<(), SemanticError> {
+        let location = self.get_location(statement_node.sp);
+        self.write_event("assignment", &location, vec![
+            ("function", json!(self.get_current_function_name())),
+            ("target", json!(target)),
+            ("old", json!(old_value.map(Value::to_string))),
+            ("new", json!(new_value.to_string())),
+        ]);
+        Ok(())
+    }
+
+    fn on_implicit_cast<'a>(
+        &mut self,
+        input_node: &Node<'a, Value>,
+        output_value: &Value,
+    ) -> Result<(), SemanticError> {
+        let location = self.get_location(input_node.sp);
+        self.write_event("cast", &location, vec![
+            ("function", json!(self.get_current_function_name())),
+            ("from", json!(input_node.data.as_type_specifier().to_string())),
+            ("to", json!(output_value.as_type_specifier().to_string())),
+            ("value", json!(input_node.data.to_string())),
+            ("result", json!(output_value.to_string())),
+        ]);
+        Ok(())
+    }
+
+    fn enter_function<'a>(
+        &mut self,
+        function_node: &Node<'a, Function<'a>>,
+        symbol_table: &SymbolTable,
+    ) -> Result<(), SemanticError> {
+        let function_name = &function_node.data.name;
+        // `main` is not called from an expression
+        let location = match function_name.data.name.as_str() {
+            "main" => self.get_location(function_node.sp),
+            _ => self.call_sites.pop().unwrap_or_else(|| self.get_location(function_node.sp)),
+        };
+        let arguments: Vec<JsonValue> = function_node.data.params.iter().flatten()
+            .map(|param| {
+                let value = symbol_table.get_scope(function_name)
+                    .and_then(|scope| scope.get_variable_value(&param.data.identifier, None, &[]).ok())
+                    .map(|value_node| value_node.data.to_string());
+                json!({ "name": param.data.identifier.data.name, "value": value })
+            })
+            .collect();
+        self.call_stack.push(function_name.data.name.clone());
+        self.write_event("call", &location, vec![
+            ("function", json!(function_name.data.name)),
+            ("arguments", json!(arguments)),
+        ]);
+        Ok(())
+    }
+
+    fn exit_function<'a>(
+        &mut self,
+        function_node: &Node<'a, Function<'a>>,
+        result: &Result<Node<'a, Value>, SemanticError>,
+        _symbol_table: &SymbolTable,
+    ) -> Result<(), SemanticError> {
+        let location = self.get_location(function_node.data.body.data.function_return.sp);
+        let outcome = match result {
+            Ok(value_node) => ("value", json!(value_node.data.to_string())),
+            Err(error) => ("error", json!(error.get_error().variant.message())),
+        };
+        self.write_event("return", &location, vec![
+            ("function", json!(function_node.data.name.data.name)),
+            outcome,
+        ]);
+        self.call_stack.pop();
+        Ok(())
+    }
+}